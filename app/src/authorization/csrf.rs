@@ -0,0 +1,193 @@
+// app/src/authorization/csrf.rs
+
+// dependencies
+use crate::authorization::CSRF_TOKEN;
+use crate::configuration::CsrfConfig;
+use crate::errors::ApiError;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use data_encoding::BASE64URL_NOPAD;
+use pavex::http::header::{AUTHORIZATION, HOST, ORIGIN, REFERER};
+use pavex::http::{HeaderMap, Method};
+use pavex::middleware::Processing;
+use pavex::pre_process;
+use pavex::request::RequestHead;
+use pavex_session::Session;
+use subtle::ConstantTimeEq;
+
+/// The header browser scripts send the CSRF token of their session in.
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+const TOKEN_BYTES: usize = 32;
+
+/// The CSRF token of the session, created the first time a page asks for it.
+///
+/// Signing in or out clears the session, so every sign-in starts with a fresh token.
+pub async fn csrf_token(session: &mut Session<'_>) -> String {
+    if let Some(token) = session.get::<String>(CSRF_TOKEN).await.unwrap_or(None) {
+        return token;
+    }
+
+    let mut bytes = [0u8; TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    let token = BASE64URL_NOPAD.encode(&bytes);
+    session.insert(CSRF_TOKEN, token.clone()).await.unwrap();
+    token
+}
+
+// middleware which refuses state-changing requests a page of this site did not make
+//
+// Requests authenticated with an API token carry no cookie-borne authority, so they are let through.
+#[pre_process]
+pub async fn reject_cross_site_requests(
+    request_head: &RequestHead,
+    session: &Session<'_>,
+    config: &CsrfConfig,
+) -> Result<Processing, ApiError> {
+    let headers = &request_head.headers;
+    if is_safe(&request_head.method) || has_bearer_token(headers) {
+        return Ok(Processing::Continue);
+    }
+
+    let authority = headers
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| request_head.target.authority().map(|a| a.as_str()));
+    if !from_trusted_origin(headers, authority, &config.trusted_origins) {
+        tracing::info!("Cross-site request refused");
+        return Err(ApiError::Forbidden("Cross-site request refused".into()));
+    }
+
+    let expected: Option<String> = session.get(CSRF_TOKEN).await.unwrap_or(None);
+    let provided = headers
+        .get(CSRF_HEADER)
+        .and_then(|value| value.to_str().ok());
+    match (expected, provided) {
+        (Some(expected), Some(provided))
+            if bool::from(expected.as_bytes().ct_eq(provided.as_bytes())) =>
+        {
+            Ok(Processing::Continue)
+        }
+        _ => Err(ApiError::Forbidden("Missing or invalid CSRF token".into())),
+    }
+}
+
+// methods which never change state
+fn is_safe(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    )
+}
+
+fn has_bearer_token(headers: &HeaderMap) -> bool {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split_once(' '))
+        .is_some_and(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+}
+
+// whether the Origin, or failing that the Referer, names the host the request is addressed to
+// or a trusted origin; clients which send neither are not browsers and left to the token check
+fn from_trusted_origin(headers: &HeaderMap, authority: Option<&str>, trusted: &[String]) -> bool {
+    let Some(source) = headers.get(ORIGIN).or_else(|| headers.get(REFERER)) else {
+        return true;
+    };
+    let Some(origin) = source.to_str().ok().and_then(origin_of) else {
+        return false;
+    };
+
+    let same_host = authority.is_some_and(|authority| {
+        origin
+            .split_once("://")
+            .is_some_and(|(_, host)| host.eq_ignore_ascii_case(authority))
+    });
+    same_host
+        || trusted
+            .iter()
+            .any(|trusted| trusted.trim_end_matches('/').eq_ignore_ascii_case(&origin))
+}
+
+// the `scheme://host[:port]` part of a URL, `None` for opaque origins such as `null`
+fn origin_of(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    (!scheme.is_empty() && !authority.is_empty()).then(|| format!("{scheme}://{authority}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_trusted_origin, has_bearer_token, origin_of};
+    use pavex::http::header::{AUTHORIZATION, ORIGIN, REFERER};
+    use pavex::http::{HeaderMap, HeaderValue};
+
+    fn headers(pairs: &[(pavex::http::HeaderName, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(name.clone(), HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn origin_is_the_scheme_and_authority() {
+        assert_eq!(
+            origin_of("https://example.com:8443/auth/login?next=/"),
+            Some("https://example.com:8443".to_string())
+        );
+        assert_eq!(origin_of("null"), None);
+        assert_eq!(origin_of("https:///path"), None);
+    }
+
+    #[test]
+    fn requests_from_their_own_host_are_trusted() {
+        let same = headers(&[(ORIGIN, "http://localhost:8000")]);
+        assert!(from_trusted_origin(&same, Some("localhost:8000"), &[]));
+        assert!(!from_trusted_origin(&same, Some("localhost:9000"), &[]));
+
+        let referred = headers(&[(REFERER, "http://localhost:8000/account")]);
+        assert!(from_trusted_origin(&referred, Some("localhost:8000"), &[]));
+
+        let cross_site = headers(&[
+            (ORIGIN, "https://evil.example"),
+            (REFERER, "http://localhost:8000/"),
+        ]);
+        assert!(!from_trusted_origin(
+            &cross_site,
+            Some("localhost:8000"),
+            &[]
+        ));
+
+        let opaque = headers(&[(ORIGIN, "null")]);
+        assert!(!from_trusted_origin(&opaque, Some("localhost:8000"), &[]));
+
+        // Non-browser clients send neither header
+        assert!(from_trusted_origin(
+            &HeaderMap::new(),
+            Some("localhost:8000"),
+            &[]
+        ));
+    }
+
+    #[test]
+    fn configured_origins_are_trusted_behind_a_proxy() {
+        let proxied = headers(&[(ORIGIN, "https://example.com")]);
+        let trusted = vec!["https://example.com/".to_string()];
+        assert!(from_trusted_origin(
+            &proxied,
+            Some("10.0.0.7:8000"),
+            &trusted
+        ));
+        assert!(!from_trusted_origin(&proxied, Some("10.0.0.7:8000"), &[]));
+    }
+
+    #[test]
+    fn only_bearer_credentials_are_exempt() {
+        assert!(has_bearer_token(&headers(&[(AUTHORIZATION, "Bearer abc")])));
+        assert!(!has_bearer_token(&headers(&[(
+            AUTHORIZATION,
+            "Basic YTpi"
+        )])));
+        assert!(!has_bearer_token(&HeaderMap::new()));
+    }
+}
@@ -1,31 +1,108 @@
 // app/src/authorization/current_user.rs
 
 // dependencies
-use crate::authorization::{USER_ID, USERNAME, USER_ROLE};
+use crate::authorization::{USER_GENERATION, USER_ID, session_info};
+use crate::models::{Capability, TokenScope};
+use crate::routes::account::{ApiTokenServiceContainer, SessionServiceContainer};
+use crate::routes::admin::CapabilityServiceContainer;
+use pavex::http::header::AUTHORIZATION;
 use pavex::methods;
+use pavex::request::RequestHead;
 use pavex_session::Session;
 use crate::{errors::ApiError, models::UserRole};
 use uuid::Uuid;
 
+// enum type to represent how the current user proved who they are
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuthMethod {
+  /// A cookie session, which carries every scope.
+  Session,
+  /// An `Authorization: Bearer` API token, limited to its scopes.
+  Token { id: Uuid, scopes: Vec<TokenScope> },
+}
+
 #[derive(Clone, Debug)]
 pub struct CurrentUser {
   pub id: Uuid,
   pub username: String,
   pub role: UserRole,
+  pub auth: AuthMethod,
+  /// What the role is granted in the capability registry, read once per request.
+  pub capabilities: Vec<Capability>,
 }
 
 #[methods]
 impl CurrentUser {
   #[request_scoped]
-  pub async fn new(session: &Session<'_>) -> Result<Self, ApiError> {
-    let id: Option<Uuid> = session.get(USER_ID).await.unwrap_or(None);
-    let username: Option<String> = session.get(USERNAME).await.unwrap_or(None);
-    let role: Option<UserRole> = session.get(USER_ROLE).await.unwrap_or(None);
-
-    match (id, username, role) {
-      (Some(id), Some(username), Some(role)) => Ok(Self { id, username, role }),
-      _ => Err(ApiError::Unauthorized("Invalid session".into())),
+  pub async fn new(
+    session: &Session<'_>,
+    request_head: &RequestHead,
+    api_tokens: &ApiTokenServiceContainer,
+    sessions: &SessionServiceContainer,
+    capabilities: &CapabilityServiceContainer,
+  ) -> Result<Self, ApiError> {
+    // A bearer token takes precedence, and an invalid one is never papered over by the session
+    if let Some(token) = bearer_token(request_head)? {
+      let principal = api_tokens.0.authenticate(token).await?;
+      return Ok(Self {
+        id: principal.user_id,
+        username: principal.username,
+        role: principal.role,
+        auth: AuthMethod::Token { id: principal.token_id, scopes: principal.scopes },
+        capabilities: capabilities.0.capabilities_for(principal.role).await?,
+      });
     }
+
+    let Some(id) = session.get::<Uuid>(USER_ID).await.unwrap_or(None) else {
+      return Err(ApiError::Unauthorized("Invalid session".into()));
+    };
+    // Sessions signed in before generations were recorded belong to the first one
+    let generation: i32 = session.get(USER_GENERATION).await.unwrap_or(None).unwrap_or(0);
+    let info = session_info(session).await;
+
+    // The session only names the account, whether it may still act and with which role is
+    // read from the database, so demotions and deactivations apply to open sessions too.
+    // Checking the session also records its activity for the session listing.
+    let account = sessions.0.check_session(id, generation, info.as_ref()).await?;
+
+    let capabilities = capabilities.0.capabilities_for(account.role).await?;
+
+    Ok(Self {
+      id,
+      username: account.username,
+      role: account.role,
+      auth: AuthMethod::Session,
+      capabilities,
+    })
   }
 }
 
+impl CurrentUser {
+  // Whether the request may act within `scope`
+  pub fn has_scope(&self, scope: TokenScope) -> bool {
+    match &self.auth {
+      AuthMethod::Session => true,
+      AuthMethod::Token { scopes, .. } => scopes.contains(&scope),
+    }
+  }
+
+  // Whether the role is granted `capability`, regardless of how the request was authenticated
+  pub fn has_capability(&self, capability: Capability) -> bool {
+    self.capabilities.contains(&capability)
+  }
+}
+
+// read the token of an `Authorization: Bearer <token>` header, if there is one
+fn bearer_token(request_head: &RequestHead) -> Result<Option<&str>, ApiError> {
+  let Some(value) = request_head.headers.get(AUTHORIZATION) else {
+    return Ok(None);
+  };
+
+  value
+    .to_str()
+    .ok()
+    .and_then(|value| value.split_once(' '))
+    .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+    .map(|(_, token)| Some(token.trim()))
+    .ok_or_else(|| ApiError::Unauthorized("Expected a Bearer token".into()))
+}
@@ -1,33 +1,66 @@
 // app/src/authorization/guards.rs
 
 // dependencies
-use crate::authorization::CurrentUser;
+use crate::authorization::{AuthMethod, CurrentUser};
 use crate::errors::ApiError;
-use crate::models::UserRole;
+use crate::models::{Capability, TokenScope};
+use uuid::Uuid;
 
-// guard funciton which takes the current user as input, checks their role
-pub fn require_admin(user: &CurrentUser) -> Result<(), ApiError> {
-    if matches!(user.role, UserRole::Admin) {
+// guard function which checks the role of the current user was granted `capability`,
+// API tokens additionally need the scope covering it
+pub fn require_capability(user: &CurrentUser, capability: Capability) -> Result<(), ApiError> {
+    if !user.has_capability(capability) {
+        return Err(ApiError::Forbidden(format!(
+            "Missing the `{}` capability",
+            capability.as_str()
+        )));
+    }
+    require_scope(user, capability.scope())
+}
+
+// guard function for content owned by `owner_id`, e.g. editing someone else's post needs
+// `edit_others_posts` where editing one's own only needs `edit_posts`
+pub fn require_capability_over(
+    user: &CurrentUser,
+    capability: Capability,
+    owner_id: Uuid,
+) -> Result<(), ApiError> {
+    if owner_id == user.id {
+        require_capability(user, capability)
+    } else {
+        require_capability(user, capability.over_others())
+    }
+}
+
+// guard function which checks that an API token was granted `scope`, sessions carry every scope
+pub fn require_scope(user: &CurrentUser, scope: TokenScope) -> Result<(), ApiError> {
+    if user.has_scope(scope) {
         Ok(())
     } else {
-        Err(ApiError::Forbidden("Admin access required".into()))
+        Err(ApiError::Forbidden(format!(
+            "API token lacks the `{}` scope",
+            scope.as_str()
+        )))
     }
 }
 
-pub fn require_roles(user: &CurrentUser, allowed: &[UserRole]) -> Result<(), ApiError> {
-    if allowed.contains(&user.role) {
+// guard function for account security settings, which API tokens can never change
+pub fn require_session(user: &CurrentUser) -> Result<(), ApiError> {
+    if user.auth == AuthMethod::Session {
         Ok(())
     } else {
-        Err(ApiError::Forbidden("Insufficient permissions".into()))
+        Err(ApiError::Forbidden(
+            "This action requires signing in, API tokens cannot perform it".into(),
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{require_admin, require_roles};
-    use crate::authorization::CurrentUser;
+    use super::{require_capability, require_capability_over, require_scope, require_session};
+    use crate::authorization::{AuthMethod, CurrentUser};
     use crate::errors::ApiError;
-    use crate::models::UserRole;
+    use crate::models::{Capability, TokenScope, UserRole};
     use uuid::Uuid;
 
     fn user_with(role: UserRole) -> CurrentUser {
@@ -35,17 +68,35 @@ mod tests {
             id: Uuid::new_v4(),
             username: "test".to_string(),
             role,
+            auth: AuthMethod::Session,
+            capabilities: Capability::ALL
+                .into_iter()
+                .filter(|capability| capability.default_roles().contains(&role))
+                .collect(),
+        }
+    }
+
+    fn token_user_with(role: UserRole, scopes: &[TokenScope]) -> CurrentUser {
+        CurrentUser {
+            auth: AuthMethod::Token {
+                id: Uuid::new_v4(),
+                scopes: scopes.to_vec(),
+            },
+            ..user_with(role)
         }
     }
 
     #[test]
-    fn require_admin_allows_admin() {
+    fn require_capability_allows_granted_capabilities() {
         let user = user_with(UserRole::Admin);
-        assert!(require_admin(&user).is_ok());
+        assert!(require_capability(&user, Capability::ManageUsers).is_ok());
+
+        let user = user_with(UserRole::Editor);
+        assert!(require_capability(&user, Capability::ModerateComments).is_ok());
     }
 
     #[test]
-    fn require_admin_rejects_non_admins() {
+    fn require_capability_rejects_missing_capabilities() {
         for role in [
             UserRole::Editor,
             UserRole::Author,
@@ -53,7 +104,7 @@ mod tests {
             UserRole::Subscriber,
         ] {
             let user = user_with(role);
-            let err = require_admin(&user).unwrap_err();
+            let err = require_capability(&user, Capability::ManageUsers).unwrap_err();
             match err {
                 ApiError::Forbidden(_) => {}
                 _ => panic!("expected Forbidden for role {:?}", role),
@@ -62,17 +113,48 @@ mod tests {
     }
 
     #[test]
-    fn require_roles_allows_allowed_role() {
-        let user = user_with(UserRole::Editor);
-        let allowed = [UserRole::Admin, UserRole::Editor];
-        assert!(require_roles(&user, &allowed).is_ok());
+    fn require_capability_follows_the_granted_set_not_the_role() {
+        let mut user = user_with(UserRole::Subscriber);
+        user.capabilities.push(Capability::ModerateComments);
+        assert!(require_capability(&user, Capability::ModerateComments).is_ok());
+
+        let mut user = user_with(UserRole::Admin);
+        user.capabilities.retain(|c| *c != Capability::UploadMedia);
+        assert!(require_capability(&user, Capability::UploadMedia).is_err());
+    }
+
+    #[test]
+    fn require_capability_over_distinguishes_own_content() {
+        let author = user_with(UserRole::Author);
+        assert!(require_capability_over(&author, Capability::EditPosts, author.id).is_ok());
+        let err =
+            require_capability_over(&author, Capability::EditPosts, Uuid::new_v4()).unwrap_err();
+        assert!(matches!(err, ApiError::Forbidden(_)));
+
+        let editor = user_with(UserRole::Editor);
+        assert!(require_capability_over(&editor, Capability::DeletePosts, author.id).is_ok());
     }
 
     #[test]
-    fn require_roles_rejects_disallowed_role() {
+    fn require_scope_allows_sessions_and_granted_scopes() {
         let user = user_with(UserRole::Author);
-        let allowed = [UserRole::Admin, UserRole::Editor];
-        let err = require_roles(&user, &allowed).unwrap_err();
-        matches!(err, ApiError::Forbidden(_));
+        assert!(require_scope(&user, TokenScope::PostsWrite).is_ok());
+
+        let user = token_user_with(UserRole::Author, &[TokenScope::PostsWrite]);
+        assert!(require_scope(&user, TokenScope::PostsWrite).is_ok());
+        let err = require_scope(&user, TokenScope::MediaWrite).unwrap_err();
+        assert!(matches!(err, ApiError::Forbidden(_)));
+    }
+
+    #[test]
+    fn tokens_need_the_scope_of_the_capability_and_never_pass_require_session() {
+        let user = token_user_with(UserRole::Admin, &[TokenScope::PostsWrite]);
+        assert!(require_capability(&user, Capability::ManageUsers).is_err());
+        assert!(require_capability(&user, Capability::EditPosts).is_ok());
+        assert!(require_session(&user).is_err());
+
+        let user = token_user_with(UserRole::Admin, &[TokenScope::Admin]);
+        assert!(require_capability(&user, Capability::ManageUsers).is_ok());
+        assert!(require_session(&user_with(UserRole::Subscriber)).is_ok());
     }
 }
@@ -1,14 +1,29 @@
 // app/src/authorization/mod.rs
 
 // modules
+pub mod csrf;
 pub mod current_user;
 pub mod guards;
+pub mod passkey_ceremony;
+pub mod pending_login;
+pub mod session_client;
 
 // re-exports
+pub use csrf::*;
 pub use current_user::*;
 pub use guards::*;
+pub use passkey_ceremony::*;
+pub use pending_login::*;
+pub use session_client::*;
 
 // constants used in sessions
 pub const USER_ID: &str = "user.id";
-pub const USER_ROLE: &str = "user.role";
-pub const USERNAME: &str = "user.username";
\ No newline at end of file
+pub const USERNAME: &str = "user.username";
+pub const USER_GENERATION: &str = "user.generation";
+pub const PENDING_LOGIN: &str = "login.pending";
+pub const OIDC_LOGIN: &str = "login.oidc";
+pub const PASSKEY_REGISTRATION: &str = "passkey.registration";
+pub const PASSKEY_AUTHENTICATION: &str = "passkey.authentication";
+pub const PASSKEY_LOGIN: &str = "passkey.login";
+pub const SESSION_INFO: &str = "session.info";
+pub const CSRF_TOKEN: &str = "csrf.token";
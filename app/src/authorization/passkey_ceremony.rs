@@ -0,0 +1,60 @@
+// app/src/authorization/passkey_ceremony.rs
+
+// dependencies
+use crate::authorization::{PASSKEY_AUTHENTICATION, PASSKEY_LOGIN, PASSKEY_REGISTRATION};
+use crate::models::PasskeyLoginState;
+use pavex::time::Timestamp;
+use pavex_session::Session;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+use webauthn_rs::prelude::{PasskeyAuthentication, PasskeyRegistration};
+
+// trait to tell which session key each kind of ceremony is kept under
+pub trait CeremonyState: Serialize + DeserializeOwned {
+    const SESSION_KEY: &'static str;
+}
+
+impl CeremonyState for PasskeyRegistration {
+    const SESSION_KEY: &'static str = PASSKEY_REGISTRATION;
+}
+
+impl CeremonyState for PasskeyAuthentication {
+    const SESSION_KEY: &'static str = PASSKEY_AUTHENTICATION;
+}
+
+impl CeremonyState for PasskeyLoginState {
+    const SESSION_KEY: &'static str = PASSKEY_LOGIN;
+}
+
+// struct type to represent a WebAuthn ceremony waiting on the browser to answer its challenge
+//
+// The state holds the challenge, so it never leaves the server and can only be answered once.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PasskeyCeremony<S> {
+    pub user_id: Uuid,
+    pub state: S,
+    pub started_at: Timestamp,
+}
+
+impl<S: CeremonyState> PasskeyCeremony<S> {
+    pub fn new(user_id: Uuid, state: S) -> Self {
+        Self {
+            user_id,
+            state,
+            started_at: Timestamp::now(),
+        }
+    }
+
+    /// Remember the ceremony in `session`, replacing one of the same kind started earlier.
+    pub async fn store(&self, session: &mut Session<'_>) {
+        session.insert(S::SESSION_KEY, self).await.unwrap();
+    }
+
+    /// Take the ceremony out of `session`, unless it has waited longer than `timeout`.
+    pub async fn take(session: &mut Session<'_>, timeout: Duration) -> Option<Self> {
+        let ceremony: Self = session.remove(S::SESSION_KEY).await.unwrap_or(None)?;
+        (Timestamp::now() < ceremony.started_at + timeout).then_some(ceremony)
+    }
+}
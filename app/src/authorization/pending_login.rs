@@ -0,0 +1,90 @@
+// app/src/authorization/pending_login.rs
+
+// dependencies
+use crate::authorization::{
+    PENDING_LOGIN, SESSION_INFO, SessionClient, USER_GENERATION, USER_ID, USERNAME,
+};
+use crate::errors::ApiError;
+use crate::models::{SessionInfo, UserSummary};
+use crate::routes::account::SessionServiceContainer;
+use pavex::time::Timestamp;
+use pavex_session::Session;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+
+// enum type to represent what a half-authenticated login still has to do
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingStep {
+    /// Enter a code from the enrolled authenticator, or a recovery code.
+    Verify,
+    /// Enroll an authenticator, because the role of the account requires one.
+    Setup,
+}
+
+// struct type to represent a login which passed the password check and awaits its second factor
+//
+// The session holds no `user.*` keys meanwhile, so `CurrentUser` rejects it everywhere else.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingLogin {
+    pub user_id: Uuid,
+    pub step: PendingStep,
+    pub started_at: Timestamp,
+}
+
+impl PendingLogin {
+    pub fn new(user_id: Uuid, step: PendingStep) -> Self {
+        Self {
+            user_id,
+            step,
+            started_at: Timestamp::now(),
+        }
+    }
+
+    /// The pending login held by `session`, unless it has waited longer than `timeout`.
+    pub async fn load(session: &Session<'_>, timeout: Duration) -> Option<Self> {
+        let pending: Self = session.get(PENDING_LOGIN).await.unwrap_or(None)?;
+        (Timestamp::now() < pending.started_at + timeout).then_some(pending)
+    }
+
+    /// Replace whatever `session` holds with this pending login.
+    pub async fn store(&self, session: &mut Session<'_>) {
+        session.clear().await.unwrap();
+        session.cycle_id();
+        session.insert(PENDING_LOGIN, self).await.unwrap();
+    }
+}
+
+/// Sign `user` in from `client`, dropping any pending login held by `session`.
+///
+/// The session is tied to the current session generation of the account, moving the account
+/// to a new generation signs it out.
+pub async fn sign_in(
+    session: &mut Session<'_>,
+    user: &UserSummary,
+    client: &SessionClient,
+    sessions: &SessionServiceContainer,
+) -> Result<(), ApiError> {
+    let generation = sessions.0.session_generation(user.id).await?;
+    session.clear().await.unwrap();
+    session.cycle_id();
+
+    session.insert(USER_ID, user.id).await.unwrap();
+    session
+        .insert(USERNAME, user.username.clone())
+        .await
+        .unwrap();
+    session.insert(USER_GENERATION, generation).await.unwrap();
+    session
+        .insert(SESSION_INFO, client.session_info())
+        .await
+        .unwrap();
+
+    Ok(())
+}
+
+/// The info recorded when `session` signed in, if it is a signed-in session.
+pub async fn session_info(session: &Session<'_>) -> Option<SessionInfo> {
+    session.get(SESSION_INFO).await.unwrap_or(None)
+}
@@ -0,0 +1,39 @@
+// app/src/authorization/session_client.rs
+
+// dependencies
+use crate::models::SessionInfo;
+use crate::rate_limit::ClientIp;
+use pavex::http::header::USER_AGENT;
+use pavex::methods;
+use pavex::request::RequestHead;
+
+// struct type to represent the device a request comes from, as recorded when it signs in
+#[derive(Clone, Debug)]
+pub struct SessionClient {
+    pub user_agent: Option<String>,
+    pub ip: ClientIp,
+}
+
+#[methods]
+impl SessionClient {
+    #[request_scoped]
+    pub fn new(request_head: &RequestHead, client_ip: &ClientIp) -> Self {
+        let user_agent = request_head
+            .headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        Self {
+            user_agent,
+            ip: *client_ip,
+        }
+    }
+}
+
+impl SessionClient {
+    /// The info of a session signed in from this device.
+    pub fn session_info(&self) -> SessionInfo {
+        SessionInfo::new(self.user_agent.as_deref(), self.ip.0)
+    }
+}
@@ -1,6 +1,7 @@
 // app/src/blueprint.rs
 
 // dependencies
+use crate::authorization::REJECT_CROSS_SITE_REQUESTS;
 use crate::telemetry;
 use pavex::{Blueprint, blueprint::from, cookie::INJECT_RESPONSE_COOKIES};
 use pavex_session::FINALIZE_SESSION;
@@ -28,6 +29,9 @@ pub fn blueprint() -> Blueprint {
 
     telemetry::instrument(&mut bp);
 
+    // Refuse state-changing requests which do not carry the CSRF token of their session
+    bp.pre_process(REJECT_CROSS_SITE_REQUESTS);
+
     bp.routes(from![crate]);
     bp
 }
@@ -1,13 +1,16 @@
 // app/src/configuration.rs
 
 // dependencies
+use crate::models::UserRole;
 use pavex::server::IncomingStream;
 use pavex::{config, methods, prebuilt};
 use secrecy::{ExposeSecret, SecretString};
 use serde_aux::field_attributes::deserialize_number_from_string;
 use sqlx::ConnectOptions;
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions, PgSslMode};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 // server configuration
 #[derive(serde::Deserialize, Debug, Clone)]
@@ -114,6 +117,819 @@ impl DatabaseConfig {
     }
 }
 
+// struct type to represent how the application is reached from outside
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "site", include_if_unused, default_if_missing)]
+pub struct SiteConfig {
+    /// The public address of the application, used to build the links it hands out, in emails
+    /// and as the address identity providers send the browser back to.
+    ///
+    /// Set the `PX_SITE__BASE_URL` environment variable to override its value.
+    #[serde(default = "SiteConfig::default_base_url")]
+    pub base_url: String,
+}
+
+impl SiteConfig {
+    fn default_base_url() -> String {
+        "http://localhost:8000".into()
+    }
+
+    /// Build an absolute link to a path of this application.
+    pub fn link(&self, path_and_query: &str) -> String {
+        format!("{}{}", self.base_url.trim_end_matches('/'), path_and_query)
+    }
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        Self {
+            base_url: Self::default_base_url(),
+        }
+    }
+}
+
+// struct type to represent the outbound email configuration
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "mailer", include_if_unused, default_if_missing)]
+pub struct MailerConfig {
+    /// The mailbox outgoing emails are sent from.
+    ///
+    /// E.g. `Rusty Word Smith <no-reply@example.com>`.
+    pub sender: String,
+    /// How emails are delivered.
+    pub transport: MailerTransport,
+}
+
+// enum type to represent the supported email transports
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum MailerTransport {
+    /// Relay emails through an SMTP server.
+    Smtp(SmtpConfig),
+    /// Write emails as `.eml` files into a local directory, for the dev and test profiles.
+    File { dir: PathBuf },
+}
+
+// struct type to represent the SMTP server configuration
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: String,
+    /// Set the `PX_MAILER__TRANSPORT__PASSWORD` environment variable to provide its value.
+    #[serde(default = "empty_secret")]
+    pub password: SecretString,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+}
+
+// enum type to represent how the connection to the SMTP server is secured
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Implicit TLS, usually on port 465.
+    Tls,
+    /// Upgrade a plain connection with STARTTLS, usually on port 587.
+    #[default]
+    Starttls,
+    /// No encryption at all, only suitable for local mail catchers.
+    None,
+}
+
+fn empty_secret() -> SecretString {
+    SecretString::from(String::new())
+}
+
+impl Default for MailerConfig {
+    fn default() -> Self {
+        Self {
+            sender: "Rusty Word Smith <no-reply@localhost>".into(),
+            transport: MailerTransport::File {
+                dir: PathBuf::from("outbox"),
+            },
+        }
+    }
+}
+
+// struct type to represent the rate limiting configuration
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "ratelimit", include_if_unused, default_if_missing)]
+pub struct RateLimitConfig {
+    /// Where the counters are kept.
+    ///
+    /// Counters fall back to process memory whenever Postgres is unavailable.
+    #[serde(default)]
+    pub backend: RateLimitBackend,
+    /// Whether the client address is read from the `X-Forwarded-For` header.
+    ///
+    /// Only enable this behind a reverse proxy which sets the header, otherwise
+    /// clients are free to pick the key they are rate limited by.
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
+    /// How many reverse proxies in front of the app append to `X-Forwarded-For`.
+    ///
+    /// The client address is the entry this many places from the right, entries further
+    /// left were sent by the client and could be anything.
+    #[serde(default = "RateLimitConfig::default_trusted_proxy_hops")]
+    pub trusted_proxy_hops: usize,
+    /// The policy applied to each rate limited route.
+    #[serde(default)]
+    pub policies: RateLimitPolicies,
+}
+
+impl RateLimitConfig {
+    fn default_trusted_proxy_hops() -> usize {
+        1
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            backend: RateLimitBackend::default(),
+            trust_forwarded_for: false,
+            trusted_proxy_hops: Self::default_trusted_proxy_hops(),
+            policies: RateLimitPolicies::default(),
+        }
+    }
+}
+
+// enum type to represent where rate limiting counters are kept
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitBackend {
+    /// Shared by every replica and kept across restarts.
+    #[default]
+    Postgres,
+    /// Local to the process, for development.
+    Memory,
+}
+
+// struct type to represent the policy of every rate limited route
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct RateLimitPolicies {
+    /// `POST /auth/login`, keyed by client address.
+    pub login: RateLimitPolicy,
+    /// `POST /auth/register`, keyed by client address.
+    pub register: RateLimitPolicy,
+    /// `POST /auth/resend-verification`, keyed by email address.
+    pub resend_verification: RateLimitPolicy,
+    /// `POST /auth/forgot-password`, keyed by email address.
+    pub password_reset: RateLimitPolicy,
+    /// `POST /auth/forgot-password`, keyed by client address.
+    pub password_reset_ip: RateLimitPolicy,
+    /// Second factor codes, keyed by account.
+    pub two_factor: RateLimitPolicy,
+    /// `POST /api/me/email`, keyed by account.
+    pub email_change: RateLimitPolicy,
+    /// `POST /auth/magic-link`, keyed by email address.
+    pub magic_link: RateLimitPolicy,
+    /// `POST /auth/magic-link`, keyed by client address.
+    pub magic_link_ip: RateLimitPolicy,
+}
+
+// enum type to represent how requests sharing a key are limited
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RateLimitPolicy {
+    /// At most `limit` requests for every `window`, e.g. `window: "1h"`.
+    FixedWindow {
+        limit: u32,
+        #[serde(with = "humantime_serde")]
+        window: Duration,
+    },
+    /// Bursts of up to `capacity` requests, then one more for every `refill_every`.
+    TokenBucket {
+        capacity: u32,
+        #[serde(with = "humantime_serde")]
+        refill_every: Duration,
+    },
+    /// No limit at all.
+    Unlimited,
+}
+
+impl Default for RateLimitPolicies {
+    fn default() -> Self {
+        Self {
+            login: RateLimitPolicy::TokenBucket {
+                capacity: 10,
+                refill_every: Duration::from_secs(30),
+            },
+            register: RateLimitPolicy::FixedWindow {
+                limit: 10,
+                window: Duration::from_secs(60 * 60),
+            },
+            resend_verification: RateLimitPolicy::FixedWindow {
+                limit: 1,
+                window: Duration::from_secs(60),
+            },
+            password_reset: RateLimitPolicy::FixedWindow {
+                limit: 1,
+                window: Duration::from_secs(60),
+            },
+            password_reset_ip: RateLimitPolicy::FixedWindow {
+                limit: 10,
+                window: Duration::from_secs(60 * 60),
+            },
+            two_factor: RateLimitPolicy::FixedWindow {
+                limit: 5,
+                window: Duration::from_secs(5 * 60),
+            },
+            email_change: RateLimitPolicy::FixedWindow {
+                limit: 3,
+                window: Duration::from_secs(60 * 60),
+            },
+            magic_link: RateLimitPolicy::FixedWindow {
+                limit: 1,
+                window: Duration::from_secs(60),
+            },
+            magic_link_ip: RateLimitPolicy::FixedWindow {
+                limit: 10,
+                window: Duration::from_secs(60 * 60),
+            },
+        }
+    }
+}
+
+// struct type to represent the brute-force protection of the login endpoint
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "lockout", include_if_unused, default_if_missing)]
+pub struct LockoutConfig {
+    /// Failed logins counted against the targeted account.
+    #[serde(default = "LockoutPolicy::account")]
+    pub account: LockoutPolicy,
+    /// Failed logins counted against the client address.
+    #[serde(default = "LockoutPolicy::ip")]
+    pub ip: LockoutPolicy,
+}
+
+// struct type to represent how failed logins are penalised
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+pub struct LockoutPolicy {
+    /// Failures tolerated before any delay is imposed.
+    pub free_attempts: u32,
+    /// Delay imposed by the first failure past `free_attempts`, doubled by every further one.
+    #[serde(with = "humantime_serde")]
+    pub base_delay: Duration,
+    /// Upper bound of the exponential back-off.
+    #[serde(with = "humantime_serde")]
+    pub max_delay: Duration,
+    /// Failures after which logins are locked out for `lockout_duration`.
+    pub lockout_after: u32,
+    #[serde(with = "humantime_serde")]
+    pub lockout_duration: Duration,
+    /// Failures are forgotten after this long without a new one.
+    #[serde(with = "humantime_serde")]
+    pub reset_after: Duration,
+}
+
+impl LockoutPolicy {
+    fn account() -> Self {
+        Self {
+            free_attempts: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5 * 60),
+            lockout_after: 10,
+            lockout_duration: Duration::from_secs(15 * 60),
+            reset_after: Duration::from_secs(60 * 60),
+        }
+    }
+
+    fn ip() -> Self {
+        Self {
+            free_attempts: 10,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5 * 60),
+            lockout_after: 50,
+            lockout_duration: Duration::from_secs(60 * 60),
+            reset_after: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl Default for LockoutConfig {
+    fn default() -> Self {
+        Self {
+            account: LockoutPolicy::account(),
+            ip: LockoutPolicy::ip(),
+        }
+    }
+}
+
+// struct type to represent the two-factor authentication configuration
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "twofactor", include_if_unused, default_if_missing)]
+pub struct TwoFactorConfig {
+    /// The name authenticator apps list the account under.
+    #[serde(default = "TwoFactorConfig::default_issuer")]
+    pub issuer: String,
+    /// Roles which must enroll an authenticator before they can sign in.
+    #[serde(default = "TwoFactorConfig::default_required_roles")]
+    pub required_roles: Vec<UserRole>,
+    /// How long a login may wait on its second factor before starting over.
+    #[serde(
+        default = "TwoFactorConfig::default_pending_timeout",
+        with = "humantime_serde"
+    )]
+    pub pending_timeout: Duration,
+    /// The key authenticator secrets are encrypted with before they are stored, which never
+    /// reaches the database.
+    ///
+    /// Set the `PX_TWOFACTOR__SEALING_KEY` environment variable to provide its value.
+    /// Authenticators enrolled before it is changed stop working.
+    #[serde(default)]
+    pub sealing_key: Option<SecretString>,
+}
+
+impl TwoFactorConfig {
+    fn default_issuer() -> String {
+        "Rusty Word Smith".into()
+    }
+
+    fn default_required_roles() -> Vec<UserRole> {
+        vec![UserRole::Admin, UserRole::Editor]
+    }
+
+    fn default_pending_timeout() -> Duration {
+        Duration::from_secs(5 * 60)
+    }
+}
+
+impl Default for TwoFactorConfig {
+    fn default() -> Self {
+        Self {
+            issuer: Self::default_issuer(),
+            required_roles: Self::default_required_roles(),
+            pending_timeout: Self::default_pending_timeout(),
+            sealing_key: None,
+        }
+    }
+}
+
+// struct type to represent how account deletions are carried out
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "accountdeletion", include_if_unused, default_if_missing)]
+pub struct AccountDeletionConfig {
+    /// How long a requested deletion can still be cancelled.
+    #[serde(
+        default = "AccountDeletionConfig::default_grace_period",
+        with = "humantime_serde"
+    )]
+    pub grace_period: Duration,
+    /// How often the server looks for deletions whose grace period is over.
+    #[serde(
+        default = "AccountDeletionConfig::default_sweep_every",
+        with = "humantime_serde"
+    )]
+    pub sweep_every: Duration,
+}
+
+impl AccountDeletionConfig {
+    fn default_grace_period() -> Duration {
+        Duration::from_secs(30 * 24 * 60 * 60)
+    }
+
+    fn default_sweep_every() -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+}
+
+impl Default for AccountDeletionConfig {
+    fn default() -> Self {
+        Self {
+            grace_period: Self::default_grace_period(),
+            sweep_every: Self::default_sweep_every(),
+        }
+    }
+}
+
+// struct type to represent how scheduled posts and pages are published
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "scheduler", include_if_unused, default_if_missing)]
+pub struct SchedulerConfig {
+    /// How often the server looks for scheduled content whose publication date has come.
+    #[serde(
+        default = "SchedulerConfig::default_publish_every",
+        deserialize_with = "SchedulerConfig::deserialize_publish_every"
+    )]
+    pub publish_every: Duration,
+}
+
+impl SchedulerConfig {
+    fn default_publish_every() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    // a zero period would make the scheduler's interval panic, it is refused when loading
+    fn deserialize_publish_every<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let period: Duration = humantime_serde::deserialize(deserializer)?;
+        if period.is_zero() {
+            Err(serde::de::Error::custom(
+                "scheduler.publish_every must be longer than zero",
+            ))
+        } else {
+            Ok(period)
+        }
+    }
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            publish_every: Self::default_publish_every(),
+        }
+    }
+}
+
+// struct type to represent how long published content is kept in memory
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "contentcache", include_if_unused, default_if_missing)]
+pub struct ContentCacheConfig {
+    /// How long a post listing is served from memory, unless the posts change before.
+    ///
+    /// Changes made through the API or by the scheduler drop listings right away on every
+    /// server instance, this bounds how long other changes, such as deleted accounts, take.
+    #[serde(
+        default = "ContentCacheConfig::default_listing_ttl",
+        with = "humantime_serde"
+    )]
+    pub listing_ttl: Duration,
+}
+
+impl ContentCacheConfig {
+    fn default_listing_ttl() -> Duration {
+        Duration::from_secs(5 * 60)
+    }
+}
+
+impl Default for ContentCacheConfig {
+    fn default() -> Self {
+        Self {
+            listing_ttl: Self::default_listing_ttl(),
+        }
+    }
+}
+
+// struct type to represent how passwords are hashed
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "passwordhashing", include_if_unused, default_if_missing)]
+pub struct PasswordHashingConfig {
+    /// The Argon2 variant new hashes are made with.
+    #[serde(default)]
+    pub algorithm: PasswordAlgorithm,
+    /// Memory cost, in KiB.
+    #[serde(
+        default = "PasswordHashingConfig::default_m_cost",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub m_cost: u32,
+    /// Number of passes over the memory.
+    #[serde(
+        default = "PasswordHashingConfig::default_t_cost",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub t_cost: u32,
+    /// Degree of parallelism.
+    #[serde(
+        default = "PasswordHashingConfig::default_p_cost",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub p_cost: u32,
+    /// A secret mixed into every hash, which never reaches the database.
+    ///
+    /// Set the `PX_PASSWORDHASHING__PEPPER` environment variable to provide its value.
+    /// Passwords hashed with a pepper stop matching once it is changed or removed.
+    #[serde(default)]
+    pub pepper: Option<SecretString>,
+}
+
+// enum type to represent the supported password hashing algorithms
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordAlgorithm {
+    #[default]
+    Argon2id,
+    Argon2i,
+    Argon2d,
+}
+
+impl PasswordHashingConfig {
+    // the defaults match `Argon2::default()`, which hashed every password so far
+    fn default_m_cost() -> u32 {
+        19 * 1024
+    }
+
+    fn default_t_cost() -> u32 {
+        2
+    }
+
+    fn default_p_cost() -> u32 {
+        1
+    }
+}
+
+impl Default for PasswordHashingConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: PasswordAlgorithm::default(),
+            m_cost: Self::default_m_cost(),
+            t_cost: Self::default_t_cost(),
+            p_cost: Self::default_p_cost(),
+            pepper: None,
+        }
+    }
+}
+
+// struct type to represent how one-time tokens sent by email are stored
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "tokenhashing", include_if_unused, default_if_missing)]
+pub struct TokenHashingConfig {
+    /// The key tokens are hashed with before they are stored, which never reaches the database.
+    /// Recovery codes are hashed with it like tokens.
+    ///
+    /// Set the `PX_TOKENHASHING__SECRET` environment variable to provide its value.
+    /// Links sent before it is changed stop working, and so do enrolled authenticators.
+    #[serde(default)]
+    pub secret: Option<SecretString>,
+    /// Whether the server refuses to start without a secret, only turned off for development.
+    #[serde(default = "TokenHashingConfig::default_require_secret")]
+    pub require_secret: bool,
+}
+
+impl TokenHashingConfig {
+    fn default_require_secret() -> bool {
+        true
+    }
+}
+
+impl Default for TokenHashingConfig {
+    fn default() -> Self {
+        Self {
+            secret: None,
+            require_secret: Self::default_require_secret(),
+        }
+    }
+}
+
+// struct type to represent who may create an account
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "registration", include_if_unused, default_if_missing)]
+pub struct RegistrationConfig {
+    /// Whether anyone, only invited people, or nobody can register.
+    #[serde(default)]
+    pub mode: RegistrationMode,
+    /// How long an invitation stays valid when the admin does not pick an expiry.
+    #[serde(
+        default = "RegistrationConfig::default_invitation_ttl",
+        with = "humantime_serde"
+    )]
+    pub invitation_ttl: Duration,
+}
+
+// enum type to represent the registration modes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrationMode {
+    #[default]
+    Open,
+    InviteOnly,
+    Closed,
+}
+
+impl RegistrationConfig {
+    fn default_invitation_ttl() -> Duration {
+        Duration::from_secs(7 * 24 * 60 * 60)
+    }
+}
+
+impl Default for RegistrationConfig {
+    fn default() -> Self {
+        Self {
+            mode: RegistrationMode::default(),
+            invitation_ttl: Self::default_invitation_ttl(),
+        }
+    }
+}
+
+// struct type to represent the OpenID Connect providers users can sign in with
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "oidc", include_if_unused, default_if_missing)]
+pub struct OidcConfig {
+    /// The providers offered on the login page, none unless configured.
+    #[serde(default)]
+    pub providers: Vec<OidcProviderConfig>,
+    /// How long a sign-in may take at the provider before it has to be started over.
+    #[serde(
+        default = "OidcConfig::default_login_timeout",
+        with = "humantime_serde"
+    )]
+    pub login_timeout: Duration,
+}
+
+// struct type to represent a single OpenID Connect provider
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct OidcProviderConfig {
+    /// Identifies the provider in the sign-in paths and in linked identities, e.g. `google`.
+    pub name: String,
+    /// The name shown on the login page, e.g. `Google`.
+    pub display_name: String,
+    /// The issuer identifier, its metadata is discovered from
+    /// `{issuer_url}/.well-known/openid-configuration`.
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Provide it via an environment variable, e.g. `PX_OIDC__PROVIDERS__0__CLIENT_SECRET`.
+    pub client_secret: SecretString,
+    /// The scopes requested, `openid` is always included.
+    #[serde(default = "OidcProviderConfig::default_scopes")]
+    pub scopes: Vec<String>,
+}
+
+impl OidcConfig {
+    fn default_login_timeout() -> Duration {
+        Duration::from_secs(10 * 60)
+    }
+
+    /// The provider configured under `name`.
+    pub fn provider(&self, name: &str) -> Option<&OidcProviderConfig> {
+        self.providers.iter().find(|provider| provider.name == name)
+    }
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            providers: Vec::new(),
+            login_timeout: Self::default_login_timeout(),
+        }
+    }
+}
+
+impl OidcProviderConfig {
+    fn default_scopes() -> Vec<String> {
+        vec!["openid".into(), "email".into(), "profile".into()]
+    }
+}
+
+// struct type to represent the relying party passkeys are registered with
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "webauthn", include_if_unused, default_if_missing)]
+pub struct WebauthnConfig {
+    /// The domain passkeys are bound to, e.g. `example.com`. It can never change without
+    /// invalidating every registered passkey.
+    #[serde(default = "WebauthnConfig::default_rp_id")]
+    pub rp_id: String,
+    /// The origin browsers run the ceremonies from, on the domain of `rp_id`.
+    #[serde(default = "WebauthnConfig::default_rp_origin")]
+    pub rp_origin: String,
+    /// The name authenticators show for this site.
+    #[serde(default = "WebauthnConfig::default_rp_name")]
+    pub rp_name: String,
+    /// How long a browser may take to answer a registration or sign-in challenge.
+    #[serde(
+        default = "WebauthnConfig::default_ceremony_timeout",
+        with = "humantime_serde"
+    )]
+    pub ceremony_timeout: Duration,
+}
+
+impl WebauthnConfig {
+    fn default_rp_id() -> String {
+        "localhost".into()
+    }
+
+    fn default_rp_origin() -> String {
+        "http://localhost:8000".into()
+    }
+
+    fn default_rp_name() -> String {
+        "Rusty Word Smith".into()
+    }
+
+    fn default_ceremony_timeout() -> Duration {
+        Duration::from_secs(5 * 60)
+    }
+}
+
+impl Default for WebauthnConfig {
+    fn default() -> Self {
+        Self {
+            rp_id: Self::default_rp_id(),
+            rp_origin: Self::default_rp_origin(),
+            rp_name: Self::default_rp_name(),
+            ceremony_timeout: Self::default_ceremony_timeout(),
+        }
+    }
+}
+
+// struct type to represent who may sign in with an emailed link instead of a password
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "magiclink", include_if_unused, default_if_missing)]
+pub struct MagicLinkConfig {
+    /// Roles which can ask for a sign-in link, a mailbox is no stronger than their password.
+    #[serde(default = "MagicLinkConfig::default_allowed_roles")]
+    pub allowed_roles: Vec<UserRole>,
+    /// How long a sign-in link stays valid.
+    #[serde(
+        default = "MagicLinkConfig::default_link_ttl",
+        with = "humantime_serde"
+    )]
+    pub link_ttl: Duration,
+}
+
+impl MagicLinkConfig {
+    fn default_allowed_roles() -> Vec<UserRole> {
+        vec![UserRole::Subscriber, UserRole::Contributor]
+    }
+
+    fn default_link_ttl() -> Duration {
+        Duration::from_secs(15 * 60)
+    }
+}
+
+impl Default for MagicLinkConfig {
+    fn default() -> Self {
+        Self {
+            allowed_roles: Self::default_allowed_roles(),
+            link_ttl: Self::default_link_ttl(),
+        }
+    }
+}
+
+// struct type to represent how long a signed-in session lasts and how often what it may do is re-read
+#[derive(Clone, Debug, serde::Deserialize)]
+#[config(key = "sessionlimits", include_if_unused, default_if_missing)]
+pub struct SessionLimitsConfig {
+    /// Sign a session out once it has not been used for this long.
+    #[serde(
+        default = "SessionLimitsConfig::default_idle_timeout",
+        with = "humantime_serde"
+    )]
+    pub idle_timeout: Duration,
+    /// Sign a session out this long after it signed in, however active it has been.
+    #[serde(
+        default = "SessionLimitsConfig::default_absolute_timeout",
+        with = "humantime_serde"
+    )]
+    pub absolute_timeout: Duration,
+    /// How long the role and status of an account are trusted before a request reads them again.
+    #[serde(
+        default = "SessionLimitsConfig::default_account_cache_ttl",
+        with = "humantime_serde"
+    )]
+    pub account_cache_ttl: Duration,
+    /// How long the capabilities granted to each role are trusted before they are read again.
+    #[serde(
+        default = "SessionLimitsConfig::default_capability_cache_ttl",
+        with = "humantime_serde"
+    )]
+    pub capability_cache_ttl: Duration,
+}
+
+impl SessionLimitsConfig {
+    fn default_idle_timeout() -> Duration {
+        Duration::from_secs(2 * 60 * 60)
+    }
+
+    fn default_absolute_timeout() -> Duration {
+        Duration::from_secs(7 * 24 * 60 * 60)
+    }
+
+    fn default_account_cache_ttl() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_capability_cache_ttl() -> Duration {
+        Duration::from_secs(30)
+    }
+}
+
+impl Default for SessionLimitsConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Self::default_idle_timeout(),
+            absolute_timeout: Self::default_absolute_timeout(),
+            account_cache_ttl: Self::default_account_cache_ttl(),
+            capability_cache_ttl: Self::default_capability_cache_ttl(),
+        }
+    }
+}
+
+// struct type to represent where state-changing browser requests may come from
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[config(key = "csrf", include_if_unused, default_if_missing)]
+pub struct CsrfConfig {
+    /// Origins trusted besides the host a request is addressed to, e.g. `https://example.com`
+    /// when a reverse proxy rewrites the `Host` header.
+    #[serde(default)]
+    pub trusted_origins: Vec<String>,
+}
+
 // register a prebuilt type for the template configuration
 #[config(key = "templateconfig", include_if_unused)]
 pub use pavex_tera_template::TemplateConfig;
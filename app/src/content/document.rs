@@ -0,0 +1,563 @@
+// app/src/content/document.rs
+
+// dependencies
+use super::error::ContentError;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Version of the documents written by this code, stored with each one
+pub const CONTENT_VERSION: u32 = 1;
+
+// limits which keep a single document from costing too much to store or render
+const MAX_BLOCKS: usize = 5000;
+const MAX_DEPTH: usize = 8;
+const MAX_LANGUAGE_LENGTH: usize = 32;
+const MAX_ALT_LENGTH: usize = 1000;
+const MAX_URL_LENGTH: usize = 2000;
+
+/// The content of a post, page or revision, as stored in their `content` column.
+///
+/// Documents are a tree of typed blocks rather than markup, so they can be checked when
+/// written, and rendered safely into HTML or plain text whatever their author typed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentDocument {
+    pub version: u32,
+    #[serde(default)]
+    pub blocks: Vec<Block>,
+}
+
+// enum type to represent the blocks a document is made of
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Block {
+    Paragraph {
+        children: Vec<Inline>,
+    },
+    Heading {
+        level: u8,
+        children: Vec<Inline>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        anchor: Option<String>,
+    },
+    List {
+        #[serde(default)]
+        ordered: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start: Option<u32>,
+        items: Vec<ListItem>,
+    },
+    Quote {
+        blocks: Vec<Block>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        citation: Vec<Inline>,
+    },
+    Code {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language: Option<String>,
+        code: String,
+    },
+    Image {
+        media_id: Uuid,
+        #[serde(default)]
+        alt: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        caption: Vec<Inline>,
+    },
+    Embed {
+        url: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        caption: Vec<Inline>,
+    },
+    Table {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        align: Vec<Alignment>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        header: Vec<TableCell>,
+        rows: Vec<Vec<TableCell>>,
+    },
+    Divider,
+    Footnote {
+        label: String,
+        blocks: Vec<Block>,
+    },
+}
+
+// struct type to represent an item of a list, which holds blocks of its own, nested lists too
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListItem {
+    pub blocks: Vec<Block>,
+    /// Set on the items of task lists, whether the task is done
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checked: Option<bool>,
+}
+
+// struct type to represent a table cell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableCell {
+    pub children: Vec<Inline>,
+}
+
+// enum type to represent the alignment of a table column
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Alignment {
+    #[default]
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+// enum type to represent the inline content of paragraphs, headings, captions and cells
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Inline {
+    Text {
+        text: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        marks: Vec<Mark>,
+    },
+    Link {
+        href: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        children: Vec<Inline>,
+    },
+    LineBreak,
+    FootnoteReference {
+        label: String,
+    },
+}
+
+// enum type to represent the formatting of a run of text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mark {
+    Bold,
+    Italic,
+    Underline,
+    Strikethrough,
+    Code,
+    Superscript,
+    Subscript,
+}
+
+// enum type to represent the formats content is written and read in over the API
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFormat {
+    #[default]
+    Document,
+    Markdown,
+}
+
+impl Default for ContentDocument {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl ContentDocument {
+    pub fn new(blocks: Vec<Block>) -> Self {
+        Self {
+            version: CONTENT_VERSION,
+            blocks,
+        }
+    }
+
+    /// Parse and validate a document sent by a client
+    pub fn from_value(value: serde_json::Value) -> Result<Self, ContentError> {
+        // Check the version first, documents of other versions may not parse at all
+        if let Some(version) = value.get("version").and_then(serde_json::Value::as_u64)
+            && version != u64::from(CONTENT_VERSION)
+        {
+            return Err(ContentError::UnsupportedVersion(
+                u32::try_from(version).unwrap_or(u32::MAX),
+            ));
+        }
+        let document: Self = serde_json::from_value(value)?;
+        document.validate()?;
+        Ok(document)
+    }
+
+    /// The document as JSON, as stored in the database
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("content documents always serialize")
+    }
+
+    /// Whether the document has no blocks at all
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Ids of the media the images of the document show, to look them up before rendering
+    pub fn media_ids(&self) -> Vec<Uuid> {
+        fn collect(blocks: &[Block], ids: &mut Vec<Uuid>) {
+            for block in blocks {
+                match block {
+                    Block::Image { media_id, .. } if !ids.contains(media_id) => ids.push(*media_id),
+                    Block::List { items, .. } => {
+                        for item in items {
+                            collect(&item.blocks, ids);
+                        }
+                    }
+                    Block::Quote { blocks, .. } | Block::Footnote { blocks, .. } => {
+                        collect(blocks, ids)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let mut ids = Vec::new();
+        collect(&self.blocks, &mut ids);
+        ids
+    }
+
+    /// Check the rules serde cannot: versions, heading levels, safe URLs, table shapes and size
+    pub fn validate(&self) -> Result<(), ContentError> {
+        if self.version != CONTENT_VERSION {
+            return Err(ContentError::UnsupportedVersion(self.version));
+        }
+        let mut checker = Checker { blocks: 0 };
+        checker.blocks(&self.blocks, "blocks", 0)
+    }
+}
+
+impl Block {
+    /// A paragraph holding plain text
+    pub fn paragraph(text: impl Into<String>) -> Self {
+        Block::Paragraph {
+            children: vec![Inline::text(text)],
+        }
+    }
+}
+
+impl Inline {
+    /// A run of text without formatting
+    pub fn text(text: impl Into<String>) -> Self {
+        Inline::Text {
+            text: text.into(),
+            marks: Vec::new(),
+        }
+    }
+}
+
+/// Whether a link target is safe to put in an `href`: web and mail links, or site-relative ones.
+///
+/// Anything with another scheme, `javascript:` and `data:` above all, is refused.
+pub fn is_safe_link(url: &str) -> bool {
+    if url.is_empty() || url.len() > MAX_URL_LENGTH || url.chars().any(char::is_control) {
+        return false;
+    }
+    match scheme_of(url) {
+        Some(scheme) => matches!(scheme.as_str(), "http" | "https" | "mailto"),
+        // browsers read `//host` and `/\host` as links to another host
+        None => !(url.starts_with("//") || url.starts_with("/\\") || url.starts_with('\\')),
+    }
+}
+
+/// Whether a URL may be embedded: absolute web URLs only
+pub fn is_safe_embed(url: &str) -> bool {
+    is_safe_link(url) && matches!(scheme_of(url).as_deref(), Some("http" | "https"))
+}
+
+// the lowercased scheme of an absolute URL, none for relative ones
+fn scheme_of(url: &str) -> Option<String> {
+    let end = url.find([':', '/', '?', '#'])?;
+    (url.as_bytes()[end] == b':').then(|| url[..end].to_ascii_lowercase())
+}
+
+// walks a document, counting blocks and keeping track of where it is for error messages
+struct Checker {
+    blocks: usize,
+}
+
+impl Checker {
+    fn blocks(&mut self, blocks: &[Block], path: &str, depth: usize) -> Result<(), ContentError> {
+        if depth > MAX_DEPTH {
+            return Err(invalid(
+                path,
+                format!("nested deeper than {MAX_DEPTH} levels"),
+            ));
+        }
+        for (i, block) in blocks.iter().enumerate() {
+            self.block(block, &format!("{path}[{i}]"), depth)?;
+        }
+        Ok(())
+    }
+
+    fn block(&mut self, block: &Block, path: &str, depth: usize) -> Result<(), ContentError> {
+        self.blocks += 1;
+        if self.blocks > MAX_BLOCKS {
+            return Err(invalid(path, format!("more than {MAX_BLOCKS} blocks")));
+        }
+        match block {
+            Block::Paragraph { children } => self.inlines(children, &format!("{path}.children")),
+            Block::Heading {
+                level,
+                children,
+                anchor,
+            } => {
+                if !(1..=6).contains(level) {
+                    return Err(invalid(path, "heading level must be 1-6"));
+                }
+                if let Some(anchor) = anchor
+                    && (anchor.is_empty()
+                        || anchor.len() > 255
+                        || !anchor
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                {
+                    return Err(invalid(
+                        path,
+                        "anchor can only contain letters, numbers, hyphens and underscores",
+                    ));
+                }
+                self.inlines(children, &format!("{path}.children"))
+            }
+            Block::List { items, .. } => {
+                if items.is_empty() {
+                    return Err(invalid(path, "lists need at least one item"));
+                }
+                for (i, item) in items.iter().enumerate() {
+                    self.blocks(
+                        &item.blocks,
+                        &format!("{path}.items[{i}].blocks"),
+                        depth + 1,
+                    )?;
+                }
+                Ok(())
+            }
+            Block::Quote { blocks, citation } => {
+                self.blocks(blocks, &format!("{path}.blocks"), depth + 1)?;
+                self.inlines(citation, &format!("{path}.citation"))
+            }
+            Block::Code { language, .. } => match language {
+                Some(language)
+                    if language.is_empty()
+                        || language.len() > MAX_LANGUAGE_LENGTH
+                        || !language
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || "+-_.#".contains(c)) =>
+                {
+                    Err(invalid(path, "invalid code language"))
+                }
+                _ => Ok(()),
+            },
+            Block::Image { alt, caption, .. } => {
+                if alt.len() > MAX_ALT_LENGTH {
+                    return Err(invalid(
+                        path,
+                        format!("alt text cannot exceed {MAX_ALT_LENGTH} characters"),
+                    ));
+                }
+                self.inlines(caption, &format!("{path}.caption"))
+            }
+            Block::Embed { url, caption } => {
+                if !is_safe_embed(url) {
+                    return Err(invalid(path, "embeds need an http or https URL"));
+                }
+                self.inlines(caption, &format!("{path}.caption"))
+            }
+            Block::Table {
+                align,
+                header,
+                rows,
+            } => self.table(align, header, rows, path),
+            Block::Divider => Ok(()),
+            Block::Footnote { label, blocks } => {
+                check_label(label, path)?;
+                self.blocks(blocks, &format!("{path}.blocks"), depth + 1)
+            }
+        }
+    }
+
+    fn table(
+        &mut self,
+        align: &[Alignment],
+        header: &[TableCell],
+        rows: &[Vec<TableCell>],
+        path: &str,
+    ) -> Result<(), ContentError> {
+        // Every row is as wide as the first one, header and alignments included
+        let Some(width) = (!header.is_empty())
+            .then_some(header.len())
+            .or_else(|| rows.first().map(Vec::len))
+        else {
+            return Err(invalid(path, "tables need a header or at least one row"));
+        };
+        if width == 0 {
+            return Err(invalid(path, "table rows need at least one cell"));
+        }
+        if !align.is_empty() && align.len() != width {
+            return Err(invalid(path, "table alignments must match its columns"));
+        }
+        for (i, cell) in header.iter().enumerate() {
+            self.inlines(&cell.children, &format!("{path}.header[{i}].children"))?;
+        }
+        for (r, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(invalid(
+                    &format!("{path}.rows[{r}]"),
+                    format!("table rows must have {width} cells"),
+                ));
+            }
+            for (i, cell) in row.iter().enumerate() {
+                self.inlines(&cell.children, &format!("{path}.rows[{r}][{i}].children"))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn inlines(&mut self, inlines: &[Inline], path: &str) -> Result<(), ContentError> {
+        for (i, inline) in inlines.iter().enumerate() {
+            if let Inline::FootnoteReference { label } = inline {
+                check_label(label, &format!("{path}[{i}]"))?;
+            }
+            if let Inline::Link { href, children, .. } = inline {
+                let path = format!("{path}[{i}]");
+                if !is_safe_link(href) {
+                    return Err(invalid(
+                        &path,
+                        "links need an http, https, mailto or relative URL",
+                    ));
+                }
+                if children
+                    .iter()
+                    .any(|child| matches!(child, Inline::Link { .. }))
+                {
+                    return Err(invalid(&path, "links cannot contain links"));
+                }
+                self.inlines(children, &format!("{path}.children"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// footnote labels tie references to their footnote, they cannot be blank
+fn check_label(label: &str, path: &str) -> Result<(), ContentError> {
+    if label.trim().is_empty() || label.len() > 255 {
+        return Err(invalid(path, "footnote labels must be 1-255 characters"));
+    }
+    Ok(())
+}
+
+// build the error for a rule broken at `path`
+fn invalid(path: &str, message: impl Into<String>) -> ContentError {
+    ContentError::Invalid {
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn documents_round_trip_through_json() {
+        let value = json!({
+            "version": 1,
+            "blocks": [
+                { "type": "heading", "level": 2, "children": [{ "type": "text", "text": "Hi" }] },
+                { "type": "paragraph", "children": [
+                    { "type": "text", "text": "bold", "marks": ["bold"] },
+                    { "type": "line_break" },
+                    { "type": "link", "href": "/about", "children": [{ "type": "text", "text": "us" }] }
+                ] },
+                { "type": "list", "ordered": true, "items": [
+                    { "blocks": [{ "type": "paragraph", "children": [] }], "checked": true }
+                ] },
+                { "type": "code", "language": "rust", "code": "fn main() {}" },
+                { "type": "image", "media_id": "6f9619ff-8b86-d011-b42d-00cf4fc964ff", "alt": "A cat" },
+                { "type": "table", "header": [{ "children": [] }], "rows": [[{ "children": [] }]] },
+                { "type": "divider" }
+            ]
+        });
+
+        let document = ContentDocument::from_value(value.clone()).unwrap();
+        assert_eq!(document.blocks.len(), 7);
+        assert_eq!(document.to_value(), value);
+    }
+
+    #[test]
+    fn malformed_and_future_documents_are_refused() {
+        assert!(matches!(
+            ContentDocument::from_value(json!({ "version": 2, "blocks": [{ "type": "poll" }] })),
+            Err(ContentError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            ContentDocument::from_value(json!({ "version": 1, "blocks": [{ "type": "marquee" }] })),
+            Err(ContentError::Malformed(_))
+        ));
+        assert!(matches!(
+            ContentDocument::from_value(json!({ "blocks": [] })),
+            Err(ContentError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn rules_are_checked_with_the_path_of_the_offending_block() {
+        let unsafe_link = json!({ "version": 1, "blocks": [
+            { "type": "paragraph", "children": [] },
+            { "type": "quote", "blocks": [{ "type": "paragraph", "children": [
+                { "type": "link", "href": "JavaScript:alert(1)", "children": [] }
+            ] }] }
+        ] });
+        match ContentDocument::from_value(unsafe_link) {
+            Err(ContentError::Invalid { path, .. }) => {
+                assert_eq!(path, "blocks[1].blocks[0].children[0]")
+            }
+            other => panic!("expected an invalid document, got {other:?}"),
+        }
+
+        let ragged_table = ContentDocument::new(vec![Block::Table {
+            align: Vec::new(),
+            header: vec![TableCell { children: vec![] }; 2],
+            rows: vec![vec![TableCell { children: vec![] }]],
+        }]);
+        assert!(ragged_table.validate().is_err());
+
+        let bad_heading = ContentDocument::new(vec![Block::Heading {
+            level: 7,
+            children: vec![],
+            anchor: None,
+        }]);
+        assert!(bad_heading.validate().is_err());
+
+        let script_embed = ContentDocument::new(vec![Block::Embed {
+            url: "data:text/html,<script>".into(),
+            caption: vec![],
+        }]);
+        assert!(script_embed.validate().is_err());
+    }
+
+    #[test]
+    fn deep_nesting_is_refused() {
+        let mut block = Block::paragraph("deep");
+        for _ in 0..=MAX_DEPTH {
+            block = Block::Quote {
+                blocks: vec![block],
+                citation: vec![],
+            };
+        }
+        assert!(ContentDocument::new(vec![block]).validate().is_err());
+    }
+
+    #[test]
+    fn only_web_mail_and_relative_links_are_safe() {
+        assert!(is_safe_link("https://example.com"));
+        assert!(is_safe_link("mailto:me@example.com"));
+        assert!(is_safe_link("/posts/hello?x=1#top"));
+        assert!(is_safe_link("#top"));
+        assert!(!is_safe_link("javascript:alert(1)"));
+        assert!(!is_safe_link(" javascript:alert(1)"));
+        assert!(!is_safe_link("java\tscript:alert(1)"));
+        assert!(!is_safe_link("//evil.example.com"));
+        assert!(!is_safe_link("/\\evil.example.com"));
+        assert!(!is_safe_link(""));
+        assert!(!is_safe_embed("/relative"));
+    }
+}
@@ -0,0 +1,17 @@
+// app/src/content/error.rs
+
+// dependencies
+use thiserror::Error;
+
+// enum type to represent why a content document was refused
+#[derive(Debug, Error)]
+pub enum ContentError {
+    #[error("Malformed content document: {0}")]
+    Malformed(#[from] serde_json::Error),
+
+    #[error("Unsupported content version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("Invalid content at {path}: {message}")]
+    Invalid { path: String, message: String },
+}
@@ -0,0 +1,455 @@
+// app/src/content/html.rs
+
+// dependencies
+use super::document::{
+    Alignment, Block, ContentDocument, Inline, ListItem, Mark, TableCell, is_safe_embed,
+    is_safe_link,
+};
+use std::collections::HashMap;
+use std::fmt::Write;
+use uuid::Uuid;
+
+// struct type to represent the file behind an image block, as looked up from the media table
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaSource {
+    pub url: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+}
+
+/// Renders documents into HTML for templates.
+///
+/// The output is safe to include unescaped: every piece of text is escaped, only the tags below
+/// are ever written, and link targets are checked again, in case a document skipped validation.
+#[derive(Debug, Clone, Default)]
+pub struct HtmlRenderer {
+    media: HashMap<Uuid, MediaSource>,
+}
+
+impl HtmlRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Files of the images to render, images whose media is not among them are left out
+    pub fn with_media(self, media: HashMap<Uuid, MediaSource>) -> Self {
+        Self { media }
+    }
+
+    pub fn render(&self, document: &ContentDocument) -> String {
+        let mut html = String::new();
+        self.blocks(&document.blocks, &mut html);
+        html
+    }
+
+    fn blocks(&self, nodes: &[Block], html: &mut String) {
+        for block in nodes {
+            self.block(block, html);
+        }
+    }
+
+    fn block(&self, block: &Block, html: &mut String) {
+        match block {
+            Block::Paragraph { children } => {
+                html.push_str("<p>");
+                inlines(children, html);
+                html.push_str("</p>\n");
+            }
+            Block::Heading {
+                level,
+                children,
+                anchor,
+            } => {
+                let level = (*level).clamp(1, 6);
+                let _ = write!(html, "<h{level}");
+                if let Some(anchor) = anchor {
+                    html.push_str(" id=\"");
+                    escape(anchor, html);
+                    html.push('"');
+                }
+                html.push('>');
+                inlines(children, html);
+                let _ = writeln!(html, "</h{level}>");
+            }
+            Block::List {
+                ordered,
+                start,
+                items,
+            } => {
+                let tag = if *ordered { "ol" } else { "ul" };
+                match start {
+                    Some(start) if *ordered && *start != 1 => {
+                        let _ = writeln!(html, "<ol start=\"{start}\">");
+                    }
+                    _ => {
+                        let _ = writeln!(html, "<{tag}>");
+                    }
+                }
+                for item in items {
+                    self.list_item(item, html);
+                }
+                let _ = writeln!(html, "</{tag}>");
+            }
+            Block::Quote { blocks, citation } => {
+                if citation.is_empty() {
+                    html.push_str("<blockquote>\n");
+                    self.blocks(blocks, html);
+                    html.push_str("</blockquote>\n");
+                } else {
+                    html.push_str("<figure class=\"quote\"><blockquote>\n");
+                    self.blocks(blocks, html);
+                    html.push_str("</blockquote><figcaption>");
+                    inlines(citation, html);
+                    html.push_str("</figcaption></figure>\n");
+                }
+            }
+            Block::Code { language, code } => {
+                html.push_str("<pre><code");
+                if let Some(language) = language {
+                    html.push_str(" class=\"language-");
+                    escape(language, html);
+                    html.push('"');
+                }
+                html.push('>');
+                escape(code, html);
+                html.push_str("</code></pre>\n");
+            }
+            Block::Image {
+                media_id,
+                alt,
+                caption,
+            } => {
+                let Some(media) = self.media.get(media_id) else {
+                    return;
+                };
+                if !is_safe_link(&media.url) {
+                    return;
+                }
+                html.push_str("<figure><img src=\"");
+                escape(&media.url, html);
+                html.push_str("\" alt=\"");
+                escape(alt, html);
+                html.push('"');
+                if let (Some(width), Some(height)) = (media.width, media.height) {
+                    let _ = write!(html, " width=\"{width}\" height=\"{height}\"");
+                }
+                html.push_str(" loading=\"lazy\">");
+                figcaption(caption, html);
+                html.push_str("</figure>\n");
+            }
+            Block::Embed { url, caption } => {
+                if !is_safe_embed(url) {
+                    return;
+                }
+                html.push_str("<figure class=\"embed\"><a href=\"");
+                escape(url, html);
+                html.push_str("\" rel=\"noopener noreferrer nofollow\">");
+                escape(url, html);
+                html.push_str("</a>");
+                figcaption(caption, html);
+                html.push_str("</figure>\n");
+            }
+            Block::Table {
+                align,
+                header,
+                rows,
+            } => {
+                html.push_str("<table>\n");
+                if !header.is_empty() {
+                    html.push_str("<thead>\n");
+                    table_row(header, align, "th", html);
+                    html.push_str("</thead>\n");
+                }
+                if !rows.is_empty() {
+                    html.push_str("<tbody>\n");
+                    for row in rows {
+                        table_row(row, align, "td", html);
+                    }
+                    html.push_str("</tbody>\n");
+                }
+                html.push_str("</table>\n");
+            }
+            Block::Divider => html.push_str("<hr>\n"),
+            Block::Footnote { label, blocks } => {
+                html.push_str("<div class=\"footnote\" id=\"fn-");
+                escape(&footnote_id(label), html);
+                html.push_str("\"><sup>");
+                escape(label, html);
+                html.push_str("</sup>\n");
+                self.blocks(blocks, html);
+                html.push_str("</div>\n");
+            }
+        }
+    }
+
+    fn list_item(&self, item: &ListItem, html: &mut String) {
+        match item.checked {
+            Some(checked) => {
+                html.push_str("<li class=\"task\"><input type=\"checkbox\" disabled");
+                if checked {
+                    html.push_str(" checked");
+                }
+                html.push('>');
+            }
+            None => html.push_str("<li>"),
+        }
+        // Items holding a single paragraph render without it, as tight lists do
+        match item.blocks.as_slice() {
+            [Block::Paragraph { children }] => inlines(children, html),
+            blocks => {
+                html.push('\n');
+                self.blocks(blocks, html);
+            }
+        }
+        html.push_str("</li>\n");
+    }
+}
+
+impl ContentDocument {
+    /// Render the document into HTML, without images as their media is not known here
+    pub fn to_html(&self) -> String {
+        HtmlRenderer::new().render(self)
+    }
+}
+
+// render inline content
+fn inlines(nodes: &[Inline], html: &mut String) {
+    for inline in nodes {
+        match inline {
+            Inline::Text { text, marks } => {
+                for mark in marks {
+                    let _ = write!(html, "<{}>", mark_tag(*mark));
+                }
+                escape(text, html);
+                for mark in marks.iter().rev() {
+                    let _ = write!(html, "</{}>", mark_tag(*mark));
+                }
+            }
+            Inline::Link {
+                href,
+                title,
+                children,
+            } => {
+                // Unsafe targets lose their link, their text stays
+                if !is_safe_link(href) {
+                    inlines(children, html);
+                    continue;
+                }
+                html.push_str("<a href=\"");
+                escape(href, html);
+                html.push('"');
+                if let Some(title) = title {
+                    html.push_str(" title=\"");
+                    escape(title, html);
+                    html.push('"');
+                }
+                html.push('>');
+                inlines(children, html);
+                html.push_str("</a>");
+            }
+            Inline::LineBreak => html.push_str("<br>"),
+            Inline::FootnoteReference { label } => {
+                let id = footnote_id(label);
+                html.push_str("<sup class=\"footnote-ref\"><a href=\"#fn-");
+                escape(&id, html);
+                html.push_str("\" id=\"fnref-");
+                escape(&id, html);
+                html.push_str("\">");
+                escape(label, html);
+                html.push_str("</a></sup>");
+            }
+        }
+    }
+}
+
+// render a caption, when there is one
+fn figcaption(caption: &[Inline], html: &mut String) {
+    if !caption.is_empty() {
+        html.push_str("<figcaption>");
+        inlines(caption, html);
+        html.push_str("</figcaption>");
+    }
+}
+
+// render a row of table cells, aligned as their column
+fn table_row(cells: &[TableCell], align: &[Alignment], tag: &str, html: &mut String) {
+    html.push_str("<tr>");
+    for (i, cell) in cells.iter().enumerate() {
+        match align.get(i).copied().unwrap_or_default() {
+            Alignment::None => {
+                let _ = write!(html, "<{tag}>");
+            }
+            Alignment::Left => {
+                let _ = write!(html, "<{tag} style=\"text-align: left\">");
+            }
+            Alignment::Center => {
+                let _ = write!(html, "<{tag} style=\"text-align: center\">");
+            }
+            Alignment::Right => {
+                let _ = write!(html, "<{tag} style=\"text-align: right\">");
+            }
+        }
+        inlines(&cell.children, html);
+        let _ = write!(html, "</{tag}>");
+    }
+    html.push_str("</tr>\n");
+}
+
+// the part of element ids naming a footnote, labels may hold characters ids should not
+fn footnote_id(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+// the element a mark renders as
+fn mark_tag(mark: Mark) -> &'static str {
+    match mark {
+        Mark::Bold => "strong",
+        Mark::Italic => "em",
+        Mark::Underline => "u",
+        Mark::Strikethrough => "s",
+        Mark::Code => "code",
+        Mark::Superscript => "sup",
+        Mark::Subscript => "sub",
+    }
+}
+
+// escape text for use in HTML content and quoted attribute values
+fn escape(text: &str, html: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            _ => html.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_is_escaped_and_marks_nest() {
+        let document = ContentDocument::new(vec![Block::Paragraph {
+            children: vec![
+                Inline::text("<script>alert(\"x\")</script> & "),
+                Inline::Text {
+                    text: "loud".into(),
+                    marks: vec![Mark::Bold, Mark::Italic],
+                },
+                Inline::LineBreak,
+                Inline::Link {
+                    href: "https://example.com/?a=1&b=\"2\"".into(),
+                    title: None,
+                    children: vec![Inline::text("link")],
+                },
+            ],
+        }]);
+
+        assert_eq!(
+            document.to_html(),
+            "<p>&lt;script&gt;alert(&quot;x&quot;)&lt;/script&gt; &amp; \
+             <strong><em>loud</em></strong><br>\
+             <a href=\"https://example.com/?a=1&amp;b=&quot;2&quot;\">link</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn unsafe_links_keep_only_their_text() {
+        let document = ContentDocument::new(vec![Block::Paragraph {
+            children: vec![Inline::Link {
+                href: "javascript:alert(1)".into(),
+                title: None,
+                children: vec![Inline::text("click")],
+            }],
+        }]);
+
+        assert_eq!(document.to_html(), "<p>click</p>\n");
+    }
+
+    #[test]
+    fn blocks_render_into_their_elements() {
+        let document = ContentDocument::new(vec![
+            Block::Heading {
+                level: 2,
+                children: vec![Inline::text("Title")],
+                anchor: Some("title".into()),
+            },
+            Block::List {
+                ordered: true,
+                start: Some(3),
+                items: vec![ListItem {
+                    blocks: vec![Block::paragraph("three")],
+                    checked: None,
+                }],
+            },
+            Block::List {
+                ordered: false,
+                start: None,
+                items: vec![ListItem {
+                    blocks: vec![Block::paragraph("done")],
+                    checked: Some(true),
+                }],
+            },
+            Block::Code {
+                language: Some("rust".into()),
+                code: "a < b".into(),
+            },
+            Block::Table {
+                align: vec![Alignment::None, Alignment::Right],
+                header: vec![
+                    TableCell {
+                        children: vec![Inline::text("a")],
+                    },
+                    TableCell {
+                        children: vec![Inline::text("b")],
+                    },
+                ],
+                rows: vec![],
+            },
+            Block::Divider,
+        ]);
+
+        assert_eq!(
+            document.to_html(),
+            "<h2 id=\"title\">Title</h2>\n\
+             <ol start=\"3\">\n<li>three</li>\n</ol>\n\
+             <ul>\n<li class=\"task\"><input type=\"checkbox\" disabled checked>done</li>\n</ul>\n\
+             <pre><code class=\"language-rust\">a &lt; b</code></pre>\n\
+             <table>\n<thead>\n<tr><th>a</th><th style=\"text-align: right\">b</th></tr>\n\
+             </thead>\n</table>\n\
+             <hr>\n"
+        );
+    }
+
+    #[test]
+    fn images_render_only_with_their_media() {
+        let media_id = Uuid::new_v4();
+        let document = ContentDocument::new(vec![Block::Image {
+            media_id,
+            alt: "A \"cat\"".into(),
+            caption: vec![Inline::text("Cat")],
+        }]);
+        assert_eq!(document.to_html(), "");
+
+        let media = HashMap::from([(
+            media_id,
+            MediaSource {
+                url: "/uploads/cat.jpg".into(),
+                width: Some(640),
+                height: Some(480),
+            },
+        )]);
+        assert_eq!(
+            HtmlRenderer::new().with_media(media).render(&document),
+            "<figure><img src=\"/uploads/cat.jpg\" alt=\"A &quot;cat&quot;\" width=\"640\" \
+             height=\"480\" loading=\"lazy\"><figcaption>Cat</figcaption></figure>\n"
+        );
+    }
+}
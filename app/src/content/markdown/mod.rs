@@ -0,0 +1,8 @@
+// app/src/content/markdown/mod.rs
+
+// modules
+mod parse;
+mod write;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,487 @@
+// app/src/content/markdown/parse.rs
+
+// dependencies
+use crate::content::document::{
+    Alignment, Block, ContentDocument, Inline, ListItem, Mark, TableCell,
+};
+use crate::content::error::ContentError;
+use pulldown_cmark::{CodeBlockKind, Event, LinkType, Options, Parser, Tag, TagEnd};
+use uuid::Uuid;
+
+// the scheme of image URLs pointing at uploaded media, as in `![alt](media:<id>)`
+pub(super) const MEDIA_SCHEME: &str = "media:";
+
+// CommonMark with the GitHub extensions authors expect, and `{#anchor}` after headings
+fn options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_HEADING_ATTRIBUTES
+}
+
+impl ContentDocument {
+    /// Parse Markdown (CommonMark with GitHub tables, task lists, footnotes and
+    /// strikethrough) into a checked document.
+    ///
+    /// Raw HTML is kept as text, and images must point at uploaded media with a
+    /// `media:<id>` URL, alone in their paragraph.
+    pub fn from_markdown(markdown: &str) -> Result<Self, ContentError> {
+        let mut builder = Builder::default();
+        for (event, range) in Parser::new_ext(markdown, options()).into_offset_iter() {
+            builder
+                .event(event)
+                .map_err(|message| ContentError::Invalid {
+                    path: format!("line {}", line_of(markdown, range.start)),
+                    message,
+                })?;
+        }
+        let document = ContentDocument::new(builder.finish());
+        document.validate()?;
+        Ok(document)
+    }
+}
+
+// the line, counted from one, where the byte at `offset` is
+fn line_of(source: &str, offset: usize) -> usize {
+    source.as_bytes()[..offset.min(source.len())]
+        .iter()
+        .filter(|&&byte| byte == b'\n')
+        .count()
+        + 1
+}
+
+// the parts of the document still being built, innermost last
+enum Frame {
+    Quote(Vec<Block>),
+    List {
+        ordered: bool,
+        start: Option<u32>,
+        items: Vec<ListItem>,
+    },
+    // items of tight lists hold their text directly, it becomes a paragraph
+    Item {
+        blocks: Vec<Block>,
+        checked: Option<bool>,
+        text: Vec<Inline>,
+    },
+    Footnote {
+        label: String,
+        blocks: Vec<Block>,
+    },
+    Paragraph {
+        children: Vec<Inline>,
+        image: Option<Block>,
+    },
+    Heading {
+        level: u8,
+        anchor: Option<String>,
+        children: Vec<Inline>,
+    },
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+    Table {
+        align: Vec<Alignment>,
+        header: Vec<TableCell>,
+        rows: Vec<Vec<TableCell>>,
+        in_header: bool,
+    },
+    Cell(Vec<Inline>),
+    Link {
+        href: String,
+        title: Option<String>,
+        children: Vec<Inline>,
+    },
+    Image {
+        url: String,
+        title: String,
+        alt: String,
+    },
+}
+
+// turns parser events into blocks, refusing what documents cannot hold
+#[derive(Default)]
+struct Builder {
+    blocks: Vec<Block>,
+    frames: Vec<Frame>,
+    marks: Vec<Mark>,
+}
+
+impl Builder {
+    fn finish(self) -> Vec<Block> {
+        self.blocks
+    }
+
+    fn event(&mut self, event: Event) -> Result<(), String> {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.text(&text),
+            Event::Code(code) if matches!(self.frames.last(), Some(Frame::Image { .. })) => {
+                self.text(&code)
+            }
+            Event::Code(code) => {
+                let mut marks = self.marks.clone();
+                marks.push(Mark::Code);
+                self.inline(text_run(&code, marks))
+            }
+            // raw HTML is never trusted, it stays text for the renderers to escape
+            Event::Html(html) | Event::InlineHtml(html) => self.text(&html.replace('\n', " ")),
+            Event::FootnoteReference(label) => self.inline(Inline::FootnoteReference {
+                label: label.to_string(),
+            }),
+            Event::SoftBreak => self.text(" "),
+            Event::HardBreak => self.inline(Inline::LineBreak),
+            Event::Rule => self.block(Block::Divider),
+            Event::TaskListMarker(checked) => {
+                if let Some(Frame::Item { checked: item, .. }) = self.frames.last_mut() {
+                    *item = Some(checked);
+                }
+                Ok(())
+            }
+            Event::InlineMath(_) | Event::DisplayMath(_) => Err("math is not supported".into()),
+        }
+    }
+
+    fn start(&mut self, tag: Tag) -> Result<(), String> {
+        let frame = match tag {
+            Tag::Paragraph | Tag::HtmlBlock => Frame::Paragraph {
+                children: Vec::new(),
+                image: None,
+            },
+            Tag::Heading { level, id, .. } => Frame::Heading {
+                level: level as u8,
+                anchor: id.map(|id| id.to_string()),
+                children: Vec::new(),
+            },
+            Tag::BlockQuote(_) => Frame::Quote(Vec::new()),
+            Tag::CodeBlock(kind) => Frame::Code {
+                language: match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().map(str::to_string)
+                    }
+                    CodeBlockKind::Indented => None,
+                },
+                code: String::new(),
+            },
+            Tag::List(start) => Frame::List {
+                ordered: start.is_some(),
+                start: start
+                    .filter(|&start| start != 1)
+                    .map(|start| u32::try_from(start).unwrap_or(u32::MAX)),
+                items: Vec::new(),
+            },
+            Tag::Item => Frame::Item {
+                blocks: Vec::new(),
+                checked: None,
+                text: Vec::new(),
+            },
+            Tag::FootnoteDefinition(label) => Frame::Footnote {
+                label: label.to_string(),
+                blocks: Vec::new(),
+            },
+            Tag::Table(align) => Frame::Table {
+                align: alignments(&align),
+                header: Vec::new(),
+                rows: Vec::new(),
+                in_header: false,
+            },
+            Tag::TableHead => {
+                if let Some(Frame::Table { in_header, .. }) = self.frames.last_mut() {
+                    *in_header = true;
+                }
+                return Ok(());
+            }
+            Tag::TableRow => {
+                if let Some(Frame::Table { rows, .. }) = self.frames.last_mut() {
+                    rows.push(Vec::new());
+                }
+                return Ok(());
+            }
+            Tag::TableCell => Frame::Cell(Vec::new()),
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough => {
+                self.marks.push(match tag {
+                    Tag::Emphasis => Mark::Italic,
+                    Tag::Strong => Mark::Bold,
+                    _ => Mark::Strikethrough,
+                });
+                return Ok(());
+            }
+            Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                ..
+            } => Frame::Link {
+                href: match link_type {
+                    LinkType::Email => format!("mailto:{dest_url}"),
+                    _ => dest_url.to_string(),
+                },
+                title: Some(title.to_string()).filter(|title| !title.is_empty()),
+                children: Vec::new(),
+            },
+            Tag::Image {
+                dest_url, title, ..
+            } => Frame::Image {
+                url: dest_url.to_string(),
+                title: title.to_string(),
+                alt: String::new(),
+            },
+            Tag::Superscript
+            | Tag::Subscript
+            | Tag::DefinitionList
+            | Tag::DefinitionListTitle
+            | Tag::DefinitionListDefinition
+            | Tag::MetadataBlock(_) => return Err("unsupported Markdown extension".into()),
+        };
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    fn end(&mut self, tag: TagEnd) -> Result<(), String> {
+        match tag {
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.marks.pop();
+                return Ok(());
+            }
+            TagEnd::TableHead => {
+                if let Some(Frame::Table { in_header, .. }) = self.frames.last_mut() {
+                    *in_header = false;
+                }
+                return Ok(());
+            }
+            TagEnd::TableRow => return Ok(()),
+            _ => {}
+        }
+        let Some(frame) = self.frames.pop() else {
+            return Ok(());
+        };
+        match frame {
+            Frame::Paragraph { children, image } => match image {
+                Some(image) => self.block(image),
+                // an HTML block ends with its newline, which is not part of the text
+                None => self.block(Block::Paragraph {
+                    children: trim_end(children),
+                }),
+            },
+            Frame::Heading {
+                level,
+                anchor,
+                children,
+            } => self.block(Block::Heading {
+                level,
+                children,
+                anchor,
+            }),
+            Frame::Quote(blocks) => self.block(Block::Quote {
+                blocks,
+                citation: Vec::new(),
+            }),
+            Frame::Code { language, mut code } => {
+                if code.ends_with('\n') {
+                    code.pop();
+                }
+                self.block(Block::Code { language, code })
+            }
+            Frame::List {
+                ordered,
+                start,
+                items,
+            } => self.block(Block::List {
+                ordered,
+                start,
+                items,
+            }),
+            Frame::Item {
+                mut blocks,
+                checked,
+                text,
+            } => {
+                flush_text(text, &mut blocks);
+                if let Some(Frame::List { items, .. }) = self.frames.last_mut() {
+                    items.push(ListItem { blocks, checked });
+                }
+                Ok(())
+            }
+            Frame::Footnote { label, blocks } => self.block(Block::Footnote { label, blocks }),
+            Frame::Table {
+                align,
+                header,
+                rows,
+                ..
+            } => self.block(Block::Table {
+                align,
+                header,
+                rows,
+            }),
+            Frame::Cell(children) => {
+                let cell = TableCell { children };
+                match self.frames.last_mut() {
+                    Some(Frame::Table {
+                        header,
+                        in_header: true,
+                        ..
+                    }) => header.push(cell),
+                    Some(Frame::Table { rows, .. }) => {
+                        if let Some(row) = rows.last_mut() {
+                            row.push(cell);
+                        }
+                    }
+                    _ => {}
+                }
+                Ok(())
+            }
+            Frame::Link {
+                href,
+                title,
+                children,
+            } => self.inline(Inline::Link {
+                href,
+                title,
+                children,
+            }),
+            Frame::Image { url, title, alt } => self.image(url, title, alt),
+        }
+    }
+
+    // text goes to the alt text of an image being read, or inline with the rest
+    fn text(&mut self, text: &str) -> Result<(), String> {
+        match self.frames.last_mut() {
+            Some(Frame::Image { alt, .. }) => {
+                alt.push_str(text);
+                Ok(())
+            }
+            Some(Frame::Code { code, .. }) => {
+                code.push_str(text);
+                Ok(())
+            }
+            _ => self.inline(text_run(text, self.marks.clone())),
+        }
+    }
+
+    fn inline(&mut self, inline: Inline) -> Result<(), String> {
+        let children = match self.frames.last_mut() {
+            Some(Frame::Paragraph { image: Some(_), .. }) => {
+                return match &inline {
+                    Inline::Text { text, .. } if text.trim().is_empty() => Ok(()),
+                    _ => Err(image_alone()),
+                };
+            }
+            Some(
+                Frame::Paragraph { children, .. }
+                | Frame::Heading { children, .. }
+                | Frame::Cell(children)
+                | Frame::Link { children, .. }
+                | Frame::Item { text: children, .. },
+            ) => children,
+            _ => return Ok(()),
+        };
+        // runs of text with the same marks are kept together
+        if let (
+            Some(Inline::Text { text, marks }),
+            Inline::Text {
+                text: more,
+                marks: more_marks,
+            },
+        ) = (children.last_mut(), &inline)
+            && marks == more_marks
+        {
+            text.push_str(more);
+            return Ok(());
+        }
+        children.push(inline);
+        Ok(())
+    }
+
+    fn block(&mut self, block: Block) -> Result<(), String> {
+        match self.frames.last_mut() {
+            None => self.blocks.push(block),
+            Some(Frame::Quote(blocks) | Frame::Footnote { blocks, .. }) => blocks.push(block),
+            Some(Frame::Item { blocks, text, .. }) => {
+                flush_text(std::mem::take(text), blocks);
+                blocks.push(block);
+            }
+            Some(_) => return Err("this block cannot be nested here".into()),
+        }
+        Ok(())
+    }
+
+    // images are blocks pointing at uploaded media, their paragraph becomes the image
+    fn image(&mut self, url: String, title: String, alt: String) -> Result<(), String> {
+        let media_id = url
+            .strip_prefix(MEDIA_SCHEME)
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(|| format!("images must point at uploaded media as {MEDIA_SCHEME}<id>"))?;
+        let Some(Frame::Paragraph { children, image }) = self.frames.last_mut() else {
+            return Err(image_alone());
+        };
+        let blank = children
+            .iter()
+            .all(|inline| matches!(inline, Inline::Text { text, .. } if text.trim().is_empty()));
+        if image.is_some() || !blank {
+            return Err(image_alone());
+        }
+        *image = Some(Block::Image {
+            media_id,
+            alt,
+            caption: if title.is_empty() {
+                Vec::new()
+            } else {
+                vec![Inline::text(title)]
+            },
+        });
+        Ok(())
+    }
+}
+
+fn image_alone() -> String {
+    "images must stand alone in their paragraph".into()
+}
+
+// a run of text, with its marks in a stable order
+fn text_run(text: &str, mut marks: Vec<Mark>) -> Inline {
+    marks.sort();
+    marks.dedup();
+    Inline::Text {
+        text: text.to_string(),
+        marks,
+    }
+}
+
+// the text held directly by a list item becomes its paragraph
+fn flush_text(text: Vec<Inline>, blocks: &mut Vec<Block>) {
+    if !text.is_empty() {
+        blocks.push(Block::Paragraph { children: text });
+    }
+}
+
+// trailing whitespace of the last run of text, dropped when a paragraph ends
+fn trim_end(mut children: Vec<Inline>) -> Vec<Inline> {
+    if let Some(Inline::Text { text, .. }) = children.last_mut() {
+        text.truncate(text.trim_end().len());
+        if text.is_empty() {
+            children.pop();
+        }
+    }
+    children
+}
+
+// table column alignments, left out altogether when no column is aligned
+fn alignments(align: &[pulldown_cmark::Alignment]) -> Vec<Alignment> {
+    let align: Vec<Alignment> = align
+        .iter()
+        .map(|align| match align {
+            pulldown_cmark::Alignment::None => Alignment::None,
+            pulldown_cmark::Alignment::Left => Alignment::Left,
+            pulldown_cmark::Alignment::Center => Alignment::Center,
+            pulldown_cmark::Alignment::Right => Alignment::Right,
+        })
+        .collect();
+    if align.iter().all(|align| *align == Alignment::None) {
+        Vec::new()
+    } else {
+        align
+    }
+}
@@ -0,0 +1,106 @@
+// app/src/content/markdown/tests.rs
+
+// Golden files: each `<case>.md` in `golden/` parses into `<case>.json`, which writes
+// back out as `<case>.out.md`. Run with `UPDATE_GOLDEN=1` to rewrite the expected files
+// after a deliberate change, then review the diff.
+
+// dependencies
+use crate::content::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/content/markdown/golden")
+}
+
+fn cases() -> Vec<PathBuf> {
+    let mut cases: Vec<PathBuf> = fs::read_dir(golden_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.ends_with(".md") && !name.ends_with(".out.md")
+        })
+        .collect();
+    cases.sort();
+    assert!(!cases.is_empty(), "no golden files found");
+    cases
+}
+
+// compare with the expected file, or rewrite it when updating the golden files
+fn check(path: &Path, actual: &str) {
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(path)
+        .unwrap_or_else(|_| panic!("missing {}, run with UPDATE_GOLDEN=1", path.display()));
+    assert_eq!(actual, expected, "{} is out of date", path.display());
+}
+
+#[test]
+fn golden_files_parse_and_write_back() {
+    for case in cases() {
+        let markdown = fs::read_to_string(&case).unwrap();
+        let document = ContentDocument::from_markdown(&markdown)
+            .unwrap_or_else(|e| panic!("{}: {e}", case.display()));
+
+        let json = serde_json::to_string_pretty(&document).unwrap() + "\n";
+        check(&case.with_extension("json"), &json);
+
+        let written = document.to_markdown();
+        check(&case.with_extension("out.md"), &written);
+
+        // what is written parses back into the same document, and writes the same again
+        let reparsed = ContentDocument::from_markdown(&written)
+            .unwrap_or_else(|e| panic!("{}: written Markdown fails: {e}", case.display()));
+        assert_eq!(reparsed, document, "{} does not round-trip", case.display());
+        assert_eq!(reparsed.to_markdown(), written);
+    }
+}
+
+#[test]
+fn images_must_be_uploaded_media_on_their_own() {
+    let error = ContentDocument::from_markdown("Intro\n\n![alt](https://example.com/a.png)")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("line 3"), "{error}");
+    assert!(error.contains("media:<id>"), "{error}");
+
+    let inline = "Look ![alt](media:0195f2a4-7c1e-7000-8000-000000000001) here";
+    assert!(ContentDocument::from_markdown(inline).is_err());
+}
+
+#[test]
+fn unsafe_links_are_refused() {
+    let error = ContentDocument::from_markdown("[click](javascript:alert(1))").unwrap_err();
+    assert!(matches!(error, ContentError::Invalid { .. }), "{error}");
+}
+
+#[test]
+fn raw_html_is_kept_as_text() {
+    let document = ContentDocument::from_markdown("Hi <b>there</b>").unwrap();
+    assert_eq!(document.blocks, vec![Block::paragraph("Hi <b>there</b>")]);
+    assert_eq!(document.to_html(), "<p>Hi &lt;b&gt;there&lt;/b&gt;</p>\n");
+}
+
+#[test]
+fn what_markdown_cannot_express_is_dropped_on_export() {
+    let document = ContentDocument::new(vec![Block::Paragraph {
+        children: vec![
+            Inline::Text {
+                text: "H".into(),
+                marks: vec![],
+            },
+            Inline::Text {
+                text: "2".into(),
+                marks: vec![Mark::Subscript],
+            },
+            Inline::Text {
+                text: "O".into(),
+                marks: vec![Mark::Underline, Mark::Bold],
+            },
+        ],
+    }]);
+    assert_eq!(document.to_markdown(), "H2**O**\n");
+}
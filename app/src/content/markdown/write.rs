@@ -0,0 +1,496 @@
+// app/src/content/markdown/write.rs
+
+// dependencies
+use super::parse::MEDIA_SCHEME;
+use crate::content::document::{
+    Alignment, Block, ContentDocument, Inline, ListItem, Mark, TableCell,
+};
+
+impl ContentDocument {
+    /// Write the document as Markdown, which parses back into the same document.
+    ///
+    /// Underline, superscript and subscript have no Markdown syntax and are left out,
+    /// quote citations and embed captions become paragraphs of their own.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = blocks(&self.blocks, "\n\n");
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        markdown
+    }
+}
+
+// where inline content is written, which decides what it may hold
+#[derive(Clone, Copy, PartialEq)]
+enum Context {
+    Flow,
+    Heading,
+    Cell,
+}
+
+// write blocks one after the other, dropping those which came out empty
+fn blocks(nodes: &[Block], separator: &str) -> String {
+    let mut written = Vec::new();
+    let mut previous_list = None;
+    let mut alternate = false;
+    for node in nodes {
+        // two lists in a row would merge, unless the second uses other markers
+        let list = match node {
+            Block::List { ordered, .. } => Some(*ordered),
+            _ => None,
+        };
+        alternate = list.is_some() && list == previous_list && !alternate;
+        previous_list = list;
+
+        let markdown = block(node, alternate);
+        if !markdown.is_empty() {
+            written.push(markdown);
+        }
+    }
+    written.join(separator)
+}
+
+fn block(node: &Block, alternate: bool) -> String {
+    match node {
+        Block::Paragraph { children } => escape_line_starts(&inlines(children, Context::Flow)),
+        Block::Heading {
+            level,
+            children,
+            anchor,
+        } => {
+            let mut heading = "#".repeat(usize::from(*level));
+            let text = inlines(children, Context::Heading);
+            if !text.is_empty() {
+                heading.push(' ');
+                heading.push_str(&text);
+            }
+            if let Some(anchor) = anchor {
+                heading.push_str(&format!(" {{#{anchor}}}"));
+            }
+            heading
+        }
+        Block::List {
+            ordered,
+            start,
+            items,
+        } => list(*ordered, start.unwrap_or(1), items, alternate),
+        Block::Quote {
+            blocks: quoted,
+            citation,
+        } => {
+            let mut inner = blocks(quoted, "\n\n");
+            if !citation.is_empty() {
+                if !inner.is_empty() {
+                    inner.push_str("\n\n");
+                }
+                inner.push_str("— ");
+                inner.push_str(&inlines(citation, Context::Flow));
+            }
+            prefix_lines(&inner, ">")
+        }
+        Block::Code { language, code } => {
+            let fence = "`".repeat(longest_run(code, '`').max(2) + 1);
+            let language = language.as_deref().unwrap_or_default();
+            if code.is_empty() {
+                format!("{fence}{language}\n{fence}")
+            } else {
+                format!("{fence}{language}\n{code}\n{fence}")
+            }
+        }
+        Block::Image {
+            media_id,
+            alt,
+            caption,
+        } => {
+            let alt = escape(alt, Context::Flow);
+            let caption = plain(caption);
+            if caption.is_empty() {
+                format!("![{alt}]({MEDIA_SCHEME}{media_id})")
+            } else {
+                format!("![{alt}]({MEDIA_SCHEME}{media_id} {})", title(&caption))
+            }
+        }
+        Block::Embed { url, caption } => {
+            let mut embed = format!("<{url}>");
+            if !caption.is_empty() {
+                embed.push_str("\n\n");
+                embed.push_str(&escape_line_starts(&inlines(caption, Context::Flow)));
+            }
+            embed
+        }
+        Block::Table {
+            align,
+            header,
+            rows,
+        } => table(align, header, rows),
+        Block::Divider => "---".to_string(),
+        Block::Footnote {
+            label,
+            blocks: note,
+        } => {
+            let marker = format!("[^{label}]: ");
+            let body = indent(&blocks(note, "\n\n"), 4);
+            format!("{marker}{body}").trim_end().to_string()
+        }
+    }
+}
+
+// items of tight lists are written on consecutive lines, without blank lines in between
+fn list(ordered: bool, start: u32, items: &[ListItem], alternate: bool) -> String {
+    let tight = items.iter().all(|item| match item.blocks.split_first() {
+        None => true,
+        Some((first, rest)) => {
+            let nested = |block: &Block| matches!(block, Block::List { .. });
+            (matches!(first, Block::Paragraph { .. }) || nested(first)) && rest.iter().all(nested)
+        }
+    });
+    let separator = if tight { "\n" } else { "\n\n" };
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let marker = match (ordered, alternate) {
+                (true, false) => format!("{}. ", u64::from(start) + i as u64),
+                (true, true) => format!("{}) ", u64::from(start) + i as u64),
+                (false, false) => "- ".to_string(),
+                (false, true) => "* ".to_string(),
+            };
+            let task = match item.checked {
+                Some(true) => "[x] ",
+                Some(false) => "[ ] ",
+                None => "",
+            };
+            let body = blocks(&item.blocks, separator);
+            format!("{marker}{task}{}", indent(&body, marker.len()))
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn table(align: &[Alignment], header: &[TableCell], rows: &[Vec<TableCell>]) -> String {
+    let columns = rows
+        .iter()
+        .map(Vec::len)
+        .chain([header.len()])
+        .max()
+        .unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+    let row = |cells: &[TableCell]| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| {
+                cells
+                    .get(i)
+                    .map(|cell| inlines(&cell.children, Context::Cell))
+                    .unwrap_or_default()
+            })
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let delimiters: Vec<&str> = (0..columns)
+        .map(|i| match align.get(i).copied().unwrap_or_default() {
+            Alignment::None => "---",
+            Alignment::Left => ":--",
+            Alignment::Center => ":-:",
+            Alignment::Right => "--:",
+        })
+        .collect();
+
+    let mut lines = vec![row(header), format!("| {} |", delimiters.join(" | "))];
+    lines.extend(rows.iter().map(|cells| row(cells)));
+    lines.join("\n")
+}
+
+// the text of inline content, for image captions which Markdown keeps as plain titles
+fn plain(nodes: &[Inline]) -> String {
+    nodes
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text { text, .. } => text.clone(),
+            Inline::Link { children, .. } => plain(children),
+            Inline::LineBreak => " ".to_string(),
+            Inline::FootnoteReference { .. } => String::new(),
+        })
+        .collect()
+}
+
+fn inlines(nodes: &[Inline], context: Context) -> String {
+    let mut writer = InlineWriter {
+        markdown: String::new(),
+        open: Vec::new(),
+        context,
+    };
+    writer.write(nodes);
+    writer.switch(&[]);
+    writer.markdown
+}
+
+// writes inline content, opening and closing emphasis as the marks of the runs change
+struct InlineWriter {
+    markdown: String,
+    open: Vec<Mark>,
+    context: Context,
+}
+
+impl InlineWriter {
+    fn write(&mut self, nodes: &[Inline]) {
+        for inline in nodes {
+            match inline {
+                Inline::Text { text, marks } => self.text(text, marks),
+                Inline::Link {
+                    href,
+                    title: link_title,
+                    children,
+                } => {
+                    self.switch(&[]);
+                    if link_title.is_none() && is_autolink(href, children) {
+                        self.markdown.push_str(&format!("<{}>", plain(children)));
+                        continue;
+                    }
+                    let text = inlines(children, self.context);
+                    self.markdown
+                        .push_str(&format!("[{text}]({}", destination(href)));
+                    if let Some(link_title) = link_title {
+                        self.markdown.push(' ');
+                        self.markdown.push_str(&title(link_title));
+                    }
+                    self.markdown.push(')');
+                }
+                Inline::LineBreak => {
+                    self.switch(&[]);
+                    match self.context {
+                        Context::Flow => self.markdown.push_str("\\\n"),
+                        Context::Heading | Context::Cell => self.markdown.push(' '),
+                    }
+                }
+                Inline::FootnoteReference { label } => {
+                    self.switch(&[]);
+                    self.markdown.push_str(&format!("[^{label}]"));
+                }
+            }
+        }
+    }
+
+    fn text(&mut self, text: &str, marks: &[Mark]) {
+        let mut marks: Vec<Mark> = marks
+            .iter()
+            .copied()
+            .filter(|mark| delimiter(*mark).is_some() || *mark == Mark::Code)
+            .collect();
+        marks.sort();
+        marks.dedup();
+        let code = marks.contains(&Mark::Code);
+        marks.retain(|mark| *mark != Mark::Code);
+
+        if code {
+            self.switch(&marks);
+            self.markdown.push_str(&code_span(text, self.context));
+            return;
+        }
+        // delimiters only open before text, the whitespace leading it goes first
+        let body = text.trim_start();
+        let lead = &text[..text.len() - body.len()];
+        if !lead.is_empty() && marks.iter().any(|mark| !self.open.contains(mark)) {
+            self.markdown.push_str(&escape(lead, self.context));
+            if body.is_empty() {
+                return;
+            }
+            self.switch(&marks);
+            self.markdown.push_str(&escape(body, self.context));
+        } else if !text.is_empty() {
+            self.switch(&marks);
+            self.markdown.push_str(&escape(text, self.context));
+        }
+    }
+
+    // close the marks which no longer apply, then open the new ones
+    fn switch(&mut self, marks: &[Mark]) {
+        let keep = self
+            .open
+            .iter()
+            .take_while(|mark| marks.contains(mark))
+            .count();
+        for mark in self.open.split_off(keep).into_iter().rev() {
+            // closing delimiters must follow text, trailing whitespace goes after them
+            let trailing = self.markdown.len() - self.markdown.trim_end().len();
+            let whitespace = self.markdown.split_off(self.markdown.len() - trailing);
+            self.markdown.push_str(delimiter(mark).unwrap_or_default());
+            self.markdown.push_str(&whitespace);
+        }
+        for mark in marks {
+            if !self.open.contains(mark) {
+                self.markdown.push_str(delimiter(*mark).unwrap_or_default());
+                self.open.push(*mark);
+            }
+        }
+    }
+}
+
+// the delimiter of a mark, None for the marks Markdown cannot express
+fn delimiter(mark: Mark) -> Option<&'static str> {
+    match mark {
+        Mark::Bold => Some("**"),
+        Mark::Italic => Some("*"),
+        Mark::Strikethrough => Some("~~"),
+        Mark::Code | Mark::Underline | Mark::Superscript | Mark::Subscript => None,
+    }
+}
+
+// links whose text is their address are written as `<address>`
+fn is_autolink(href: &str, children: &[Inline]) -> bool {
+    let [Inline::Text { text, marks }] = children else {
+        return false;
+    };
+    if !marks.is_empty()
+        || text.is_empty()
+        || text.contains(|c: char| c.is_whitespace() || c == '<' || c == '>')
+    {
+        return false;
+    }
+    let url = text == href && (href.starts_with("http://") || href.starts_with("https://"));
+    let email = href.strip_prefix("mailto:") == Some(text.as_str()) && text.contains('@');
+    url || email
+}
+
+// a link destination, in angle brackets when it would not otherwise parse whole
+fn destination(href: &str) -> String {
+    let href = escape_entities(&href.replace('\\', "\\\\"));
+    if href.is_empty() || href.contains([' ', '(', ')', '<', '>']) {
+        format!("<{}>", href.replace('<', "\\<").replace('>', "\\>"))
+    } else {
+        href
+    }
+}
+
+// a link or image title, in double quotes
+fn title(text: &str) -> String {
+    let text = escape_entities(&text.replace('\\', "\\\\").replace('"', "\\\""));
+    format!("\"{text}\"")
+}
+
+fn code_span(text: &str, context: Context) -> String {
+    let mut code = text.replace('\n', " ");
+    if context == Context::Cell {
+        code = code.replace('|', "\\|");
+    }
+    let fence = "`".repeat(longest_run(&code, '`') + 1);
+    // a space on both sides is stripped by the parser, so it is added when the code needs it
+    let padded = code.starts_with('`')
+        || code.ends_with('`')
+        || (code.starts_with(' ') && code.ends_with(' ') && !code.trim().is_empty());
+    if padded {
+        format!("{fence} {code} {fence}")
+    } else {
+        format!("{fence}{code}{fence}")
+    }
+}
+
+// escape what Markdown would read as syntax in text
+fn escape(text: &str, context: Context) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut escaped = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let word = |j: Option<usize>| {
+            j.and_then(|j| chars.get(j))
+                .is_some_and(|c| c.is_alphanumeric())
+        };
+        let syntax = match c {
+            '\\' | '`' | '*' | '[' | ']' | '<' | '~' => true,
+            // underscores inside words never start emphasis
+            '_' => !(word(i.checked_sub(1)) && word(Some(i + 1))),
+            '&' => is_entity(&chars[i..]),
+            '|' => context == Context::Cell,
+            '#' | '{' => context == Context::Heading,
+            _ => false,
+        };
+        if syntax {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// escape the ampersands which would start a character reference
+fn escape_entities(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut escaped = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '&' && is_entity(&chars[i..]) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// whether the text starts with something like `&amp;` or `&#38;`
+fn is_entity(chars: &[char]) -> bool {
+    let name: Vec<&char> = chars
+        .iter()
+        .skip(1)
+        .take_while(|c| c.is_ascii_alphanumeric() || **c == '#')
+        .collect();
+    !name.is_empty() && chars.get(name.len() + 1) == Some(&';')
+}
+
+// escape what would turn the start of a line of text into a block
+fn escape_line_starts(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            if line.starts_with(['#', '+', '-', '=', '>']) {
+                return format!("\\{line}");
+            }
+            let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits > 0 && line[digits..].starts_with(['.', ')']) {
+                return format!("{}\\{}", &line[..digits], &line[digits..]);
+            }
+            line.to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// indent every line but the first, leaving blank lines empty
+fn indent(text: &str, width: usize) -> String {
+    let padding = " ".repeat(width);
+    text.split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 || line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{padding}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// prefix every line, as quotes do with `>`
+fn prefix_lines(text: &str, prefix: &str) -> String {
+    if text.is_empty() {
+        return prefix.to_string();
+    }
+    text.split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                prefix.to_string()
+            } else {
+                format!("{prefix} {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// the length of the longest run of a character, to pick a fence longer than it
+fn longest_run(text: &str, c: char) -> usize {
+    text.split(|other| other != c)
+        .map(str::len)
+        .max()
+        .unwrap_or(0)
+}
@@ -0,0 +1,13 @@
+// app/src/content/mod.rs
+
+// modules
+mod document;
+mod error;
+mod html;
+mod markdown;
+mod text;
+
+// re-exports
+pub use document::*;
+pub use error::*;
+pub use html::*;
@@ -0,0 +1,161 @@
+// app/src/content/text.rs
+
+// dependencies
+use super::document::{Block, ContentDocument, Inline, TableCell};
+
+impl ContentDocument {
+    /// The text of the document without any markup, one paragraph per block, for search
+    pub fn to_plain_text(&self) -> String {
+        let mut paragraphs = Vec::new();
+        blocks(&self.blocks, &mut paragraphs);
+        paragraphs.join("\n\n")
+    }
+
+    /// The start of the text of the document, on a single line and cut between words
+    pub fn excerpt(&self, max_chars: usize) -> String {
+        let text = self
+            .to_plain_text()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if text.chars().count() <= max_chars {
+            return text;
+        }
+        // Leave room for the ellipsis, then cut back to the last word which fits whole
+        let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+        let cut_between_words = text.chars().nth(cut.chars().count()) == Some(' ');
+        let kept = match cut.rfind(' ') {
+            Some(end) if !cut_between_words => &cut[..end],
+            _ => cut.as_str(),
+        };
+        format!("{}…", kept.trim_end())
+    }
+}
+
+// collect the text of blocks, skipping those without any
+fn blocks(nodes: &[Block], paragraphs: &mut Vec<String>) {
+    for block in nodes {
+        match block {
+            Block::Paragraph { children } | Block::Heading { children, .. } => {
+                push(paragraphs, inlines(children));
+            }
+            Block::List { items, .. } => {
+                for item in items {
+                    blocks(&item.blocks, paragraphs);
+                }
+            }
+            Block::Quote {
+                blocks: quoted,
+                citation,
+            } => {
+                blocks(quoted, paragraphs);
+                push(paragraphs, inlines(citation));
+            }
+            Block::Code { code, .. } => push(paragraphs, code.clone()),
+            Block::Image { caption, .. } | Block::Embed { caption, .. } => {
+                push(paragraphs, inlines(caption));
+            }
+            Block::Table { header, rows, .. } => {
+                push(paragraphs, cells(header));
+                for row in rows {
+                    push(paragraphs, cells(row));
+                }
+            }
+            Block::Footnote { blocks: notes, .. } => blocks(notes, paragraphs),
+            Block::Divider => {}
+        }
+    }
+}
+
+// keep a paragraph, unless it holds nothing but whitespace
+fn push(paragraphs: &mut Vec<String>, text: String) {
+    if !text.trim().is_empty() {
+        paragraphs.push(text);
+    }
+}
+
+// the text of a row of table cells
+fn cells(cells: &[TableCell]) -> String {
+    cells
+        .iter()
+        .map(|cell| inlines(&cell.children))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// the text of inline content
+fn inlines(nodes: &[Inline]) -> String {
+    let mut text = String::new();
+    for inline in nodes {
+        match inline {
+            Inline::Text { text: run, .. } => text.push_str(run),
+            Inline::Link { children, .. } => text.push_str(&inlines(children)),
+            Inline::LineBreak => text.push('\n'),
+            Inline::FootnoteReference { .. } => {}
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::content::*;
+
+    fn document() -> ContentDocument {
+        ContentDocument::new(vec![
+            Block::Heading {
+                level: 1,
+                children: vec![Inline::text("Hello")],
+                anchor: None,
+            },
+            Block::Paragraph {
+                children: vec![
+                    Inline::Text {
+                        text: "Bold".into(),
+                        marks: vec![Mark::Bold],
+                    },
+                    Inline::text(" and "),
+                    Inline::Link {
+                        href: "/x".into(),
+                        title: None,
+                        children: vec![Inline::text("<linked>")],
+                    },
+                ],
+            },
+            Block::Divider,
+            Block::List {
+                ordered: false,
+                start: None,
+                items: vec![
+                    ListItem {
+                        blocks: vec![Block::paragraph("one")],
+                        checked: None,
+                    },
+                    ListItem {
+                        blocks: vec![Block::paragraph("two")],
+                        checked: None,
+                    },
+                ],
+            },
+        ])
+    }
+
+    #[test]
+    fn plain_text_drops_markup_but_keeps_the_text() {
+        assert_eq!(
+            document().to_plain_text(),
+            "Hello\n\nBold and <linked>\n\none\n\ntwo"
+        );
+    }
+
+    #[test]
+    fn excerpts_are_cut_between_words() {
+        let document = document();
+
+        assert_eq!(document.excerpt(100), "Hello Bold and <linked> one two");
+        assert_eq!(document.excerpt(12), "Hello Bold…");
+        assert_eq!(document.excerpt(11), "Hello Bold…");
+        assert_eq!(document.excerpt(4), "Hel…");
+        assert_eq!(ContentDocument::default().excerpt(10), "");
+    }
+}
@@ -0,0 +1,26 @@
+// app/src/email/error.rs
+
+// dependencies
+use thiserror::Error;
+
+// enum type to represent the errors raised while composing or delivering an email
+#[derive(Debug, Error)]
+pub enum EmailError {
+    #[error("Template error: {0}")]
+    Template(#[from] pavex_tera_template::TemplateError),
+
+    #[error("Invalid email address: {0}")]
+    Address(#[from] lettre::address::AddressError),
+
+    #[error("Unable to build the email: {0}")]
+    Message(#[from] lettre::error::Error),
+
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+
+    #[error("File transport error: {0}")]
+    File(#[from] lettre::transport::file::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
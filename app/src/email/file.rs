@@ -0,0 +1,47 @@
+// app/src/email/file.rs
+
+// dependencies
+use super::{EmailError, EmailMessage, Mailer};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::{AsyncFileTransport, AsyncTransport, Tokio1Executor};
+use std::path::{Path, PathBuf};
+
+// struct type to represent a mailer which writes emails to disk instead of delivering them,
+// intended for the dev and test profiles
+pub struct FileMailer {
+    from: Mailbox,
+    dir: PathBuf,
+    transport: AsyncFileTransport<Tokio1Executor>,
+}
+
+impl FileMailer {
+    pub fn new(from: Mailbox, dir: &Path) -> Result<Self, EmailError> {
+        // make sure the outbox exists before the first email is written
+        std::fs::create_dir_all(dir)?;
+
+        Ok(Self {
+            from,
+            dir: dir.to_path_buf(),
+            transport: AsyncFileTransport::new(dir),
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: EmailMessage) -> Result<(), EmailError> {
+        let to = email.to.clone();
+        let subject = email.subject.clone();
+        let message = email.into_message(&self.from)?;
+        let id = self.transport.send(message).await?;
+
+        tracing::info!(
+            email.to = %to,
+            email.subject = %subject,
+            email.path = %self.dir.join(format!("{id}.eml")).display(),
+            "Email written to the outbox"
+        );
+        Ok(())
+    }
+}
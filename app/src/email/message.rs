@@ -0,0 +1,31 @@
+// app/src/email/message.rs
+
+// dependencies
+use super::EmailError;
+use lettre::Message;
+use lettre::message::{Mailbox, MultiPart};
+
+// struct type to represent an outgoing email, with both an HTML and a plain-text body
+#[derive(Clone, Debug)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+}
+
+impl EmailMessage {
+    /// Build a `multipart/alternative` message, sent from the given mailbox.
+    pub fn into_message(self, from: &Mailbox) -> Result<Message, EmailError> {
+        let message = Message::builder()
+            .from(from.clone())
+            .to(self.to.parse()?)
+            .subject(self.subject)
+            .multipart(MultiPart::alternative_plain_html(
+                self.text_body,
+                self.html_body,
+            ))?;
+
+        Ok(message)
+    }
+}
@@ -0,0 +1,111 @@
+// app/src/email/mod.rs
+
+// modules
+mod error;
+mod file;
+mod message;
+mod smtp;
+
+// re-exports
+pub use error::*;
+pub use file::*;
+pub use message::*;
+pub use smtp::*;
+
+// dependencies
+use crate::configuration::{MailerConfig, MailerTransport, SiteConfig};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use pavex::methods;
+use pavex_tera_template::{Context, TemplateEngine};
+use std::sync::Arc;
+use tracing_log_error::log_error;
+
+// traits
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: EmailMessage) -> Result<(), EmailError>;
+}
+
+// struct type to wrap the configured mailer in a container
+pub struct MailerContainer {
+    mailer: Arc<dyn Mailer>,
+    site: SiteConfig,
+}
+
+#[methods]
+impl MailerContainer {
+    #[singleton]
+    pub fn new(config: &MailerConfig, site: &SiteConfig) -> Result<Self, EmailError> {
+        let from: Mailbox = config.sender.parse()?;
+        let mailer: Arc<dyn Mailer> = match &config.transport {
+            MailerTransport::Smtp(smtp) => Arc::new(SmtpMailer::new(from, smtp)?),
+            MailerTransport::File { dir } => Arc::new(FileMailer::new(from, dir)?),
+        };
+
+        Ok(Self {
+            mailer,
+            site: site.clone(),
+        })
+    }
+}
+
+impl MailerContainer {
+    /// Build an absolute link to a path of this application, to be embedded in an email.
+    pub fn link(&self, path_and_query: &str) -> String {
+        self.site.link(path_and_query)
+    }
+
+    /// Send an already composed email.
+    pub async fn send(&self, email: EmailMessage) -> Result<(), EmailError> {
+        self.mailer.send(email).await
+    }
+
+    /// Send an email from a background task, failures are logged.
+    ///
+    /// Handlers which must not reveal whether an account exists use this, so that
+    /// the response takes as long whether or not an email is sent.
+    pub fn send_in_background(&self, email: EmailMessage) {
+        let mailer = self.mailer.clone();
+        tokio::spawn(async move {
+            if let Err(e) = mailer.send(email).await {
+                log_error!(e, "Failed to send an email in the background");
+            }
+        });
+    }
+
+    /// Render `emails/{name}.html` and `emails/{name}.txt` with the given context
+    /// into a single multipart email, without sending it.
+    pub fn compose(
+        &self,
+        template: &TemplateEngine,
+        to: &str,
+        subject: &str,
+        name: &str,
+        context: &Context,
+    ) -> Result<EmailMessage, EmailError> {
+        let html_body = template.render(&format!("emails/{name}.html"), context)?;
+        let text_body = template.render(&format!("emails/{name}.txt"), context)?;
+
+        Ok(EmailMessage {
+            to: to.to_string(),
+            subject: subject.to_string(),
+            html_body,
+            text_body,
+        })
+    }
+
+    /// Render `emails/{name}.html` and `emails/{name}.txt` with the given context
+    /// and send them as a single multipart email.
+    pub async fn send_template(
+        &self,
+        template: &TemplateEngine,
+        to: &str,
+        subject: &str,
+        name: &str,
+        context: &Context,
+    ) -> Result<(), EmailError> {
+        let email = self.compose(template, to, subject, name, context)?;
+        self.send(email).await
+    }
+}
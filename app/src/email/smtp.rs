@@ -0,0 +1,52 @@
+// app/src/email/smtp.rs
+
+// dependencies
+use super::{EmailError, EmailMessage, Mailer};
+use crate::configuration::{SmtpConfig, SmtpSecurity};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use secrecy::ExposeSecret;
+
+// struct type to represent a mailer which relays emails through an SMTP server
+pub struct SmtpMailer {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub fn new(from: Mailbox, config: &SmtpConfig) -> Result<Self, EmailError> {
+        let builder = match config.security {
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+            SmtpSecurity::Starttls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+            }
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            }
+        };
+
+        let mut builder = builder.port(config.port);
+        if !config.username.is_empty() {
+            builder = builder.credentials(Credentials::new(
+                config.username.clone(),
+                config.password.expose_secret().to_string(),
+            ));
+        }
+
+        Ok(Self {
+            from,
+            transport: builder.build(),
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: EmailMessage) -> Result<(), EmailError> {
+        let message = email.into_message(&self.from)?;
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
@@ -1,8 +1,14 @@
 // app/src/errors.rs
 
 // dependencies
-use crate::models::UserError;
+use crate::models::{
+    ApiTokenError, CapabilityError, IdentityError, InvitationError, MagicLinkError, PasskeyError,
+    PostError, PrivacyError, ScheduleError, SessionError, UserError,
+};
+use crate::oidc::OidcError;
+use crate::rate_limit::RateLimitExceeded;
 use crate::response::{ApiResponse, Status};
+use pavex::http::{HeaderValue, header::RETRY_AFTER};
 use pavex::{Response, error_handler, http::StatusCode, time::Timestamp};
 use serde::Serialize;
 use std::convert::From;
@@ -28,6 +34,42 @@ pub enum ApiError {
 
     #[error("Forbidden: {0}")]
     Forbidden(String),
+
+    #[error("API token error: {0}")]
+    ApiTokenError(#[from] ApiTokenError),
+
+    #[error("Session error: {0}")]
+    SessionError(#[from] SessionError),
+
+    #[error("Privacy error: {0}")]
+    PrivacyError(#[from] PrivacyError),
+
+    #[error("Invitation error: {0}")]
+    InvitationError(#[from] InvitationError),
+
+    #[error("Capability error: {0}")]
+    CapabilityError(#[from] CapabilityError),
+
+    #[error("Identity error: {0}")]
+    IdentityError(#[from] IdentityError),
+
+    #[error("Sign-in error: {0}")]
+    OidcError(#[from] OidcError),
+
+    #[error("Passkey error: {0}")]
+    PasskeyError(#[from] PasskeyError),
+
+    #[error("Sign-in link error: {0}")]
+    MagicLinkError(#[from] MagicLinkError),
+
+    #[error("Post error: {0}")]
+    PostError(#[from] PostError),
+
+    #[error("Schedule error: {0}")]
+    ScheduleError(#[from] ScheduleError),
+
+    #[error(transparent)]
+    RateLimited(#[from] RateLimitExceeded),
 }
 
 // The error‑side of an API response never carries data, so we just use
@@ -39,6 +81,7 @@ impl From<&ApiError> for ApiResponse<()> {
         let (status_code, status_tag) = match err {
             ApiError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, Status::Error),
             ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, Status::Error),
+            ApiError::RateLimited(_) => (StatusCode::TOO_MANY_REQUESTS, Status::Error),
             ApiError::TemplateError(_) => (StatusCode::INTERNAL_SERVER_ERROR, Status::Error),
             ApiError::StaticFileError(e) => {
                 let lower = e.to_string().to_lowercase();
@@ -55,12 +98,98 @@ impl From<&ApiError> for ApiResponse<()> {
                 UserError::EmailExists | UserError::UsernameExists => {
                     (StatusCode::CONFLICT, Status::Error)
                 }
-                UserError::InvalidCredentials | UserError::EmailNotVerified => {
-                    (StatusCode::UNAUTHORIZED, Status::Error)
+                UserError::InvalidCredentials
+                | UserError::EmailNotVerified
+                | UserError::InvalidSecondFactor => (StatusCode::UNAUTHORIZED, Status::Error),
+                UserError::AccountLocked { .. } => (StatusCode::TOO_MANY_REQUESTS, Status::Error),
+                UserError::RegistrationClosed | UserError::InvitationRequired => {
+                    (StatusCode::FORBIDDEN, Status::Error)
                 }
                 // Any other variant is treated as an internal server error.
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, Status::Error),
             },
+            ApiError::ApiTokenError(token_err) => match token_err {
+                ApiTokenError::Validation { .. } => (StatusCode::BAD_REQUEST, Status::Error),
+                ApiTokenError::TokenNotFound => (StatusCode::NOT_FOUND, Status::Error),
+                ApiTokenError::InvalidToken => (StatusCode::UNAUTHORIZED, Status::Error),
+                ApiTokenError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, Status::Error),
+            },
+            ApiError::SessionError(session_err) => match session_err {
+                SessionError::Validation { .. } => (StatusCode::BAD_REQUEST, Status::Error),
+                SessionError::SessionNotFound => (StatusCode::NOT_FOUND, Status::Error),
+                SessionError::SessionExpired | SessionError::SessionInvalidated => {
+                    (StatusCode::UNAUTHORIZED, Status::Error)
+                }
+                SessionError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, Status::Error),
+            },
+            ApiError::PrivacyError(privacy_err) => match privacy_err {
+                PrivacyError::Validation { .. } => (StatusCode::BAD_REQUEST, Status::Error),
+                PrivacyError::UserNotFound | PrivacyError::DeletionNotFound => {
+                    (StatusCode::NOT_FOUND, Status::Error)
+                }
+                PrivacyError::Database(_) | PrivacyError::Archive(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, Status::Error)
+                }
+            },
+            ApiError::InvitationError(invitation_err) => match invitation_err {
+                InvitationError::Validation { .. } | InvitationError::InvalidInvitation => {
+                    (StatusCode::BAD_REQUEST, Status::Error)
+                }
+                InvitationError::InvitationNotFound => (StatusCode::NOT_FOUND, Status::Error),
+                InvitationError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, Status::Error),
+            },
+            ApiError::CapabilityError(capability_err) => match capability_err {
+                CapabilityError::Validation { .. } => (StatusCode::BAD_REQUEST, Status::Error),
+                CapabilityError::CapabilityNotFound => (StatusCode::NOT_FOUND, Status::Error),
+                CapabilityError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, Status::Error),
+            },
+            ApiError::IdentityError(identity_err) => match identity_err {
+                IdentityError::EmailNotVerified | IdentityError::UnverifiedAccount { .. } => {
+                    (StatusCode::BAD_REQUEST, Status::Error)
+                }
+                IdentityError::AccountDisabled => (StatusCode::UNAUTHORIZED, Status::Error),
+                IdentityError::RegistrationClosed => (StatusCode::FORBIDDEN, Status::Error),
+                IdentityError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, Status::Error),
+            },
+            ApiError::OidcError(oidc_err) => match oidc_err {
+                OidcError::UnknownProvider => (StatusCode::NOT_FOUND, Status::Error),
+                OidcError::InvalidState => (StatusCode::BAD_REQUEST, Status::Error),
+                OidcError::Denied(_) | OidcError::InvalidIdToken(_) => {
+                    (StatusCode::UNAUTHORIZED, Status::Error)
+                }
+                OidcError::Provider(_) | OidcError::Http(_) => {
+                    (StatusCode::BAD_GATEWAY, Status::Error)
+                }
+            },
+            ApiError::PasskeyError(passkey_err) => match passkey_err {
+                PasskeyError::Validation { .. } | PasskeyError::Webauthn(_) => {
+                    (StatusCode::BAD_REQUEST, Status::Error)
+                }
+                PasskeyError::InvalidCredentials => (StatusCode::UNAUTHORIZED, Status::Error),
+                PasskeyError::PasskeyNotFound => (StatusCode::NOT_FOUND, Status::Error),
+                PasskeyError::Database(_) | PasskeyError::Serialization(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, Status::Error)
+                }
+            },
+            ApiError::MagicLinkError(magic_link_err) => match magic_link_err {
+                MagicLinkError::Validation { .. } => (StatusCode::BAD_REQUEST, Status::Error),
+                MagicLinkError::InvalidLink => (StatusCode::UNAUTHORIZED, Status::Error),
+                MagicLinkError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, Status::Error),
+            },
+            ApiError::PostError(post_err) => match post_err {
+                PostError::Validation { .. } | PostError::InvalidContent(_) => {
+                    (StatusCode::BAD_REQUEST, Status::Error)
+                }
+                PostError::PostNotFound => (StatusCode::NOT_FOUND, Status::Error),
+                PostError::SlugExists => (StatusCode::CONFLICT, Status::Error),
+                PostError::UnsupportedMediaType(_) => {
+                    (StatusCode::UNSUPPORTED_MEDIA_TYPE, Status::Error)
+                }
+                PostError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, Status::Error),
+            },
+            ApiError::ScheduleError(schedule_err) => match schedule_err {
+                ScheduleError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, Status::Error),
+            },
         };
 
         ApiResponse {
@@ -106,5 +235,18 @@ pub fn api_error2response(error: &ApiError) -> Response {
     let envelope = ApiResponse::<()>::from(error);
 
     // 2️⃣ Create the real HTTP response
-    envelope.into_response()
+    let mut response = envelope.into_response();
+
+    // 3️⃣ Tell rate limited and locked out clients when to come back
+    let retry_after = match error {
+        ApiError::RateLimited(e) => Some(e.retry_after_secs()),
+        ApiError::UserError(UserError::AccountLocked { retry_after }) => {
+            Some(retry_after.as_secs().max(1))
+        }
+        _ => None,
+    };
+    if let Some(secs) = retry_after {
+        response = response.insert_header(RETRY_AFTER, HeaderValue::from(secs));
+    }
+    response
 }
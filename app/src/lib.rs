@@ -4,11 +4,17 @@
 pub mod authorization;
 mod blueprint;
 pub mod configuration;
+pub mod content;
+pub mod email;
 pub mod errors;
 pub mod models;
+pub mod oidc;
+pub mod rate_limit;
 pub mod response;
 pub mod routes;
+pub mod sealing;
 pub mod telemetry;
+pub mod tokens;
 
 // re-export the blueprint
 pub use blueprint::blueprint;
@@ -0,0 +1,57 @@
+// app/src/models/api_token/dto.rs
+
+// dependencies
+use super::{ApiToken, TokenScope};
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Request DTO for API token creation
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct CreateApiTokenRequest {
+    #[validate(length(min = 1, max = 100, message = "Token name must be 1-100 characters"))]
+    pub name: String,
+
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<TokenScope>,
+
+    /// Tokens without an expiry stay valid until they are revoked
+    #[validate(range(min = 1, max = 365, message = "Expiry must be 1-365 days"))]
+    pub expires_in_days: Option<u32>,
+}
+
+/// Public API token response DTO - never carries the token itself
+#[derive(Debug, Serialize)]
+pub struct ApiTokenResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<Timestamp>,
+    pub last_used_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+}
+
+/// Created API token response DTO - the only time the token is shown
+#[derive(Debug, Serialize)]
+pub struct CreatedApiTokenResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub details: ApiTokenResponse,
+}
+
+// Conversion implementations
+impl From<ApiToken> for ApiTokenResponse {
+    fn from(token: ApiToken) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            prefix: token.prefix,
+            scopes: token.scopes,
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
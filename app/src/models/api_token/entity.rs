@@ -0,0 +1,108 @@
+// app/src/models/api_token/entity.rs
+
+// dependencies
+use crate::models::UserRole;
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// What an API token may be used for
+///
+/// Cookie sessions carry every scope, tokens only those they were created with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TokenScope {
+    #[serde(rename = "profile:read")]
+    ProfileRead,
+    #[serde(rename = "profile:write")]
+    ProfileWrite,
+    #[serde(rename = "posts:read")]
+    PostsRead,
+    #[serde(rename = "posts:write")]
+    PostsWrite,
+    #[serde(rename = "media:read")]
+    MediaRead,
+    #[serde(rename = "media:write")]
+    MediaWrite,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl TokenScope {
+    pub const ALL: [TokenScope; 7] = [
+        TokenScope::ProfileRead,
+        TokenScope::ProfileWrite,
+        TokenScope::PostsRead,
+        TokenScope::PostsWrite,
+        TokenScope::MediaRead,
+        TokenScope::MediaWrite,
+        TokenScope::Admin,
+    ];
+
+    // Name of the scope, as stored and as sent over the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::ProfileRead => "profile:read",
+            TokenScope::ProfileWrite => "profile:write",
+            TokenScope::PostsRead => "posts:read",
+            TokenScope::PostsWrite => "posts:write",
+            TokenScope::MediaRead => "media:read",
+            TokenScope::MediaWrite => "media:write",
+            TokenScope::Admin => "admin",
+        }
+    }
+
+    // Parse scope from string - useful for manual row mapping
+    pub fn frm_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scope| scope.as_str() == s)
+    }
+}
+
+// Personal access token, the token itself is only ever stored hashed
+#[derive(Debug, Clone)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// The first characters of the token, so its owner can tell tokens apart
+    pub prefix: String,
+    pub token_hash: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<Timestamp>,
+    pub last_used_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+}
+
+impl ApiToken {
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+// The account an API token acts on behalf of
+#[derive(Debug, Clone)]
+pub struct TokenOwner {
+    pub username: String,
+    pub role: UserRole,
+    pub is_active: bool,
+}
+
+// Everything needed to store a new API token
+#[derive(Debug, Clone)]
+pub struct NewApiToken {
+    pub user_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub token_hash: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<Timestamp>,
+}
+
+// The identity a valid API token resolves to
+#[derive(Debug, Clone)]
+pub struct TokenPrincipal {
+    pub token_id: Uuid,
+    pub user_id: Uuid,
+    pub username: String,
+    pub role: UserRole,
+    pub scopes: Vec<TokenScope>,
+}
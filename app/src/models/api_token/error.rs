@@ -0,0 +1,35 @@
+// app/src/models/api_token/error.rs
+
+use crate::response::IntoApiError;
+use pavex::http::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ApiTokenError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Validation error: {message}")]
+    Validation { message: String },
+
+    #[error("API token not found")]
+    TokenNotFound,
+
+    #[error("Invalid or expired API token")]
+    InvalidToken,
+}
+
+impl IntoApiError for ApiTokenError {
+    fn code(&self) -> Option<u16> {
+        match self {
+            ApiTokenError::Validation { .. } => Some(StatusCode::BAD_REQUEST.as_u16()),
+            ApiTokenError::TokenNotFound => Some(StatusCode::NOT_FOUND.as_u16()),
+            ApiTokenError::InvalidToken => Some(StatusCode::UNAUTHORIZED.as_u16()),
+            ApiTokenError::Database(_) => Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16()),
+        }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,149 @@
+// app/src/models/api_token/repository.rs
+
+// dependencies
+use super::entity::{ApiToken, NewApiToken, TokenOwner, TokenScope};
+use super::error::ApiTokenError;
+use crate::models::UserRole;
+use async_trait::async_trait;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+// traits
+#[async_trait]
+pub trait ApiTokenRepository: Send + Sync {
+    async fn create(&self, token: NewApiToken) -> Result<ApiToken, ApiTokenError>;
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<ApiToken>, ApiTokenError>;
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, ApiTokenError>;
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<(ApiToken, TokenOwner)>, ApiTokenError>;
+    async fn touch(&self, id: Uuid) -> Result<(), ApiTokenError>;
+}
+
+pub struct SqlxApiTokenRepository {
+    pool: PgPool,
+}
+
+impl SqlxApiTokenRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Helper function to map database row to ApiToken struct
+    fn map_row_to_token(row: &sqlx::postgres::PgRow) -> ApiToken {
+        // Scopes this version does not know about grant nothing
+        let scopes: Vec<String> = row.get("scopes");
+        ApiToken {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            name: row.get("name"),
+            prefix: row.get("prefix"),
+            token_hash: row.get("token_hash"),
+            scopes: scopes
+                .iter()
+                .filter_map(|scope| TokenScope::frm_str(scope))
+                .collect(),
+            expires_at: row
+                .get::<Option<SqlxTimestamp>, _>("expires_at")
+                .map(|t| t.into()),
+            last_used_at: row
+                .get::<Option<SqlxTimestamp>, _>("last_used_at")
+                .map(|t| t.into()),
+            created_at: row.get::<SqlxTimestamp, _>("created_at").into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ApiTokenRepository for SqlxApiTokenRepository {
+    async fn create(&self, token: NewApiToken) -> Result<ApiToken, ApiTokenError> {
+        let scopes: Vec<&str> = token.scopes.iter().map(TokenScope::as_str).collect();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO api_tokens (user_id, name, prefix, token_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING
+                id, user_id, name, prefix, token_hash, scopes,
+                expires_at, last_used_at, created_at
+            "#,
+        )
+        .bind(token.user_id)
+        .bind(&token.name)
+        .bind(&token.prefix)
+        .bind(&token.token_hash)
+        .bind(&scopes)
+        .bind(token.expires_at.map(SqlxTimestamp::from))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Self::map_row_to_token(&row))
+    }
+
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<ApiToken>, ApiTokenError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, user_id, name, prefix, token_hash, scopes,
+                expires_at, last_used_at, created_at
+            FROM api_tokens
+            WHERE user_id = $1
+            ORDER BY created_at DESC, id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::map_row_to_token).collect())
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, ApiTokenError> {
+        let result = sqlx::query("DELETE FROM api_tokens WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<(ApiToken, TokenOwner)>, ApiTokenError> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                t.id, t.user_id, t.name, t.prefix, t.token_hash, t.scopes,
+                t.expires_at, t.last_used_at, t.created_at,
+                u.username, u.role, u.is_active
+            FROM api_tokens t
+            JOIN users u ON u.id = t.user_id
+            WHERE t.token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| {
+            let owner = TokenOwner {
+                username: row.get("username"),
+                role: row.get::<UserRole, _>("role"),
+                is_active: row.get("is_active"),
+            };
+            (Self::map_row_to_token(&row), owner)
+        }))
+    }
+
+    async fn touch(&self, id: Uuid) -> Result<(), ApiTokenError> {
+        sqlx::query("UPDATE api_tokens SET last_used_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
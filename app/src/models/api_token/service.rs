@@ -0,0 +1,150 @@
+// app/src/models/api_token/service.rs
+
+// dependencies
+use super::dto::{ApiTokenResponse, CreateApiTokenRequest, CreatedApiTokenResponse};
+use super::entity::{NewApiToken, TokenPrincipal, TokenScope};
+use super::error::ApiTokenError;
+use super::repository::ApiTokenRepository;
+use crate::models::UserRole;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use async_trait::async_trait;
+use data_encoding::{BASE32_NOPAD, HEXLOWER};
+use pavex::time::Timestamp;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Every token starts with this, so leaked tokens are easy to spot, e.g. by secret scanners.
+pub const TOKEN_PREFIX: &str = "rws_";
+// random characters of the token kept in clear, to tell tokens apart
+const VISIBLE_CHARS: usize = 8;
+const TOKEN_BYTES: usize = 32;
+
+// traits
+#[async_trait]
+pub trait ApiTokenService: Send + Sync {
+    async fn create_token(
+        &self,
+        user_id: Uuid,
+        role: UserRole,
+        request: CreateApiTokenRequest,
+    ) -> Result<CreatedApiTokenResponse, ApiTokenError>;
+    async fn list_tokens(&self, user_id: Uuid) -> Result<Vec<ApiTokenResponse>, ApiTokenError>;
+    async fn revoke_token(&self, user_id: Uuid, id: Uuid) -> Result<(), ApiTokenError>;
+    async fn authenticate(&self, token: &str) -> Result<TokenPrincipal, ApiTokenError>;
+}
+
+pub struct ApiTokenServiceImpl {
+    repository: Arc<dyn ApiTokenRepository>,
+}
+
+impl ApiTokenServiceImpl {
+    pub fn new(repository: Arc<dyn ApiTokenRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Generate a new token, returning it along with its visible prefix.
+    fn generate_token() -> (String, String) {
+        let mut bytes = [0u8; TOKEN_BYTES];
+        OsRng.fill_bytes(&mut bytes);
+        let token = format!(
+            "{TOKEN_PREFIX}{}",
+            BASE32_NOPAD.encode(&bytes).to_lowercase()
+        );
+        let prefix = token[..TOKEN_PREFIX.len() + VISIBLE_CHARS].to_string();
+        (token, prefix)
+    }
+
+    /// Hash a token for storage, tokens are random enough for a plain SHA-256
+    pub fn hash_token(token: &str) -> String {
+        HEXLOWER.encode(&Sha256::digest(token.as_bytes()))
+    }
+}
+
+#[async_trait]
+impl ApiTokenService for ApiTokenServiceImpl {
+    async fn create_token(
+        &self,
+        user_id: Uuid,
+        role: UserRole,
+        request: CreateApiTokenRequest,
+    ) -> Result<CreatedApiTokenResponse, ApiTokenError> {
+        // Validate input
+        request.validate().map_err(|e| ApiTokenError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+        if request.scopes.contains(&TokenScope::Admin) && role != UserRole::Admin {
+            return Err(ApiTokenError::Validation {
+                message: "Only admins can create tokens with the admin scope".into(),
+            });
+        }
+
+        let mut scopes = request.scopes;
+        scopes.sort_by_key(|scope| scope.as_str());
+        scopes.dedup();
+        let expires_at = request
+            .expires_in_days
+            .map(|days| Timestamp::now() + Duration::from_secs(u64::from(days) * 24 * 60 * 60));
+
+        let (token, prefix) = Self::generate_token();
+        let api_token = self
+            .repository
+            .create(NewApiToken {
+                user_id,
+                name: request.name.trim().to_string(),
+                prefix,
+                token_hash: Self::hash_token(&token),
+                scopes,
+                expires_at,
+            })
+            .await?;
+        tracing::info!(user.id = %user_id, token.id = %api_token.id, "API token created");
+
+        Ok(CreatedApiTokenResponse {
+            token,
+            details: api_token.into(),
+        })
+    }
+
+    async fn list_tokens(&self, user_id: Uuid) -> Result<Vec<ApiTokenResponse>, ApiTokenError> {
+        let tokens = self.repository.list_for_user(user_id).await?;
+
+        Ok(tokens.into_iter().map(ApiTokenResponse::from).collect())
+    }
+
+    async fn revoke_token(&self, user_id: Uuid, id: Uuid) -> Result<(), ApiTokenError> {
+        // Tokens of other users look exactly like missing ones
+        if !self.repository.delete(user_id, id).await? {
+            return Err(ApiTokenError::TokenNotFound);
+        }
+        tracing::info!(user.id = %user_id, token.id = %id, "API token revoked");
+
+        Ok(())
+    }
+
+    async fn authenticate(&self, token: &str) -> Result<TokenPrincipal, ApiTokenError> {
+        if !token.starts_with(TOKEN_PREFIX) {
+            return Err(ApiTokenError::InvalidToken);
+        }
+
+        let (api_token, owner) = self
+            .repository
+            .find_by_hash(&Self::hash_token(token))
+            .await?
+            .ok_or(ApiTokenError::InvalidToken)?;
+        if api_token.is_expired(Timestamp::now()) || !owner.is_active {
+            return Err(ApiTokenError::InvalidToken);
+        }
+        self.repository.touch(api_token.id).await?;
+
+        Ok(TokenPrincipal {
+            token_id: api_token.id,
+            user_id: api_token.user_id,
+            username: owner.username,
+            role: owner.role,
+            scopes: api_token.scopes,
+        })
+    }
+}
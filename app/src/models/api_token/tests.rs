@@ -0,0 +1,243 @@
+// app/src/models/api_token/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::models::UserRole;
+    use crate::models::api_token::*;
+    use async_trait::async_trait;
+    use pavex::time::Timestamp;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    // Mock repository for testing the service layer
+    pub struct MockApiTokenRepository {
+        tokens: Arc<Mutex<HashMap<Uuid, ApiToken>>>,
+        owner: Arc<Mutex<TokenOwner>>,
+    }
+
+    impl MockApiTokenRepository {
+        pub fn new() -> Self {
+            Self {
+                tokens: Arc::new(Mutex::new(HashMap::new())),
+                owner: Arc::new(Mutex::new(TokenOwner {
+                    username: "testuser".to_string(),
+                    role: UserRole::Author,
+                    is_active: true,
+                })),
+            }
+        }
+
+        pub fn deactivate_owner(&self) {
+            self.owner.lock().unwrap().is_active = false;
+        }
+
+        pub fn expire(&self, id: Uuid) {
+            let mut tokens = self.tokens.lock().unwrap();
+            if let Some(token) = tokens.get_mut(&id) {
+                token.expires_at = Some(Timestamp::now() - Duration::from_secs(1));
+            }
+        }
+
+        pub fn last_used_at(&self, id: Uuid) -> Option<Timestamp> {
+            self.tokens.lock().unwrap()[&id].last_used_at
+        }
+    }
+
+    #[async_trait]
+    impl ApiTokenRepository for MockApiTokenRepository {
+        async fn create(&self, token: NewApiToken) -> Result<ApiToken, ApiTokenError> {
+            let api_token = ApiToken {
+                id: Uuid::new_v4(),
+                user_id: token.user_id,
+                name: token.name,
+                prefix: token.prefix,
+                token_hash: token.token_hash,
+                scopes: token.scopes,
+                expires_at: token.expires_at,
+                last_used_at: None,
+                created_at: Timestamp::now(),
+            };
+            self.tokens
+                .lock()
+                .unwrap()
+                .insert(api_token.id, api_token.clone());
+            Ok(api_token)
+        }
+
+        async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<ApiToken>, ApiTokenError> {
+            let tokens = self.tokens.lock().unwrap();
+            Ok(tokens
+                .values()
+                .filter(|token| token.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, ApiTokenError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            if tokens
+                .get(&id)
+                .is_some_and(|token| token.user_id == user_id)
+            {
+                tokens.remove(&id);
+                return Ok(true);
+            }
+            Ok(false)
+        }
+
+        async fn find_by_hash(
+            &self,
+            token_hash: &str,
+        ) -> Result<Option<(ApiToken, TokenOwner)>, ApiTokenError> {
+            let tokens = self.tokens.lock().unwrap();
+            let owner = self.owner.lock().unwrap().clone();
+            Ok(tokens
+                .values()
+                .find(|token| token.token_hash == token_hash)
+                .map(|token| (token.clone(), owner)))
+        }
+
+        async fn touch(&self, id: Uuid) -> Result<(), ApiTokenError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            if let Some(token) = tokens.get_mut(&id) {
+                token.last_used_at = Some(Timestamp::now());
+            }
+            Ok(())
+        }
+    }
+
+    fn create_request(scopes: &[TokenScope]) -> CreateApiTokenRequest {
+        CreateApiTokenRequest {
+            name: "ci".to_string(),
+            scopes: scopes.to_vec(),
+            expires_in_days: Some(30),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_created_token_authenticates_with_its_scopes() {
+        let repository = Arc::new(MockApiTokenRepository::new());
+        let service = ApiTokenServiceImpl::new(repository.clone());
+        let user_id = Uuid::new_v4();
+
+        let created = service
+            .create_token(
+                user_id,
+                UserRole::Author,
+                create_request(&[TokenScope::PostsWrite, TokenScope::PostsWrite]),
+            )
+            .await
+            .unwrap();
+        assert!(created.token.starts_with(TOKEN_PREFIX));
+        assert!(created.token.starts_with(&created.details.prefix));
+        assert_eq!(created.details.scopes, vec![TokenScope::PostsWrite]);
+
+        // Only the hash is stored
+        let stored = repository.list_for_user(user_id).await.unwrap();
+        assert_eq!(
+            stored[0].token_hash,
+            ApiTokenServiceImpl::hash_token(&created.token)
+        );
+        assert_ne!(stored[0].token_hash, created.token);
+
+        let principal = service.authenticate(&created.token).await.unwrap();
+        assert_eq!(principal.user_id, user_id);
+        assert_eq!(principal.token_id, created.details.id);
+        assert_eq!(principal.scopes, vec![TokenScope::PostsWrite]);
+        assert!(repository.last_used_at(created.details.id).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_expired_and_deactivated_tokens_are_rejected() {
+        let repository = Arc::new(MockApiTokenRepository::new());
+        let service = ApiTokenServiceImpl::new(repository.clone());
+        let created = service
+            .create_token(
+                Uuid::new_v4(),
+                UserRole::Author,
+                create_request(&[TokenScope::ProfileRead]),
+            )
+            .await
+            .unwrap();
+
+        for token in ["not-a-token", "rws_unknown"] {
+            let result = service.authenticate(token).await;
+            assert!(matches!(result, Err(ApiTokenError::InvalidToken)));
+        }
+
+        repository.deactivate_owner();
+        let result = service.authenticate(&created.token).await;
+        assert!(matches!(result, Err(ApiTokenError::InvalidToken)));
+
+        let repository = Arc::new(MockApiTokenRepository::new());
+        let service = ApiTokenServiceImpl::new(repository.clone());
+        let created = service
+            .create_token(
+                Uuid::new_v4(),
+                UserRole::Author,
+                create_request(&[TokenScope::ProfileRead]),
+            )
+            .await
+            .unwrap();
+        repository.expire(created.details.id);
+        let result = service.authenticate(&created.token).await;
+        assert!(matches!(result, Err(ApiTokenError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_only_admins_can_create_admin_tokens() {
+        let service = ApiTokenServiceImpl::new(Arc::new(MockApiTokenRepository::new()));
+
+        let result = service
+            .create_token(
+                Uuid::new_v4(),
+                UserRole::Editor,
+                create_request(&[TokenScope::Admin]),
+            )
+            .await;
+        assert!(matches!(result, Err(ApiTokenError::Validation { .. })));
+
+        let result = service
+            .create_token(
+                Uuid::new_v4(),
+                UserRole::Admin,
+                create_request(&[TokenScope::Admin]),
+            )
+            .await;
+        assert!(result.is_ok());
+
+        let result = service
+            .create_token(Uuid::new_v4(), UserRole::Admin, create_request(&[]))
+            .await;
+        assert!(matches!(result, Err(ApiTokenError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_tokens_can_only_be_revoked_by_their_owner() {
+        let service = ApiTokenServiceImpl::new(Arc::new(MockApiTokenRepository::new()));
+        let user_id = Uuid::new_v4();
+        let created = service
+            .create_token(
+                user_id,
+                UserRole::Author,
+                create_request(&[TokenScope::ProfileRead]),
+            )
+            .await
+            .unwrap();
+
+        let result = service
+            .revoke_token(Uuid::new_v4(), created.details.id)
+            .await;
+        assert!(matches!(result, Err(ApiTokenError::TokenNotFound)));
+
+        service
+            .revoke_token(user_id, created.details.id)
+            .await
+            .unwrap();
+        assert!(service.list_tokens(user_id).await.unwrap().is_empty());
+        let result = service.authenticate(&created.token).await;
+        assert!(matches!(result, Err(ApiTokenError::InvalidToken)));
+    }
+}
@@ -0,0 +1,31 @@
+// app/src/models/capability/dto.rs
+
+// dependencies
+use super::{Capability, CapabilityGrant};
+use crate::models::UserRole;
+use serde::{Deserialize, Serialize};
+
+/// Request DTO for changing the roles a capability is granted to
+#[derive(Clone, Debug, Deserialize)]
+pub struct UpdateCapabilityRolesRequest {
+    pub roles: Vec<UserRole>,
+}
+
+/// Public capability response DTO
+#[derive(Debug, Serialize)]
+pub struct CapabilityResponse {
+    pub capability: Capability,
+    pub description: Option<String>,
+    pub roles: Vec<UserRole>,
+}
+
+// Conversion implementations
+impl From<CapabilityGrant> for CapabilityResponse {
+    fn from(grant: CapabilityGrant) -> Self {
+        Self {
+            capability: grant.capability,
+            description: grant.description,
+            roles: grant.roles,
+        }
+    }
+}
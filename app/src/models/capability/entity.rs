@@ -0,0 +1,109 @@
+// app/src/models/capability/entity.rs
+
+// dependencies
+use crate::models::{TokenScope, UserRole};
+use serde::{Deserialize, Serialize};
+
+/// Something a user may do, granted to roles through the capability registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    AccessAdmin,
+    EditPosts,
+    EditOthersPosts,
+    PublishPosts,
+    DeletePosts,
+    DeleteOthersPosts,
+    ModerateComments,
+    UploadMedia,
+    ManageUsers,
+    ManageCapabilities,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 10] = [
+        Capability::AccessAdmin,
+        Capability::EditPosts,
+        Capability::EditOthersPosts,
+        Capability::PublishPosts,
+        Capability::DeletePosts,
+        Capability::DeleteOthersPosts,
+        Capability::ModerateComments,
+        Capability::UploadMedia,
+        Capability::ManageUsers,
+        Capability::ManageCapabilities,
+    ];
+
+    // Name of the capability, as stored and as sent over the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::AccessAdmin => "access_admin",
+            Capability::EditPosts => "edit_posts",
+            Capability::EditOthersPosts => "edit_others_posts",
+            Capability::PublishPosts => "publish_posts",
+            Capability::DeletePosts => "delete_posts",
+            Capability::DeleteOthersPosts => "delete_others_posts",
+            Capability::ModerateComments => "moderate_comments",
+            Capability::UploadMedia => "upload_media",
+            Capability::ManageUsers => "manage_users",
+            Capability::ManageCapabilities => "manage_capabilities",
+        }
+    }
+
+    // Parse capability from string - useful for manual row mapping
+    pub fn frm_str(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|capability| capability.as_str() == s)
+    }
+
+    /// The roles granted the capability until an admin changes it, as seeded by the migrations
+    pub fn default_roles(&self) -> &'static [UserRole] {
+        use UserRole::*;
+        match self {
+            Capability::EditPosts | Capability::DeletePosts => {
+                &[Admin, Editor, Author, Contributor]
+            }
+            Capability::PublishPosts | Capability::UploadMedia => &[Admin, Editor, Author],
+            Capability::EditOthersPosts
+            | Capability::DeleteOthersPosts
+            | Capability::ModerateComments => &[Admin, Editor],
+            Capability::AccessAdmin | Capability::ManageUsers | Capability::ManageCapabilities => {
+                &[Admin]
+            }
+        }
+    }
+
+    /// The capability needed to do the same to content someone else owns
+    pub fn over_others(&self) -> Self {
+        match self {
+            Capability::EditPosts => Capability::EditOthersPosts,
+            Capability::DeletePosts => Capability::DeleteOthersPosts,
+            other => *other,
+        }
+    }
+
+    /// The scope an API token needs on top of the role, sessions carry every scope
+    pub fn scope(&self) -> TokenScope {
+        match self {
+            Capability::EditPosts
+            | Capability::EditOthersPosts
+            | Capability::PublishPosts
+            | Capability::DeletePosts
+            | Capability::DeleteOthersPosts
+            | Capability::ModerateComments => TokenScope::PostsWrite,
+            Capability::UploadMedia => TokenScope::MediaWrite,
+            Capability::AccessAdmin | Capability::ManageUsers | Capability::ManageCapabilities => {
+                TokenScope::Admin
+            }
+        }
+    }
+}
+
+// A capability together with the roles it is currently granted to
+#[derive(Debug, Clone)]
+pub struct CapabilityGrant {
+    pub capability: Capability,
+    pub description: Option<String>,
+    pub roles: Vec<UserRole>,
+}
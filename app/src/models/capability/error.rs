@@ -0,0 +1,31 @@
+// app/src/models/capability/error.rs
+
+use crate::response::IntoApiError;
+use pavex::http::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CapabilityError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Validation error: {message}")]
+    Validation { message: String },
+
+    #[error("Capability not found")]
+    CapabilityNotFound,
+}
+
+impl IntoApiError for CapabilityError {
+    fn code(&self) -> Option<u16> {
+        match self {
+            CapabilityError::Validation { .. } => Some(StatusCode::BAD_REQUEST.as_u16()),
+            CapabilityError::CapabilityNotFound => Some(StatusCode::NOT_FOUND.as_u16()),
+            CapabilityError::Database(_) => Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16()),
+        }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
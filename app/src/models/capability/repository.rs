@@ -0,0 +1,111 @@
+// app/src/models/capability/repository.rs
+
+// dependencies
+use super::entity::{Capability, CapabilityGrant};
+use super::error::CapabilityError;
+use crate::models::UserRole;
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+
+// traits
+#[async_trait]
+pub trait CapabilityRepository: Send + Sync {
+    async fn list(&self) -> Result<Vec<CapabilityGrant>, CapabilityError>;
+    async fn set_roles(
+        &self,
+        capability: Capability,
+        roles: &[UserRole],
+    ) -> Result<CapabilityGrant, CapabilityError>;
+}
+
+// Core capabilities with the roles they are granted, completed by a WHERE and GROUP BY clause
+const GRANT_SELECT: &str = r#"
+    SELECT
+        core.capability,
+        MAX(core.description) AS description,
+        COALESCE(
+            array_agg(granted.role::text) FILTER (WHERE granted.role IS NOT NULL),
+            '{}'
+        ) AS roles
+    FROM core_capabilities core
+    LEFT JOIN role_capabilities granted ON granted.capability = core.capability
+"#;
+
+pub struct SqlxCapabilityRepository {
+    pool: PgPool,
+}
+
+impl SqlxCapabilityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Helper function to map database row to CapabilityGrant struct, unknown names map to None
+    fn map_row_to_grant(row: &sqlx::postgres::PgRow) -> Option<CapabilityGrant> {
+        let capability = Capability::frm_str(row.get("capability"))?;
+        let granted: Vec<String> = row.get("roles");
+        let roles = UserRole::ALL
+            .into_iter()
+            .filter(|role| granted.iter().any(|granted| granted == role.as_str()))
+            .collect();
+
+        Some(CapabilityGrant {
+            capability,
+            description: row.get("description"),
+            roles,
+        })
+    }
+}
+
+#[async_trait]
+impl CapabilityRepository for SqlxCapabilityRepository {
+    async fn list(&self) -> Result<Vec<CapabilityGrant>, CapabilityError> {
+        let rows = sqlx::query(&format!(
+            "{GRANT_SELECT} GROUP BY core.capability ORDER BY core.capability"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().filter_map(Self::map_row_to_grant).collect())
+    }
+
+    async fn set_roles(
+        &self,
+        capability: Capability,
+        roles: &[UserRole],
+    ) -> Result<CapabilityGrant, CapabilityError> {
+        let roles: Vec<&str> = roles.iter().map(UserRole::as_str).collect();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO core_capabilities (capability) VALUES ($1) ON CONFLICT DO NOTHING",
+        )
+        .bind(capability.as_str())
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM role_capabilities WHERE capability = $1")
+            .bind(capability.as_str())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO role_capabilities (role, capability)
+            SELECT role::user_role, $1 FROM unnest($2::text[]) AS role
+            "#,
+        )
+        .bind(capability.as_str())
+        .bind(&roles)
+        .execute(&mut *tx)
+        .await?;
+        let row = sqlx::query(&format!(
+            "{GRANT_SELECT} WHERE core.capability = $1 GROUP BY core.capability"
+        ))
+        .bind(capability.as_str())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Self::map_row_to_grant(&row).ok_or(CapabilityError::CapabilityNotFound)
+    }
+}
@@ -0,0 +1,138 @@
+// app/src/models/capability/service.rs
+
+// dependencies
+use super::dto::{CapabilityResponse, UpdateCapabilityRolesRequest};
+use super::entity::{Capability, CapabilityGrant};
+use super::error::CapabilityError;
+use super::repository::CapabilityRepository;
+use crate::configuration::SessionLimitsConfig;
+use crate::models::UserRole;
+use async_trait::async_trait;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+// traits
+#[async_trait]
+pub trait CapabilityService: Send + Sync {
+    async fn capabilities_for(&self, role: UserRole) -> Result<Vec<Capability>, CapabilityError>;
+    // Administration related operations, `actor` is the id of the acting admin
+    async fn list_capabilities(&self) -> Result<Vec<CapabilityResponse>, CapabilityError>;
+    async fn update_capability_roles(
+        &self,
+        actor: Uuid,
+        capability: Capability,
+        request: UpdateCapabilityRolesRequest,
+    ) -> Result<CapabilityResponse, CapabilityError>;
+}
+
+// the registry as last read from the database
+struct CachedGrants {
+    loaded_at: Instant,
+    grants: Vec<CapabilityGrant>,
+}
+
+pub struct CapabilityServiceImpl {
+    repository: Arc<dyn CapabilityRepository>,
+    cache_ttl: Duration,
+    cache: RwLock<Option<CachedGrants>>,
+}
+
+impl CapabilityServiceImpl {
+    pub fn new(repository: Arc<dyn CapabilityRepository>) -> Self {
+        Self {
+            repository,
+            cache_ttl: SessionLimitsConfig::default().capability_cache_ttl,
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// Keep the role to capability map for `capability_cache_ttl` before reading it again, so
+    /// that changes made through another server instance are picked up without a restart.
+    pub fn with_config(mut self, limits: SessionLimitsConfig) -> Self {
+        self.cache_ttl = limits.capability_cache_ttl;
+        self
+    }
+
+    /// Every capability with the roles it is granted to, in registry order.
+    async fn grants(&self) -> Result<Vec<CapabilityGrant>, CapabilityError> {
+        if let Some(cached) = self.cache.read().unwrap().as_ref()
+            && cached.loaded_at.elapsed() < self.cache_ttl
+        {
+            return Ok(cached.grants.clone());
+        }
+
+        let stored = self.repository.list().await?;
+        // A capability without a row keeps its default roles
+        let grants: Vec<CapabilityGrant> = Capability::ALL
+            .into_iter()
+            .map(|capability| {
+                stored
+                    .iter()
+                    .find(|grant| grant.capability == capability)
+                    .cloned()
+                    .unwrap_or_else(|| CapabilityGrant {
+                        capability,
+                        description: None,
+                        roles: capability.default_roles().to_vec(),
+                    })
+            })
+            .collect();
+        *self.cache.write().unwrap() = Some(CachedGrants {
+            loaded_at: Instant::now(),
+            grants: grants.clone(),
+        });
+
+        Ok(grants)
+    }
+}
+
+#[async_trait]
+impl CapabilityService for CapabilityServiceImpl {
+    async fn capabilities_for(&self, role: UserRole) -> Result<Vec<Capability>, CapabilityError> {
+        let grants = self.grants().await?;
+
+        Ok(grants
+            .into_iter()
+            .filter(|grant| grant.roles.contains(&role))
+            .map(|grant| grant.capability)
+            .collect())
+    }
+
+    async fn list_capabilities(&self) -> Result<Vec<CapabilityResponse>, CapabilityError> {
+        let grants = self.grants().await?;
+
+        Ok(grants.into_iter().map(CapabilityResponse::from).collect())
+    }
+
+    async fn update_capability_roles(
+        &self,
+        actor: Uuid,
+        capability: Capability,
+        request: UpdateCapabilityRolesRequest,
+    ) -> Result<CapabilityResponse, CapabilityError> {
+        // Otherwise nobody could ever hand the capability back
+        if capability == Capability::ManageCapabilities && !request.roles.contains(&UserRole::Admin)
+        {
+            return Err(CapabilityError::Validation {
+                message: "Admins cannot lose the manage_capabilities capability".into(),
+            });
+        }
+
+        let roles: Vec<UserRole> = UserRole::ALL
+            .into_iter()
+            .filter(|role| request.roles.contains(role))
+            .collect();
+        let grant = self.repository.set_roles(capability, &roles).await?;
+        // The next check reads the new mapping
+        *self.cache.write().unwrap() = None;
+        tracing::info!(
+            admin.id = %actor,
+            capability = capability.as_str(),
+            roles = ?roles,
+            "Capability roles changed"
+        );
+
+        Ok(grant.into())
+    }
+}
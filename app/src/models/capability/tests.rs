@@ -0,0 +1,178 @@
+// app/src/models/capability/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::configuration::SessionLimitsConfig;
+    use crate::models::capability::*;
+    use crate::models::{TokenScope, UserRole};
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    // Mock repository for testing the service layer
+    pub struct MockCapabilityRepository {
+        grants: Arc<Mutex<Vec<CapabilityGrant>>>,
+        reads: Arc<Mutex<usize>>,
+    }
+
+    impl MockCapabilityRepository {
+        pub fn new() -> Self {
+            Self {
+                grants: Arc::new(Mutex::new(Vec::new())),
+                reads: Arc::new(Mutex::new(0)),
+            }
+        }
+
+        pub fn reads(&self) -> usize {
+            *self.reads.lock().unwrap()
+        }
+    }
+
+    #[async_trait]
+    impl CapabilityRepository for MockCapabilityRepository {
+        async fn list(&self) -> Result<Vec<CapabilityGrant>, CapabilityError> {
+            *self.reads.lock().unwrap() += 1;
+            Ok(self.grants.lock().unwrap().clone())
+        }
+
+        async fn set_roles(
+            &self,
+            capability: Capability,
+            roles: &[UserRole],
+        ) -> Result<CapabilityGrant, CapabilityError> {
+            let mut grants = self.grants.lock().unwrap();
+            grants.retain(|grant| grant.capability != capability);
+            let grant = CapabilityGrant {
+                capability,
+                description: None,
+                roles: roles.to_vec(),
+            };
+            grants.push(grant.clone());
+            Ok(grant)
+        }
+    }
+
+    // Entity tests
+    #[test]
+    fn test_capability_names_round_trip() {
+        for capability in Capability::ALL {
+            assert_eq!(Capability::frm_str(capability.as_str()), Some(capability));
+        }
+        assert_eq!(Capability::frm_str("invalid"), None);
+    }
+
+    #[test]
+    fn test_ownership_and_token_scopes() {
+        assert_eq!(
+            Capability::EditPosts.over_others(),
+            Capability::EditOthersPosts
+        );
+        assert_eq!(
+            Capability::DeletePosts.over_others(),
+            Capability::DeleteOthersPosts
+        );
+        assert_eq!(
+            Capability::PublishPosts.over_others(),
+            Capability::PublishPosts
+        );
+
+        assert_eq!(Capability::EditPosts.scope(), TokenScope::PostsWrite);
+        assert_eq!(Capability::UploadMedia.scope(), TokenScope::MediaWrite);
+        assert_eq!(Capability::ManageUsers.scope(), TokenScope::Admin);
+    }
+
+    // Service tests
+    #[tokio::test]
+    async fn test_default_roles_apply_until_changed() {
+        let repository = Arc::new(MockCapabilityRepository::new());
+        let service = CapabilityServiceImpl::new(repository.clone());
+
+        let admin = service.capabilities_for(UserRole::Admin).await.unwrap();
+        assert_eq!(admin.len(), Capability::ALL.len());
+
+        let author = service.capabilities_for(UserRole::Author).await.unwrap();
+        assert!(author.contains(&Capability::EditPosts));
+        assert!(author.contains(&Capability::PublishPosts));
+        assert!(!author.contains(&Capability::EditOthersPosts));
+        assert!(!author.contains(&Capability::ModerateComments));
+
+        let contributor = service
+            .capabilities_for(UserRole::Contributor)
+            .await
+            .unwrap();
+        assert!(contributor.contains(&Capability::EditPosts));
+        assert!(!contributor.contains(&Capability::PublishPosts));
+
+        let subscriber = service
+            .capabilities_for(UserRole::Subscriber)
+            .await
+            .unwrap();
+        assert!(subscriber.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_updated_roles_take_effect_immediately() {
+        let repository = Arc::new(MockCapabilityRepository::new());
+        let service = CapabilityServiceImpl::new(repository.clone());
+        let author = service.capabilities_for(UserRole::Author).await.unwrap();
+        assert!(!author.contains(&Capability::ModerateComments));
+        // The mapping is cached between checks
+        service.capabilities_for(UserRole::Author).await.unwrap();
+        assert_eq!(repository.reads(), 1);
+
+        let updated = service
+            .update_capability_roles(
+                Uuid::new_v4(),
+                Capability::ModerateComments,
+                UpdateCapabilityRolesRequest {
+                    roles: vec![UserRole::Author, UserRole::Admin, UserRole::Author],
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.roles, vec![UserRole::Admin, UserRole::Author]);
+
+        let author = service.capabilities_for(UserRole::Author).await.unwrap();
+        assert!(author.contains(&Capability::ModerateComments));
+        let editor = service.capabilities_for(UserRole::Editor).await.unwrap();
+        assert!(!editor.contains(&Capability::ModerateComments));
+
+        let listed = service.list_capabilities().await.unwrap();
+        assert_eq!(listed.len(), Capability::ALL.len());
+    }
+
+    #[tokio::test]
+    async fn test_admins_keep_manage_capabilities() {
+        let repository = Arc::new(MockCapabilityRepository::new());
+        let service = CapabilityServiceImpl::new(repository.clone());
+
+        let result = service
+            .update_capability_roles(
+                Uuid::new_v4(),
+                Capability::ManageCapabilities,
+                UpdateCapabilityRolesRequest {
+                    roles: vec![UserRole::Editor],
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(CapabilityError::Validation { .. })));
+
+        let admin = service.capabilities_for(UserRole::Admin).await.unwrap();
+        assert!(admin.contains(&Capability::ManageCapabilities));
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl_is_configured() {
+        let repository = Arc::new(MockCapabilityRepository::new());
+        let service =
+            CapabilityServiceImpl::new(repository.clone()).with_config(SessionLimitsConfig {
+                capability_cache_ttl: Duration::ZERO,
+                ..SessionLimitsConfig::default()
+            });
+
+        service.capabilities_for(UserRole::Author).await.unwrap();
+        service.capabilities_for(UserRole::Author).await.unwrap();
+        assert_eq!(repository.reads(), 2);
+    }
+}
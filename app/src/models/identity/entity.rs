@@ -0,0 +1,37 @@
+// app/src/models/identity/entity.rs
+
+// dependencies
+use crate::models::UserRole;
+use uuid::Uuid;
+
+// Who an identity provider vouched for, after the ID token passed every check
+#[derive(Debug, Clone)]
+pub struct VerifiedIdentity {
+    /// The configured name of the provider
+    pub provider: String,
+    /// The stable id the provider knows the user by
+    pub subject: String,
+    pub email: Option<String>,
+    /// Whether the provider confirmed that the user owns `email`
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+    pub name: Option<String>,
+}
+
+// The parts of an account needed to sign it in through an identity provider
+#[derive(Debug, Clone)]
+pub struct IdentityAccount {
+    pub id: Uuid,
+    pub username: String,
+    pub role: UserRole,
+    pub is_active: bool,
+    pub email_verified: bool,
+}
+
+// Everything needed to create an account for an identity nobody registered yet
+#[derive(Debug, Clone)]
+pub struct NewIdentityAccount {
+    pub username: String,
+    pub email: String,
+    pub display_name: Option<String>,
+}
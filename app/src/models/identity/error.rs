@@ -0,0 +1,40 @@
+// app/src/models/identity/error.rs
+
+use crate::response::IntoApiError;
+use pavex::http::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum IdentityError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("The identity provider did not confirm your email address")]
+    EmailNotVerified,
+
+    #[error("Verify the email address of your account before signing in with {provider}")]
+    UnverifiedAccount { provider: String },
+
+    #[error("Invalid credentials")]
+    AccountDisabled,
+
+    #[error("Registration is closed, ask an admin for an invitation")]
+    RegistrationClosed,
+}
+
+impl IntoApiError for IdentityError {
+    fn code(&self) -> Option<u16> {
+        match self {
+            IdentityError::EmailNotVerified | IdentityError::UnverifiedAccount { .. } => {
+                Some(StatusCode::BAD_REQUEST.as_u16())
+            }
+            IdentityError::AccountDisabled => Some(StatusCode::UNAUTHORIZED.as_u16()),
+            IdentityError::RegistrationClosed => Some(StatusCode::FORBIDDEN.as_u16()),
+            IdentityError::Database(_) => Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16()),
+        }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,170 @@
+// app/src/models/identity/repository.rs
+
+// dependencies
+use super::entity::{IdentityAccount, NewIdentityAccount, VerifiedIdentity};
+use super::error::IdentityError;
+use crate::models::{UNUSABLE_PASSWORD_HASH, UserRole};
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+// traits
+#[async_trait]
+pub trait IdentityRepository: Send + Sync {
+    async fn find_by_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<IdentityAccount>, IdentityError>;
+    async fn find_by_email(&self, email: &str) -> Result<Option<IdentityAccount>, IdentityError>;
+    async fn username_taken(&self, username: &str) -> Result<bool, IdentityError>;
+    async fn link(&self, user_id: Uuid, identity: &VerifiedIdentity) -> Result<(), IdentityError>;
+    /// Create a password-less account together with its first identity
+    async fn create_account(
+        &self,
+        account: NewIdentityAccount,
+        identity: &VerifiedIdentity,
+    ) -> Result<IdentityAccount, IdentityError>;
+    async fn touch(&self, provider: &str, subject: &str) -> Result<(), IdentityError>;
+}
+
+pub struct SqlxIdentityRepository {
+    pool: PgPool,
+}
+
+impl SqlxIdentityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Helper function to map database row to IdentityAccount struct
+    fn map_row_to_account(row: &sqlx::postgres::PgRow) -> IdentityAccount {
+        IdentityAccount {
+            id: row.get("id"),
+            username: row.get("username"),
+            role: row.get::<UserRole, _>("role"),
+            is_active: row.get("is_active"),
+            email_verified: row.get("email_verified"),
+        }
+    }
+}
+
+#[async_trait]
+impl IdentityRepository for SqlxIdentityRepository {
+    async fn find_by_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<IdentityAccount>, IdentityError> {
+        let row = sqlx::query(
+            r#"
+            SELECT u.id, u.username, u.role, u.is_active, u.email_verified
+            FROM user_identities i
+            JOIN users u ON u.id = i.user_id
+            WHERE i.provider = $1 AND i.subject = $2
+            "#,
+        )
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Self::map_row_to_account))
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<IdentityAccount>, IdentityError> {
+        // Providers do not necessarily preserve the case the address was registered with
+        let row = sqlx::query(
+            r#"
+            SELECT id, username, role, is_active, email_verified
+            FROM users
+            WHERE LOWER(email) = LOWER($1)
+            "#,
+        )
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Self::map_row_to_account))
+    }
+
+    async fn username_taken(&self, username: &str) -> Result<bool, IdentityError> {
+        let taken: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE username = $1)")
+                .bind(username)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(taken)
+    }
+
+    async fn link(&self, user_id: Uuid, identity: &VerifiedIdentity) -> Result<(), IdentityError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_identities (user_id, provider, subject, email, last_used_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (provider, subject) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(&identity.provider)
+        .bind(&identity.subject)
+        .bind(&identity.email)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_account(
+        &self,
+        account: NewIdentityAccount,
+        identity: &VerifiedIdentity,
+    ) -> Result<IdentityAccount, IdentityError> {
+        let mut tx = self.pool.begin().await?;
+
+        // The provider confirmed the address, and the account has no password to sign in with
+        let row = sqlx::query(
+            r#"
+            INSERT INTO users (username, email, password_hash, display_name, role, email_verified)
+            VALUES ($1, $2, $3, $4, 'subscriber', true)
+            RETURNING id, username, role, is_active, email_verified
+            "#,
+        )
+        .bind(&account.username)
+        .bind(&account.email)
+        .bind(UNUSABLE_PASSWORD_HASH)
+        .bind(&account.display_name)
+        .fetch_one(&mut *tx)
+        .await?;
+        let created = Self::map_row_to_account(&row);
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_identities (user_id, provider, subject, email, last_used_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            "#,
+        )
+        .bind(created.id)
+        .bind(&identity.provider)
+        .bind(&identity.subject)
+        .bind(&identity.email)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    async fn touch(&self, provider: &str, subject: &str) -> Result<(), IdentityError> {
+        sqlx::query(
+            "UPDATE user_identities SET last_used_at = NOW() WHERE provider = $1 AND subject = $2",
+        )
+        .bind(provider)
+        .bind(subject)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+// app/src/models/identity/service.rs
+
+// dependencies
+use super::entity::{IdentityAccount, NewIdentityAccount, VerifiedIdentity};
+use super::error::IdentityError;
+use super::repository::IdentityRepository;
+use crate::configuration::{RegistrationConfig, RegistrationMode};
+use crate::models::UserSummary;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use async_trait::async_trait;
+use data_encoding::HEXLOWER;
+use std::sync::Arc;
+
+// usernames are 3-50 characters, leaving room for a numeric suffix
+const USERNAME_BASE_MAX: usize = 40;
+const USERNAME_NUMBERED_ATTEMPTS: u32 = 20;
+
+// traits
+#[async_trait]
+pub trait IdentityService: Send + Sync {
+    /// Find, link or create the account `identity` signs in to.
+    async fn sign_in(&self, identity: VerifiedIdentity) -> Result<UserSummary, IdentityError>;
+}
+
+pub struct IdentityServiceImpl {
+    repository: Arc<dyn IdentityRepository>,
+    registration: RegistrationConfig,
+}
+
+impl IdentityServiceImpl {
+    pub fn new(repository: Arc<dyn IdentityRepository>) -> Self {
+        Self {
+            repository,
+            registration: RegistrationConfig::default(),
+        }
+    }
+
+    pub fn with_registration(self, registration: RegistrationConfig) -> Self {
+        Self {
+            registration,
+            ..self
+        }
+    }
+
+    /// The username candidate for an identity, limited to what registration accepts.
+    pub fn username_base(identity: &VerifiedIdentity) -> String {
+        let source = identity
+            .preferred_username
+            .as_deref()
+            .or_else(|| identity.email.as_deref()?.split('@').next())
+            .unwrap_or_default();
+        let base: String = source
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .take(USERNAME_BASE_MAX)
+            .collect();
+        let base = base.trim_matches('_').to_string();
+
+        if base.len() < 3 {
+            format!("user_{base}").trim_end_matches('_').to_string()
+        } else {
+            base
+        }
+    }
+
+    /// A free username, numbered if the plain one is taken.
+    async fn free_username(&self, identity: &VerifiedIdentity) -> Result<String, IdentityError> {
+        let base = Self::username_base(identity);
+        if !self.repository.username_taken(&base).await? {
+            return Ok(base);
+        }
+        for n in 2..USERNAME_NUMBERED_ATTEMPTS + 2 {
+            let candidate = format!("{base}_{n}");
+            if !self.repository.username_taken(&candidate).await? {
+                return Ok(candidate);
+            }
+        }
+
+        let mut bytes = [0u8; 4];
+        OsRng.fill_bytes(&mut bytes);
+        Ok(format!("{base}_{}", HEXLOWER.encode(&bytes)))
+    }
+
+    fn summary(account: IdentityAccount) -> UserSummary {
+        UserSummary {
+            id: account.id,
+            username: account.username,
+            display_name: None,
+            avatar_url: None,
+            role: account.role,
+        }
+    }
+}
+
+#[async_trait]
+impl IdentityService for IdentityServiceImpl {
+    async fn sign_in(&self, identity: VerifiedIdentity) -> Result<UserSummary, IdentityError> {
+        // An identity signed in before leads straight to its account
+        if let Some(account) = self
+            .repository
+            .find_by_identity(&identity.provider, &identity.subject)
+            .await?
+        {
+            if !account.is_active {
+                return Err(IdentityError::AccountDisabled);
+            }
+            self.repository
+                .touch(&identity.provider, &identity.subject)
+                .await?;
+            return Ok(Self::summary(account));
+        }
+
+        // Otherwise the email address is all that ties the identity to an account
+        let email = match identity.email.as_deref() {
+            Some(email) if identity.email_verified => email.trim().to_string(),
+            _ => return Err(IdentityError::EmailNotVerified),
+        };
+
+        if let Some(account) = self.repository.find_by_email(&email).await? {
+            // Someone who registered the address without proving it must not gain the identity
+            if !account.email_verified {
+                return Err(IdentityError::UnverifiedAccount {
+                    provider: identity.provider,
+                });
+            }
+            if !account.is_active {
+                return Err(IdentityError::AccountDisabled);
+            }
+            self.repository.link(account.id, &identity).await?;
+            tracing::info!(
+                user.id = %account.id,
+                provider = %identity.provider,
+                "Identity linked to an existing account"
+            );
+            return Ok(Self::summary(account));
+        }
+
+        if self.registration.mode != RegistrationMode::Open {
+            return Err(IdentityError::RegistrationClosed);
+        }
+        let username = self.free_username(&identity).await?;
+        let display_name = identity
+            .name
+            .as_deref()
+            .map(|name| name.chars().take(100).collect());
+        let account = self
+            .repository
+            .create_account(
+                NewIdentityAccount {
+                    username,
+                    email,
+                    display_name,
+                },
+                &identity,
+            )
+            .await?;
+        tracing::info!(
+            user.id = %account.id,
+            provider = %identity.provider,
+            "Account created from an identity"
+        );
+
+        Ok(Self::summary(account))
+    }
+}
@@ -0,0 +1,239 @@
+// app/src/models/identity/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::configuration::{RegistrationConfig, RegistrationMode};
+    use crate::models::UserRole;
+    use crate::models::identity::*;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    // An account of the mock repository, along with its email address
+    #[derive(Clone)]
+    struct MockAccount {
+        account: IdentityAccount,
+        email: String,
+    }
+
+    // Mock repository for testing the service layer
+    pub struct MockIdentityRepository {
+        accounts: Arc<Mutex<Vec<MockAccount>>>,
+        identities: Arc<Mutex<HashMap<(String, String), Uuid>>>,
+    }
+
+    impl MockIdentityRepository {
+        pub fn new() -> Self {
+            Self {
+                accounts: Arc::new(Mutex::new(Vec::new())),
+                identities: Arc::new(Mutex::new(HashMap::new())),
+            }
+        }
+
+        pub fn add_account(&self, username: &str, email: &str, email_verified: bool) -> Uuid {
+            let id = Uuid::new_v4();
+            self.accounts.lock().unwrap().push(MockAccount {
+                account: IdentityAccount {
+                    id,
+                    username: username.to_string(),
+                    role: UserRole::Author,
+                    is_active: true,
+                    email_verified,
+                },
+                email: email.to_string(),
+            });
+            id
+        }
+
+        pub fn account_count(&self) -> usize {
+            self.accounts.lock().unwrap().len()
+        }
+
+        pub fn linked_to(&self, provider: &str, subject: &str) -> Option<Uuid> {
+            self.identities
+                .lock()
+                .unwrap()
+                .get(&(provider.to_string(), subject.to_string()))
+                .copied()
+        }
+    }
+
+    #[async_trait]
+    impl IdentityRepository for MockIdentityRepository {
+        async fn find_by_identity(
+            &self,
+            provider: &str,
+            subject: &str,
+        ) -> Result<Option<IdentityAccount>, IdentityError> {
+            let Some(id) = self.linked_to(provider, subject) else {
+                return Ok(None);
+            };
+            Ok(self
+                .accounts
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|a| a.account.id == id)
+                .map(|a| a.account.clone()))
+        }
+
+        async fn find_by_email(
+            &self,
+            email: &str,
+        ) -> Result<Option<IdentityAccount>, IdentityError> {
+            Ok(self
+                .accounts
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|a| a.email.eq_ignore_ascii_case(email))
+                .map(|a| a.account.clone()))
+        }
+
+        async fn username_taken(&self, username: &str) -> Result<bool, IdentityError> {
+            Ok(self
+                .accounts
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|a| a.account.username == username))
+        }
+
+        async fn link(
+            &self,
+            user_id: Uuid,
+            identity: &VerifiedIdentity,
+        ) -> Result<(), IdentityError> {
+            self.identities.lock().unwrap().insert(
+                (identity.provider.clone(), identity.subject.clone()),
+                user_id,
+            );
+            Ok(())
+        }
+
+        async fn create_account(
+            &self,
+            account: NewIdentityAccount,
+            identity: &VerifiedIdentity,
+        ) -> Result<IdentityAccount, IdentityError> {
+            let created = IdentityAccount {
+                id: Uuid::new_v4(),
+                username: account.username,
+                role: UserRole::Subscriber,
+                is_active: true,
+                email_verified: true,
+            };
+            self.accounts.lock().unwrap().push(MockAccount {
+                account: created.clone(),
+                email: account.email,
+            });
+            self.link(created.id, identity).await?;
+            Ok(created)
+        }
+
+        async fn touch(&self, _provider: &str, _subject: &str) -> Result<(), IdentityError> {
+            Ok(())
+        }
+    }
+
+    fn identity(subject: &str, email: &str, email_verified: bool) -> VerifiedIdentity {
+        VerifiedIdentity {
+            provider: "stub".to_string(),
+            subject: subject.to_string(),
+            email: Some(email.to_string()),
+            email_verified,
+            preferred_username: None,
+            name: Some("Jane Doe".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_identity_links_to_the_account_with_its_verified_email() {
+        let repository = Arc::new(MockIdentityRepository::new());
+        let service = IdentityServiceImpl::new(repository.clone());
+        let id = repository.add_account("jane", "jane@example.com", true);
+
+        let summary = service
+            .sign_in(identity("sub-1", "Jane@Example.com", true))
+            .await
+            .unwrap();
+        assert_eq!(summary.id, id);
+        assert_eq!(summary.role, UserRole::Author);
+        assert_eq!(repository.linked_to("stub", "sub-1"), Some(id));
+
+        // Once linked, the subject is enough, even if the address changed at the provider
+        let summary = service
+            .sign_in(identity("sub-1", "other@example.com", false))
+            .await
+            .unwrap();
+        assert_eq!(summary.id, id);
+        assert_eq!(repository.account_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unverified_addresses_never_link_accounts() {
+        let repository = Arc::new(MockIdentityRepository::new());
+        let service = IdentityServiceImpl::new(repository.clone());
+        repository.add_account("jane", "jane@example.com", true);
+        repository.add_account("squatter", "victim@example.com", false);
+
+        let result = service
+            .sign_in(identity("sub-1", "jane@example.com", false))
+            .await;
+        assert!(matches!(result, Err(IdentityError::EmailNotVerified)));
+
+        let result = service
+            .sign_in(identity("sub-2", "victim@example.com", true))
+            .await;
+        assert!(matches!(
+            result,
+            Err(IdentityError::UnverifiedAccount { .. })
+        ));
+        assert_eq!(repository.linked_to("stub", "sub-2"), None);
+    }
+
+    #[tokio::test]
+    async fn test_new_identity_creates_an_account_while_registration_is_open() {
+        let repository = Arc::new(MockIdentityRepository::new());
+        let service = IdentityServiceImpl::new(repository.clone());
+        repository.add_account("jane_doe", "someone@example.com", true);
+
+        let summary = service
+            .sign_in(VerifiedIdentity {
+                preferred_username: Some("jane.doe".to_string()),
+                ..identity("sub-1", "jane@example.com", true)
+            })
+            .await
+            .unwrap();
+        assert_eq!(summary.username, "jane_doe_2");
+        assert_eq!(summary.role, UserRole::Subscriber);
+
+        let service = IdentityServiceImpl::new(repository.clone()).with_registration(
+            RegistrationConfig {
+                mode: RegistrationMode::InviteOnly,
+                ..RegistrationConfig::default()
+            },
+        );
+        let result = service
+            .sign_in(identity("sub-2", "new@example.com", true))
+            .await;
+        assert!(matches!(result, Err(IdentityError::RegistrationClosed)));
+        assert_eq!(repository.account_count(), 2);
+    }
+
+    #[test]
+    fn test_username_base_is_a_valid_username() {
+        let base = IdentityServiceImpl::username_base(&identity("s", "j.o-e+x@example.com", true));
+        assert_eq!(base, "j_o_e_x");
+
+        let base = IdentityServiceImpl::username_base(&identity("s", "jo@example.com", true));
+        assert_eq!(base, "user_jo");
+
+        let base = IdentityServiceImpl::username_base(&VerifiedIdentity {
+            email: None,
+            ..identity("s", "", true)
+        });
+        assert_eq!(base, "user");
+    }
+}
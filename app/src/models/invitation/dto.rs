@@ -0,0 +1,64 @@
+// app/src/models/invitation/dto.rs
+
+// dependencies
+use super::{Invitation, InvitationStatus};
+use crate::models::UserRole;
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Request DTO for invitation creation
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct CreateInvitationRequest {
+    #[validate(email(message = "Invalid email address"))]
+    #[validate(length(max = 255, message = "Email cannot exceed 255 characters"))]
+    pub email: String,
+
+    /// The role the invited account is created with
+    #[serde(default)]
+    pub role: UserRole,
+
+    /// Invitations without an expiry use the configured `invitation_ttl`
+    #[validate(range(min = 1, max = 90, message = "Expiry must be 1-90 days"))]
+    pub expires_in_days: Option<u32>,
+}
+
+/// Public invitation response DTO - never carries the token itself
+#[derive(Debug, Serialize)]
+pub struct InvitationResponse {
+    pub id: Uuid,
+    pub email: String,
+    pub role: UserRole,
+    pub status: InvitationStatus,
+    pub invited_by: Option<Uuid>,
+    pub expires_at: Timestamp,
+    pub accepted_at: Option<Timestamp>,
+    pub accepted_by: Option<Uuid>,
+    pub created_at: Timestamp,
+}
+
+/// Created invitation response DTO - the only time the token is shown
+#[derive(Debug, Serialize)]
+pub struct CreatedInvitationResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub details: InvitationResponse,
+}
+
+// Conversion implementations
+impl From<Invitation> for InvitationResponse {
+    fn from(invitation: Invitation) -> Self {
+        Self {
+            id: invitation.id,
+            status: invitation.status(Timestamp::now()),
+            email: invitation.email,
+            role: invitation.role,
+            invited_by: invitation.invited_by,
+            expires_at: invitation.expires_at,
+            accepted_at: invitation.accepted_at,
+            accepted_by: invitation.accepted_by,
+            created_at: invitation.created_at,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// app/src/models/invitation/entity.rs
+
+// dependencies
+use crate::models::UserRole;
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Where an invitation stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Expired,
+}
+
+// Invitation to create an account with a pre-assigned role, the token itself is only ever stored hashed
+#[derive(Debug, Clone)]
+pub struct Invitation {
+    pub id: Uuid,
+    pub email: String,
+    pub role: UserRole,
+    pub token_hash: String,
+    /// Unset once the admin who sent it is deleted
+    pub invited_by: Option<Uuid>,
+    pub expires_at: Timestamp,
+    /// Set as soon as a registration claims the invitation
+    pub accepted_at: Option<Timestamp>,
+    pub accepted_by: Option<Uuid>,
+    pub created_at: Timestamp,
+}
+
+impl Invitation {
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at <= now
+    }
+
+    pub fn status(&self, now: Timestamp) -> InvitationStatus {
+        if self.accepted_at.is_some() {
+            InvitationStatus::Accepted
+        } else if self.is_expired(now) {
+            InvitationStatus::Expired
+        } else {
+            InvitationStatus::Pending
+        }
+    }
+}
+
+// Everything needed to store a new invitation
+#[derive(Debug, Clone)]
+pub struct NewInvitation {
+    pub email: String,
+    pub role: UserRole,
+    pub token_hash: String,
+    pub invited_by: Uuid,
+    pub expires_at: Timestamp,
+}
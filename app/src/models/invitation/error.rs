@@ -0,0 +1,36 @@
+// app/src/models/invitation/error.rs
+
+use crate::response::IntoApiError;
+use pavex::http::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum InvitationError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Validation error: {message}")]
+    Validation { message: String },
+
+    #[error("Invitation not found")]
+    InvitationNotFound,
+
+    #[error("Invalid, expired or already used invitation")]
+    InvalidInvitation,
+}
+
+impl IntoApiError for InvitationError {
+    fn code(&self) -> Option<u16> {
+        match self {
+            InvitationError::Validation { .. } | InvitationError::InvalidInvitation => {
+                Some(StatusCode::BAD_REQUEST.as_u16())
+            }
+            InvitationError::InvitationNotFound => Some(StatusCode::NOT_FOUND.as_u16()),
+            InvitationError::Database(_) => Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16()),
+        }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
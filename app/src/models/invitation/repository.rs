@@ -0,0 +1,152 @@
+// app/src/models/invitation/repository.rs
+
+// dependencies
+use super::entity::{Invitation, NewInvitation};
+use super::error::InvitationError;
+use crate::models::UserRole;
+use async_trait::async_trait;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+// traits
+#[async_trait]
+pub trait InvitationRepository: Send + Sync {
+    async fn create(&self, invitation: NewInvitation) -> Result<Invitation, InvitationError>;
+    async fn list(&self) -> Result<Vec<Invitation>, InvitationError>;
+    async fn delete_pending(&self, id: Uuid) -> Result<bool, InvitationError>;
+    async fn email_registered(&self, email: &str) -> Result<bool, InvitationError>;
+    // Redemption, an invitation is claimed before the account is created and released if that fails
+    async fn claim(&self, token_hash: &str) -> Result<Option<Invitation>, InvitationError>;
+    async fn release(&self, id: Uuid) -> Result<(), InvitationError>;
+    async fn set_accepted_by(&self, id: Uuid, user_id: Uuid) -> Result<(), InvitationError>;
+}
+
+pub struct SqlxInvitationRepository {
+    pool: PgPool,
+}
+
+impl SqlxInvitationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Helper function to map database row to Invitation struct
+    fn map_row_to_invitation(row: &sqlx::postgres::PgRow) -> Invitation {
+        Invitation {
+            id: row.get("id"),
+            email: row.get("email"),
+            role: row.get::<UserRole, _>("role"),
+            token_hash: row.get("token_hash"),
+            invited_by: row.get("invited_by"),
+            expires_at: row.get::<SqlxTimestamp, _>("expires_at").into(),
+            accepted_at: row
+                .get::<Option<SqlxTimestamp>, _>("accepted_at")
+                .map(|t| t.into()),
+            accepted_by: row.get("accepted_by"),
+            created_at: row.get::<SqlxTimestamp, _>("created_at").into(),
+        }
+    }
+}
+
+#[async_trait]
+impl InvitationRepository for SqlxInvitationRepository {
+    async fn create(&self, invitation: NewInvitation) -> Result<Invitation, InvitationError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO invitations (email, role, token_hash, invited_by, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING
+                id, email, role, token_hash, invited_by,
+                expires_at, accepted_at, accepted_by, created_at
+            "#,
+        )
+        .bind(&invitation.email)
+        .bind(invitation.role)
+        .bind(&invitation.token_hash)
+        .bind(invitation.invited_by)
+        .bind(SqlxTimestamp::from(invitation.expires_at))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Self::map_row_to_invitation(&row))
+    }
+
+    async fn list(&self) -> Result<Vec<Invitation>, InvitationError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, email, role, token_hash, invited_by,
+                expires_at, accepted_at, accepted_by, created_at
+            FROM invitations
+            ORDER BY created_at DESC, id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::map_row_to_invitation).collect())
+    }
+
+    async fn delete_pending(&self, id: Uuid) -> Result<bool, InvitationError> {
+        // Accepted invitations are kept, they record how an account was created
+        let result = sqlx::query("DELETE FROM invitations WHERE id = $1 AND accepted_at IS NULL")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn email_registered(&self, email: &str) -> Result<bool, InvitationError> {
+        let registered: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE email = $1)")
+                .bind(email)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(registered)
+    }
+
+    async fn claim(&self, token_hash: &str) -> Result<Option<Invitation>, InvitationError> {
+        // A single statement, so two registrations racing for one invitation cannot both win
+        let row = sqlx::query(
+            r#"
+            UPDATE invitations
+            SET accepted_at = NOW()
+            WHERE token_hash = $1
+            AND accepted_at IS NULL
+            AND expires_at > NOW()
+            RETURNING
+                id, email, role, token_hash, invited_by,
+                expires_at, accepted_at, accepted_by, created_at
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Self::map_row_to_invitation))
+    }
+
+    async fn release(&self, id: Uuid) -> Result<(), InvitationError> {
+        sqlx::query(
+            "UPDATE invitations SET accepted_at = NULL WHERE id = $1 AND accepted_by IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_accepted_by(&self, id: Uuid, user_id: Uuid) -> Result<(), InvitationError> {
+        sqlx::query("UPDATE invitations SET accepted_by = $2 WHERE id = $1")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
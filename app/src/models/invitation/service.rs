@@ -0,0 +1,161 @@
+// app/src/models/invitation/service.rs
+
+// dependencies
+use super::dto::{CreateInvitationRequest, CreatedInvitationResponse, InvitationResponse};
+use super::entity::{Invitation, NewInvitation};
+use super::error::InvitationError;
+use super::repository::InvitationRepository;
+use crate::configuration::RegistrationConfig;
+use crate::tokens::TokenHasher;
+use async_trait::async_trait;
+use pavex::time::Timestamp;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+use validator::Validate;
+
+// traits
+#[async_trait]
+pub trait InvitationService: Send + Sync {
+    // Administration related operations, `actor` is the id of the acting admin
+    async fn create_invitation(
+        &self,
+        actor: Uuid,
+        request: CreateInvitationRequest,
+    ) -> Result<CreatedInvitationResponse, InvitationError>;
+    async fn list_invitations(&self) -> Result<Vec<InvitationResponse>, InvitationError>;
+    async fn revoke_invitation(&self, actor: Uuid, id: Uuid) -> Result<(), InvitationError>;
+    // Redemption related operations, used while registering with an invite link
+    async fn claim_invitation(
+        &self,
+        token: &str,
+        email: &str,
+    ) -> Result<Invitation, InvitationError>;
+    async fn release_invitation(&self, id: Uuid) -> Result<(), InvitationError>;
+    async fn complete_invitation(&self, id: Uuid, user_id: Uuid) -> Result<(), InvitationError>;
+}
+
+pub struct InvitationServiceImpl {
+    repository: Arc<dyn InvitationRepository>,
+    registration: RegistrationConfig,
+    tokens: TokenHasher,
+}
+
+impl InvitationServiceImpl {
+    pub fn new(repository: Arc<dyn InvitationRepository>, tokens: TokenHasher) -> Self {
+        Self {
+            repository,
+            registration: RegistrationConfig::default(),
+            tokens,
+        }
+    }
+
+    pub fn with_registration(self, registration: RegistrationConfig) -> Self {
+        Self {
+            registration,
+            ..self
+        }
+    }
+}
+
+#[async_trait]
+impl InvitationService for InvitationServiceImpl {
+    async fn create_invitation(
+        &self,
+        actor: Uuid,
+        request: CreateInvitationRequest,
+    ) -> Result<CreatedInvitationResponse, InvitationError> {
+        // Validate input
+        request
+            .validate()
+            .map_err(|e| InvitationError::Validation {
+                message: format!("Validation failed: {e}"),
+            })?;
+        let email = request.email.trim().to_string();
+        if self.repository.email_registered(&email).await? {
+            return Err(InvitationError::Validation {
+                message: "An account with this email address already exists".into(),
+            });
+        }
+
+        let ttl = request
+            .expires_in_days
+            .map_or(self.registration.invitation_ttl, |days| {
+                Duration::from_secs(u64::from(days) * 24 * 60 * 60)
+            });
+        let token = TokenHasher::generate();
+        let invitation = self
+            .repository
+            .create(NewInvitation {
+                email,
+                role: request.role,
+                token_hash: self.tokens.hash(&token),
+                invited_by: actor,
+                expires_at: Timestamp::now() + ttl,
+            })
+            .await?;
+        tracing::info!(
+            invitation.id = %invitation.id,
+            admin.id = %actor,
+            role = ?invitation.role,
+            "Invitation created"
+        );
+
+        Ok(CreatedInvitationResponse {
+            token,
+            details: invitation.into(),
+        })
+    }
+
+    async fn list_invitations(&self) -> Result<Vec<InvitationResponse>, InvitationError> {
+        let invitations = self.repository.list().await?;
+
+        Ok(invitations
+            .into_iter()
+            .map(InvitationResponse::from)
+            .collect())
+    }
+
+    async fn revoke_invitation(&self, actor: Uuid, id: Uuid) -> Result<(), InvitationError> {
+        // Accepted invitations look exactly like missing ones
+        if !self.repository.delete_pending(id).await? {
+            return Err(InvitationError::InvitationNotFound);
+        }
+        tracing::info!(invitation.id = %id, admin.id = %actor, "Invitation revoked");
+
+        Ok(())
+    }
+
+    async fn claim_invitation(
+        &self,
+        token: &str,
+        email: &str,
+    ) -> Result<Invitation, InvitationError> {
+        let invitation = self
+            .repository
+            .claim(&self.tokens.hash(token.trim()))
+            .await?
+            .ok_or(InvitationError::InvalidInvitation)?;
+
+        // The link only proves the address it was sent to
+        if !invitation.email.eq_ignore_ascii_case(email.trim()) {
+            self.repository.release(invitation.id).await?;
+            return Err(InvitationError::Validation {
+                message: "This invitation was sent to another email address".into(),
+            });
+        }
+
+        Ok(invitation)
+    }
+
+    async fn release_invitation(&self, id: Uuid) -> Result<(), InvitationError> {
+        self.repository.release(id).await
+    }
+
+    async fn complete_invitation(&self, id: Uuid, user_id: Uuid) -> Result<(), InvitationError> {
+        self.repository.set_accepted_by(id, user_id).await?;
+        tracing::info!(invitation.id = %id, user.id = %user_id, "Invitation accepted");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+// app/src/models/invitation/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::configuration::{RegistrationConfig, TokenHashingConfig};
+    use crate::models::UserRole;
+    use crate::models::invitation::*;
+    use crate::tokens::TokenHasher;
+    use async_trait::async_trait;
+    use pavex::time::Timestamp;
+    use secrecy::SecretString;
+    use std::collections::{HashMap, HashSet};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    // Mock repository for testing the service layer
+    pub struct MockInvitationRepository {
+        invitations: Arc<Mutex<HashMap<Uuid, Invitation>>>,
+        registered_emails: Arc<Mutex<HashSet<String>>>,
+    }
+
+    impl MockInvitationRepository {
+        pub fn new() -> Self {
+            Self {
+                invitations: Arc::new(Mutex::new(HashMap::new())),
+                registered_emails: Arc::new(Mutex::new(HashSet::new())),
+            }
+        }
+
+        pub fn register_email(&self, email: &str) {
+            self.registered_emails
+                .lock()
+                .unwrap()
+                .insert(email.to_string());
+        }
+
+        pub fn expire(&self, id: Uuid) {
+            let mut invitations = self.invitations.lock().unwrap();
+            if let Some(invitation) = invitations.get_mut(&id) {
+                invitation.expires_at = Timestamp::now() - Duration::from_secs(1);
+            }
+        }
+
+        pub fn get(&self, id: Uuid) -> Invitation {
+            self.invitations.lock().unwrap()[&id].clone()
+        }
+    }
+
+    #[async_trait]
+    impl InvitationRepository for MockInvitationRepository {
+        async fn create(&self, invitation: NewInvitation) -> Result<Invitation, InvitationError> {
+            let invitation = Invitation {
+                id: Uuid::new_v4(),
+                email: invitation.email,
+                role: invitation.role,
+                token_hash: invitation.token_hash,
+                invited_by: Some(invitation.invited_by),
+                expires_at: invitation.expires_at,
+                accepted_at: None,
+                accepted_by: None,
+                created_at: Timestamp::now(),
+            };
+            self.invitations
+                .lock()
+                .unwrap()
+                .insert(invitation.id, invitation.clone());
+            Ok(invitation)
+        }
+
+        async fn list(&self) -> Result<Vec<Invitation>, InvitationError> {
+            Ok(self.invitations.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn delete_pending(&self, id: Uuid) -> Result<bool, InvitationError> {
+            let mut invitations = self.invitations.lock().unwrap();
+            if invitations
+                .get(&id)
+                .is_some_and(|invitation| invitation.accepted_at.is_none())
+            {
+                invitations.remove(&id);
+                return Ok(true);
+            }
+            Ok(false)
+        }
+
+        async fn email_registered(&self, email: &str) -> Result<bool, InvitationError> {
+            Ok(self.registered_emails.lock().unwrap().contains(email))
+        }
+
+        async fn claim(&self, token_hash: &str) -> Result<Option<Invitation>, InvitationError> {
+            let mut invitations = self.invitations.lock().unwrap();
+            let now = Timestamp::now();
+            Ok(invitations
+                .values_mut()
+                .find(|invitation| {
+                    invitation.token_hash == token_hash
+                        && invitation.accepted_at.is_none()
+                        && !invitation.is_expired(now)
+                })
+                .map(|invitation| {
+                    invitation.accepted_at = Some(now);
+                    invitation.clone()
+                }))
+        }
+
+        async fn release(&self, id: Uuid) -> Result<(), InvitationError> {
+            let mut invitations = self.invitations.lock().unwrap();
+            if let Some(invitation) = invitations.get_mut(&id)
+                && invitation.accepted_by.is_none()
+            {
+                invitation.accepted_at = None;
+            }
+            Ok(())
+        }
+
+        async fn set_accepted_by(&self, id: Uuid, user_id: Uuid) -> Result<(), InvitationError> {
+            let mut invitations = self.invitations.lock().unwrap();
+            if let Some(invitation) = invitations.get_mut(&id) {
+                invitation.accepted_by = Some(user_id);
+            }
+            Ok(())
+        }
+    }
+
+    // the token hasher of every service under test
+    fn hasher() -> TokenHasher {
+        TokenHasher::new(&TokenHashingConfig {
+            secret: Some(SecretString::from("test-token-secret")),
+            ..TokenHashingConfig::default()
+        })
+    }
+
+    fn create_request(email: &str, role: UserRole) -> CreateInvitationRequest {
+        CreateInvitationRequest {
+            email: email.to_string(),
+            role,
+            expires_in_days: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_created_invitation_stores_only_the_hash_and_uses_the_configured_ttl() {
+        let repository = Arc::new(MockInvitationRepository::new());
+        let service = InvitationServiceImpl::new(repository.clone(), hasher()).with_registration(
+            RegistrationConfig {
+                invitation_ttl: Duration::from_secs(60 * 60),
+                ..RegistrationConfig::default()
+            },
+        );
+        let admin_id = Uuid::new_v4();
+
+        let created = service
+            .create_invitation(
+                admin_id,
+                create_request("new@example.com", UserRole::Author),
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.details.role, UserRole::Author);
+        assert_eq!(created.details.status, InvitationStatus::Pending);
+        assert_eq!(created.details.invited_by, Some(admin_id));
+
+        let stored = repository.get(created.details.id);
+        assert!(hasher().verify(&created.token, &stored.token_hash));
+        assert_ne!(stored.token_hash, created.token);
+        assert!(stored.expires_at <= Timestamp::now() + Duration::from_secs(60 * 60));
+        assert!(stored.expires_at > Timestamp::now() + Duration::from_secs(59 * 60));
+    }
+
+    #[tokio::test]
+    async fn test_invitations_for_registered_or_invalid_addresses_are_rejected() {
+        let repository = Arc::new(MockInvitationRepository::new());
+        let service = InvitationServiceImpl::new(repository.clone(), hasher());
+        repository.register_email("taken@example.com");
+
+        let result = service
+            .create_invitation(
+                Uuid::new_v4(),
+                create_request("taken@example.com", UserRole::Editor),
+            )
+            .await;
+        assert!(matches!(result, Err(InvitationError::Validation { .. })));
+
+        let result = service
+            .create_invitation(
+                Uuid::new_v4(),
+                create_request("not-an-email", UserRole::Editor),
+            )
+            .await;
+        assert!(matches!(result, Err(InvitationError::Validation { .. })));
+
+        let result = service
+            .create_invitation(
+                Uuid::new_v4(),
+                CreateInvitationRequest {
+                    expires_in_days: Some(0),
+                    ..create_request("new@example.com", UserRole::Editor)
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(InvitationError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_invitation_is_single_use() {
+        let repository = Arc::new(MockInvitationRepository::new());
+        let service = InvitationServiceImpl::new(repository.clone(), hasher());
+        let created = service
+            .create_invitation(
+                Uuid::new_v4(),
+                create_request("new@example.com", UserRole::Editor),
+            )
+            .await
+            .unwrap();
+
+        let invitation = service
+            .claim_invitation(&created.token, "NEW@example.com")
+            .await
+            .unwrap();
+        assert_eq!(invitation.role, UserRole::Editor);
+        let user_id = Uuid::new_v4();
+        service
+            .complete_invitation(invitation.id, user_id)
+            .await
+            .unwrap();
+        assert_eq!(repository.get(invitation.id).accepted_by, Some(user_id));
+
+        // Releasing a completed invitation does not make it usable again
+        service.release_invitation(invitation.id).await.unwrap();
+        let result = service
+            .claim_invitation(&created.token, "new@example.com")
+            .await;
+        assert!(matches!(result, Err(InvitationError::InvalidInvitation)));
+
+        // Accepted invitations cannot be revoked anymore
+        let result = service
+            .revoke_invitation(Uuid::new_v4(), invitation.id)
+            .await;
+        assert!(matches!(result, Err(InvitationError::InvitationNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_released_invitation_can_be_claimed_again() {
+        let repository = Arc::new(MockInvitationRepository::new());
+        let service = InvitationServiceImpl::new(repository.clone(), hasher());
+        let created = service
+            .create_invitation(
+                Uuid::new_v4(),
+                create_request("new@example.com", UserRole::Author),
+            )
+            .await
+            .unwrap();
+
+        let invitation = service
+            .claim_invitation(&created.token, "new@example.com")
+            .await
+            .unwrap();
+        let result = service
+            .claim_invitation(&created.token, "new@example.com")
+            .await;
+        assert!(matches!(result, Err(InvitationError::InvalidInvitation)));
+
+        service.release_invitation(invitation.id).await.unwrap();
+        assert!(
+            service
+                .claim_invitation(&created.token, "new@example.com")
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invitation_only_proves_its_own_address() {
+        let repository = Arc::new(MockInvitationRepository::new());
+        let service = InvitationServiceImpl::new(repository.clone(), hasher());
+        let created = service
+            .create_invitation(
+                Uuid::new_v4(),
+                create_request("new@example.com", UserRole::Author),
+            )
+            .await
+            .unwrap();
+
+        let result = service
+            .claim_invitation(&created.token, "other@example.com")
+            .await;
+        assert!(matches!(result, Err(InvitationError::Validation { .. })));
+        // The mismatch leaves the invitation usable by its addressee
+        assert!(repository.get(created.details.id).accepted_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_expired_revoked_and_unknown_invitations_are_rejected() {
+        let repository = Arc::new(MockInvitationRepository::new());
+        let service = InvitationServiceImpl::new(repository.clone(), hasher());
+        let admin_id = Uuid::new_v4();
+
+        let result = service.claim_invitation("unknown", "new@example.com").await;
+        assert!(matches!(result, Err(InvitationError::InvalidInvitation)));
+
+        let expired = service
+            .create_invitation(
+                admin_id,
+                create_request("new@example.com", UserRole::Author),
+            )
+            .await
+            .unwrap();
+        repository.expire(expired.details.id);
+        let listed = service.list_invitations().await.unwrap();
+        assert_eq!(listed[0].status, InvitationStatus::Expired);
+        let result = service
+            .claim_invitation(&expired.token, "new@example.com")
+            .await;
+        assert!(matches!(result, Err(InvitationError::InvalidInvitation)));
+
+        let revoked = service
+            .create_invitation(
+                admin_id,
+                create_request("new@example.com", UserRole::Author),
+            )
+            .await
+            .unwrap();
+        service
+            .revoke_invitation(admin_id, revoked.details.id)
+            .await
+            .unwrap();
+        let result = service
+            .claim_invitation(&revoked.token, "new@example.com")
+            .await;
+        assert!(matches!(result, Err(InvitationError::InvalidInvitation)));
+    }
+}
@@ -0,0 +1,27 @@
+// app/src/models/magic_link/dto.rs
+
+// dependencies
+use serde::Deserialize;
+use validator::Validate;
+
+/// Sign-in link request DTO
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct MagicLinkRequest {
+    #[validate(email(message = "Invalid email address"))]
+    #[validate(length(max = 255, message = "Email cannot exceed 255 characters"))]
+    pub email: String,
+}
+
+impl MagicLinkRequest {
+    /// The address accounts are looked up by, and requests are counted against.
+    pub fn normalized_email(&self) -> &str {
+        self.email.trim()
+    }
+}
+
+/// Request DTO carrying the token of a sign-in link, to sign in with it
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct MagicLinkTokenRequest {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+}
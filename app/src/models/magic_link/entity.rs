@@ -0,0 +1,34 @@
+// app/src/models/magic_link/entity.rs
+
+// dependencies
+use crate::models::UserRole;
+use pavex::time::Timestamp;
+use uuid::Uuid;
+
+// Emailed sign-in link, the token itself is only ever stored hashed
+#[derive(Debug, Clone)]
+pub struct MagicLink {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: Timestamp,
+    /// Set as soon as the link signs someone in
+    pub used_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+}
+
+impl MagicLink {
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at <= now
+    }
+}
+
+// The parts of an account deciding whether it may sign in with a link
+#[derive(Debug, Clone)]
+pub struct MagicLinkAccount {
+    pub id: Uuid,
+    pub email: String,
+    pub role: UserRole,
+    pub is_active: bool,
+    pub email_verified: bool,
+}
@@ -0,0 +1,31 @@
+// app/src/models/magic_link/error.rs
+
+use crate::response::IntoApiError;
+use pavex::http::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MagicLinkError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Validation error: {message}")]
+    Validation { message: String },
+
+    #[error("Invalid, expired or already used sign-in link")]
+    InvalidLink,
+}
+
+impl IntoApiError for MagicLinkError {
+    fn code(&self) -> Option<u16> {
+        match self {
+            MagicLinkError::Validation { .. } => Some(StatusCode::BAD_REQUEST.as_u16()),
+            MagicLinkError::InvalidLink => Some(StatusCode::UNAUTHORIZED.as_u16()),
+            MagicLinkError::Database(_) => Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16()),
+        }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
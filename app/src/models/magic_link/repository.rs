@@ -0,0 +1,148 @@
+// app/src/models/magic_link/repository.rs
+
+// dependencies
+use super::entity::{MagicLink, MagicLinkAccount};
+use super::error::MagicLinkError;
+use crate::models::UserRole;
+use async_trait::async_trait;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use pavex::time::Timestamp;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+// traits
+#[async_trait]
+pub trait MagicLinkRepository: Send + Sync {
+    async fn find_account_by_email(
+        &self,
+        email: &str,
+    ) -> Result<Option<MagicLinkAccount>, MagicLinkError>;
+    async fn find_account_by_id(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<MagicLinkAccount>, MagicLinkError>;
+    /// Store a new link for the user, replacing any link they have not used yet.
+    async fn create(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        expires_at: Timestamp,
+    ) -> Result<MagicLink, MagicLinkError>;
+    /// Mark the unused, unexpired link with this token hash as used and return it.
+    async fn claim(&self, token_hash: &str) -> Result<Option<MagicLink>, MagicLinkError>;
+}
+
+pub struct SqlxMagicLinkRepository {
+    pool: PgPool,
+}
+
+impl SqlxMagicLinkRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Helper function to map database row to MagicLink struct
+    fn map_row_to_magic_link(row: &sqlx::postgres::PgRow) -> MagicLink {
+        MagicLink {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            token_hash: row.get("token_hash"),
+            expires_at: row.get::<SqlxTimestamp, _>("expires_at").into(),
+            used_at: row
+                .get::<Option<SqlxTimestamp>, _>("used_at")
+                .map(|t| t.into()),
+            created_at: row.get::<SqlxTimestamp, _>("created_at").into(),
+        }
+    }
+
+    /// Helper function to map database row to MagicLinkAccount struct
+    fn map_row_to_account(row: &sqlx::postgres::PgRow) -> MagicLinkAccount {
+        MagicLinkAccount {
+            id: row.get("id"),
+            email: row.get("email"),
+            role: row.get::<UserRole, _>("role"),
+            is_active: row.get("is_active"),
+            email_verified: row.get("email_verified"),
+        }
+    }
+}
+
+#[async_trait]
+impl MagicLinkRepository for SqlxMagicLinkRepository {
+    async fn find_account_by_email(
+        &self,
+        email: &str,
+    ) -> Result<Option<MagicLinkAccount>, MagicLinkError> {
+        let row = sqlx::query(
+            "SELECT id, email, role, is_active, email_verified FROM users WHERE email = $1",
+        )
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Self::map_row_to_account))
+    }
+
+    async fn find_account_by_id(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<MagicLinkAccount>, MagicLinkError> {
+        let row = sqlx::query(
+            "SELECT id, email, role, is_active, email_verified FROM users WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Self::map_row_to_account))
+    }
+
+    async fn create(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        expires_at: Timestamp,
+    ) -> Result<MagicLink, MagicLinkError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Only the latest link works, used ones stay behind as a record of the sign-in
+        sqlx::query("DELETE FROM magic_links WHERE user_id = $1 AND used_at IS NULL")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        let row = sqlx::query(
+            r#"
+            INSERT INTO magic_links (user_id, token_hash, expires_at)
+            VALUES ($1, $2, $3)
+            RETURNING id, user_id, token_hash, expires_at, used_at, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(token_hash)
+        .bind(SqlxTimestamp::from(expires_at))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Self::map_row_to_magic_link(&row))
+    }
+
+    async fn claim(&self, token_hash: &str) -> Result<Option<MagicLink>, MagicLinkError> {
+        // A single statement, so a link opened twice at once still signs in only once
+        let row = sqlx::query(
+            r#"
+            UPDATE magic_links
+            SET used_at = NOW()
+            WHERE token_hash = $1
+            AND used_at IS NULL
+            AND expires_at > NOW()
+            RETURNING id, user_id, token_hash, expires_at, used_at, created_at
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Self::map_row_to_magic_link))
+    }
+}
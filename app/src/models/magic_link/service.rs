@@ -0,0 +1,105 @@
+// app/src/models/magic_link/service.rs
+
+// dependencies
+use super::dto::MagicLinkRequest;
+use super::entity::MagicLinkAccount;
+use super::error::MagicLinkError;
+use super::repository::MagicLinkRepository;
+use crate::configuration::MagicLinkConfig;
+use crate::tokens::TokenHasher;
+use async_trait::async_trait;
+use pavex::time::Timestamp;
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+// traits
+#[async_trait]
+pub trait MagicLinkService: Send + Sync {
+    /// Issue a sign-in link token, `None` when the address may not sign in with a link.
+    async fn request_link(
+        &self,
+        request: MagicLinkRequest,
+    ) -> Result<Option<String>, MagicLinkError>;
+    /// Use up a sign-in link, returning the id of the account it signs in.
+    async fn redeem_link(&self, token: &str) -> Result<Uuid, MagicLinkError>;
+}
+
+pub struct MagicLinkServiceImpl {
+    repository: Arc<dyn MagicLinkRepository>,
+    config: MagicLinkConfig,
+    tokens: TokenHasher,
+}
+
+impl MagicLinkServiceImpl {
+    pub fn new(repository: Arc<dyn MagicLinkRepository>, tokens: TokenHasher) -> Self {
+        Self {
+            repository,
+            config: MagicLinkConfig::default(),
+            tokens,
+        }
+    }
+
+    pub fn with_config(self, config: MagicLinkConfig) -> Self {
+        Self { config, ..self }
+    }
+
+    // accounts whose role warrants more than a mailbox never get a link
+    fn may_sign_in(&self, account: &MagicLinkAccount) -> bool {
+        account.is_active
+            && account.email_verified
+            && self.config.allowed_roles.contains(&account.role)
+    }
+}
+
+#[async_trait]
+impl MagicLinkService for MagicLinkServiceImpl {
+    async fn request_link(
+        &self,
+        request: MagicLinkRequest,
+    ) -> Result<Option<String>, MagicLinkError> {
+        // Validate input
+        request.validate().map_err(|e| MagicLinkError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let account = self
+            .repository
+            .find_account_by_email(request.normalized_email())
+            .await?;
+        let Some(account) = account.filter(|account| self.may_sign_in(account)) else {
+            return Ok(None);
+        };
+
+        let token = TokenHasher::generate();
+        self.repository
+            .create(
+                account.id,
+                &self.tokens.hash(&token),
+                Timestamp::now() + self.config.link_ttl,
+            )
+            .await?;
+        tracing::info!(user.id = %account.id, "Sign-in link requested");
+
+        Ok(Some(token))
+    }
+
+    async fn redeem_link(&self, token: &str) -> Result<Uuid, MagicLinkError> {
+        let link = self
+            .repository
+            .claim(&self.tokens.hash(token.trim()))
+            .await?
+            .ok_or(MagicLinkError::InvalidLink)?;
+
+        // The account may have been disabled or promoted since the link was sent
+        let account = self
+            .repository
+            .find_account_by_id(link.user_id)
+            .await?
+            .filter(|account| self.may_sign_in(account))
+            .ok_or(MagicLinkError::InvalidLink)?;
+        tracing::info!(user.id = %account.id, "Signed in with a sign-in link");
+
+        Ok(account.id)
+    }
+}
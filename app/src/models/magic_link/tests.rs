@@ -0,0 +1,233 @@
+// app/src/models/magic_link/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::configuration::{MagicLinkConfig, TokenHashingConfig};
+    use crate::models::UserRole;
+    use crate::models::magic_link::*;
+    use crate::tokens::TokenHasher;
+    use async_trait::async_trait;
+    use pavex::time::Timestamp;
+    use secrecy::SecretString;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    // Mock repository for testing the service layer
+    pub struct MockMagicLinkRepository {
+        accounts: Arc<Mutex<HashMap<Uuid, MagicLinkAccount>>>,
+        links: Arc<Mutex<HashMap<Uuid, MagicLink>>>,
+    }
+
+    impl MockMagicLinkRepository {
+        pub fn new() -> Self {
+            Self {
+                accounts: Arc::new(Mutex::new(HashMap::new())),
+                links: Arc::new(Mutex::new(HashMap::new())),
+            }
+        }
+
+        pub fn add_account(&self, email: &str, role: UserRole) -> Uuid {
+            let account = MagicLinkAccount {
+                id: Uuid::new_v4(),
+                email: email.to_string(),
+                role,
+                is_active: true,
+                email_verified: true,
+            };
+            let id = account.id;
+            self.accounts.lock().unwrap().insert(id, account);
+            id
+        }
+
+        pub fn update_account(&self, id: Uuid, update: impl FnOnce(&mut MagicLinkAccount)) {
+            update(self.accounts.lock().unwrap().get_mut(&id).unwrap());
+        }
+
+        pub fn links_for(&self, user_id: Uuid) -> Vec<MagicLink> {
+            self.links
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|link| link.user_id == user_id)
+                .cloned()
+                .collect()
+        }
+
+        pub fn expire_all(&self) {
+            for link in self.links.lock().unwrap().values_mut() {
+                link.expires_at = Timestamp::now() - Duration::from_secs(1);
+            }
+        }
+    }
+
+    #[async_trait]
+    impl MagicLinkRepository for MockMagicLinkRepository {
+        async fn find_account_by_email(
+            &self,
+            email: &str,
+        ) -> Result<Option<MagicLinkAccount>, MagicLinkError> {
+            Ok(self
+                .accounts
+                .lock()
+                .unwrap()
+                .values()
+                .find(|account| account.email == email)
+                .cloned())
+        }
+
+        async fn find_account_by_id(
+            &self,
+            id: Uuid,
+        ) -> Result<Option<MagicLinkAccount>, MagicLinkError> {
+            Ok(self.accounts.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn create(
+            &self,
+            user_id: Uuid,
+            token_hash: &str,
+            expires_at: Timestamp,
+        ) -> Result<MagicLink, MagicLinkError> {
+            let mut links = self.links.lock().unwrap();
+            links.retain(|_, link| link.user_id != user_id || link.used_at.is_some());
+            let link = MagicLink {
+                id: Uuid::new_v4(),
+                user_id,
+                token_hash: token_hash.to_string(),
+                expires_at,
+                used_at: None,
+                created_at: Timestamp::now(),
+            };
+            links.insert(link.id, link.clone());
+            Ok(link)
+        }
+
+        async fn claim(&self, token_hash: &str) -> Result<Option<MagicLink>, MagicLinkError> {
+            let mut links = self.links.lock().unwrap();
+            let now = Timestamp::now();
+            Ok(links
+                .values_mut()
+                .find(|link| {
+                    link.token_hash == token_hash && link.used_at.is_none() && !link.is_expired(now)
+                })
+                .map(|link| {
+                    link.used_at = Some(now);
+                    link.clone()
+                }))
+        }
+    }
+
+    // the token hasher of every service under test
+    fn hasher() -> TokenHasher {
+        TokenHasher::new(&TokenHashingConfig {
+            secret: Some(SecretString::from("test-token-secret")),
+            ..TokenHashingConfig::default()
+        })
+    }
+
+    fn link_request(email: &str) -> MagicLinkRequest {
+        MagicLinkRequest {
+            email: email.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_link_stores_only_the_hash_and_signs_in_once() {
+        let repository = Arc::new(MockMagicLinkRepository::new());
+        let service =
+            MagicLinkServiceImpl::new(repository.clone(), hasher()).with_config(MagicLinkConfig {
+                link_ttl: Duration::from_secs(10 * 60),
+                ..MagicLinkConfig::default()
+            });
+        let id = repository.add_account("reader@example.com", UserRole::Subscriber);
+
+        let token = service
+            .request_link(link_request("reader@example.com"))
+            .await
+            .unwrap()
+            .expect("subscribers should get a link");
+        let stored = repository.links_for(id);
+        assert_eq!(stored.len(), 1);
+        assert!(hasher().verify(&token, &stored[0].token_hash));
+        assert_ne!(stored[0].token_hash, token);
+        assert!(stored[0].expires_at <= Timestamp::now() + Duration::from_secs(10 * 60));
+
+        assert_eq!(service.redeem_link(&token).await.unwrap(), id);
+        let result = service.redeem_link(&token).await;
+        assert!(matches!(result, Err(MagicLinkError::InvalidLink)));
+    }
+
+    #[tokio::test]
+    async fn test_only_the_latest_link_works() {
+        let repository = Arc::new(MockMagicLinkRepository::new());
+        let service = MagicLinkServiceImpl::new(repository.clone(), hasher());
+        repository.add_account("writer@example.com", UserRole::Contributor);
+
+        let first = service
+            .request_link(link_request("writer@example.com"))
+            .await
+            .unwrap()
+            .unwrap();
+        let second = service
+            .request_link(link_request("writer@example.com"))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let result = service.redeem_link(&first).await;
+        assert!(matches!(result, Err(MagicLinkError::InvalidLink)));
+        assert!(service.redeem_link(&second).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_no_link_for_privileged_inactive_unverified_or_unknown_accounts() {
+        let repository = Arc::new(MockMagicLinkRepository::new());
+        let service = MagicLinkServiceImpl::new(repository.clone(), hasher());
+        repository.add_account("admin@example.com", UserRole::Admin);
+        let inactive = repository.add_account("inactive@example.com", UserRole::Subscriber);
+        repository.update_account(inactive, |account| account.is_active = false);
+        let unverified = repository.add_account("unverified@example.com", UserRole::Subscriber);
+        repository.update_account(unverified, |account| account.email_verified = false);
+
+        for email in [
+            "admin@example.com",
+            "inactive@example.com",
+            "unverified@example.com",
+            "nobody@example.com",
+        ] {
+            let token = service.request_link(link_request(email)).await.unwrap();
+            assert!(token.is_none(), "{email} should not get a link");
+        }
+
+        let result = service.request_link(link_request("not-an-email")).await;
+        assert!(matches!(result, Err(MagicLinkError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_expired_links_and_promoted_accounts_are_rejected() {
+        let repository = Arc::new(MockMagicLinkRepository::new());
+        let service = MagicLinkServiceImpl::new(repository.clone(), hasher());
+        let id = repository.add_account("reader@example.com", UserRole::Subscriber);
+
+        let token = service
+            .request_link(link_request("reader@example.com"))
+            .await
+            .unwrap()
+            .unwrap();
+        repository.expire_all();
+        let result = service.redeem_link(&token).await;
+        assert!(matches!(result, Err(MagicLinkError::InvalidLink)));
+
+        // A link sent before a promotion does not sign in the promoted account
+        let token = service
+            .request_link(link_request("reader@example.com"))
+            .await
+            .unwrap()
+            .unwrap();
+        repository.update_account(id, |account| account.role = UserRole::Editor);
+        let result = service.redeem_link(&token).await;
+        assert!(matches!(result, Err(MagicLinkError::InvalidLink)));
+    }
+}
@@ -1,7 +1,29 @@
 // app/src/models/mod.rs
 
 // modules
+mod api_token;
+mod capability;
+mod identity;
+mod invitation;
+mod magic_link;
+mod passkey;
+mod post;
+mod privacy;
+mod schedule;
+mod session;
+mod slug;
 mod user;
 
-// re-export the user module
+// re-export the modules
+pub use api_token::*;
+pub use capability::*;
+pub use identity::*;
+pub use invitation::*;
+pub use magic_link::*;
+pub use passkey::*;
+pub use post::*;
+pub use privacy::*;
+pub use schedule::*;
+pub use session::*;
+pub use slug::*;
 pub use user::*;
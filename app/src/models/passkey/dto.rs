@@ -0,0 +1,65 @@
+// app/src/models/passkey/dto.rs
+
+// dependencies
+use super::PasskeyCredential;
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+use webauthn_rs::prelude::{
+    PasskeyAuthentication, PublicKeyCredential, RegisterPublicKeyCredential,
+};
+
+/// Request DTO completing a passkey registration with the browser's answer to the challenge
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct FinishPasskeyRegistrationRequest {
+    #[validate(length(min = 1, max = 100, message = "Passkey name must be 1-100 characters"))]
+    pub name: String,
+
+    pub credential: RegisterPublicKeyCredential,
+}
+
+/// Request DTO for renaming a passkey
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct RenamePasskeyRequest {
+    #[validate(length(min = 1, max = 100, message = "Passkey name must be 1-100 characters"))]
+    pub name: String,
+}
+
+/// Request DTO starting a passwordless sign-in
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct StartPasskeyLoginRequest {
+    #[validate(length(min = 1, max = 255, message = "Username or email is required"))]
+    pub username_or_email: String,
+}
+
+/// The state a passwordless sign-in keeps between its two requests, `None` when its challenge
+/// was a decoy which no passkey answers
+pub type PasskeyLoginState = Option<PasskeyAuthentication>;
+
+/// Request DTO completing a passkey sign-in with the browser's answer to the challenge
+#[derive(Clone, Debug, Deserialize)]
+pub struct FinishPasskeyLoginRequest {
+    pub credential: PublicKeyCredential,
+}
+
+/// Public passkey response DTO - the key itself stays on the server
+#[derive(Debug, Serialize)]
+pub struct PasskeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: Timestamp,
+    pub last_used_at: Option<Timestamp>,
+}
+
+// Conversion implementations
+impl From<PasskeyCredential> for PasskeyResponse {
+    fn from(passkey: PasskeyCredential) -> Self {
+        Self {
+            id: passkey.id,
+            name: passkey.name,
+            created_at: passkey.created_at,
+            last_used_at: passkey.last_used_at,
+        }
+    }
+}
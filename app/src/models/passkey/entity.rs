@@ -0,0 +1,25 @@
+// app/src/models/passkey/entity.rs
+
+// dependencies
+use pavex::time::Timestamp;
+use uuid::Uuid;
+use webauthn_rs::prelude::Passkey;
+
+/// A WebAuthn credential registered by a user, who may have several
+#[derive(Debug, Clone)]
+pub struct PasskeyCredential {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// The public key and signature counter, updated on every use.
+    pub credential: Passkey,
+    pub created_at: Timestamp,
+    pub last_used_at: Option<Timestamp>,
+}
+
+/// The account a passkey sign-in was started for
+#[derive(Debug, Clone)]
+pub struct PasskeyAccount {
+    pub id: Uuid,
+    pub is_active: bool,
+}
@@ -0,0 +1,46 @@
+// app/src/models/passkey/error.rs
+
+use crate::response::IntoApiError;
+use pavex::http::StatusCode;
+use thiserror::Error;
+use webauthn_rs::prelude::WebauthnError;
+
+#[derive(Error, Debug)]
+pub enum PasskeyError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Stored passkey could not be read: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Validation error: {message}")]
+    Validation { message: String },
+
+    #[error("Passkey could not be verified: {0}")]
+    Webauthn(#[from] WebauthnError),
+
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+
+    #[error("Passkey not found")]
+    PasskeyNotFound,
+}
+
+impl IntoApiError for PasskeyError {
+    fn code(&self) -> Option<u16> {
+        match self {
+            PasskeyError::Validation { .. } | PasskeyError::Webauthn(_) => {
+                Some(StatusCode::BAD_REQUEST.as_u16())
+            }
+            PasskeyError::InvalidCredentials => Some(StatusCode::UNAUTHORIZED.as_u16()),
+            PasskeyError::PasskeyNotFound => Some(StatusCode::NOT_FOUND.as_u16()),
+            PasskeyError::Database(_) | PasskeyError::Serialization(_) => {
+                Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16())
+            }
+        }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
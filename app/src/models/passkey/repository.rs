@@ -0,0 +1,170 @@
+// app/src/models/passkey/repository.rs
+
+// dependencies
+use super::entity::{PasskeyAccount, PasskeyCredential};
+use super::error::PasskeyError;
+use async_trait::async_trait;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+use webauthn_rs::prelude::Passkey;
+
+// traits
+#[async_trait]
+pub trait PasskeyRepository: Send + Sync {
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<PasskeyCredential>, PasskeyError>;
+    async fn create(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        credential: &Passkey,
+    ) -> Result<PasskeyCredential, PasskeyError>;
+    async fn rename(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        name: &str,
+    ) -> Result<Option<PasskeyCredential>, PasskeyError>;
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, PasskeyError>;
+    /// Record a sign-in with the passkey, storing its credential if the counter moved on.
+    async fn record_use(&self, id: Uuid, credential: Option<&Passkey>) -> Result<(), PasskeyError>;
+    async fn find_account(
+        &self,
+        username_or_email: &str,
+    ) -> Result<Option<PasskeyAccount>, PasskeyError>;
+}
+
+pub struct SqlxPasskeyRepository {
+    pool: PgPool,
+}
+
+impl SqlxPasskeyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Helper function to map database row to PasskeyCredential struct
+    fn map_row_to_passkey(row: &sqlx::postgres::PgRow) -> Result<PasskeyCredential, PasskeyError> {
+        let credential: String = row.get("credential");
+
+        Ok(PasskeyCredential {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            name: row.get("name"),
+            credential: serde_json::from_str(&credential)?,
+            created_at: row.get::<SqlxTimestamp, _>("created_at").into(),
+            last_used_at: row
+                .get::<Option<SqlxTimestamp>, _>("last_used_at")
+                .map(|t| t.into()),
+        })
+    }
+}
+
+#[async_trait]
+impl PasskeyRepository for SqlxPasskeyRepository {
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<PasskeyCredential>, PasskeyError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_id, name, credential::text AS credential, created_at, last_used_at
+            FROM passkeys
+            WHERE user_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::map_row_to_passkey).collect()
+    }
+
+    async fn create(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        credential: &Passkey,
+    ) -> Result<PasskeyCredential, PasskeyError> {
+        let credential_id: &[u8] = credential.cred_id().as_ref();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO passkeys (user_id, name, credential_id, credential)
+            VALUES ($1, $2, $3, $4::jsonb)
+            RETURNING id, user_id, name, credential::text AS credential, created_at, last_used_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(credential_id)
+        .bind(serde_json::to_string(credential)?)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Self::map_row_to_passkey(&row)
+    }
+
+    async fn rename(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        name: &str,
+    ) -> Result<Option<PasskeyCredential>, PasskeyError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE passkeys
+            SET name = $3
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, user_id, name, credential::text AS credential, created_at, last_used_at
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(Self::map_row_to_passkey).transpose()
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, PasskeyError> {
+        let result = sqlx::query("DELETE FROM passkeys WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn record_use(&self, id: Uuid, credential: Option<&Passkey>) -> Result<(), PasskeyError> {
+        let credential = credential.map(serde_json::to_string).transpose()?;
+        sqlx::query(
+            r#"
+            UPDATE passkeys
+            SET credential = COALESCE($2::jsonb, credential),
+                last_used_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(credential)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_account(
+        &self,
+        username_or_email: &str,
+    ) -> Result<Option<PasskeyAccount>, PasskeyError> {
+        let row = sqlx::query("SELECT id, is_active FROM users WHERE username = $1 OR email = $1")
+            .bind(username_or_email)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| PasskeyAccount {
+            id: row.get("id"),
+            is_active: row.get("is_active"),
+        }))
+    }
+}
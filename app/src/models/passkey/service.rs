@@ -0,0 +1,294 @@
+// app/src/models/passkey/service.rs
+
+// dependencies
+use super::dto::{
+    FinishPasskeyRegistrationRequest, PasskeyLoginState, PasskeyResponse, RenamePasskeyRequest,
+    StartPasskeyLoginRequest,
+};
+use super::error::PasskeyError;
+use super::repository::PasskeyRepository;
+use crate::configuration::WebauthnConfig;
+use crate::models::UserSummary;
+use crate::tokens::{HASH_PREFIX, TokenHasher};
+use async_trait::async_trait;
+use data_encoding::HEXLOWER;
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+use webauthn_rs::prelude::{
+    CreationChallengeResponse, Passkey, PasskeyAuthentication, PasskeyRegistration,
+    PublicKeyCredential, RequestChallengeResponse, Url, Webauthn, WebauthnBuilder,
+};
+use webauthn_rs_proto::AllowCredentials;
+
+// enough for a phone, a laptop and a couple of security keys, with room to spare
+const MAX_PASSKEYS: usize = 10;
+
+// traits
+#[async_trait]
+pub trait PasskeyService: Send + Sync {
+    async fn list_passkeys(&self, user_id: Uuid) -> Result<Vec<PasskeyResponse>, PasskeyError>;
+    async fn has_passkeys(&self, user_id: Uuid) -> Result<bool, PasskeyError>;
+    async fn start_registration(
+        &self,
+        user: &UserSummary,
+    ) -> Result<(CreationChallengeResponse, PasskeyRegistration), PasskeyError>;
+    async fn finish_registration(
+        &self,
+        user_id: Uuid,
+        request: FinishPasskeyRegistrationRequest,
+        state: &PasskeyRegistration,
+    ) -> Result<PasskeyResponse, PasskeyError>;
+    /// Start a passwordless sign-in, returning the account it is for along with the challenge.
+    ///
+    /// Unknown and disabled accounts, and accounts without passkeys, get a decoy challenge
+    /// and no state to finish with, so the sign-in only fails once it is answered.
+    async fn start_login(
+        &self,
+        request: StartPasskeyLoginRequest,
+    ) -> Result<(Uuid, RequestChallengeResponse, PasskeyLoginState), PasskeyError>;
+    async fn start_authentication(
+        &self,
+        user_id: Uuid,
+    ) -> Result<(RequestChallengeResponse, PasskeyAuthentication), PasskeyError>;
+    async fn finish_authentication(
+        &self,
+        user_id: Uuid,
+        credential: &PublicKeyCredential,
+        state: &PasskeyAuthentication,
+    ) -> Result<(), PasskeyError>;
+    async fn rename_passkey(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        request: RenamePasskeyRequest,
+    ) -> Result<PasskeyResponse, PasskeyError>;
+    async fn delete_passkey(&self, user_id: Uuid, id: Uuid) -> Result<(), PasskeyError>;
+}
+
+pub struct PasskeyServiceImpl {
+    repository: Arc<dyn PasskeyRepository>,
+    webauthn: Webauthn,
+    tokens: TokenHasher,
+}
+
+impl PasskeyServiceImpl {
+    pub fn new(
+        repository: Arc<dyn PasskeyRepository>,
+        config: &WebauthnConfig,
+        tokens: TokenHasher,
+    ) -> Result<Self, PasskeyError> {
+        let origin = Url::parse(&config.rp_origin).map_err(|e| PasskeyError::Validation {
+            message: format!("Invalid relying party origin: {e}"),
+        })?;
+        let webauthn = WebauthnBuilder::new(&config.rp_id, &origin)?
+            .rp_name(&config.rp_name)
+            .timeout(config.ceremony_timeout)
+            .build()?;
+
+        Ok(Self {
+            repository,
+            webauthn,
+            tokens,
+        })
+    }
+
+    async fn passkeys_of(&self, user_id: Uuid) -> Result<Vec<Passkey>, PasskeyError> {
+        Ok(self
+            .repository
+            .list_for_user(user_id)
+            .await?
+            .into_iter()
+            .map(|passkey| passkey.credential)
+            .collect())
+    }
+
+    // a challenge shaped like the one of an account with a single passkey, which nothing answers
+    //
+    // The credential it names is a keyed hash of the identifier, so asking again names the same
+    // one, and telling it from a real credential takes the key.
+    fn decoy_challenge(&self, identifier: &str) -> Result<RequestChallengeResponse, PasskeyError> {
+        let (mut challenge, _) = self.webauthn.start_passkey_authentication(&[])?;
+        let digest = self
+            .tokens
+            .hash(&format!("passkey decoy:{}", identifier.to_lowercase()));
+        let credential_id = HEXLOWER
+            .decode(digest.trim_start_matches(HASH_PREFIX).as_bytes())
+            .expect("keyed hashes are hex encoded");
+        challenge
+            .public_key
+            .allow_credentials
+            .push(AllowCredentials {
+                type_: "public-key".to_string(),
+                id: credential_id.into(),
+                transports: None,
+            });
+
+        Ok(challenge)
+    }
+}
+
+#[async_trait]
+impl PasskeyService for PasskeyServiceImpl {
+    async fn list_passkeys(&self, user_id: Uuid) -> Result<Vec<PasskeyResponse>, PasskeyError> {
+        let passkeys = self.repository.list_for_user(user_id).await?;
+
+        Ok(passkeys.into_iter().map(PasskeyResponse::from).collect())
+    }
+
+    async fn has_passkeys(&self, user_id: Uuid) -> Result<bool, PasskeyError> {
+        Ok(!self.repository.list_for_user(user_id).await?.is_empty())
+    }
+
+    async fn start_registration(
+        &self,
+        user: &UserSummary,
+    ) -> Result<(CreationChallengeResponse, PasskeyRegistration), PasskeyError> {
+        let passkeys = self.repository.list_for_user(user.id).await?;
+        if passkeys.len() >= MAX_PASSKEYS {
+            return Err(PasskeyError::Validation {
+                message: format!("At most {MAX_PASSKEYS} passkeys can be registered"),
+            });
+        }
+
+        // The same authenticator is not registered twice
+        let registered = passkeys
+            .iter()
+            .map(|passkey| passkey.credential.cred_id().clone())
+            .collect();
+        let display_name = user.display_name.as_deref().unwrap_or(&user.username);
+        let ceremony = self.webauthn.start_passkey_registration(
+            user.id,
+            &user.username,
+            display_name,
+            Some(registered),
+        )?;
+
+        Ok(ceremony)
+    }
+
+    async fn finish_registration(
+        &self,
+        user_id: Uuid,
+        request: FinishPasskeyRegistrationRequest,
+        state: &PasskeyRegistration,
+    ) -> Result<PasskeyResponse, PasskeyError> {
+        // Validate input
+        request.validate().map_err(|e| PasskeyError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let credential = self
+            .webauthn
+            .finish_passkey_registration(&request.credential, state)?;
+        let passkey = self
+            .repository
+            .create(user_id, request.name.trim(), &credential)
+            .await?;
+        tracing::info!(user.id = %user_id, passkey.id = %passkey.id, "Passkey registered");
+
+        Ok(passkey.into())
+    }
+
+    async fn start_login(
+        &self,
+        request: StartPasskeyLoginRequest,
+    ) -> Result<(Uuid, RequestChallengeResponse, PasskeyLoginState), PasskeyError> {
+        // Validate input
+        request.validate().map_err(|e| PasskeyError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        // Unknown and disabled accounts look like accounts without passkeys
+        let identifier = request.username_or_email.trim();
+        let account = self
+            .repository
+            .find_account(identifier)
+            .await?
+            .filter(|account| account.is_active);
+        let passkeys = match &account {
+            Some(account) => self.passkeys_of(account.id).await?,
+            None => Vec::new(),
+        };
+
+        match account.filter(|_| !passkeys.is_empty()) {
+            Some(account) => {
+                let (challenge, state) = self.webauthn.start_passkey_authentication(&passkeys)?;
+                Ok((account.id, challenge, Some(state)))
+            }
+            None => Ok((Uuid::nil(), self.decoy_challenge(identifier)?, None)),
+        }
+    }
+
+    async fn start_authentication(
+        &self,
+        user_id: Uuid,
+    ) -> Result<(RequestChallengeResponse, PasskeyAuthentication), PasskeyError> {
+        let passkeys = self.passkeys_of(user_id).await?;
+        if passkeys.is_empty() {
+            return Err(PasskeyError::InvalidCredentials);
+        }
+
+        Ok(self.webauthn.start_passkey_authentication(&passkeys)?)
+    }
+
+    async fn finish_authentication(
+        &self,
+        user_id: Uuid,
+        credential: &PublicKeyCredential,
+        state: &PasskeyAuthentication,
+    ) -> Result<(), PasskeyError> {
+        let result = self
+            .webauthn
+            .finish_passkey_authentication(credential, state)
+            .map_err(|e| {
+                tracing::warn!(user.id = %user_id, error = %e, "Passkey assertion rejected");
+                PasskeyError::InvalidCredentials
+            })?;
+
+        // The passkey may have been deleted while the browser answered the challenge
+        let mut passkey = self
+            .repository
+            .list_for_user(user_id)
+            .await?
+            .into_iter()
+            .find(|passkey| passkey.credential.cred_id() == result.cred_id())
+            .ok_or(PasskeyError::InvalidCredentials)?;
+        let updated = passkey.credential.update_credential(&result) == Some(true);
+        self.repository
+            .record_use(passkey.id, updated.then_some(&passkey.credential))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn rename_passkey(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        request: RenamePasskeyRequest,
+    ) -> Result<PasskeyResponse, PasskeyError> {
+        // Validate input
+        request.validate().map_err(|e| PasskeyError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        // Passkeys of other users look exactly like missing ones
+        let passkey = self
+            .repository
+            .rename(user_id, id, request.name.trim())
+            .await?
+            .ok_or(PasskeyError::PasskeyNotFound)?;
+
+        Ok(passkey.into())
+    }
+
+    async fn delete_passkey(&self, user_id: Uuid, id: Uuid) -> Result<(), PasskeyError> {
+        if !self.repository.delete(user_id, id).await? {
+            return Err(PasskeyError::PasskeyNotFound);
+        }
+        tracing::info!(user.id = %user_id, passkey.id = %id, "Passkey deleted");
+
+        Ok(())
+    }
+}
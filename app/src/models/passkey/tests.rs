@@ -0,0 +1,222 @@
+// app/src/models/passkey/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::configuration::{TokenHashingConfig, WebauthnConfig};
+    use crate::models::passkey::*;
+    use crate::models::{UserRole, UserSummary};
+    use crate::tokens::TokenHasher;
+    use async_trait::async_trait;
+    use secrecy::SecretString;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+    use webauthn_rs::prelude::Passkey;
+
+    // Mock repository for testing the service layer
+    //
+    // Passkeys only come out of a real ceremony, so the mock starts without any and the
+    // ceremonies themselves are covered by the integration tests.
+    pub struct MockPasskeyRepository {
+        passkeys: Arc<Mutex<Vec<PasskeyCredential>>>,
+        accounts: Arc<Mutex<Vec<(String, PasskeyAccount)>>>,
+    }
+
+    impl MockPasskeyRepository {
+        pub fn new() -> Self {
+            Self {
+                passkeys: Arc::new(Mutex::new(Vec::new())),
+                accounts: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+
+        pub fn add_account(&self, username: &str, is_active: bool) -> Uuid {
+            let id = Uuid::new_v4();
+            self.accounts
+                .lock()
+                .unwrap()
+                .push((username.to_string(), PasskeyAccount { id, is_active }));
+            id
+        }
+    }
+
+    #[async_trait]
+    impl PasskeyRepository for MockPasskeyRepository {
+        async fn list_for_user(
+            &self,
+            user_id: Uuid,
+        ) -> Result<Vec<PasskeyCredential>, PasskeyError> {
+            Ok(self
+                .passkeys
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|p| p.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn create(
+            &self,
+            user_id: Uuid,
+            name: &str,
+            credential: &Passkey,
+        ) -> Result<PasskeyCredential, PasskeyError> {
+            let passkey = PasskeyCredential {
+                id: Uuid::new_v4(),
+                user_id,
+                name: name.to_string(),
+                credential: credential.clone(),
+                created_at: pavex::time::Timestamp::now(),
+                last_used_at: None,
+            };
+            self.passkeys.lock().unwrap().push(passkey.clone());
+            Ok(passkey)
+        }
+
+        async fn rename(
+            &self,
+            user_id: Uuid,
+            id: Uuid,
+            name: &str,
+        ) -> Result<Option<PasskeyCredential>, PasskeyError> {
+            let mut passkeys = self.passkeys.lock().unwrap();
+            Ok(passkeys
+                .iter_mut()
+                .find(|p| p.id == id && p.user_id == user_id)
+                .map(|p| {
+                    p.name = name.to_string();
+                    p.clone()
+                }))
+        }
+
+        async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, PasskeyError> {
+            let mut passkeys = self.passkeys.lock().unwrap();
+            let before = passkeys.len();
+            passkeys.retain(|p| !(p.id == id && p.user_id == user_id));
+            Ok(passkeys.len() < before)
+        }
+
+        async fn record_use(
+            &self,
+            _id: Uuid,
+            _credential: Option<&Passkey>,
+        ) -> Result<(), PasskeyError> {
+            Ok(())
+        }
+
+        async fn find_account(
+            &self,
+            username_or_email: &str,
+        ) -> Result<Option<PasskeyAccount>, PasskeyError> {
+            Ok(self
+                .accounts
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(username, _)| username == username_or_email)
+                .map(|(_, account)| account.clone()))
+        }
+    }
+
+    fn hasher() -> TokenHasher {
+        TokenHasher::new(&TokenHashingConfig {
+            secret: Some(SecretString::from("test-token-secret")),
+            ..TokenHashingConfig::default()
+        })
+    }
+
+    fn service(repository: Arc<MockPasskeyRepository>) -> PasskeyServiceImpl {
+        PasskeyServiceImpl::new(repository, &WebauthnConfig::default(), hasher()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_registration_challenge_names_the_user() {
+        let service = service(Arc::new(MockPasskeyRepository::new()));
+        let user = UserSummary {
+            id: Uuid::new_v4(),
+            username: "jane".to_string(),
+            display_name: Some("Jane Doe".to_string()),
+            avatar_url: None,
+            role: UserRole::Editor,
+        };
+
+        let (challenge, _) = service.start_registration(&user).await.unwrap();
+        let options = serde_json::to_value(&challenge).unwrap();
+        assert_eq!(options["publicKey"]["user"]["name"], "jane");
+        assert_eq!(options["publicKey"]["user"]["displayName"], "Jane Doe");
+        assert_eq!(options["publicKey"]["rp"]["id"], "localhost");
+    }
+
+    #[tokio::test]
+    async fn test_passwordless_login_reveals_nothing_about_accounts() {
+        let repository = Arc::new(MockPasskeyRepository::new());
+        let service = service(repository.clone());
+        repository.add_account("jane", true);
+        repository.add_account("disabled", false);
+
+        let start = |username: &str| {
+            service.start_login(StartPasskeyLoginRequest {
+                username_or_email: username.to_string(),
+            })
+        };
+        let mut decoys = Vec::new();
+        for username in ["nobody", "disabled", "jane"] {
+            // Every one gets a challenge naming a credential, none can ever be finished
+            let (_, challenge, state) = start(username).await.unwrap();
+            assert!(state.is_none());
+            let options = serde_json::to_value(&challenge).unwrap();
+            assert_eq!(options["publicKey"]["userVerification"], "required");
+            let allowed = options["publicKey"]["allowCredentials"].clone();
+            assert_eq!(allowed.as_array().unwrap().len(), 1);
+            decoys.push(allowed);
+        }
+        assert_ne!(decoys[0], decoys[1]);
+        assert_ne!(decoys[1], decoys[2]);
+
+        // Asking again names the same credential, like it would for a real account
+        let (_, challenge, _) = start("Nobody").await.unwrap();
+        let options = serde_json::to_value(&challenge).unwrap();
+        assert_eq!(options["publicKey"]["allowCredentials"], decoys[0]);
+    }
+
+    #[tokio::test]
+    async fn test_passkeys_of_other_users_are_not_found() {
+        let service = service(Arc::new(MockPasskeyRepository::new()));
+
+        let result = service
+            .rename_passkey(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                RenamePasskeyRequest {
+                    name: "Laptop".to_string(),
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(PasskeyError::PasskeyNotFound)));
+
+        let result = service
+            .rename_passkey(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                RenamePasskeyRequest {
+                    name: String::new(),
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(PasskeyError::Validation { .. })));
+
+        let result = service.delete_passkey(Uuid::new_v4(), Uuid::new_v4()).await;
+        assert!(matches!(result, Err(PasskeyError::PasskeyNotFound)));
+    }
+
+    #[test]
+    fn test_origin_must_belong_to_the_relying_party() {
+        let config = WebauthnConfig {
+            rp_id: "example.com".to_string(),
+            rp_origin: "https://example.org".to_string(),
+            ..WebauthnConfig::default()
+        };
+        let result = PasskeyServiceImpl::new(Arc::new(MockPasskeyRepository::new()), &config);
+        assert!(result.is_err());
+    }
+}
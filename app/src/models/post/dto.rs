@@ -0,0 +1,332 @@
+// app/src/models/post/dto.rs
+
+// dependencies
+use super::{CommentStatus, DEFAULT_POST_TYPE, Post, PostError, PostSeo, PostStatus};
+use crate::content::{Block, ContentDocument, ContentFormat, Inline};
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// Request DTO for creating a post - the slug is derived from the title when left out
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct CreatePostRequest {
+    #[validate(length(min = 1, max = 255, message = "Title must be 1-255 characters"))]
+    pub title: String,
+
+    #[validate(length(min = 1, max = 255, message = "Slug must be 1-255 characters"))]
+    #[validate(regex(
+        path = "SLUG_REGEX",
+        message = "Slug can only contain lowercase letters, numbers, and single hyphens"
+    ))]
+    pub slug: Option<String>,
+
+    /// A content document, checked by the service as serde cannot report where it is wrong
+    pub content: serde_json::Value,
+
+    /// Whether `content` is a content document or a string of Markdown
+    #[serde(default)]
+    pub format: ContentFormat,
+
+    #[validate(length(max = 1000, message = "Excerpt cannot exceed 1000 characters"))]
+    pub excerpt: Option<String>,
+
+    #[validate(url(message = "Featured image URL must be valid"))]
+    #[validate(length(max = 500, message = "Featured image URL cannot exceed 500 characters"))]
+    pub featured_image_url: Option<String>,
+
+    #[serde(default)]
+    pub status: PostStatus,
+
+    #[serde(default)]
+    pub comment_status: CommentStatus,
+
+    #[serde(default)]
+    pub is_featured: bool,
+
+    pub published_at: Option<Timestamp>,
+
+    #[validate(length(min = 1, max = 50, message = "Post type must be 1-50 characters"))]
+    #[serde(default = "default_post_type")]
+    pub post_type: String,
+
+    #[validate(custom(function = "validate_json_object"))]
+    pub custom_fields: Option<serde_json::Value>,
+
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub seo: PostSeo,
+}
+
+/// Request DTO for post updates - fields left out keep their current value
+#[derive(Clone, Debug, Default, Deserialize, Validate)]
+pub struct UpdatePostRequest {
+    #[validate(length(min = 1, max = 255, message = "Title must be 1-255 characters"))]
+    pub title: Option<String>,
+
+    #[validate(length(min = 1, max = 255, message = "Slug must be 1-255 characters"))]
+    #[validate(regex(
+        path = "SLUG_REGEX",
+        message = "Slug can only contain lowercase letters, numbers, and single hyphens"
+    ))]
+    pub slug: Option<String>,
+
+    pub content: Option<serde_json::Value>,
+
+    #[serde(default)]
+    pub format: ContentFormat,
+
+    #[validate(length(max = 1000, message = "Excerpt cannot exceed 1000 characters"))]
+    pub excerpt: Option<String>,
+
+    #[validate(url(message = "Featured image URL must be valid"))]
+    #[validate(length(max = 500, message = "Featured image URL cannot exceed 500 characters"))]
+    pub featured_image_url: Option<String>,
+
+    pub status: Option<PostStatus>,
+
+    pub comment_status: Option<CommentStatus>,
+
+    pub is_featured: Option<bool>,
+
+    pub published_at: Option<Timestamp>,
+
+    #[validate(length(min = 1, max = 50, message = "Post type must be 1-50 characters"))]
+    pub post_type: Option<String>,
+
+    #[validate(custom(function = "validate_json_object"))]
+    pub custom_fields: Option<serde_json::Value>,
+
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub seo: PostSeo,
+}
+
+/// Query DTO for reading a post, as JSON or as Markdown
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PostFormatQuery {
+    #[serde(default)]
+    pub format: ContentFormat,
+}
+
+/// Query DTO for post listings
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Validate)]
+pub struct PostListQuery {
+    #[validate(range(min = 1, message = "Page must be at least 1"))]
+    pub page: Option<u32>,
+
+    #[validate(range(min = 1, max = 100, message = "Page size must be 1-100"))]
+    pub per_page: Option<u32>,
+
+    pub status: Option<PostStatus>,
+
+    pub author_id: Option<Uuid>,
+
+    #[validate(length(max = 50, message = "Post type cannot exceed 50 characters"))]
+    pub post_type: Option<String>,
+
+    #[validate(length(max = 255, message = "Search cannot exceed 255 characters"))]
+    pub search: Option<String>,
+}
+
+impl CreatePostRequest {
+    /// A draft from a Markdown document, titled by the `# ` heading it starts with
+    pub fn from_markdown(markdown: &str) -> Result<Self, PostError> {
+        let (title, content) = split_title(markdown)?;
+        let title = title.ok_or_else(|| PostError::Validation {
+            message: "Markdown posts start with their title as a `# ` heading".to_string(),
+        })?;
+
+        Ok(Self {
+            title,
+            slug: None,
+            content: content.to_value(),
+            format: ContentFormat::Document,
+            excerpt: None,
+            featured_image_url: None,
+            status: PostStatus::default(),
+            comment_status: CommentStatus::default(),
+            is_featured: false,
+            published_at: None,
+            post_type: default_post_type(),
+            custom_fields: None,
+            seo: PostSeo::default(),
+        })
+    }
+}
+
+impl UpdatePostRequest {
+    /// New content from a Markdown document, and a new title if it starts with a `# ` heading
+    pub fn from_markdown(markdown: &str) -> Result<Self, PostError> {
+        let (title, content) = split_title(markdown)?;
+
+        Ok(Self {
+            title,
+            content: Some(content.to_value()),
+            ..Self::default()
+        })
+    }
+}
+
+impl PostListQuery {
+    pub const DEFAULT_PER_PAGE: u32 = 20;
+
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> u32 {
+        self.per_page
+            .unwrap_or(Self::DEFAULT_PER_PAGE)
+            .clamp(1, 100)
+    }
+
+    pub fn offset(&self) -> i64 {
+        i64::from(self.page() - 1) * i64::from(self.per_page())
+    }
+
+    // Trimmed search term, if any
+    pub fn search_term(&self) -> Option<&str> {
+        self.search
+            .as_deref()
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+    }
+}
+
+/// Full post response DTO
+#[derive(Debug, Serialize)]
+pub struct PostResponse {
+    pub id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub content: ContentDocument,
+    pub excerpt: Option<String>,
+    pub featured_image_url: Option<String>,
+    pub author_id: Uuid,
+    pub status: PostStatus,
+    pub comment_status: CommentStatus,
+    pub is_featured: bool,
+    pub view_count: i32,
+    #[serde(flatten)]
+    pub seo: PostSeo,
+    pub post_type: String,
+    pub custom_fields: Option<serde_json::Value>,
+    pub published_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+/// Summary post response for listings - leaves out the content
+#[derive(Debug, Clone, Serialize)]
+pub struct PostSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub excerpt: Option<String>,
+    pub featured_image_url: Option<String>,
+    pub author_id: Uuid,
+    pub status: PostStatus,
+    pub is_featured: bool,
+    pub post_type: String,
+    pub published_at: Option<Timestamp>,
+    pub updated_at: Timestamp,
+}
+
+/// Paginated post listing response DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct PostListResponse {
+    pub posts: Vec<PostSummary>,
+    pub total: i64,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl PostResponse {
+    /// The post as a Markdown document, its title as the `# ` heading it starts with
+    pub fn to_markdown(&self) -> String {
+        let mut blocks = vec![Block::Heading {
+            level: 1,
+            children: vec![Inline::text(self.title.clone())],
+            anchor: None,
+        }];
+        blocks.extend(self.content.blocks.iter().cloned());
+        ContentDocument::new(blocks).to_markdown()
+    }
+}
+
+// Parse Markdown, taking the title off the top if it starts with a level one heading
+fn split_title(markdown: &str) -> Result<(Option<String>, ContentDocument), PostError> {
+    let mut document = ContentDocument::from_markdown(markdown)?;
+    let title = match document.blocks.first() {
+        Some(Block::Heading { level: 1, .. }) => {
+            let heading = document.blocks.remove(0);
+            Some(ContentDocument::new(vec![heading]).to_plain_text())
+        }
+        _ => None,
+    };
+
+    Ok((title, document))
+}
+
+// Serde default for the post type
+fn default_post_type() -> String {
+    DEFAULT_POST_TYPE.to_string()
+}
+
+// Custom fields are stored as a JSONB object, never as a bare value or an array
+fn validate_json_object(value: &serde_json::Value) -> Result<(), ValidationError> {
+    if value.is_object() {
+        Ok(())
+    } else {
+        Err(ValidationError::new("json_object").with_message("Must be a JSON object".into()))
+    }
+}
+
+// Validation regex - lowercase words joined by single hyphens
+static SLUG_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^[a-z0-9]+(?:-[a-z0-9]+)*$").unwrap());
+
+// Conversion implementations
+impl From<Post> for PostResponse {
+    fn from(post: Post) -> Self {
+        Self {
+            id: post.id,
+            title: post.title,
+            slug: post.slug,
+            content: post.content,
+            excerpt: post.excerpt,
+            featured_image_url: post.featured_image_url,
+            author_id: post.author_id,
+            status: post.status,
+            comment_status: post.comment_status,
+            is_featured: post.is_featured,
+            view_count: post.view_count,
+            seo: post.seo,
+            post_type: post.post_type,
+            custom_fields: post.custom_fields,
+            published_at: post.published_at,
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+        }
+    }
+}
+
+impl From<Post> for PostSummary {
+    fn from(post: Post) -> Self {
+        Self {
+            id: post.id,
+            title: post.title,
+            slug: post.slug,
+            excerpt: post.excerpt,
+            featured_image_url: post.featured_image_url,
+            author_id: post.author_id,
+            status: post.status,
+            is_featured: post.is_featured,
+            post_type: post.post_type,
+            published_at: post.published_at,
+            updated_at: post.updated_at,
+        }
+    }
+}
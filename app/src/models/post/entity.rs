@@ -0,0 +1,164 @@
+// app/src/models/post/entity.rs
+
+// dependencies
+use crate::content::ContentDocument;
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Post type of regular blog posts, custom post types use their own name
+pub const DEFAULT_POST_TYPE: &str = "post";
+
+/// Publication status enum that matches the `content_status` database enum
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Type, Serialize, Deserialize)]
+#[sqlx(type_name = "content_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    #[default]
+    Draft,
+    Published,
+    Private,
+    Trash,
+    /// Published by the scheduler once its `published_at` date comes
+    Scheduled,
+}
+
+impl PostStatus {
+    // Name of the status, as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Published => "published",
+            Self::Private => "private",
+            Self::Trash => "trash",
+            Self::Scheduled => "scheduled",
+        }
+    }
+
+    // Parse status from string - useful for manual row mapping
+    pub fn frm_str(s: &str) -> Option<Self> {
+        match s {
+            "draft" => Some(Self::Draft),
+            "published" => Some(Self::Published),
+            "private" => Some(Self::Private),
+            "trash" => Some(Self::Trash),
+            "scheduled" => Some(Self::Scheduled),
+            _ => None,
+        }
+    }
+
+    /// Whether everyone may read posts in this status, not only their author and editors
+    pub fn is_public(&self) -> bool {
+        *self == Self::Published
+    }
+
+    /// Whether moving a post to or from this status takes the `publish_posts` capability
+    pub fn requires_publishing(&self) -> bool {
+        matches!(self, Self::Published | Self::Private | Self::Scheduled)
+    }
+}
+
+/// Comment status enum that matches the `comment_status` database enum
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Type, Serialize, Deserialize)]
+#[sqlx(type_name = "comment_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CommentStatus {
+    #[default]
+    Open,
+    Closed,
+    Moderated,
+}
+
+/// Search engine and social media fields of a post, each falling back to the post itself
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate)]
+pub struct PostSeo {
+    #[validate(length(max = 255, message = "Meta title cannot exceed 255 characters"))]
+    pub meta_title: Option<String>,
+
+    #[validate(length(max = 1000, message = "Meta description cannot exceed 1000 characters"))]
+    pub meta_description: Option<String>,
+
+    #[validate(length(max = 1000, message = "Meta keywords cannot exceed 1000 characters"))]
+    pub meta_keywords: Option<String>,
+
+    #[validate(url(message = "Canonical URL must be valid"))]
+    #[validate(length(max = 500, message = "Canonical URL cannot exceed 500 characters"))]
+    pub canonical_url: Option<String>,
+
+    #[validate(length(max = 255, message = "Open Graph title cannot exceed 255 characters"))]
+    pub og_title: Option<String>,
+
+    #[validate(length(
+        max = 1000,
+        message = "Open Graph description cannot exceed 1000 characters"
+    ))]
+    pub og_description: Option<String>,
+
+    #[validate(url(message = "Open Graph image must be a valid URL"))]
+    #[validate(length(max = 500, message = "Open Graph image cannot exceed 500 characters"))]
+    pub og_image: Option<String>,
+
+    #[validate(length(max = 255, message = "Twitter title cannot exceed 255 characters"))]
+    pub twitter_title: Option<String>,
+
+    #[validate(length(
+        max = 1000,
+        message = "Twitter description cannot exceed 1000 characters"
+    ))]
+    pub twitter_description: Option<String>,
+
+    #[validate(url(message = "Twitter image must be a valid URL"))]
+    #[validate(length(max = 500, message = "Twitter image cannot exceed 500 characters"))]
+    pub twitter_image: Option<String>,
+}
+
+// Core Post entity that maps directly to the posts table
+#[derive(Debug, Clone)]
+pub struct Post {
+    pub id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub content: ContentDocument,
+    pub excerpt: Option<String>,
+    pub featured_image_url: Option<String>,
+    pub author_id: Uuid,
+    pub status: PostStatus,
+    pub comment_status: CommentStatus,
+    pub is_featured: bool,
+    pub view_count: i32,
+    pub seo: PostSeo,
+    pub post_type: String,
+    pub custom_fields: Option<serde_json::Value>,
+    pub published_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+// Everything needed to store a new post
+#[derive(Debug, Clone)]
+pub struct NewPost {
+    pub author_id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub content: ContentDocument,
+    pub excerpt: Option<String>,
+    pub featured_image_url: Option<String>,
+    pub status: PostStatus,
+    pub comment_status: CommentStatus,
+    pub is_featured: bool,
+    pub seo: PostSeo,
+    pub post_type: String,
+    pub custom_fields: Option<serde_json::Value>,
+    pub published_at: Option<Timestamp>,
+}
+
+/// Which posts a listing may include, on top of what its query filters on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PostVisibility {
+    /// Every post, for those who may edit posts of others
+    All,
+    /// Published posts, and any post of the given author
+    PublishedOrOwnedBy(Uuid),
+}
@@ -0,0 +1,59 @@
+// app/src/models/post/error.rs
+
+use crate::content::ContentError;
+use crate::models::SlugError;
+use crate::response::IntoApiError;
+use pavex::http::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PostError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Validation error: {message}")]
+    Validation { message: String },
+
+    #[error(transparent)]
+    InvalidContent(#[from] ContentError),
+
+    #[error("Post not found")]
+    PostNotFound,
+
+    #[error("Slug already exists")]
+    SlugExists,
+
+    #[error("Unsupported content type: {0}")]
+    UnsupportedMediaType(String),
+}
+
+// Slugs asked for which are reserved are invalid, those taken conflict like any other
+impl From<SlugError> for PostError {
+    fn from(err: SlugError) -> Self {
+        match err {
+            SlugError::Database(e) => PostError::Database(e),
+            SlugError::Reserved(_) => PostError::Validation {
+                message: err.to_string(),
+            },
+            SlugError::Taken => PostError::SlugExists,
+        }
+    }
+}
+
+impl IntoApiError for PostError {
+    fn code(&self) -> Option<u16> {
+        match self {
+            PostError::Validation { .. } | PostError::InvalidContent(_) => {
+                Some(StatusCode::BAD_REQUEST.as_u16())
+            }
+            PostError::PostNotFound => Some(StatusCode::NOT_FOUND.as_u16()),
+            PostError::SlugExists => Some(StatusCode::CONFLICT.as_u16()),
+            PostError::UnsupportedMediaType(_) => Some(StatusCode::UNSUPPORTED_MEDIA_TYPE.as_u16()),
+            PostError::Database(_) => Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16()),
+        }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
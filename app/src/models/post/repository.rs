@@ -0,0 +1,326 @@
+// app/src/models/post/repository.rs
+
+// dependencies
+use super::dto::{PostListQuery, UpdatePostRequest};
+use super::entity::{NewPost, Post, PostSeo, PostVisibility};
+use super::error::PostError;
+use crate::content::ContentDocument;
+use async_trait::async_trait;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use sqlx::types::Json;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+// columns returned by every query that yields whole posts
+const POST_COLUMNS: &str = r#"
+    id, title, slug, content, excerpt, featured_image_url, author_id,
+    status, comment_status, is_featured, view_count,
+    meta_title, meta_description, meta_keywords, canonical_url,
+    og_title, og_description, og_image, twitter_title, twitter_description, twitter_image,
+    post_type, custom_fields, published_at, created_at, updated_at
+"#;
+
+// filters shared by the listing and its count, trash is only listed when asked for
+const LIST_FILTERS: &str = r#"
+    WHERE (($1::content_status IS NULL AND status <> 'trash') OR status = $1)
+    AND ($2::uuid IS NULL OR author_id = $2)
+    AND ($3::text IS NULL OR post_type = $3)
+    AND ($4::text IS NULL OR title ILIKE $4 OR excerpt ILIKE $4)
+    AND ($5::uuid IS NULL OR status = 'published' OR author_id = $5)
+"#;
+
+// traits
+#[async_trait]
+pub trait PostRepository: Send + Sync {
+    async fn create(&self, post: NewPost) -> Result<Post, PostError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Post>, PostError>;
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Post>, PostError>;
+    async fn update(&self, id: Uuid, request: UpdatePostRequest) -> Result<Post, PostError>;
+    async fn delete(&self, id: Uuid) -> Result<bool, PostError>;
+    async fn list(
+        &self,
+        query: &PostListQuery,
+        visibility: PostVisibility,
+    ) -> Result<Vec<Post>, PostError>;
+    async fn count(
+        &self,
+        query: &PostListQuery,
+        visibility: PostVisibility,
+    ) -> Result<i64, PostError>;
+    /// The current version of the posts, which every change listings would show moves on
+    async fn listing_version(&self) -> Result<i64, PostError>;
+    async fn bump_listing_version(&self) -> Result<(), PostError>;
+}
+
+// SQLx implementation
+pub struct SqlxPostRepository {
+    pool: PgPool,
+}
+
+impl SqlxPostRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ILIKE pattern for the search term, with its wildcards escaped
+    fn search_pattern(query: &PostListQuery) -> Option<String> {
+        query.search_term().map(|term| {
+            let escaped = term
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("%{escaped}%")
+        })
+    }
+
+    // the author whose posts are listed whatever their status, if the listing is restricted
+    fn visible_owner(visibility: PostVisibility) -> Option<Uuid> {
+        match visibility {
+            PostVisibility::All => None,
+            PostVisibility::PublishedOrOwnedBy(owner) => Some(owner),
+        }
+    }
+
+    // slugs are unique, the index settles any race between two writers
+    fn map_slug_conflict(e: sqlx::Error) -> PostError {
+        match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => PostError::SlugExists,
+            _ => e.into(),
+        }
+    }
+
+    /// Helper function to map database row to Post struct
+    fn map_row_to_post(row: sqlx::postgres::PgRow) -> Result<Post, PostError> {
+        Ok(Post {
+            id: row.get("id"),
+            title: row.get("title"),
+            slug: row.get("slug"),
+            content: row.try_get::<Json<ContentDocument>, _>("content")?.0,
+            excerpt: row.get("excerpt"),
+            featured_image_url: row.get("featured_image_url"),
+            author_id: row.get("author_id"),
+            status: row.get("status"),
+            comment_status: row.get("comment_status"),
+            is_featured: row.get("is_featured"),
+            view_count: row.get("view_count"),
+            seo: PostSeo {
+                meta_title: row.get("meta_title"),
+                meta_description: row.get("meta_description"),
+                meta_keywords: row.get("meta_keywords"),
+                canonical_url: row.get("canonical_url"),
+                og_title: row.get("og_title"),
+                og_description: row.get("og_description"),
+                og_image: row.get("og_image"),
+                twitter_title: row.get("twitter_title"),
+                twitter_description: row.get("twitter_description"),
+                twitter_image: row.get("twitter_image"),
+            },
+            post_type: row.get("post_type"),
+            custom_fields: row.get("custom_fields"),
+            published_at: row
+                .get::<Option<SqlxTimestamp>, _>("published_at")
+                .map(|t| t.into()),
+            created_at: row.get::<SqlxTimestamp, _>("created_at").into(),
+            updated_at: row.get::<SqlxTimestamp, _>("updated_at").into(),
+        })
+    }
+}
+
+#[async_trait]
+impl PostRepository for SqlxPostRepository {
+    async fn create(&self, post: NewPost) -> Result<Post, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO posts (
+                title, slug, content, excerpt, featured_image_url, author_id,
+                status, comment_status, is_featured,
+                meta_title, meta_description, meta_keywords, canonical_url,
+                og_title, og_description, og_image,
+                twitter_title, twitter_description, twitter_image,
+                post_type, custom_fields, published_at
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11,
+                $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22
+            )
+            RETURNING {POST_COLUMNS}
+            "#
+        ))
+        .bind(&post.title)
+        .bind(&post.slug)
+        .bind(Json(&post.content))
+        .bind(&post.excerpt)
+        .bind(&post.featured_image_url)
+        .bind(post.author_id)
+        .bind(post.status)
+        .bind(post.comment_status)
+        .bind(post.is_featured)
+        .bind(&post.seo.meta_title)
+        .bind(&post.seo.meta_description)
+        .bind(&post.seo.meta_keywords)
+        .bind(&post.seo.canonical_url)
+        .bind(&post.seo.og_title)
+        .bind(&post.seo.og_description)
+        .bind(&post.seo.og_image)
+        .bind(&post.seo.twitter_title)
+        .bind(&post.seo.twitter_description)
+        .bind(&post.seo.twitter_image)
+        .bind(&post.post_type)
+        .bind(&post.custom_fields)
+        .bind(post.published_at.map(SqlxTimestamp::from))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Self::map_slug_conflict)?;
+
+        Self::map_row_to_post(row)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Post>, PostError> {
+        let row = sqlx::query(&format!("SELECT {POST_COLUMNS} FROM posts WHERE id = $1"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(Self::map_row_to_post).transpose()
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Post>, PostError> {
+        let row = sqlx::query(&format!("SELECT {POST_COLUMNS} FROM posts WHERE slug = $1"))
+            .bind(slug)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(Self::map_row_to_post).transpose()
+    }
+
+    async fn update(&self, id: Uuid, request: UpdatePostRequest) -> Result<Post, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE posts
+            SET
+                title = COALESCE($2, title),
+                slug = COALESCE($3, slug),
+                content = COALESCE($4, content),
+                excerpt = COALESCE($5, excerpt),
+                featured_image_url = COALESCE($6, featured_image_url),
+                status = COALESCE($7, status),
+                comment_status = COALESCE($8, comment_status),
+                is_featured = COALESCE($9, is_featured),
+                published_at = COALESCE($10, published_at),
+                post_type = COALESCE($11, post_type),
+                custom_fields = COALESCE($12, custom_fields),
+                meta_title = COALESCE($13, meta_title),
+                meta_description = COALESCE($14, meta_description),
+                meta_keywords = COALESCE($15, meta_keywords),
+                canonical_url = COALESCE($16, canonical_url),
+                og_title = COALESCE($17, og_title),
+                og_description = COALESCE($18, og_description),
+                og_image = COALESCE($19, og_image),
+                twitter_title = COALESCE($20, twitter_title),
+                twitter_description = COALESCE($21, twitter_description),
+                twitter_image = COALESCE($22, twitter_image),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {POST_COLUMNS}
+            "#
+        ))
+        .bind(id)
+        .bind(&request.title)
+        .bind(&request.slug)
+        .bind(&request.content)
+        .bind(&request.excerpt)
+        .bind(&request.featured_image_url)
+        .bind(request.status)
+        .bind(request.comment_status)
+        .bind(request.is_featured)
+        .bind(request.published_at.map(SqlxTimestamp::from))
+        .bind(&request.post_type)
+        .bind(&request.custom_fields)
+        .bind(&request.seo.meta_title)
+        .bind(&request.seo.meta_description)
+        .bind(&request.seo.meta_keywords)
+        .bind(&request.seo.canonical_url)
+        .bind(&request.seo.og_title)
+        .bind(&request.seo.og_description)
+        .bind(&request.seo.og_image)
+        .bind(&request.seo.twitter_title)
+        .bind(&request.seo.twitter_description)
+        .bind(&request.seo.twitter_image)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_slug_conflict)?
+        .ok_or(PostError::PostNotFound)?;
+
+        Self::map_row_to_post(row)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, PostError> {
+        let result = sqlx::query("DELETE FROM posts WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list(
+        &self,
+        query: &PostListQuery,
+        visibility: PostVisibility,
+    ) -> Result<Vec<Post>, PostError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {POST_COLUMNS}
+            FROM posts
+            {LIST_FILTERS}
+            ORDER BY COALESCE(published_at, created_at) DESC, id
+            LIMIT $6 OFFSET $7
+            "#
+        ))
+        .bind(query.status)
+        .bind(query.author_id)
+        .bind(&query.post_type)
+        .bind(Self::search_pattern(query))
+        .bind(Self::visible_owner(visibility))
+        .bind(i64::from(query.per_page()))
+        .bind(query.offset())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(Self::map_row_to_post).collect()
+    }
+
+    async fn count(
+        &self,
+        query: &PostListQuery,
+        visibility: PostVisibility,
+    ) -> Result<i64, PostError> {
+        let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM posts {LIST_FILTERS}"))
+            .bind(query.status)
+            .bind(query.author_id)
+            .bind(&query.post_type)
+            .bind(Self::search_pattern(query))
+            .bind(Self::visible_owner(visibility))
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(total)
+    }
+
+    async fn listing_version(&self) -> Result<i64, PostError> {
+        let version: Option<i64> =
+            sqlx::query_scalar("SELECT version FROM content_versions WHERE kind = 'post'")
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(version.unwrap_or_default())
+    }
+
+    async fn bump_listing_version(&self) -> Result<(), PostError> {
+        sqlx::query("UPDATE content_versions SET version = version + 1 WHERE kind = 'post'")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
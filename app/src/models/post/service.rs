@@ -0,0 +1,307 @@
+// app/src/models/post/service.rs
+
+// dependencies
+use super::dto::{
+    CreatePostRequest, PostListQuery, PostListResponse, PostResponse, UpdatePostRequest,
+};
+use super::entity::{NewPost, PostStatus, PostVisibility};
+use super::error::PostError;
+use super::repository::PostRepository;
+use crate::configuration::ContentCacheConfig;
+use crate::content::{ContentDocument, ContentFormat};
+use crate::models::{SlugLookup, SlugScope, SlugService};
+use async_trait::async_trait;
+use pavex::time::Timestamp;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use uuid::Uuid;
+use validator::Validate;
+
+// listings kept in memory before they are all dropped
+const MAX_CACHED_LISTINGS: usize = 1_000;
+
+// traits
+#[async_trait]
+pub trait PostService: Send + Sync {
+    async fn create_post(
+        &self,
+        author_id: Uuid,
+        request: CreatePostRequest,
+    ) -> Result<PostResponse, PostError>;
+    async fn get_post(&self, id: Uuid) -> Result<PostResponse, PostError>;
+    /// The post with a slug, or the post which had it before being renamed
+    async fn find_post_by_slug(&self, slug: &str) -> Result<SlugLookup<PostResponse>, PostError>;
+    async fn update_post(
+        &self,
+        id: Uuid,
+        request: UpdatePostRequest,
+    ) -> Result<PostResponse, PostError>;
+    async fn delete_post(&self, id: Uuid) -> Result<(), PostError>;
+    async fn list_posts(
+        &self,
+        query: PostListQuery,
+        visibility: PostVisibility,
+    ) -> Result<PostListResponse, PostError>;
+}
+
+// a listing as read at a version of the posts
+struct CachedListing {
+    version: i64,
+    loaded_at: Instant,
+    listing: PostListResponse,
+}
+
+pub struct PostServiceImpl {
+    repository: Arc<dyn PostRepository>,
+    slugs: Arc<dyn SlugService>,
+    cache: ContentCacheConfig,
+    listings: RwLock<HashMap<(PostListQuery, PostVisibility), CachedListing>>,
+}
+
+impl PostServiceImpl {
+    pub fn new(repository: Arc<dyn PostRepository>, slugs: Arc<dyn SlugService>) -> Self {
+        Self {
+            repository,
+            slugs,
+            cache: ContentCacheConfig::default(),
+            listings: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_config(mut self, cache: ContentCacheConfig) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Drop the cached listings of every server instance, after a change they may show.
+    async fn invalidate_listings(&self) -> Result<(), PostError> {
+        self.repository.bump_listing_version().await?;
+        self.listings.write().unwrap().clear();
+
+        Ok(())
+    }
+}
+
+// Parse content in the format it was written in, a JSON document or a string of Markdown
+fn parse_content(
+    content: serde_json::Value,
+    format: ContentFormat,
+) -> Result<ContentDocument, PostError> {
+    match (format, content) {
+        (ContentFormat::Document, content) => Ok(ContentDocument::from_value(content)?),
+        (ContentFormat::Markdown, serde_json::Value::String(markdown)) => {
+            Ok(ContentDocument::from_markdown(&markdown)?)
+        }
+        (ContentFormat::Markdown, _) => Err(PostError::Validation {
+            message: "Markdown content must be a string".to_string(),
+        }),
+    }
+}
+
+// Posts published with a date still to come are scheduled, scheduled ones already due published
+fn schedule(status: PostStatus, published_at: Option<Timestamp>) -> Result<PostStatus, PostError> {
+    match (status, published_at.map(|date| date > Timestamp::now())) {
+        (PostStatus::Published, Some(true)) => Ok(PostStatus::Scheduled),
+        (PostStatus::Scheduled, Some(false)) => Ok(PostStatus::Published),
+        (PostStatus::Scheduled, None) => Err(PostError::Validation {
+            message: "Scheduled posts need a publication date".to_string(),
+        }),
+        (status, _) => Ok(status),
+    }
+}
+
+#[async_trait]
+impl PostService for PostServiceImpl {
+    async fn create_post(
+        &self,
+        author_id: Uuid,
+        request: CreatePostRequest,
+    ) -> Result<PostResponse, PostError> {
+        // Validate input
+        request.validate().map_err(|e| PostError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let content = parse_content(request.content, request.format)?;
+        let slug = self
+            .slugs
+            .claim(
+                SlugScope::Post,
+                request.slug.as_deref(),
+                &request.title,
+                None,
+            )
+            .await?;
+
+        // Posts published without a date are published now
+        let status = schedule(request.status, request.published_at)?;
+        let published_at = match request.published_at {
+            None if status.requires_publishing() => Some(Timestamp::now()),
+            published_at => published_at,
+        };
+
+        let post = self
+            .repository
+            .create(NewPost {
+                author_id,
+                title: request.title,
+                slug,
+                content,
+                excerpt: request.excerpt,
+                featured_image_url: request.featured_image_url,
+                status,
+                comment_status: request.comment_status,
+                is_featured: request.is_featured,
+                seo: request.seo,
+                post_type: request.post_type,
+                custom_fields: request.custom_fields,
+                published_at,
+            })
+            .await?;
+        self.invalidate_listings().await?;
+
+        Ok(post.into())
+    }
+
+    async fn get_post(&self, id: Uuid) -> Result<PostResponse, PostError> {
+        let post = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or(PostError::PostNotFound)?;
+
+        Ok(post.into())
+    }
+
+    async fn find_post_by_slug(&self, slug: &str) -> Result<SlugLookup<PostResponse>, PostError> {
+        if let Some(post) = self.repository.find_by_slug(slug).await? {
+            return Ok(SlugLookup::Found(post.into()));
+        }
+        let current = self
+            .slugs
+            .redirect(SlugScope::Post, slug)
+            .await?
+            .ok_or(PostError::PostNotFound)?;
+        let post = self
+            .repository
+            .find_by_slug(&current)
+            .await?
+            .ok_or(PostError::PostNotFound)?;
+
+        Ok(SlugLookup::Moved(post.into()))
+    }
+
+    async fn update_post(
+        &self,
+        id: Uuid,
+        mut request: UpdatePostRequest,
+    ) -> Result<PostResponse, PostError> {
+        // Validate input
+        request.validate().map_err(|e| PostError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        // Content is stored as parsed, in its canonical form
+        if let Some(content) = request.content.take() {
+            request.content = Some(parse_content(content, request.format)?.to_value());
+            request.format = ContentFormat::Document;
+        }
+
+        let post = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or(PostError::PostNotFound)?;
+
+        // Moving the publication date of a published post to the future schedules it again
+        let status = schedule(
+            request.status.unwrap_or(post.status),
+            request.published_at.or(post.published_at),
+        )?;
+        if request.status.is_some() || status != post.status {
+            request.status = Some(status);
+        }
+
+        // A first publication without a date is dated now, later ones keep the original date
+        let publishing = request
+            .status
+            .is_some_and(|status| status.requires_publishing());
+        if publishing && request.published_at.is_none() && post.published_at.is_none() {
+            request.published_at = Some(Timestamp::now());
+        }
+
+        // A new slug must be free, the old one keeps answering with a redirect
+        let old_slug = post.slug;
+        if let Some(slug) = request.slug.as_deref().filter(|slug| *slug != old_slug) {
+            let slug = self
+                .slugs
+                .claim(SlugScope::Post, Some(slug), &post.title, Some(id))
+                .await?;
+            request.slug = Some(slug);
+        }
+
+        let post = self.repository.update(id, request).await?;
+        self.slugs
+            .record_rename(SlugScope::Post, id, &old_slug, &post.slug)
+            .await?;
+        self.invalidate_listings().await?;
+        Ok(post.into())
+    }
+
+    async fn delete_post(&self, id: Uuid) -> Result<(), PostError> {
+        if self.repository.delete(id).await? {
+            self.slugs.forget(SlugScope::Post, id).await?;
+            self.invalidate_listings().await?;
+            Ok(())
+        } else {
+            Err(PostError::PostNotFound)
+        }
+    }
+
+    async fn list_posts(
+        &self,
+        query: PostListQuery,
+        visibility: PostVisibility,
+    ) -> Result<PostListResponse, PostError> {
+        // Validate input
+        query.validate().map_err(|e| PostError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        // Read before the posts, a listing read while they change is kept under the old version
+        let version = self.repository.listing_version().await?;
+        let key = (query, visibility);
+        if let Some(cached) = self.listings.read().unwrap().get(&key)
+            && cached.version == version
+            && cached.loaded_at.elapsed() < self.cache.listing_ttl
+        {
+            return Ok(cached.listing.clone());
+        }
+
+        let (query, visibility) = &key;
+        let posts = self.repository.list(query, *visibility).await?;
+        let total = self.repository.count(query, *visibility).await?;
+        let listing = PostListResponse {
+            posts: posts.into_iter().map(Into::into).collect(),
+            total,
+            page: query.page(),
+            per_page: query.per_page(),
+        };
+
+        let mut listings = self.listings.write().unwrap();
+        if listings.len() >= MAX_CACHED_LISTINGS {
+            listings.clear();
+        }
+        listings.insert(
+            key,
+            CachedListing {
+                version,
+                loaded_at: Instant::now(),
+                listing: listing.clone(),
+            },
+        );
+
+        Ok(listing)
+    }
+}
@@ -0,0 +1,603 @@
+// app/src/models/post/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::content::{Block, ContentDocument, ContentFormat};
+    use crate::models::post::*;
+    use crate::models::{SlugError, SlugLookup, SlugRepository, SlugScope, SlugServiceImpl};
+    use async_trait::async_trait;
+    use pavex::time::Timestamp;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    // Mock repository for testing the service layer
+    pub struct MockPostRepository {
+        posts: Arc<Mutex<HashMap<Uuid, Post>>>,
+        version: Arc<Mutex<i64>>,
+    }
+
+    impl MockPostRepository {
+        pub fn new() -> Self {
+            Self {
+                posts: Arc::new(Mutex::new(HashMap::new())),
+                version: Arc::new(Mutex::new(0)),
+            }
+        }
+
+        // whether a post is visible under the listing rules of the SQL repository
+        fn matches(post: &Post, query: &PostListQuery, visibility: PostVisibility) -> bool {
+            let status = match query.status {
+                Some(status) => post.status == status,
+                None => post.status != PostStatus::Trash,
+            };
+            let visible = match visibility {
+                PostVisibility::All => true,
+                PostVisibility::PublishedOrOwnedBy(owner) => {
+                    post.status.is_public() || post.author_id == owner
+                }
+            };
+            status
+                && visible
+                && query
+                    .author_id
+                    .is_none_or(|author| post.author_id == author)
+        }
+    }
+
+    #[async_trait]
+    impl PostRepository for MockPostRepository {
+        async fn create(&self, new: NewPost) -> Result<Post, PostError> {
+            let mut posts = self.posts.lock().unwrap();
+            if posts.values().any(|post| post.slug == new.slug) {
+                return Err(PostError::SlugExists);
+            }
+            let post = Post {
+                id: Uuid::new_v4(),
+                title: new.title,
+                slug: new.slug,
+                content: new.content,
+                excerpt: new.excerpt,
+                featured_image_url: new.featured_image_url,
+                author_id: new.author_id,
+                status: new.status,
+                comment_status: new.comment_status,
+                is_featured: new.is_featured,
+                view_count: 0,
+                seo: new.seo,
+                post_type: new.post_type,
+                custom_fields: new.custom_fields,
+                published_at: new.published_at,
+                created_at: Timestamp::now(),
+                updated_at: Timestamp::now(),
+            };
+            posts.insert(post.id, post.clone());
+            Ok(post)
+        }
+
+        async fn find_by_id(&self, id: Uuid) -> Result<Option<Post>, PostError> {
+            Ok(self.posts.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn find_by_slug(&self, slug: &str) -> Result<Option<Post>, PostError> {
+            let posts = self.posts.lock().unwrap();
+            Ok(posts.values().find(|post| post.slug == slug).cloned())
+        }
+
+        async fn update(&self, id: Uuid, request: UpdatePostRequest) -> Result<Post, PostError> {
+            let mut posts = self.posts.lock().unwrap();
+            let post = posts.get_mut(&id).ok_or(PostError::PostNotFound)?;
+            if let Some(title) = request.title {
+                post.title = title;
+            }
+            if let Some(slug) = request.slug {
+                post.slug = slug;
+            }
+            if let Some(content) = request.content {
+                post.content = serde_json::from_value(content).unwrap();
+            }
+            if let Some(status) = request.status {
+                post.status = status;
+            }
+            if request.published_at.is_some() {
+                post.published_at = request.published_at;
+            }
+            post.updated_at = Timestamp::now();
+            Ok(post.clone())
+        }
+
+        async fn delete(&self, id: Uuid) -> Result<bool, PostError> {
+            Ok(self.posts.lock().unwrap().remove(&id).is_some())
+        }
+
+        async fn list(
+            &self,
+            query: &PostListQuery,
+            visibility: PostVisibility,
+        ) -> Result<Vec<Post>, PostError> {
+            let posts = self.posts.lock().unwrap();
+            Ok(posts
+                .values()
+                .filter(|post| Self::matches(post, query, visibility))
+                .cloned()
+                .collect())
+        }
+
+        async fn count(
+            &self,
+            query: &PostListQuery,
+            visibility: PostVisibility,
+        ) -> Result<i64, PostError> {
+            Ok(self.list(query, visibility).await?.len() as i64)
+        }
+
+        async fn listing_version(&self) -> Result<i64, PostError> {
+            Ok(*self.version.lock().unwrap())
+        }
+
+        async fn bump_listing_version(&self) -> Result<(), PostError> {
+            *self.version.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    fn create_request(title: &str, status: PostStatus) -> CreatePostRequest {
+        serde_json::from_value(json!({
+            "title": title,
+            "content": { "version": 1, "blocks": [] },
+            "status": status,
+        }))
+        .unwrap()
+    }
+
+    // Mock slug repository, reading the slugs in use from the posts of the mock repository
+    pub struct MockSlugRepository {
+        posts: Arc<Mutex<HashMap<Uuid, Post>>>,
+        history: Mutex<HashMap<String, Uuid>>,
+    }
+
+    #[async_trait]
+    impl SlugRepository for MockSlugRepository {
+        async fn find_taken(
+            &self,
+            _scope: SlugScope,
+            base: &str,
+            except: Option<Uuid>,
+        ) -> Result<Vec<String>, SlugError> {
+            let prefix = format!("{base}-");
+            Ok(self
+                .posts
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|post| Some(post.id) != except)
+                .filter(|post| post.slug == base || post.slug.starts_with(&prefix))
+                .map(|post| post.slug.clone())
+                .collect())
+        }
+
+        async fn record_rename(
+            &self,
+            _scope: SlugScope,
+            entity_id: Uuid,
+            old_slug: &str,
+            new_slug: &str,
+        ) -> Result<(), SlugError> {
+            let mut history = self.history.lock().unwrap();
+            history.remove(new_slug);
+            history.insert(old_slug.to_string(), entity_id);
+            Ok(())
+        }
+
+        async fn find_current(
+            &self,
+            _scope: SlugScope,
+            slug: &str,
+        ) -> Result<Option<String>, SlugError> {
+            let id = self.history.lock().unwrap().get(slug).copied();
+            let posts = self.posts.lock().unwrap();
+            Ok(id
+                .and_then(|id| posts.get(&id))
+                .map(|post| post.slug.clone()))
+        }
+
+        async fn forget(&self, _scope: SlugScope, entity_id: Uuid) -> Result<(), SlugError> {
+            self.history
+                .lock()
+                .unwrap()
+                .retain(|_, id| *id != entity_id);
+            Ok(())
+        }
+    }
+
+    fn service() -> PostServiceImpl {
+        service_with(Arc::new(MockPostRepository::new()))
+    }
+
+    fn service_with(repository: Arc<MockPostRepository>) -> PostServiceImpl {
+        let slugs = MockSlugRepository {
+            posts: repository.posts.clone(),
+            history: Mutex::new(HashMap::new()),
+        };
+        PostServiceImpl::new(repository, Arc::new(SlugServiceImpl::new(Arc::new(slugs))))
+    }
+
+    #[tokio::test]
+    async fn test_create_post_uses_defaults_and_derives_the_slug() {
+        let service = service();
+        let author_id = Uuid::new_v4();
+
+        let post = service
+            .create_post(
+                author_id,
+                create_request("My First Post", PostStatus::Draft),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(post.slug, "my-first-post");
+        assert_eq!(post.author_id, author_id);
+        assert_eq!(post.status, PostStatus::Draft);
+        assert_eq!(post.comment_status, CommentStatus::Open);
+        assert_eq!(post.post_type, DEFAULT_POST_TYPE);
+        assert!(post.published_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_post_rejects_invalid_requests() {
+        let service = service();
+
+        let mut request = create_request("Title", PostStatus::Draft);
+        request.slug = Some("Not A Slug".into());
+        assert!(matches!(
+            service.create_post(Uuid::new_v4(), request).await,
+            Err(PostError::Validation { .. })
+        ));
+
+        let mut request = create_request("Title", PostStatus::Draft);
+        request.custom_fields = Some(json!(["not", "an", "object"]));
+        assert!(matches!(
+            service.create_post(Uuid::new_v4(), request).await,
+            Err(PostError::Validation { .. })
+        ));
+
+        let mut request = create_request("Title", PostStatus::Draft);
+        request.seo.canonical_url = Some("not a url".into());
+        assert!(matches!(
+            service.create_post(Uuid::new_v4(), request).await,
+            Err(PostError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_content_is_checked_on_write() {
+        let service = service();
+
+        let mut request = create_request("Title", PostStatus::Draft);
+        request.content = json!({ "version": 1, "blocks": [
+            { "type": "paragraph", "children": [
+                { "type": "link", "href": "javascript:alert(1)", "children": [] }
+            ] }
+        ] });
+        assert!(matches!(
+            service.create_post(Uuid::new_v4(), request).await,
+            Err(PostError::InvalidContent(_))
+        ));
+
+        let mut request = create_request("Title", PostStatus::Draft);
+        request.content = json!(["not", "a", "document"]);
+        assert!(matches!(
+            service.create_post(Uuid::new_v4(), request).await,
+            Err(PostError::InvalidContent(_))
+        ));
+
+        let post = service
+            .create_post(Uuid::new_v4(), create_request("Title", PostStatus::Draft))
+            .await
+            .unwrap();
+        let update = UpdatePostRequest {
+            content: Some(
+                json!({ "version": 1, "blocks": [{ "type": "heading", "level": 9, "children": [] }] }),
+            ),
+            ..Default::default()
+        };
+        assert!(matches!(
+            service.update_post(post.id, update).await,
+            Err(PostError::InvalidContent(_))
+        ));
+
+        let document = ContentDocument::new(vec![Block::paragraph("Hello")]);
+        let update = UpdatePostRequest {
+            content: Some(document.to_value()),
+            ..Default::default()
+        };
+        let updated = service.update_post(post.id, update).await.unwrap();
+        assert_eq!(updated.content, document);
+    }
+
+    #[tokio::test]
+    async fn test_content_can_be_written_in_markdown() {
+        let service = service();
+
+        let mut request = create_request("Title", PostStatus::Draft);
+        request.content = json!("Hello *world*");
+        request.format = ContentFormat::Markdown;
+        let post = service.create_post(Uuid::new_v4(), request).await.unwrap();
+        assert_eq!(post.content.to_plain_text(), "Hello world");
+
+        let update = UpdatePostRequest {
+            content: Some(json!({ "version": 1, "blocks": [] })),
+            format: ContentFormat::Markdown,
+            ..Default::default()
+        };
+        assert!(matches!(
+            service.update_post(post.id, update).await,
+            Err(PostError::Validation { .. })
+        ));
+
+        // A Markdown document brings its title along as its first heading
+        let update = UpdatePostRequest::from_markdown("# New title\n\n- one\n- two\n").unwrap();
+        let updated = service.update_post(post.id, update).await.unwrap();
+        assert_eq!(updated.title, "New title");
+        assert_eq!(updated.content.to_plain_text(), "one\n\ntwo");
+        assert_eq!(updated.to_markdown(), "# New title\n\n- one\n- two\n");
+
+        assert!(matches!(
+            CreatePostRequest::from_markdown("No heading"),
+            Err(PostError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_derived_slugs_are_made_unique_but_chosen_ones_are_not() {
+        let service = service();
+
+        service
+            .create_post(Uuid::new_v4(), create_request("Same", PostStatus::Draft))
+            .await
+            .unwrap();
+        let second = service
+            .create_post(Uuid::new_v4(), create_request("Same", PostStatus::Draft))
+            .await
+            .unwrap();
+        assert_eq!(second.slug, "same-2");
+
+        let mut request = create_request("Other", PostStatus::Draft);
+        request.slug = Some("same".into());
+        let result = service.create_post(Uuid::new_v4(), request).await;
+        assert!(matches!(result, Err(PostError::SlugExists)));
+
+        let mut request = create_request("Admin", PostStatus::Draft);
+        request.slug = Some("admin".into());
+        let result = service.create_post(Uuid::new_v4(), request).await;
+        assert!(matches!(result, Err(PostError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_renamed_posts_are_found_by_their_old_slug() {
+        let service = service();
+        let post = service
+            .create_post(Uuid::new_v4(), create_request("Hello", PostStatus::Draft))
+            .await
+            .unwrap();
+
+        let rename = UpdatePostRequest {
+            slug: Some("hello-again".into()),
+            ..Default::default()
+        };
+        service.update_post(post.id, rename).await.unwrap();
+
+        match service.find_post_by_slug("hello").await.unwrap() {
+            SlugLookup::Moved(moved) => assert_eq!(moved.slug, "hello-again"),
+            SlugLookup::Found(_) => panic!("the old slug should have moved"),
+        }
+        assert!(matches!(
+            service.find_post_by_slug("hello-again").await.unwrap(),
+            SlugLookup::Found(_)
+        ));
+
+        service.delete_post(post.id).await.unwrap();
+        assert!(matches!(
+            service.find_post_by_slug("hello").await,
+            Err(PostError::PostNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_first_publication_is_dated_once() {
+        let service = service();
+
+        let published = service
+            .create_post(
+                Uuid::new_v4(),
+                create_request("Live", PostStatus::Published),
+            )
+            .await
+            .unwrap();
+        assert!(published.published_at.is_some());
+
+        let draft = service
+            .create_post(Uuid::new_v4(), create_request("Later", PostStatus::Draft))
+            .await
+            .unwrap();
+        let publish = UpdatePostRequest {
+            status: Some(PostStatus::Published),
+            ..Default::default()
+        };
+        let first = service
+            .update_post(draft.id, publish.clone())
+            .await
+            .unwrap();
+        let first_date = first.published_at.expect("publishing dates the post");
+
+        let unpublish = UpdatePostRequest {
+            status: Some(PostStatus::Draft),
+            ..Default::default()
+        };
+        service.update_post(draft.id, unpublish).await.unwrap();
+        let again = service.update_post(draft.id, publish).await.unwrap();
+        assert_eq!(again.published_at, Some(first_date));
+    }
+
+    #[tokio::test]
+    async fn test_posts_dated_in_the_future_are_scheduled() {
+        let service = service();
+        let tomorrow = Timestamp::now() + Duration::from_secs(24 * 60 * 60);
+        let yesterday = Timestamp::now() - Duration::from_secs(24 * 60 * 60);
+
+        let mut request = create_request("Soon", PostStatus::Published);
+        request.published_at = Some(tomorrow);
+        let post = service.create_post(Uuid::new_v4(), request).await.unwrap();
+        assert_eq!(post.status, PostStatus::Scheduled);
+        assert!(!post.status.is_public());
+
+        // Bringing the date forward publishes it, moving it back schedules it again
+        let update = |published_at| UpdatePostRequest {
+            published_at: Some(published_at),
+            ..Default::default()
+        };
+        let post = service
+            .update_post(post.id, update(yesterday))
+            .await
+            .unwrap();
+        assert_eq!(post.status, PostStatus::Published);
+        let post = service
+            .update_post(post.id, update(tomorrow))
+            .await
+            .unwrap();
+        assert_eq!(post.status, PostStatus::Scheduled);
+
+        // Scheduling takes a date
+        assert!(matches!(
+            service
+                .create_post(
+                    Uuid::new_v4(),
+                    create_request("When?", PostStatus::Scheduled)
+                )
+                .await,
+            Err(PostError::Validation { .. })
+        ));
+        let draft = service
+            .create_post(Uuid::new_v4(), create_request("Draft", PostStatus::Draft))
+            .await
+            .unwrap();
+        let post = service
+            .update_post(draft.id, update(tomorrow))
+            .await
+            .unwrap();
+        assert_eq!(post.status, PostStatus::Draft);
+    }
+
+    #[tokio::test]
+    async fn test_listing_hides_trash_and_others_drafts() {
+        let service = service();
+        let author = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        service
+            .create_post(author, create_request("Own draft", PostStatus::Draft))
+            .await
+            .unwrap();
+        service
+            .create_post(other, create_request("Other draft", PostStatus::Draft))
+            .await
+            .unwrap();
+        service
+            .create_post(other, create_request("Other live", PostStatus::Published))
+            .await
+            .unwrap();
+        service
+            .create_post(other, create_request("Binned", PostStatus::Trash))
+            .await
+            .unwrap();
+
+        let own = service
+            .list_posts(
+                PostListQuery::default(),
+                PostVisibility::PublishedOrOwnedBy(author),
+            )
+            .await
+            .unwrap();
+        let mut titles: Vec<_> = own.posts.iter().map(|post| post.title.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, ["Other live", "Own draft"]);
+        assert_eq!(own.total, 2);
+
+        let all = service
+            .list_posts(PostListQuery::default(), PostVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(all.total, 3);
+
+        let trash = PostListQuery {
+            status: Some(PostStatus::Trash),
+            ..Default::default()
+        };
+        let trash = service
+            .list_posts(trash, PostVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(trash.total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_listings_are_cached_until_the_posts_change() {
+        let repository = Arc::new(MockPostRepository::new());
+        let service = service_with(repository.clone());
+        let author = Uuid::new_v4();
+        let listed = || async {
+            service
+                .list_posts(PostListQuery::default(), PostVisibility::All)
+                .await
+                .unwrap()
+                .total
+        };
+
+        let post = service
+            .create_post(author, create_request("First", PostStatus::Published))
+            .await
+            .unwrap();
+        assert_eq!(listed().await, 1);
+
+        // A post published behind the service's back, as the scheduler does
+        let mut published = repository.posts.lock().unwrap()[&post.id].clone();
+        published.id = Uuid::new_v4();
+        published.slug = "second".to_string();
+        repository
+            .posts
+            .lock()
+            .unwrap()
+            .insert(published.id, published);
+        assert_eq!(listed().await, 1);
+
+        // Until the posts move to a new version, as the scheduler's publish hook does
+        repository.bump_listing_version().await.unwrap();
+        assert_eq!(listed().await, 2);
+
+        // Changes made through the service apply right away
+        service.delete_post(post.id).await.unwrap();
+        assert_eq!(listed().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_missing_posts_are_not_found() {
+        let service = service();
+
+        assert!(matches!(
+            service.get_post(Uuid::new_v4()).await,
+            Err(PostError::PostNotFound)
+        ));
+        assert!(matches!(
+            service
+                .update_post(Uuid::new_v4(), UpdatePostRequest::default())
+                .await,
+            Err(PostError::PostNotFound)
+        ));
+        assert!(matches!(
+            service.delete_post(Uuid::new_v4()).await,
+            Err(PostError::PostNotFound)
+        ));
+    }
+}
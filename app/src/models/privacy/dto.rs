@@ -0,0 +1,47 @@
+// app/src/models/privacy/dto.rs
+
+// dependencies
+use super::entity::{AccountDeletion, DeletionMode};
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Request DTO for users deleting their own account
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeleteAccountRequest {
+    pub password: String,
+    #[serde(default)]
+    pub mode: DeletionMode,
+}
+
+/// Request DTO for admins scheduling the deletion of an account
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleDeletionRequest {
+    #[serde(default)]
+    pub mode: DeletionMode,
+}
+
+/// Query parameters for admins deleting an account right away
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EraseAccountQuery {
+    /// Defaults to removing the account
+    pub mode: Option<DeletionMode>,
+}
+
+/// Public account deletion response DTO
+#[derive(Debug, Serialize)]
+pub struct AccountDeletionResponse {
+    pub mode: DeletionMode,
+    pub requested_at: Timestamp,
+    pub scheduled_for: Timestamp,
+}
+
+// Conversion implementations
+impl From<AccountDeletion> for AccountDeletionResponse {
+    fn from(deletion: AccountDeletion) -> Self {
+        Self {
+            mode: deletion.mode,
+            requested_at: deletion.requested_at,
+            scheduled_for: deletion.scheduled_for,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+// app/src/models/privacy/entity.rs
+
+// dependencies
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// What becomes of an account once its deletion is carried out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeletionMode {
+    /// The account is kept, scrubbed of everything which identifies its owner
+    #[default]
+    Anonymize,
+    /// The account is removed, its content is handed over to the placeholder author
+    Delete,
+}
+
+impl DeletionMode {
+    // Name of the mode, as stored
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeletionMode::Anonymize => "anonymize",
+            DeletionMode::Delete => "delete",
+        }
+    }
+
+    // Parse mode from string - useful for manual row mapping
+    pub fn frm_str(s: &str) -> Option<Self> {
+        match s {
+            "anonymize" => Some(Self::Anonymize),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+// Deletion of an account, pending until its grace period is over
+#[derive(Debug, Clone)]
+pub struct AccountDeletion {
+    pub user_id: Uuid,
+    pub mode: DeletionMode,
+    /// Unset once the requester is gone, equal to `user_id` when users delete themselves
+    pub requested_by: Option<Uuid>,
+    pub requested_at: Timestamp,
+    pub scheduled_for: Timestamp,
+}
+
+// Personal data of a user, every entry is a JSON document
+#[derive(Debug, Clone)]
+pub struct UserDataExport {
+    pub user: String,
+    pub posts: String,
+    pub comments: String,
+    pub media: String,
+    pub sessions: String,
+}
+
+impl UserDataExport {
+    // The files of the export archive, along with their content
+    pub fn files(&self) -> [(&'static str, &str); 5] {
+        [
+            ("user.json", &self.user),
+            ("posts.json", &self.posts),
+            ("comments.json", &self.comments),
+            ("media.json", &self.media),
+            ("sessions.json", &self.sessions),
+        ]
+    }
+}
@@ -0,0 +1,41 @@
+// app/src/models/privacy/error.rs
+
+use crate::response::IntoApiError;
+use pavex::http::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PrivacyError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Validation error: {message}")]
+    Validation { message: String },
+
+    #[error("User not found")]
+    UserNotFound,
+
+    #[error("No account deletion is pending")]
+    DeletionNotFound,
+
+    #[error("Export archive error: {0}")]
+    Archive(String),
+}
+
+impl IntoApiError for PrivacyError {
+    fn code(&self) -> Option<u16> {
+        match self {
+            PrivacyError::Validation { .. } => Some(StatusCode::BAD_REQUEST.as_u16()),
+            PrivacyError::UserNotFound | PrivacyError::DeletionNotFound => {
+                Some(StatusCode::NOT_FOUND.as_u16())
+            }
+            PrivacyError::Database(_) | PrivacyError::Archive(_) => {
+                Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16())
+            }
+        }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,402 @@
+// app/src/models/privacy/repository.rs
+
+// dependencies
+use super::entity::{AccountDeletion, DeletionMode, UserDataExport};
+use super::error::PrivacyError;
+use crate::models::{DELETED_USER_ID, UNUSABLE_PASSWORD_HASH};
+use async_trait::async_trait;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use pavex::time::Timestamp;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::time::Duration;
+use uuid::Uuid;
+
+// traits
+#[async_trait]
+pub trait PrivacyRepository: Send + Sync {
+    async fn export(&self, user_id: Uuid) -> Result<Option<UserDataExport>, PrivacyError>;
+    async fn find_deletion(&self, user_id: Uuid) -> Result<Option<AccountDeletion>, PrivacyError>;
+    async fn schedule_deletion(
+        &self,
+        user_id: Uuid,
+        mode: DeletionMode,
+        requested_by: Uuid,
+        grace_period: Duration,
+    ) -> Result<Option<AccountDeletion>, PrivacyError>;
+    async fn cancel_deletion(&self, user_id: Uuid) -> Result<bool, PrivacyError>;
+    async fn due_deletions(&self, now: Timestamp) -> Result<Vec<AccountDeletion>, PrivacyError>;
+    async fn erase(&self, user_id: Uuid, mode: DeletionMode) -> Result<bool, PrivacyError>;
+    async fn erase_due(&self, user_id: Uuid) -> Result<Option<DeletionMode>, PrivacyError>;
+}
+
+pub struct SqlxPrivacyRepository {
+    pool: PgPool,
+}
+
+impl SqlxPrivacyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Helper function to map an account_deletions row to AccountDeletion struct
+    fn map_row_to_deletion(row: &sqlx::postgres::PgRow) -> Result<AccountDeletion, PrivacyError> {
+        let mode: String = row.get("mode");
+        let mode = DeletionMode::frm_str(&mode).ok_or_else(|| PrivacyError::Validation {
+            message: format!("Unknown deletion mode: {mode}"),
+        })?;
+
+        Ok(AccountDeletion {
+            user_id: row.get("user_id"),
+            mode,
+            requested_by: row.get("requested_by"),
+            requested_at: row.get::<SqlxTimestamp, _>("requested_at").into(),
+            scheduled_for: row.get::<SqlxTimestamp, _>("scheduled_for").into(),
+        })
+    }
+
+    // erase the account within `tx`, returning false if it does not exist
+    async fn erase_within(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        mode: DeletionMode,
+    ) -> Result<bool, PrivacyError> {
+        let Some(email) =
+            sqlx::query_scalar::<_, String>("SELECT email FROM users WHERE id = $1 FOR UPDATE")
+                .bind(user_id)
+                .fetch_optional(&mut **tx)
+                .await?
+        else {
+            return Ok(false);
+        };
+
+        // Comments stay in their threads, without anything pointing back at their author
+        sqlx::query(
+            r#"
+            UPDATE comments
+            SET author_name = 'Deleted user',
+                author_email = 'deleted@users.invalid',
+                author_url = NULL,
+                author_fingerprint = NULL,
+                user_agent = NULL
+            WHERE user_id = $1 OR author_email = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(&email)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query("DELETE FROM sessions WHERE state ->> 'user.id' = $1")
+            .bind(user_id.to_string())
+            .execute(&mut **tx)
+            .await?;
+        sqlx::query("DELETE FROM account_deletions WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+
+        match mode {
+            DeletionMode::Delete => {
+                // Content outlives its author, the foreign keys refuse to cascade
+                for statement in [
+                    "UPDATE posts SET author_id = $2 WHERE author_id = $1",
+                    "UPDATE pages SET author_id = $2 WHERE author_id = $1",
+                    "UPDATE revisions SET author_id = $2 WHERE author_id = $1",
+                    "UPDATE media SET uploaded_by = $2 WHERE uploaded_by = $1",
+                ] {
+                    sqlx::query(statement)
+                        .bind(user_id)
+                        .bind(DELETED_USER_ID)
+                        .execute(&mut **tx)
+                        .await?;
+                }
+
+                // Credentials and pending email changes go along with the account
+                sqlx::query("DELETE FROM users WHERE id = $1")
+                    .bind(user_id)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+            DeletionMode::Anonymize => {
+                for statement in [
+                    "DELETE FROM api_tokens WHERE user_id = $1",
+                    "DELETE FROM user_totp WHERE user_id = $1",
+                    "DELETE FROM user_recovery_codes WHERE user_id = $1",
+                    "DELETE FROM email_changes WHERE user_id = $1",
+                    "DELETE FROM user_identities WHERE user_id = $1",
+                    "DELETE FROM passkeys WHERE user_id = $1",
+                    "DELETE FROM magic_links WHERE user_id = $1",
+                ] {
+                    sqlx::query(statement)
+                        .bind(user_id)
+                        .execute(&mut **tx)
+                        .await?;
+                }
+
+                sqlx::query(
+                    r#"
+                    UPDATE users
+                    SET username = 'deleted_' || replace(id::text, '-', ''),
+                        email = 'deleted_' || replace(id::text, '-', '') || '@users.invalid',
+                        password_hash = $2,
+                        display_name = 'Deleted user',
+                        bio = NULL,
+                        avatar_url = NULL,
+                        role = 'subscriber',
+                        is_active = false,
+                        email_verified = false,
+                        email_verification_token = NULL,
+                        email_verification_expires_at = NULL,
+                        password_reset_token = NULL,
+                        password_reset_expires_at = NULL,
+                        social_twitter = NULL,
+                        social_facebook = NULL,
+                        social_linkedin = NULL,
+                        social_instagram = NULL,
+                        social_youtube = NULL,
+                        social_github = NULL,
+                        website_url = NULL,
+                        updated_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(user_id)
+                .bind(UNUSABLE_PASSWORD_HASH)
+                .execute(&mut **tx)
+                .await?;
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl PrivacyRepository for SqlxPrivacyRepository {
+    async fn export(&self, user_id: Uuid) -> Result<Option<UserDataExport>, PrivacyError> {
+        // Every file is read from the same snapshot, so they agree with each other
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+
+        // Only the listed columns are exported, so secrets and one-time tokens, including those
+        // of columns added later, never leak through an export
+        let Some(row) = sqlx::query(
+            r#"
+            SELECT
+                jsonb_build_object(
+                    'id', id,
+                    'username', username,
+                    'email', email,
+                    'display_name', display_name,
+                    'bio', bio,
+                    'avatar_url', avatar_url,
+                    'role', role,
+                    'is_active', is_active,
+                    'email_verified', email_verified,
+                    'social_twitter', social_twitter,
+                    'social_facebook', social_facebook,
+                    'social_linkedin', social_linkedin,
+                    'social_instagram', social_instagram,
+                    'social_youtube', social_youtube,
+                    'social_github', social_github,
+                    'website_url', website_url,
+                    'created_at', created_at,
+                    'updated_at', updated_at
+                )::text AS document,
+                email
+            FROM users
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+        let user: String = row.get("document");
+        let email: String = row.get("email");
+
+        let posts: String = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(jsonb_agg(to_jsonb(p) - 'password' ORDER BY p.created_at), '[]')::text
+            FROM posts p
+            WHERE author_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Comments left before signing up only carry the address they were left with
+        let comments: String = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(jsonb_agg(to_jsonb(c) ORDER BY c.created_at), '[]')::text
+            FROM comments c
+            WHERE user_id = $1 OR author_email = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(&email)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let media: String = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(jsonb_agg(to_jsonb(m) ORDER BY m.created_at), '[]')::text
+            FROM media m
+            WHERE uploaded_by = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Session ids are credentials, only what the session list shows is exported
+        let sessions: String = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(jsonb_agg(
+                jsonb_build_object(
+                    'info',
+                    state -> 'session.info'
+                        || jsonb_strip_nulls(jsonb_build_object('last_seen_at', activity.last_seen_at)),
+                    'expires_at',
+                    deadline
+                )
+                ORDER BY deadline
+            ), '[]')::text
+            FROM sessions
+            LEFT JOIN session_activity activity
+                ON activity.handle::text = state -> 'session.info' ->> 'handle'
+            WHERE state ->> 'user.id' = $1
+            AND deadline > NOW()
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(UserDataExport {
+            user,
+            posts,
+            comments,
+            media,
+            sessions,
+        }))
+    }
+
+    async fn find_deletion(&self, user_id: Uuid) -> Result<Option<AccountDeletion>, PrivacyError> {
+        let row = sqlx::query(
+            r#"
+            SELECT user_id, mode, requested_by, requested_at, scheduled_for
+            FROM account_deletions
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(Self::map_row_to_deletion).transpose()
+    }
+
+    async fn schedule_deletion(
+        &self,
+        user_id: Uuid,
+        mode: DeletionMode,
+        requested_by: Uuid,
+        grace_period: Duration,
+    ) -> Result<Option<AccountDeletion>, PrivacyError> {
+        let scheduled_for = Timestamp::now() + grace_period;
+
+        // Asking again starts the grace period over, with the latest choice of mode
+        let row = sqlx::query(
+            r#"
+            INSERT INTO account_deletions (user_id, mode, requested_by, scheduled_for)
+            SELECT id, $2, $3, $4 FROM users WHERE id = $1
+            ON CONFLICT (user_id) DO UPDATE
+            SET mode = EXCLUDED.mode,
+                requested_by = EXCLUDED.requested_by,
+                requested_at = NOW(),
+                scheduled_for = EXCLUDED.scheduled_for
+            RETURNING user_id, mode, requested_by, requested_at, scheduled_for
+            "#,
+        )
+        .bind(user_id)
+        .bind(mode.as_str())
+        .bind(requested_by)
+        .bind(SqlxTimestamp::from(scheduled_for))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(Self::map_row_to_deletion).transpose()
+    }
+
+    async fn cancel_deletion(&self, user_id: Uuid) -> Result<bool, PrivacyError> {
+        let result = sqlx::query("DELETE FROM account_deletions WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn due_deletions(&self, now: Timestamp) -> Result<Vec<AccountDeletion>, PrivacyError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, mode, requested_by, requested_at, scheduled_for
+            FROM account_deletions
+            WHERE scheduled_for <= $1
+            ORDER BY scheduled_for
+            "#,
+        )
+        .bind(SqlxTimestamp::from(now))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::map_row_to_deletion).collect()
+    }
+
+    async fn erase(&self, user_id: Uuid, mode: DeletionMode) -> Result<bool, PrivacyError> {
+        let mut tx = self.pool.begin().await?;
+        if !Self::erase_within(&mut tx, user_id, mode).await? {
+            return Ok(false);
+        }
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn erase_due(&self, user_id: Uuid) -> Result<Option<DeletionMode>, PrivacyError> {
+        let mut tx = self.pool.begin().await?;
+
+        // The deletion may have been cancelled or pushed back since the sweep read it
+        let Some(mode) = sqlx::query_scalar::<_, String>(
+            r#"
+            DELETE FROM account_deletions
+            WHERE user_id = $1 AND scheduled_for <= NOW()
+            RETURNING mode
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+        let mode = DeletionMode::frm_str(&mode).ok_or_else(|| PrivacyError::Validation {
+            message: format!("Unknown deletion mode: {mode}"),
+        })?;
+
+        if !Self::erase_within(&mut tx, user_id, mode).await? {
+            return Ok(None);
+        }
+        tx.commit().await?;
+
+        Ok(Some(mode))
+    }
+}
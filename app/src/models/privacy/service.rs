@@ -0,0 +1,206 @@
+// app/src/models/privacy/service.rs
+
+// dependencies
+use super::dto::AccountDeletionResponse;
+use super::entity::{DeletionMode, UserDataExport};
+use super::error::PrivacyError;
+use super::repository::PrivacyRepository;
+use crate::configuration::AccountDeletionConfig;
+use crate::models::DELETED_USER_ID;
+use async_trait::async_trait;
+use pavex::time::Timestamp;
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+use tracing_log_error::log_error;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+// traits
+#[async_trait]
+pub trait PrivacyService: Send + Sync {
+    async fn export_data(&self, user_id: Uuid) -> Result<Vec<u8>, PrivacyError>;
+    async fn pending_deletion(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<AccountDeletionResponse>, PrivacyError>;
+    // `actor` is the id of whoever asked, the user themselves or an admin
+    async fn schedule_deletion(
+        &self,
+        actor: Uuid,
+        user_id: Uuid,
+        mode: DeletionMode,
+    ) -> Result<AccountDeletionResponse, PrivacyError>;
+    async fn cancel_deletion(&self, actor: Uuid, user_id: Uuid) -> Result<(), PrivacyError>;
+    async fn erase_account(
+        &self,
+        actor: Uuid,
+        user_id: Uuid,
+        mode: DeletionMode,
+    ) -> Result<(), PrivacyError>;
+    async fn process_due_deletions(&self) -> Result<u64, PrivacyError>;
+}
+
+pub struct PrivacyServiceImpl {
+    repository: Arc<dyn PrivacyRepository>,
+    deletion: AccountDeletionConfig,
+}
+
+impl PrivacyServiceImpl {
+    pub fn new(repository: Arc<dyn PrivacyRepository>) -> Self {
+        Self {
+            repository,
+            deletion: AccountDeletionConfig::default(),
+        }
+    }
+
+    pub fn with_deletion(self, deletion: AccountDeletionConfig) -> Self {
+        Self { deletion, ..self }
+    }
+
+    // the placeholder author holds the content of every deleted account
+    fn forbid_placeholder(user_id: Uuid) -> Result<(), PrivacyError> {
+        if user_id == DELETED_USER_ID {
+            return Err(PrivacyError::Validation {
+                message: "The placeholder author cannot be deleted".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Pack the personal data of a user into a ZIP archive, one pretty-printed JSON file per kind
+pub fn export_archive(export: &UserDataExport) -> Result<Vec<u8>, PrivacyError> {
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, document) in export.files() {
+        let value: serde_json::Value =
+            serde_json::from_str(document).map_err(|e| PrivacyError::Archive(e.to_string()))?;
+        let contents =
+            serde_json::to_vec_pretty(&value).map_err(|e| PrivacyError::Archive(e.to_string()))?;
+
+        archive
+            .start_file(name, options)
+            .map_err(|e| PrivacyError::Archive(e.to_string()))?;
+        archive
+            .write_all(&contents)
+            .map_err(|e| PrivacyError::Archive(e.to_string()))?;
+    }
+
+    let archive = archive
+        .finish()
+        .map_err(|e| PrivacyError::Archive(e.to_string()))?;
+    Ok(archive.into_inner())
+}
+
+#[async_trait]
+impl PrivacyService for PrivacyServiceImpl {
+    async fn export_data(&self, user_id: Uuid) -> Result<Vec<u8>, PrivacyError> {
+        let export = self
+            .repository
+            .export(user_id)
+            .await?
+            .ok_or(PrivacyError::UserNotFound)?;
+        let archive = export_archive(&export)?;
+        tracing::info!(user.id = %user_id, "Personal data exported");
+
+        Ok(archive)
+    }
+
+    async fn pending_deletion(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<AccountDeletionResponse>, PrivacyError> {
+        let deletion = self.repository.find_deletion(user_id).await?;
+
+        Ok(deletion.map(AccountDeletionResponse::from))
+    }
+
+    async fn schedule_deletion(
+        &self,
+        actor: Uuid,
+        user_id: Uuid,
+        mode: DeletionMode,
+    ) -> Result<AccountDeletionResponse, PrivacyError> {
+        Self::forbid_placeholder(user_id)?;
+
+        let deletion = self
+            .repository
+            .schedule_deletion(user_id, mode, actor, self.deletion.grace_period)
+            .await?
+            .ok_or(PrivacyError::UserNotFound)?;
+        tracing::info!(
+            user.id = %user_id,
+            actor.id = %actor,
+            deletion.mode = mode.as_str(),
+            deletion.scheduled_for = %deletion.scheduled_for,
+            "Account deletion scheduled"
+        );
+
+        Ok(deletion.into())
+    }
+
+    async fn cancel_deletion(&self, actor: Uuid, user_id: Uuid) -> Result<(), PrivacyError> {
+        if !self.repository.cancel_deletion(user_id).await? {
+            return Err(PrivacyError::DeletionNotFound);
+        }
+        tracing::info!(user.id = %user_id, actor.id = %actor, "Account deletion cancelled");
+
+        Ok(())
+    }
+
+    async fn erase_account(
+        &self,
+        actor: Uuid,
+        user_id: Uuid,
+        mode: DeletionMode,
+    ) -> Result<(), PrivacyError> {
+        // Admins go through the grace period like everyone else for their own account
+        if actor == user_id {
+            return Err(PrivacyError::Validation {
+                message: "You cannot delete your own account".into(),
+            });
+        }
+        Self::forbid_placeholder(user_id)?;
+
+        if !self.repository.erase(user_id, mode).await? {
+            return Err(PrivacyError::UserNotFound);
+        }
+        tracing::info!(
+            user.id = %user_id,
+            admin.id = %actor,
+            deletion.mode = mode.as_str(),
+            "Account erased"
+        );
+
+        Ok(())
+    }
+
+    async fn process_due_deletions(&self) -> Result<u64, PrivacyError> {
+        let due = self.repository.due_deletions(Timestamp::now()).await?;
+
+        // One account failing must not hold up the others, it is retried on the next sweep
+        let mut erased = 0;
+        for deletion in due {
+            // The deletion is checked again when erasing, it may have been cancelled meanwhile
+            match self.repository.erase_due(deletion.user_id).await {
+                Ok(Some(mode)) => {
+                    erased += 1;
+                    tracing::info!(
+                        user.id = %deletion.user_id,
+                        deletion.mode = mode.as_str(),
+                        "Account erased after its grace period"
+                    );
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(user.id = %deletion.user_id, "Account erasure will be retried");
+                    log_error!(e, "Failed to erase an account");
+                }
+            }
+        }
+
+        Ok(erased)
+    }
+}
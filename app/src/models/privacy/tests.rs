@@ -0,0 +1,269 @@
+// app/src/models/privacy/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::configuration::AccountDeletionConfig;
+    use crate::models::DELETED_USER_ID;
+    use crate::models::privacy::*;
+    use async_trait::async_trait;
+    use pavex::time::Timestamp;
+    use std::collections::HashMap;
+    use std::io::{Cursor, Read};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    // Mock repository for testing the service layer
+    pub struct MockPrivacyRepository {
+        users: Arc<Mutex<HashMap<Uuid, Option<DeletionMode>>>>,
+        deletions: Arc<Mutex<HashMap<Uuid, AccountDeletion>>>,
+    }
+
+    impl MockPrivacyRepository {
+        pub fn new() -> Self {
+            Self {
+                users: Arc::new(Mutex::new(HashMap::new())),
+                deletions: Arc::new(Mutex::new(HashMap::new())),
+            }
+        }
+
+        pub fn insert_user(&self) -> Uuid {
+            let id = Uuid::new_v4();
+            self.users.lock().unwrap().insert(id, None);
+            id
+        }
+
+        // how the account was erased, if it was
+        pub fn erased_as(&self, id: Uuid) -> Option<DeletionMode> {
+            self.users.lock().unwrap().get(&id).copied().flatten()
+        }
+
+        pub fn backdate_deletion(&self, id: Uuid) {
+            let mut deletions = self.deletions.lock().unwrap();
+            let deletion = deletions.get_mut(&id).unwrap();
+            deletion.scheduled_for = Timestamp::now() - Duration::from_secs(1);
+        }
+    }
+
+    #[async_trait]
+    impl PrivacyRepository for MockPrivacyRepository {
+        async fn export(&self, user_id: Uuid) -> Result<Option<UserDataExport>, PrivacyError> {
+            if !self.users.lock().unwrap().contains_key(&user_id) {
+                return Ok(None);
+            }
+            Ok(Some(UserDataExport {
+                user: format!(r#"{{"id":"{user_id}","email":"writer@example.com"}}"#),
+                posts: r#"[{"title":"Hello"}]"#.into(),
+                comments: "[]".into(),
+                media: "[]".into(),
+                sessions: "[]".into(),
+            }))
+        }
+
+        async fn find_deletion(
+            &self,
+            user_id: Uuid,
+        ) -> Result<Option<AccountDeletion>, PrivacyError> {
+            Ok(self.deletions.lock().unwrap().get(&user_id).cloned())
+        }
+
+        async fn schedule_deletion(
+            &self,
+            user_id: Uuid,
+            mode: DeletionMode,
+            requested_by: Uuid,
+            grace_period: Duration,
+        ) -> Result<Option<AccountDeletion>, PrivacyError> {
+            if !self.users.lock().unwrap().contains_key(&user_id) {
+                return Ok(None);
+            }
+            let now = Timestamp::now();
+            let deletion = AccountDeletion {
+                user_id,
+                mode,
+                requested_by: Some(requested_by),
+                requested_at: now,
+                scheduled_for: now + grace_period,
+            };
+            self.deletions
+                .lock()
+                .unwrap()
+                .insert(user_id, deletion.clone());
+            Ok(Some(deletion))
+        }
+
+        async fn cancel_deletion(&self, user_id: Uuid) -> Result<bool, PrivacyError> {
+            Ok(self.deletions.lock().unwrap().remove(&user_id).is_some())
+        }
+
+        async fn due_deletions(
+            &self,
+            now: Timestamp,
+        ) -> Result<Vec<AccountDeletion>, PrivacyError> {
+            let deletions = self.deletions.lock().unwrap();
+            Ok(deletions
+                .values()
+                .filter(|deletion| deletion.scheduled_for <= now)
+                .cloned()
+                .collect())
+        }
+
+        async fn erase(&self, user_id: Uuid, mode: DeletionMode) -> Result<bool, PrivacyError> {
+            let mut users = self.users.lock().unwrap();
+            let Some(erased) = users.get_mut(&user_id) else {
+                return Ok(false);
+            };
+            *erased = Some(mode);
+            self.deletions.lock().unwrap().remove(&user_id);
+            Ok(true)
+        }
+
+        async fn erase_due(&self, user_id: Uuid) -> Result<Option<DeletionMode>, PrivacyError> {
+            let mut deletions = self.deletions.lock().unwrap();
+            let Some(deletion) = deletions
+                .get(&user_id)
+                .filter(|deletion| deletion.scheduled_for <= Timestamp::now())
+                .cloned()
+            else {
+                return Ok(None);
+            };
+            deletions.remove(&user_id);
+            let mut users = self.users.lock().unwrap();
+            let Some(erased) = users.get_mut(&user_id) else {
+                return Ok(None);
+            };
+            *erased = Some(deletion.mode);
+            Ok(Some(deletion.mode))
+        }
+    }
+
+    #[test]
+    fn test_export_archive_holds_one_json_file_per_kind() {
+        let export = UserDataExport {
+            user: r#"{"username":"writer"}"#.into(),
+            posts: "[]".into(),
+            comments: "[]".into(),
+            media: "[]".into(),
+            sessions: "[]".into(),
+        };
+
+        let bytes = export_archive(&export).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut names: Vec<_> = archive.file_names().map(String::from).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "comments.json",
+                "media.json",
+                "posts.json",
+                "sessions.json",
+                "user.json"
+            ]
+        );
+
+        let mut user = String::new();
+        archive
+            .by_name("user.json")
+            .unwrap()
+            .read_to_string(&mut user)
+            .unwrap();
+        assert_eq!(user, "{\n  \"username\": \"writer\"\n}");
+    }
+
+    #[tokio::test]
+    async fn test_export_data_of_missing_user() {
+        let repository = Arc::new(MockPrivacyRepository::new());
+        let service = PrivacyServiceImpl::new(repository.clone());
+        let id = repository.insert_user();
+
+        assert!(!service.export_data(id).await.unwrap().is_empty());
+        let result = service.export_data(Uuid::new_v4()).await;
+        assert!(matches!(result, Err(PrivacyError::UserNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_schedule_and_cancel_deletion() {
+        let repository = Arc::new(MockPrivacyRepository::new());
+        let service =
+            PrivacyServiceImpl::new(repository.clone()).with_deletion(AccountDeletionConfig {
+                grace_period: Duration::from_secs(7 * 24 * 60 * 60),
+                ..AccountDeletionConfig::default()
+            });
+        let id = repository.insert_user();
+
+        let deletion = service
+            .schedule_deletion(id, id, DeletionMode::Delete)
+            .await
+            .unwrap();
+        assert_eq!(deletion.mode, DeletionMode::Delete);
+        assert!(deletion.scheduled_for > Timestamp::now() + Duration::from_secs(6 * 24 * 60 * 60));
+        assert!(service.pending_deletion(id).await.unwrap().is_some());
+
+        service.cancel_deletion(id, id).await.unwrap();
+        assert!(service.pending_deletion(id).await.unwrap().is_none());
+        let result = service.cancel_deletion(id, id).await;
+        assert!(matches!(result, Err(PrivacyError::DeletionNotFound)));
+
+        let result = service
+            .schedule_deletion(id, Uuid::new_v4(), DeletionMode::Delete)
+            .await;
+        assert!(matches!(result, Err(PrivacyError::UserNotFound)));
+        let result = service
+            .schedule_deletion(id, DELETED_USER_ID, DeletionMode::Delete)
+            .await;
+        assert!(matches!(result, Err(PrivacyError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_erase_account_refuses_self_and_placeholder() {
+        let repository = Arc::new(MockPrivacyRepository::new());
+        let service = PrivacyServiceImpl::new(repository.clone());
+        let admin = repository.insert_user();
+        let user = repository.insert_user();
+
+        let result = service
+            .erase_account(admin, admin, DeletionMode::Delete)
+            .await;
+        assert!(matches!(result, Err(PrivacyError::Validation { .. })));
+        let result = service
+            .erase_account(admin, DELETED_USER_ID, DeletionMode::Delete)
+            .await;
+        assert!(matches!(result, Err(PrivacyError::Validation { .. })));
+        let result = service
+            .erase_account(admin, Uuid::new_v4(), DeletionMode::Delete)
+            .await;
+        assert!(matches!(result, Err(PrivacyError::UserNotFound)));
+
+        service
+            .erase_account(admin, user, DeletionMode::Anonymize)
+            .await
+            .unwrap();
+        assert_eq!(repository.erased_as(user), Some(DeletionMode::Anonymize));
+        assert_eq!(repository.erased_as(admin), None);
+    }
+
+    #[tokio::test]
+    async fn test_process_due_deletions_waits_for_the_grace_period() {
+        let repository = Arc::new(MockPrivacyRepository::new());
+        let service = PrivacyServiceImpl::new(repository.clone());
+        let due = repository.insert_user();
+        let pending = repository.insert_user();
+
+        service
+            .schedule_deletion(due, due, DeletionMode::Delete)
+            .await
+            .unwrap();
+        service
+            .schedule_deletion(pending, pending, DeletionMode::Anonymize)
+            .await
+            .unwrap();
+        repository.backdate_deletion(due);
+
+        assert_eq!(service.process_due_deletions().await.unwrap(), 1);
+        assert_eq!(repository.erased_as(due), Some(DeletionMode::Delete));
+        assert_eq!(repository.erased_as(pending), None);
+        assert!(service.pending_deletion(pending).await.unwrap().is_some());
+        assert_eq!(service.process_due_deletions().await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,56 @@
+// app/src/models/schedule/entity.rs
+
+// dependencies
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The kinds of content which can be scheduled, each in a table with a `content_status` column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduledKind {
+    Post,
+    Page,
+}
+
+impl ScheduledKind {
+    /// Every kind, in the order the scheduler publishes them
+    pub const ALL: [ScheduledKind; 2] = [Self::Post, Self::Page];
+
+    /// The table the content lives in
+    pub fn table(&self) -> &'static str {
+        match self {
+            Self::Post => "posts",
+            Self::Page => "pages",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Post => "post",
+            Self::Page => "page",
+        }
+    }
+
+    pub fn frm_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == s)
+    }
+}
+
+/// A post or page waiting for its publication date, or just published by the scheduler
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScheduledContent {
+    pub kind: ScheduledKind,
+    pub id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub author_id: Uuid,
+    pub published_at: Timestamp,
+}
+
+/// Published content whose publish hooks have yet to succeed, as queued in the outbox
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingPublication {
+    pub id: i64,
+    pub content: ScheduledContent,
+}
@@ -0,0 +1,30 @@
+// app/src/models/schedule/hooks.rs
+
+// dependencies
+use super::entity::ScheduledContent;
+use super::error::ScheduleError;
+use super::repository::ScheduleRepository;
+use super::service::PublishHook;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Drops the cached listings of the published content's kind, on every replica.
+///
+/// Listings are cached against the version of their kind's content, moving it to a new one makes
+/// every replica read them again on their next request.
+pub struct ContentCacheHook {
+    repository: Arc<dyn ScheduleRepository>,
+}
+
+impl ContentCacheHook {
+    pub fn new(repository: Arc<dyn ScheduleRepository>) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait]
+impl PublishHook for ContentCacheHook {
+    async fn published(&self, content: &ScheduledContent) -> Result<(), ScheduleError> {
+        self.repository.bump_content_version(content.kind).await
+    }
+}
@@ -0,0 +1,18 @@
+// app/src/models/schedule/mod.rs
+
+// modules
+mod entity;
+mod error;
+mod hooks;
+mod repository;
+mod service;
+
+#[cfg(test)]
+mod tests;
+
+// re-export the modules
+pub use entity::*;
+pub use error::*;
+pub use hooks::*;
+pub use repository::*;
+pub use service::*;
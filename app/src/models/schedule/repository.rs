@@ -0,0 +1,195 @@
+// app/src/models/schedule/repository.rs
+
+// dependencies
+use super::entity::{PendingPublication, ScheduledContent, ScheduledKind};
+use super::error::ScheduleError;
+use async_trait::async_trait;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use sqlx::{PgPool, Row};
+use std::time::Duration;
+
+// columns returned by every query that yields scheduled content
+const SCHEDULED_COLUMNS: &str = "id, title, slug, author_id, published_at";
+
+// traits
+#[async_trait]
+pub trait ScheduleRepository: Send + Sync {
+    /// Publish up to `limit` scheduled items whose date has come, returning those published.
+    ///
+    /// Rows another replica is publishing are skipped, so each item is returned only once. Each
+    /// item is queued for its publish hooks in the same statement.
+    async fn publish_due(
+        &self,
+        kind: ScheduledKind,
+        limit: i64,
+    ) -> Result<Vec<ScheduledContent>, ScheduleError>;
+    /// Scheduled items of a kind, soonest first
+    async fn list_scheduled(
+        &self,
+        kind: ScheduledKind,
+        limit: i64,
+    ) -> Result<Vec<ScheduledContent>, ScheduleError>;
+    /// Claim up to `limit` queued publications for `lease`, oldest first.
+    ///
+    /// Publications claimed by another replica are skipped until its lease has passed.
+    async fn claim_publications(
+        &self,
+        limit: i64,
+        lease: Duration,
+    ) -> Result<Vec<PendingPublication>, ScheduleError>;
+    /// Remove a publication from the queue once its hooks have succeeded
+    async fn complete_publication(&self, id: i64) -> Result<(), ScheduleError>;
+    /// Move the published content of a kind to a new version, so cached listings are read again
+    async fn bump_content_version(&self, kind: ScheduledKind) -> Result<(), ScheduleError>;
+}
+
+// SQLx implementation
+pub struct SqlxScheduleRepository {
+    pool: PgPool,
+}
+
+impl SqlxScheduleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // rows are only selected with a publication date, so it is never null
+    fn map_row(kind: ScheduledKind, row: sqlx::postgres::PgRow) -> ScheduledContent {
+        ScheduledContent {
+            kind,
+            id: row.get("id"),
+            title: row.get("title"),
+            slug: row.get("slug"),
+            author_id: row.get("author_id"),
+            published_at: row.get::<SqlxTimestamp, _>("published_at").into(),
+        }
+    }
+
+    // rows whose kind is unknown to this version are left in the queue
+    fn map_row_to_publication(row: sqlx::postgres::PgRow) -> Option<PendingPublication> {
+        let kind = ScheduledKind::frm_str(row.get("kind"))?;
+        Some(PendingPublication {
+            id: row.get("outbox_id"),
+            content: Self::map_row(kind, row),
+        })
+    }
+}
+
+#[async_trait]
+impl ScheduleRepository for SqlxScheduleRepository {
+    async fn publish_due(
+        &self,
+        kind: ScheduledKind,
+        limit: i64,
+    ) -> Result<Vec<ScheduledContent>, ScheduleError> {
+        // the status is checked again once the row is locked, a replica that got there
+        // first has already published it
+        let rows = sqlx::query(&format!(
+            r#"
+            WITH published AS (
+                UPDATE {table}
+                SET status = 'published', updated_at = NOW()
+                WHERE id IN (
+                    SELECT id FROM {table}
+                    WHERE status = 'scheduled' AND published_at <= NOW()
+                    ORDER BY published_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                AND status = 'scheduled'
+                RETURNING {SCHEDULED_COLUMNS}
+            ),
+            queued AS (
+                INSERT INTO publish_outbox (kind, content_id, title, slug, author_id, published_at)
+                SELECT $2, id, title, slug, author_id, published_at FROM published
+            )
+            SELECT {SCHEDULED_COLUMNS} FROM published
+            "#,
+            table = kind.table()
+        ))
+        .bind(limit)
+        .bind(kind.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Self::map_row(kind, row))
+            .collect())
+    }
+
+    async fn list_scheduled(
+        &self,
+        kind: ScheduledKind,
+        limit: i64,
+    ) -> Result<Vec<ScheduledContent>, ScheduleError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {SCHEDULED_COLUMNS}
+            FROM {table}
+            WHERE status = 'scheduled' AND published_at IS NOT NULL
+            ORDER BY published_at, id
+            LIMIT $1
+            "#,
+            table = kind.table()
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Self::map_row(kind, row))
+            .collect())
+    }
+
+    async fn claim_publications(
+        &self,
+        limit: i64,
+        lease: Duration,
+    ) -> Result<Vec<PendingPublication>, ScheduleError> {
+        let rows = sqlx::query(
+            r#"
+            UPDATE publish_outbox
+            SET claimed_until = NOW() + make_interval(secs => $2)
+            WHERE id IN (
+                SELECT id FROM publish_outbox
+                WHERE claimed_until IS NULL OR claimed_until <= NOW()
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id AS outbox_id, kind, content_id AS id, title, slug, author_id, published_at
+            "#,
+        )
+        .bind(limit)
+        .bind(lease.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut publications: Vec<_> = rows
+            .into_iter()
+            .filter_map(Self::map_row_to_publication)
+            .collect();
+        publications.sort_by_key(|publication| publication.id);
+        Ok(publications)
+    }
+
+    async fn complete_publication(&self, id: i64) -> Result<(), ScheduleError> {
+        sqlx::query("DELETE FROM publish_outbox WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn bump_content_version(&self, kind: ScheduledKind) -> Result<(), ScheduleError> {
+        sqlx::query("UPDATE content_versions SET version = version + 1 WHERE kind = $1")
+            .bind(kind.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+// app/src/models/schedule/service.rs
+
+// dependencies
+use super::entity::{ScheduledContent, ScheduledKind};
+use super::error::ScheduleError;
+use super::repository::ScheduleRepository;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_log_error::log_error;
+
+// how many items are published per query, and listed in the scheduled view
+const PUBLISH_BATCH: i64 = 100;
+const SCHEDULED_LIMIT: i64 = 200;
+// how many published items have their hooks run per claim, and how long a replica may take
+const HOOK_BATCH: i64 = 100;
+const HOOK_LEASE: Duration = Duration::from_secs(5 * 60);
+
+// traits
+/// Work to do once the scheduler publishes content, such as invalidating caches
+///
+/// Hooks run for every published item, by one replica at a time. The item is queued for them in
+/// the same statement that publishes it and stays queued until every hook has succeeded, so a
+/// replica stopping or a hook failing delays the hooks but never skips them. A hook may see an
+/// item again after such a failure, so hooks are idempotent. One hook going wrong neither stops
+/// the others nor unpublishes the content.
+#[async_trait]
+pub trait PublishHook: Send + Sync {
+    async fn published(&self, content: &ScheduledContent) -> Result<(), ScheduleError>;
+}
+
+#[async_trait]
+pub trait ScheduleService: Send + Sync {
+    /// Publish every post and page whose date has come, then run the publish hooks still queued
+    async fn publish_due(&self) -> Result<Vec<ScheduledContent>, ScheduleError>;
+    /// Posts and pages waiting to be published, soonest first
+    async fn list_scheduled(&self) -> Result<Vec<ScheduledContent>, ScheduleError>;
+}
+
+pub struct ScheduleServiceImpl {
+    repository: Arc<dyn ScheduleRepository>,
+    hooks: Vec<Arc<dyn PublishHook>>,
+}
+
+impl ScheduleServiceImpl {
+    pub fn new(repository: Arc<dyn ScheduleRepository>) -> Self {
+        Self {
+            repository,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Run a hook on everything the scheduler publishes, after the hooks added before it
+    pub fn with_hook(mut self, hook: Arc<dyn PublishHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Run the hooks on every queued publication no other replica is working on.
+    async fn run_hooks(&self) -> Result<(), ScheduleError> {
+        // claimed publications are skipped by the next claim, so failed ones do not come back
+        // before their lease has passed
+        loop {
+            let batch = self
+                .repository
+                .claim_publications(HOOK_BATCH, HOOK_LEASE)
+                .await?;
+            let done = (batch.len() as i64) < HOOK_BATCH;
+            for publication in &batch {
+                let mut succeeded = true;
+                for hook in &self.hooks {
+                    if let Err(e) = hook.published(&publication.content).await {
+                        log_error!(e, "A publish hook failed");
+                        succeeded = false;
+                    }
+                }
+                if succeeded {
+                    self.repository.complete_publication(publication.id).await?;
+                }
+            }
+            if done {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ScheduleService for ScheduleServiceImpl {
+    async fn publish_due(&self) -> Result<Vec<ScheduledContent>, ScheduleError> {
+        let mut published = Vec::new();
+        for kind in ScheduledKind::ALL {
+            // a full batch may leave more behind, keep going until one comes back short
+            loop {
+                let batch = self.repository.publish_due(kind, PUBLISH_BATCH).await?;
+                let done = (batch.len() as i64) < PUBLISH_BATCH;
+                published.extend(batch);
+                if done {
+                    break;
+                }
+            }
+        }
+        // including the ones left behind by earlier runs, the content is published either way
+        if let Err(e) = self.run_hooks().await {
+            log_error!(e, "Failed to run the publish hooks");
+        }
+
+        Ok(published)
+    }
+
+    async fn list_scheduled(&self) -> Result<Vec<ScheduledContent>, ScheduleError> {
+        let mut scheduled = Vec::new();
+        for kind in ScheduledKind::ALL {
+            scheduled.extend(
+                self.repository
+                    .list_scheduled(kind, SCHEDULED_LIMIT)
+                    .await?,
+            );
+        }
+        scheduled.sort_by_key(|content| (content.published_at, content.id));
+        scheduled.truncate(SCHEDULED_LIMIT as usize);
+
+        Ok(scheduled)
+    }
+}
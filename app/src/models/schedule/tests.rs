@@ -0,0 +1,244 @@
+// app/src/models/schedule/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::models::schedule::*;
+    use async_trait::async_trait;
+    use pavex::time::Timestamp;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    // Mock repository for testing the service layer, holding scheduled content and what it became
+    #[derive(Default)]
+    pub struct MockScheduleRepository {
+        scheduled: Mutex<Vec<ScheduledContent>>,
+        published: Mutex<Vec<ScheduledContent>>,
+        // queued publications, with whether a replica has claimed them
+        outbox: Mutex<Vec<(PendingPublication, bool)>>,
+        versions: Mutex<Vec<ScheduledKind>>,
+    }
+
+    impl MockScheduleRepository {
+        fn schedule(&self, kind: ScheduledKind, title: &str, published_at: Timestamp) {
+            self.scheduled.lock().unwrap().push(ScheduledContent {
+                kind,
+                id: Uuid::new_v4(),
+                title: title.to_string(),
+                slug: title.to_lowercase(),
+                author_id: Uuid::new_v4(),
+                published_at,
+            });
+        }
+
+        // as if the leases of every claimed publication had passed
+        fn expire_claims(&self) {
+            for (_, claimed) in self.outbox.lock().unwrap().iter_mut() {
+                *claimed = false;
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ScheduleRepository for MockScheduleRepository {
+        async fn publish_due(
+            &self,
+            kind: ScheduledKind,
+            limit: i64,
+        ) -> Result<Vec<ScheduledContent>, ScheduleError> {
+            let now = Timestamp::now();
+            let mut scheduled = self.scheduled.lock().unwrap();
+            let mut due = Vec::new();
+            scheduled.retain(|content| {
+                let publish = content.kind == kind
+                    && content.published_at <= now
+                    && (due.len() as i64) < limit;
+                if publish {
+                    due.push(content.clone());
+                }
+                !publish
+            });
+            // each item is queued under its position among everything published
+            let mut published = self.published.lock().unwrap();
+            let mut outbox = self.outbox.lock().unwrap();
+            for content in &due {
+                published.push(content.clone());
+                let publication = PendingPublication {
+                    id: published.len() as i64,
+                    content: content.clone(),
+                };
+                outbox.push((publication, false));
+            }
+            Ok(due)
+        }
+
+        async fn list_scheduled(
+            &self,
+            kind: ScheduledKind,
+            limit: i64,
+        ) -> Result<Vec<ScheduledContent>, ScheduleError> {
+            let mut listed: Vec<_> = self
+                .scheduled
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|content| content.kind == kind)
+                .cloned()
+                .collect();
+            listed.sort_by_key(|content| content.published_at);
+            listed.truncate(limit as usize);
+            Ok(listed)
+        }
+
+        async fn claim_publications(
+            &self,
+            limit: i64,
+            _lease: Duration,
+        ) -> Result<Vec<PendingPublication>, ScheduleError> {
+            let mut outbox = self.outbox.lock().unwrap();
+            Ok(outbox
+                .iter_mut()
+                .filter(|(_, claimed)| !*claimed)
+                .take(limit as usize)
+                .map(|(publication, claimed)| {
+                    *claimed = true;
+                    publication.clone()
+                })
+                .collect())
+        }
+
+        async fn complete_publication(&self, id: i64) -> Result<(), ScheduleError> {
+            self.outbox
+                .lock()
+                .unwrap()
+                .retain(|(publication, _)| publication.id != id);
+            Ok(())
+        }
+
+        async fn bump_content_version(&self, kind: ScheduledKind) -> Result<(), ScheduleError> {
+            self.versions.lock().unwrap().push(kind);
+            Ok(())
+        }
+    }
+
+    // Hook remembering the titles of what it was told about, failing as many times as asked first
+    #[derive(Default)]
+    pub struct RecordingHook {
+        titles: Mutex<Vec<String>>,
+        failures: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl PublishHook for RecordingHook {
+        async fn published(&self, content: &ScheduledContent) -> Result<(), ScheduleError> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(ScheduleError::Database(sqlx::Error::PoolTimedOut));
+            }
+            self.titles.lock().unwrap().push(content.title.clone());
+            Ok(())
+        }
+    }
+
+    fn hours(hours: i64) -> Timestamp {
+        let offset = Duration::from_secs(hours.unsigned_abs() * 60 * 60);
+        if hours < 0 {
+            Timestamp::now() - offset
+        } else {
+            Timestamp::now() + offset
+        }
+    }
+
+    #[tokio::test]
+    async fn test_due_content_is_published_once() {
+        let repository = Arc::new(MockScheduleRepository::default());
+        let hook = Arc::new(RecordingHook::default());
+        let service = ScheduleServiceImpl::new(repository.clone()).with_hook(hook.clone());
+        repository.schedule(ScheduledKind::Post, "Due post", hours(-1));
+        repository.schedule(ScheduledKind::Page, "Due page", hours(-2));
+        repository.schedule(ScheduledKind::Post, "Later", hours(1));
+
+        let published = service.publish_due().await.unwrap();
+        assert_eq!(published.len(), 2);
+        assert_eq!(*hook.titles.lock().unwrap(), ["Due post", "Due page"]);
+
+        // Nothing is published, nor announced, twice
+        assert!(service.publish_due().await.unwrap().is_empty());
+        assert_eq!(hook.titles.lock().unwrap().len(), 2);
+        assert_eq!(repository.published.lock().unwrap().len(), 2);
+        assert!(repository.outbox.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_hooks_are_retried_once_their_claim_has_passed() {
+        let repository = Arc::new(MockScheduleRepository::default());
+        let hook = Arc::new(RecordingHook::default());
+        *hook.failures.lock().unwrap() = 1;
+        let service = ScheduleServiceImpl::new(repository.clone()).with_hook(hook.clone());
+        repository.schedule(ScheduledKind::Post, "Due post", hours(-1));
+
+        // The post is published even though its hook failed, which stays queued
+        assert_eq!(service.publish_due().await.unwrap().len(), 1);
+        assert!(hook.titles.lock().unwrap().is_empty());
+        assert_eq!(repository.outbox.lock().unwrap().len(), 1);
+
+        // Not before the claim has passed, since another replica may still be running it
+        service.publish_due().await.unwrap();
+        assert!(hook.titles.lock().unwrap().is_empty());
+
+        repository.expire_claims();
+        assert!(service.publish_due().await.unwrap().is_empty());
+        assert_eq!(*hook.titles.lock().unwrap(), ["Due post"]);
+        assert!(repository.outbox.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_content_cache_hook_moves_the_kind_to_a_new_version() {
+        let repository = Arc::new(MockScheduleRepository::default());
+        let service = ScheduleServiceImpl::new(repository.clone())
+            .with_hook(Arc::new(ContentCacheHook::new(repository.clone())));
+        repository.schedule(ScheduledKind::Page, "Due page", hours(-1));
+        repository.schedule(ScheduledKind::Page, "Later", hours(1));
+
+        service.publish_due().await.unwrap();
+        assert_eq!(*repository.versions.lock().unwrap(), [ScheduledKind::Page]);
+    }
+
+    #[tokio::test]
+    async fn test_backlogs_are_published_in_batches() {
+        let repository = Arc::new(MockScheduleRepository::default());
+        let service = ScheduleServiceImpl::new(repository.clone());
+        for n in 0..250 {
+            repository.schedule(ScheduledKind::Post, &format!("Post {n}"), hours(-1));
+        }
+
+        assert_eq!(service.publish_due().await.unwrap().len(), 250);
+        assert!(repository.scheduled.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_content_is_listed_soonest_first() {
+        let repository = Arc::new(MockScheduleRepository::default());
+        let service = ScheduleServiceImpl::new(repository.clone());
+        repository.schedule(ScheduledKind::Post, "Third", hours(3));
+        repository.schedule(ScheduledKind::Page, "First", hours(1));
+        repository.schedule(ScheduledKind::Post, "Second", hours(2));
+
+        let titles: Vec<_> = service
+            .list_scheduled()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|content| (content.kind, content.title))
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                (ScheduledKind::Page, "First".to_string()),
+                (ScheduledKind::Post, "Second".to_string()),
+                (ScheduledKind::Post, "Third".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,41 @@
+// app/src/models/session/dto.rs
+
+// dependencies
+use super::ActiveSession;
+use pavex::time::Timestamp;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Public session response DTO
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub id: Uuid,
+    pub created_at: Timestamp,
+    pub last_seen_at: Timestamp,
+    pub expires_at: Timestamp,
+    pub user_agent: Option<String>,
+    pub ip: String,
+    /// Whether this is the session the request was made with
+    pub current: bool,
+}
+
+/// Response DTO for bulk sign-outs
+#[derive(Debug, Serialize)]
+pub struct RevokedSessionsResponse {
+    pub revoked: u64,
+}
+
+// Conversion implementations
+impl SessionResponse {
+    pub fn new(session: ActiveSession, current: Option<Uuid>) -> Self {
+        Self {
+            id: session.info.handle,
+            created_at: session.info.created_at,
+            last_seen_at: session.info.last_seen_at,
+            expires_at: session.expires_at,
+            user_agent: session.info.user_agent,
+            ip: session.info.ip,
+            current: current == Some(session.info.handle),
+        }
+    }
+}
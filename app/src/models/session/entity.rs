@@ -0,0 +1,75 @@
+// app/src/models/session/entity.rs
+
+// dependencies
+use crate::models::UserRole;
+use pavex::time::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use uuid::Uuid;
+
+// longest user agent kept, anything beyond is of no help to tell devices apart
+const USER_AGENT_MAX_CHARS: usize = 255;
+
+/// What a signed-in session records about the device it was created on
+///
+/// Stored in the session state under `session.info`, next to the `user.*` keys. The
+/// `handle` identifies the session towards its owner, the session id itself never
+/// leaves the cookie since it is a credential.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub handle: Uuid,
+    pub created_at: Timestamp,
+    /// As of signing in, later activity is recorded apart from the session state.
+    pub last_seen_at: Timestamp,
+    pub user_agent: Option<String>,
+    pub ip: String,
+}
+
+impl SessionInfo {
+    pub fn new(user_agent: Option<&str>, ip: IpAddr) -> Self {
+        let now = Timestamp::now();
+        Self {
+            handle: Uuid::new_v4(),
+            created_at: now,
+            last_seen_at: now,
+            user_agent: user_agent.map(|agent| agent.chars().take(USER_AGENT_MAX_CHARS).collect()),
+            ip: coarse_ip(ip),
+        }
+    }
+}
+
+/// The account a signed-in session belongs to, as every request made with it is checked against
+///
+/// Sessions only hold the account id and the generation it had when they signed in, the role
+/// and status are read from here so that changes apply to sessions already open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionAccount {
+    pub id: Uuid,
+    pub username: String,
+    pub role: UserRole,
+    pub is_active: bool,
+    pub session_generation: i32,
+}
+
+// A signed-in session of a user, as listed back to them
+#[derive(Debug, Clone)]
+pub struct ActiveSession {
+    pub info: SessionInfo,
+    pub expires_at: Timestamp,
+}
+
+/// Blur `ip` to its network, which is enough to recognise a location but not a device.
+///
+/// IPv4 addresses keep their /24, IPv6 addresses their /48.
+pub fn coarse_ip(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            format!("{a}.{b}.{c}.0/24")
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            format!("{:x}:{:x}:{:x}::/48", segments[0], segments[1], segments[2])
+        }
+    }
+}
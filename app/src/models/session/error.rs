@@ -0,0 +1,40 @@
+// app/src/models/session/error.rs
+
+use crate::response::IntoApiError;
+use pavex::http::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Validation error: {message}")]
+    Validation { message: String },
+
+    #[error("Session not found")]
+    SessionNotFound,
+
+    #[error("Session expired, please sign in again")]
+    SessionExpired,
+
+    #[error("Session is no longer valid, please sign in again")]
+    SessionInvalidated,
+}
+
+impl IntoApiError for SessionError {
+    fn code(&self) -> Option<u16> {
+        match self {
+            SessionError::Validation { .. } => Some(StatusCode::BAD_REQUEST.as_u16()),
+            SessionError::SessionNotFound => Some(StatusCode::NOT_FOUND.as_u16()),
+            SessionError::SessionExpired | SessionError::SessionInvalidated => {
+                Some(StatusCode::UNAUTHORIZED.as_u16())
+            }
+            SessionError::Database(_) => Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16()),
+        }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
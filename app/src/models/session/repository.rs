@@ -0,0 +1,177 @@
+// app/src/models/session/repository.rs
+
+// dependencies
+use super::entity::{ActiveSession, SessionAccount, SessionInfo};
+use super::error::SessionError;
+use async_trait::async_trait;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use pavex::time::Timestamp;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+// traits
+#[async_trait]
+pub trait SessionRepository: Send + Sync {
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<ActiveSession>, SessionError>;
+    async fn delete(&self, user_id: Uuid, handle: Uuid) -> Result<bool, SessionError>;
+    async fn delete_all_except(
+        &self,
+        user_id: Uuid,
+        keep: Option<Uuid>,
+    ) -> Result<u64, SessionError>;
+    async fn touch(&self, handle: Uuid, last_seen_at: Timestamp) -> Result<(), SessionError>;
+    async fn last_seen(&self, handle: Uuid) -> Result<Option<Timestamp>, SessionError>;
+    async fn find_account(&self, user_id: Uuid) -> Result<Option<SessionAccount>, SessionError>;
+    async fn bump_generation(&self, user_id: Uuid) -> Result<(), SessionError>;
+}
+
+// Sessions are stored by pavex_session_sqlx as a JSONB map keyed by the session
+// constants, so they are matched on their `user.id` and `session.info` entries.
+// Only pavex_session writes that state, the activity of a session is kept in
+// `session_activity` instead.
+pub struct SqlxSessionRepository {
+    pool: PgPool,
+}
+
+impl SqlxSessionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Helper function to map database row to ActiveSession struct
+    fn map_row_to_session(row: &sqlx::postgres::PgRow) -> ActiveSession {
+        ActiveSession {
+            info: SessionInfo {
+                handle: row.get("handle"),
+                created_at: row.get::<SqlxTimestamp, _>("created_at").into(),
+                last_seen_at: row.get::<SqlxTimestamp, _>("last_seen_at").into(),
+                user_agent: row.get("user_agent"),
+                ip: row.get("ip"),
+            },
+            expires_at: row.get::<SqlxTimestamp, _>("deadline").into(),
+        }
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SqlxSessionRepository {
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<ActiveSession>, SessionError> {
+        // Sessions signed in before their info was recorded cannot be told apart, so
+        // they are left out here and only go away with "sign out everywhere else"
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                (state -> 'session.info' ->> 'handle')::uuid AS handle,
+                (state -> 'session.info' ->> 'created_at')::timestamptz AS created_at,
+                GREATEST(
+                    activity.last_seen_at,
+                    (state -> 'session.info' ->> 'last_seen_at')::timestamptz
+                ) AS last_seen_at,
+                state -> 'session.info' ->> 'user_agent' AS user_agent,
+                state -> 'session.info' ->> 'ip' AS ip,
+                deadline
+            FROM sessions
+            LEFT JOIN session_activity activity
+                ON activity.handle::text = state -> 'session.info' ->> 'handle'
+            WHERE state ->> 'user.id' = $1
+            AND state ? 'session.info'
+            AND deadline > NOW()
+            ORDER BY last_seen_at DESC
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::map_row_to_session).collect())
+    }
+
+    async fn delete(&self, user_id: Uuid, handle: Uuid) -> Result<bool, SessionError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM sessions
+            WHERE state ->> 'user.id' = $1
+            AND state -> 'session.info' ->> 'handle' = $2
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(handle.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_all_except(
+        &self,
+        user_id: Uuid,
+        keep: Option<Uuid>,
+    ) -> Result<u64, SessionError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM sessions
+            WHERE state ->> 'user.id' = $1
+            AND (state -> 'session.info' ->> 'handle') IS DISTINCT FROM $2
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(keep.map(|handle| handle.to_string()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn touch(&self, handle: Uuid, last_seen_at: Timestamp) -> Result<(), SessionError> {
+        sqlx::query(
+            r#"
+            INSERT INTO session_activity (handle, last_seen_at)
+            VALUES ($1, $2)
+            ON CONFLICT (handle) DO UPDATE
+            SET last_seen_at = GREATEST(session_activity.last_seen_at, EXCLUDED.last_seen_at)
+            "#,
+        )
+        .bind(handle)
+        .bind(SqlxTimestamp::from(last_seen_at))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn last_seen(&self, handle: Uuid) -> Result<Option<Timestamp>, SessionError> {
+        let last_seen_at: Option<SqlxTimestamp> =
+            sqlx::query_scalar("SELECT last_seen_at FROM session_activity WHERE handle = $1")
+                .bind(handle)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(last_seen_at.map(|t| t.into()))
+    }
+
+    async fn find_account(&self, user_id: Uuid) -> Result<Option<SessionAccount>, SessionError> {
+        let row = sqlx::query(
+            "SELECT id, username, role, is_active, session_generation FROM users WHERE id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| SessionAccount {
+            id: row.get("id"),
+            username: row.get("username"),
+            role: row.get("role"),
+            is_active: row.get("is_active"),
+            session_generation: row.get("session_generation"),
+        }))
+    }
+
+    async fn bump_generation(&self, user_id: Uuid) -> Result<(), SessionError> {
+        sqlx::query("UPDATE users SET session_generation = session_generation + 1 WHERE id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+// app/src/models/session/service.rs
+
+// dependencies
+use super::dto::SessionResponse;
+use super::entity::{SessionAccount, SessionInfo};
+use super::error::SessionError;
+use super::repository::SessionRepository;
+use crate::configuration::SessionLimitsConfig;
+use async_trait::async_trait;
+use pavex::time::Timestamp;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing_log_error::log_error;
+use uuid::Uuid;
+
+/// How stale the recorded last-seen time may get, so not every request writes to the database.
+pub const LAST_SEEN_RESOLUTION: Duration = Duration::from_secs(60);
+
+// accounts kept in memory before the stale ones are dropped
+const MAX_CACHED_ACCOUNTS: usize = 10_000;
+
+// traits
+#[async_trait]
+pub trait SessionService: Send + Sync {
+    async fn list_sessions(
+        &self,
+        user_id: Uuid,
+        current: Option<Uuid>,
+    ) -> Result<Vec<SessionResponse>, SessionError>;
+    async fn revoke_session(
+        &self,
+        user_id: Uuid,
+        handle: Uuid,
+        current: Option<Uuid>,
+    ) -> Result<(), SessionError>;
+    async fn revoke_other_sessions(
+        &self,
+        user_id: Uuid,
+        current: Option<Uuid>,
+    ) -> Result<u64, SessionError>;
+    async fn revoke_all_sessions(&self, user_id: Uuid) -> Result<u64, SessionError>;
+    // Signed-in session related operations
+    async fn session_generation(&self, user_id: Uuid) -> Result<i32, SessionError>;
+    async fn check_session(
+        &self,
+        user_id: Uuid,
+        generation: i32,
+        info: Option<&SessionInfo>,
+    ) -> Result<SessionAccount, SessionError>;
+}
+
+// an account as last read from the database
+struct CachedAccount {
+    loaded_at: Instant,
+    account: SessionAccount,
+}
+
+pub struct SessionServiceImpl {
+    repository: Arc<dyn SessionRepository>,
+    limits: SessionLimitsConfig,
+    accounts: RwLock<HashMap<Uuid, CachedAccount>>,
+}
+
+impl SessionServiceImpl {
+    pub fn new(repository: Arc<dyn SessionRepository>) -> Self {
+        Self {
+            repository,
+            limits: SessionLimitsConfig::default(),
+            accounts: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_config(mut self, limits: SessionLimitsConfig) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// The account `user_id`, read again once the cached copy is older than the configured TTL.
+    async fn account(&self, user_id: Uuid) -> Result<Option<SessionAccount>, SessionError> {
+        let ttl = self.limits.account_cache_ttl;
+        if let Some(cached) = self.accounts.read().unwrap().get(&user_id)
+            && cached.loaded_at.elapsed() < ttl
+        {
+            return Ok(Some(cached.account.clone()));
+        }
+
+        let account = self.repository.find_account(user_id).await?;
+        let mut accounts = self.accounts.write().unwrap();
+        match &account {
+            Some(account) => {
+                if accounts.len() >= MAX_CACHED_ACCOUNTS {
+                    accounts.retain(|_, cached| cached.loaded_at.elapsed() < ttl);
+                }
+                accounts.insert(
+                    user_id,
+                    CachedAccount {
+                        loaded_at: Instant::now(),
+                        account: account.clone(),
+                    },
+                );
+            }
+            None => {
+                accounts.remove(&user_id);
+            }
+        }
+
+        Ok(account)
+    }
+
+    /// When the session was last used, as recorded since it signed in.
+    async fn last_seen(&self, info: &SessionInfo) -> Result<Timestamp, SessionError> {
+        let recorded = self.repository.last_seen(info.handle).await?;
+
+        Ok(recorded.unwrap_or(info.last_seen_at).max(info.last_seen_at))
+    }
+
+    /// Record that the session is in use, unless that was recorded recently.
+    async fn record_activity(&self, handle: Uuid, last_seen_at: Timestamp) {
+        let now = Timestamp::now();
+        if now < last_seen_at + LAST_SEEN_RESOLUTION {
+            return;
+        }
+        // Only the session listing and the idle timeout fall behind, so the request goes on
+        if let Err(e) = self.repository.touch(handle, now).await {
+            log_error!(e, level: tracing::Level::WARN, "Failed to record session activity");
+        }
+    }
+}
+
+#[async_trait]
+impl SessionService for SessionServiceImpl {
+    async fn list_sessions(
+        &self,
+        user_id: Uuid,
+        current: Option<Uuid>,
+    ) -> Result<Vec<SessionResponse>, SessionError> {
+        let sessions = self.repository.list_for_user(user_id).await?;
+
+        Ok(sessions
+            .into_iter()
+            .map(|session| SessionResponse::new(session, current))
+            .collect())
+    }
+
+    async fn revoke_session(
+        &self,
+        user_id: Uuid,
+        handle: Uuid,
+        current: Option<Uuid>,
+    ) -> Result<(), SessionError> {
+        if current == Some(handle) {
+            return Err(SessionError::Validation {
+                message: "Log out to end the current session".into(),
+            });
+        }
+
+        // Sessions of other users look exactly like missing ones
+        if !self.repository.delete(user_id, handle).await? {
+            return Err(SessionError::SessionNotFound);
+        }
+        tracing::info!(user.id = %user_id, session.handle = %handle, "Session revoked");
+
+        Ok(())
+    }
+
+    async fn revoke_other_sessions(
+        &self,
+        user_id: Uuid,
+        current: Option<Uuid>,
+    ) -> Result<u64, SessionError> {
+        let revoked = self.repository.delete_all_except(user_id, current).await?;
+        tracing::info!(
+            user.id = %user_id,
+            sessions.revoked = revoked,
+            "Other sessions revoked"
+        );
+
+        Ok(revoked)
+    }
+
+    async fn revoke_all_sessions(&self, user_id: Uuid) -> Result<u64, SessionError> {
+        // Sessions the deletion misses, e.g. one written back by a request in flight, are
+        // refused by their generation
+        self.repository.bump_generation(user_id).await?;
+        self.accounts.write().unwrap().remove(&user_id);
+        let revoked = self.repository.delete_all_except(user_id, None).await?;
+        tracing::info!(
+            user.id = %user_id,
+            sessions.revoked = revoked,
+            "All sessions revoked"
+        );
+
+        Ok(revoked)
+    }
+
+    async fn session_generation(&self, user_id: Uuid) -> Result<i32, SessionError> {
+        // Read afresh, a session signing in must not start out under a stale generation
+        self.accounts.write().unwrap().remove(&user_id);
+        let account = self
+            .account(user_id)
+            .await?
+            .ok_or(SessionError::SessionNotFound)?;
+
+        Ok(account.session_generation)
+    }
+
+    async fn check_session(
+        &self,
+        user_id: Uuid,
+        generation: i32,
+        info: Option<&SessionInfo>,
+    ) -> Result<SessionAccount, SessionError> {
+        // Every sign-in records its info, so a session without one cannot be timed
+        let Some(info) = info else {
+            return Err(SessionError::SessionExpired);
+        };
+        let now = Timestamp::now();
+        let last_seen_at = self.last_seen(info).await?;
+        if now >= info.created_at + self.limits.absolute_timeout
+            || now >= last_seen_at + self.limits.idle_timeout
+        {
+            return Err(SessionError::SessionExpired);
+        }
+
+        let account = self
+            .account(user_id)
+            .await?
+            .ok_or(SessionError::SessionInvalidated)?;
+        if !account.is_active || account.session_generation != generation {
+            return Err(SessionError::SessionInvalidated);
+        }
+        self.record_activity(info.handle, last_seen_at).await;
+
+        Ok(account)
+    }
+}
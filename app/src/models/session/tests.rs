@@ -0,0 +1,340 @@
+// app/src/models/session/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::configuration::SessionLimitsConfig;
+    use crate::models::UserRole;
+    use crate::models::session::*;
+    use async_trait::async_trait;
+    use pavex::time::Timestamp;
+    use std::collections::HashMap;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    // Mock repository for testing the service layer
+    pub struct MockSessionRepository {
+        sessions: Arc<Mutex<Vec<(Uuid, ActiveSession)>>>,
+        accounts: Arc<Mutex<HashMap<Uuid, SessionAccount>>>,
+        activity: Arc<Mutex<HashMap<Uuid, Timestamp>>>,
+    }
+
+    impl MockSessionRepository {
+        pub fn new() -> Self {
+            Self {
+                sessions: Arc::new(Mutex::new(Vec::new())),
+                accounts: Arc::new(Mutex::new(HashMap::new())),
+                activity: Arc::new(Mutex::new(HashMap::new())),
+            }
+        }
+
+        pub fn add_account(&self, role: UserRole) -> Uuid {
+            let account = SessionAccount {
+                id: Uuid::new_v4(),
+                username: "reader".to_string(),
+                role,
+                is_active: true,
+                session_generation: 0,
+            };
+            let id = account.id;
+            self.accounts.lock().unwrap().insert(id, account);
+            id
+        }
+
+        pub fn update_account(&self, id: Uuid, update: impl FnOnce(&mut SessionAccount)) {
+            update(self.accounts.lock().unwrap().get_mut(&id).unwrap());
+        }
+
+        pub fn insert_session(&self, user_id: Uuid) -> SessionInfo {
+            let info = SessionInfo::new(Some("test agent"), IpAddr::V4(Ipv4Addr::LOCALHOST));
+            self.sessions.lock().unwrap().push((
+                user_id,
+                ActiveSession {
+                    info: info.clone(),
+                    expires_at: Timestamp::now() + Duration::from_secs(3600),
+                },
+            ));
+            info
+        }
+
+        pub fn last_seen_at(&self, handle: Uuid) -> Option<Timestamp> {
+            self.activity.lock().unwrap().get(&handle).copied()
+        }
+    }
+
+    #[async_trait]
+    impl SessionRepository for MockSessionRepository {
+        async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<ActiveSession>, SessionError> {
+            let sessions = self.sessions.lock().unwrap();
+            Ok(sessions
+                .iter()
+                .filter(|(owner, _)| *owner == user_id)
+                .map(|(_, session)| session.clone())
+                .collect())
+        }
+
+        async fn delete(&self, user_id: Uuid, handle: Uuid) -> Result<bool, SessionError> {
+            let mut sessions = self.sessions.lock().unwrap();
+            let before = sessions.len();
+            sessions.retain(|(owner, session)| *owner != user_id || session.info.handle != handle);
+            Ok(sessions.len() < before)
+        }
+
+        async fn delete_all_except(
+            &self,
+            user_id: Uuid,
+            keep: Option<Uuid>,
+        ) -> Result<u64, SessionError> {
+            let mut sessions = self.sessions.lock().unwrap();
+            let before = sessions.len();
+            sessions
+                .retain(|(owner, session)| *owner != user_id || Some(session.info.handle) == keep);
+            Ok((before - sessions.len()) as u64)
+        }
+
+        async fn touch(&self, handle: Uuid, last_seen_at: Timestamp) -> Result<(), SessionError> {
+            let mut activity = self.activity.lock().unwrap();
+            let recorded = activity.entry(handle).or_insert(last_seen_at);
+            *recorded = (*recorded).max(last_seen_at);
+            Ok(())
+        }
+
+        async fn last_seen(&self, handle: Uuid) -> Result<Option<Timestamp>, SessionError> {
+            Ok(self.last_seen_at(handle))
+        }
+
+        async fn find_account(
+            &self,
+            user_id: Uuid,
+        ) -> Result<Option<SessionAccount>, SessionError> {
+            Ok(self.accounts.lock().unwrap().get(&user_id).cloned())
+        }
+
+        async fn bump_generation(&self, user_id: Uuid) -> Result<(), SessionError> {
+            if let Some(account) = self.accounts.lock().unwrap().get_mut(&user_id) {
+                account.session_generation += 1;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_coarse_ip_keeps_the_network_only() {
+        assert_eq!(
+            coarse_ip(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 42))),
+            "203.0.113.0/24"
+        );
+        assert_eq!(
+            coarse_ip(IpAddr::V6(Ipv6Addr::new(
+                0x2001, 0xdb8, 0x85a3, 1, 2, 3, 4, 5
+            ))),
+            "2001:db8:85a3::/48"
+        );
+
+        let info = SessionInfo::new(Some(&"x".repeat(1000)), IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_eq!(info.user_agent.unwrap().len(), 255);
+    }
+
+    #[tokio::test]
+    async fn test_list_sessions_marks_the_current_one() {
+        let repository = Arc::new(MockSessionRepository::new());
+        let service = SessionServiceImpl::new(repository.clone());
+        let user_id = Uuid::new_v4();
+        let current = repository.insert_session(user_id);
+        repository.insert_session(user_id);
+        repository.insert_session(Uuid::new_v4());
+
+        let sessions = service
+            .list_sessions(user_id, Some(current.handle))
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions.iter().filter(|session| session.current).count(), 1);
+        assert!(sessions.iter().all(|session| session.ip == "127.0.0.0/24"));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_session_only_ends_other_sessions_of_the_owner() {
+        let repository = Arc::new(MockSessionRepository::new());
+        let service = SessionServiceImpl::new(repository.clone());
+        let user_id = Uuid::new_v4();
+        let current = repository.insert_session(user_id);
+        let other = repository.insert_session(user_id);
+        let foreign = repository.insert_session(Uuid::new_v4());
+
+        let result = service
+            .revoke_session(user_id, current.handle, Some(current.handle))
+            .await;
+        assert!(matches!(result, Err(SessionError::Validation { .. })));
+
+        let result = service
+            .revoke_session(user_id, foreign.handle, Some(current.handle))
+            .await;
+        assert!(matches!(result, Err(SessionError::SessionNotFound)));
+
+        service
+            .revoke_session(user_id, other.handle, Some(current.handle))
+            .await
+            .unwrap();
+        let remaining = service.list_sessions(user_id, None).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, current.handle);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_other_and_all_sessions() {
+        let repository = Arc::new(MockSessionRepository::new());
+        let service = SessionServiceImpl::new(repository.clone());
+        let user_id = Uuid::new_v4();
+        let current = repository.insert_session(user_id);
+        repository.insert_session(user_id);
+        repository.insert_session(user_id);
+        repository.insert_session(Uuid::new_v4());
+
+        let revoked = service
+            .revoke_other_sessions(user_id, Some(current.handle))
+            .await
+            .unwrap();
+        assert_eq!(revoked, 2);
+
+        let revoked = service.revoke_all_sessions(user_id).await.unwrap();
+        assert_eq!(revoked, 1);
+        assert!(
+            service
+                .list_sessions(user_id, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_session_records_activity_apart_from_the_session_state() {
+        let repository = Arc::new(MockSessionRepository::new());
+        let service =
+            SessionServiceImpl::new(repository.clone()).with_config(SessionLimitsConfig {
+                idle_timeout: Duration::from_secs(30 * 60),
+                ..uncached()
+            });
+        let id = repository.add_account(UserRole::Author);
+        let mut info = repository.insert_session(id);
+
+        // A session just signed in needs no record yet
+        service.check_session(id, 0, Some(&info)).await.unwrap();
+        assert_eq!(repository.last_seen_at(info.handle), None);
+
+        info.last_seen_at = Timestamp::now() - LAST_SEEN_RESOLUTION - Duration::from_secs(1);
+        service.check_session(id, 0, Some(&info)).await.unwrap();
+        let recorded = repository.last_seen_at(info.handle).unwrap();
+        assert!(recorded > info.last_seen_at);
+
+        // The state keeps its sign-in time, the recorded activity keeps the session alive
+        info.last_seen_at = Timestamp::now() - Duration::from_secs(31 * 60);
+        service.check_session(id, 0, Some(&info)).await.unwrap();
+        assert_eq!(repository.last_seen_at(info.handle), Some(recorded));
+    }
+
+    fn uncached() -> SessionLimitsConfig {
+        SessionLimitsConfig {
+            account_cache_ttl: Duration::ZERO,
+            ..SessionLimitsConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_session_enforces_idle_and_absolute_timeouts() {
+        let repository = Arc::new(MockSessionRepository::new());
+        let service =
+            SessionServiceImpl::new(repository.clone()).with_config(SessionLimitsConfig {
+                idle_timeout: Duration::from_secs(30 * 60),
+                absolute_timeout: Duration::from_secs(24 * 60 * 60),
+                ..uncached()
+            });
+        let id = repository.add_account(UserRole::Author);
+        let mut info = repository.insert_session(id);
+
+        let account = service.check_session(id, 0, Some(&info)).await.unwrap();
+        assert_eq!(account.role, UserRole::Author);
+
+        info.last_seen_at = Timestamp::now() - Duration::from_secs(31 * 60);
+        let result = service.check_session(id, 0, Some(&info)).await;
+        assert!(matches!(result, Err(SessionError::SessionExpired)));
+
+        // Staying active does not extend a session beyond its absolute timeout
+        info.last_seen_at = Timestamp::now();
+        info.created_at = Timestamp::now() - Duration::from_secs(25 * 60 * 60);
+        let result = service.check_session(id, 0, Some(&info)).await;
+        assert!(matches!(result, Err(SessionError::SessionExpired)));
+
+        let result = service.check_session(id, 0, None).await;
+        assert!(matches!(result, Err(SessionError::SessionExpired)));
+    }
+
+    #[tokio::test]
+    async fn test_check_session_rejects_deactivated_accounts_and_old_generations() {
+        let repository = Arc::new(MockSessionRepository::new());
+        let service = SessionServiceImpl::new(repository.clone()).with_config(uncached());
+        let id = repository.add_account(UserRole::Admin);
+        let info = repository.insert_session(id);
+
+        // Roles are read from the account, not from what the session signed in with
+        repository.update_account(id, |account| account.role = UserRole::Subscriber);
+        let account = service.check_session(id, 0, Some(&info)).await.unwrap();
+        assert_eq!(account.role, UserRole::Subscriber);
+
+        repository.update_account(id, |account| account.session_generation = 1);
+        let result = service.check_session(id, 0, Some(&info)).await;
+        assert!(matches!(result, Err(SessionError::SessionInvalidated)));
+        assert!(service.check_session(id, 1, Some(&info)).await.is_ok());
+
+        repository.update_account(id, |account| account.is_active = false);
+        let result = service.check_session(id, 1, Some(&info)).await;
+        assert!(matches!(result, Err(SessionError::SessionInvalidated)));
+
+        let result = service.check_session(Uuid::new_v4(), 0, Some(&info)).await;
+        assert!(matches!(result, Err(SessionError::SessionInvalidated)));
+    }
+
+    #[tokio::test]
+    async fn test_accounts_are_cached_for_their_ttl() {
+        let repository = Arc::new(MockSessionRepository::new());
+        let service =
+            SessionServiceImpl::new(repository.clone()).with_config(SessionLimitsConfig {
+                account_cache_ttl: Duration::from_secs(60),
+                ..SessionLimitsConfig::default()
+            });
+        let id = repository.add_account(UserRole::Editor);
+        let info = repository.insert_session(id);
+
+        service.check_session(id, 0, Some(&info)).await.unwrap();
+        repository.update_account(id, |account| account.role = UserRole::Subscriber);
+        let account = service.check_session(id, 0, Some(&info)).await.unwrap();
+        assert_eq!(account.role, UserRole::Editor);
+
+        // Signing in always reads the current generation
+        repository.update_account(id, |account| account.session_generation = 4);
+        assert_eq!(service.session_generation(id).await.unwrap(), 4);
+        let account = service.check_session(id, 4, Some(&info)).await.unwrap();
+        assert_eq!(account.role, UserRole::Subscriber);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_sessions_moves_the_account_to_a_new_generation() {
+        let repository = Arc::new(MockSessionRepository::new());
+        let service =
+            SessionServiceImpl::new(repository.clone()).with_config(SessionLimitsConfig {
+                account_cache_ttl: Duration::from_secs(60),
+                ..SessionLimitsConfig::default()
+            });
+        let id = repository.add_account(UserRole::Author);
+        let info = repository.insert_session(id);
+        service.check_session(id, 0, Some(&info)).await.unwrap();
+
+        service.revoke_all_sessions(id).await.unwrap();
+        // A session written back after the deletion is still refused, despite the cache
+        let result = service.check_session(id, 0, Some(&info)).await;
+        assert!(matches!(result, Err(SessionError::SessionInvalidated)));
+        assert_eq!(service.session_generation(id).await.unwrap(), 1);
+    }
+}
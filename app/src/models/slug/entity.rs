@@ -0,0 +1,58 @@
+// app/src/models/slug/entity.rs
+
+// dependencies
+use serde::{Deserialize, Serialize};
+
+/// Slugs which are paths of the site itself, content can never take them
+pub const RESERVED_SLUGS: &[&str] = &[
+    "account", "admin", "api", "assets", "auth", "feed", "login", "logout", "media", "ping",
+    "register", "search", "sitemap", "static", "uploads",
+];
+
+/// The kinds of content with slugs, each unique within its own table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlugScope {
+    Post,
+    Page,
+    Category,
+    Tag,
+}
+
+impl SlugScope {
+    // Name of the scope, as stored in the slug history
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Post => "post",
+            Self::Page => "page",
+            Self::Category => "category",
+            Self::Tag => "tag",
+        }
+    }
+
+    /// The table the content lives in, with its `id` and `slug` columns
+    pub fn table(&self) -> &'static str {
+        match self {
+            Self::Post => "posts",
+            Self::Page => "pages",
+            Self::Category => "categories",
+            Self::Tag => "tags",
+        }
+    }
+
+    /// The longest slug the `slug` column of the table holds
+    pub fn max_length(&self) -> usize {
+        match self {
+            Self::Post | Self::Page => 255,
+            Self::Category => 100,
+            Self::Tag => 50,
+        }
+    }
+}
+
+/// Content looked up by slug: found under it, or moved away from it to the slug it has now
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlugLookup<T> {
+    Found(T),
+    Moved(T),
+}
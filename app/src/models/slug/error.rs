@@ -0,0 +1,31 @@
+// app/src/models/slug/error.rs
+
+use crate::response::IntoApiError;
+use pavex::http::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SlugError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Slug '{0}' is reserved")]
+    Reserved(String),
+
+    #[error("Slug already exists")]
+    Taken,
+}
+
+impl IntoApiError for SlugError {
+    fn code(&self) -> Option<u16> {
+        match self {
+            SlugError::Reserved(_) => Some(StatusCode::BAD_REQUEST.as_u16()),
+            SlugError::Taken => Some(StatusCode::CONFLICT.as_u16()),
+            SlugError::Database(_) => Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16()),
+        }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,16 @@
+// app/src/models/slug/mod.rs
+
+// modules
+mod entity;
+mod error;
+mod repository;
+mod service;
+
+#[cfg(test)]
+mod tests;
+
+// re-export the modules
+pub use entity::*;
+pub use error::*;
+pub use repository::*;
+pub use service::*;
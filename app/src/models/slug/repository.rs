@@ -0,0 +1,137 @@
+// app/src/models/slug/repository.rs
+
+// dependencies
+use super::entity::SlugScope;
+use super::error::SlugError;
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+// traits
+#[async_trait]
+pub trait SlugRepository: Send + Sync {
+    /// Slugs in use which are `base` or `base` with a suffix, except those of the content `except`
+    async fn find_taken(
+        &self,
+        scope: SlugScope,
+        base: &str,
+        except: Option<Uuid>,
+    ) -> Result<Vec<String>, SlugError>;
+    /// Keep the old slug of renamed content, so it redirects to the new one
+    async fn record_rename(
+        &self,
+        scope: SlugScope,
+        entity_id: Uuid,
+        old_slug: &str,
+        new_slug: &str,
+    ) -> Result<(), SlugError>;
+    /// The slug content now has, if it used to have `slug`
+    async fn find_current(&self, scope: SlugScope, slug: &str)
+    -> Result<Option<String>, SlugError>;
+    /// Drop the old slugs of content which is gone
+    async fn forget(&self, scope: SlugScope, entity_id: Uuid) -> Result<(), SlugError>;
+}
+
+// SQLx implementation
+pub struct SqlxSlugRepository {
+    pool: PgPool,
+}
+
+impl SqlxSlugRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SlugRepository for SqlxSlugRepository {
+    async fn find_taken(
+        &self,
+        scope: SlugScope,
+        base: &str,
+        except: Option<Uuid>,
+    ) -> Result<Vec<String>, SlugError> {
+        // slugs hold no LIKE wildcards, only lowercase letters, digits and hyphens
+        let slugs = sqlx::query_scalar(&format!(
+            r#"
+            SELECT slug FROM {}
+            WHERE (slug = $1 OR slug LIKE $2)
+            AND ($3::uuid IS NULL OR id <> $3)
+            "#,
+            scope.table()
+        ))
+        .bind(base)
+        .bind(format!("{base}-%"))
+        .bind(except)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(slugs)
+    }
+
+    async fn record_rename(
+        &self,
+        scope: SlugScope,
+        entity_id: Uuid,
+        old_slug: &str,
+        new_slug: &str,
+    ) -> Result<(), SlugError> {
+        let mut tx = self.pool.begin().await?;
+
+        // the new slug is in use again, it no longer redirects anywhere
+        sqlx::query("DELETE FROM slug_history WHERE scope = $1 AND slug = $2")
+            .bind(scope.as_str())
+            .bind(new_slug)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO slug_history (scope, entity_id, slug)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (scope, slug)
+            DO UPDATE SET entity_id = EXCLUDED.entity_id, created_at = NOW()
+            "#,
+        )
+        .bind(scope.as_str())
+        .bind(entity_id)
+        .bind(old_slug)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn find_current(
+        &self,
+        scope: SlugScope,
+        slug: &str,
+    ) -> Result<Option<String>, SlugError> {
+        let current = sqlx::query_scalar(&format!(
+            r#"
+            SELECT content.slug
+            FROM slug_history history
+            JOIN {} content ON content.id = history.entity_id
+            WHERE history.scope = $1 AND history.slug = $2
+            "#,
+            scope.table()
+        ))
+        .bind(scope.as_str())
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(current)
+    }
+
+    async fn forget(&self, scope: SlugScope, entity_id: Uuid) -> Result<(), SlugError> {
+        sqlx::query("DELETE FROM slug_history WHERE scope = $1 AND entity_id = $2")
+            .bind(scope.as_str())
+            .bind(entity_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
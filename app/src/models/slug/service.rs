@@ -0,0 +1,145 @@
+// app/src/models/slug/service.rs
+
+// dependencies
+use super::entity::{RESERVED_SLUGS, SlugScope};
+use super::error::SlugError;
+use super::repository::SlugRepository;
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// traits
+#[async_trait]
+pub trait SlugService: Send + Sync {
+    /// A free slug for content: the one asked for, else one derived from its title.
+    ///
+    /// Derived slugs are made unique with a numeric suffix, while a slug asked for which is
+    /// reserved or taken is refused. `owner` is the content being edited, if it exists already.
+    async fn claim(
+        &self,
+        scope: SlugScope,
+        requested: Option<&str>,
+        title: &str,
+        owner: Option<Uuid>,
+    ) -> Result<String, SlugError>;
+    async fn record_rename(
+        &self,
+        scope: SlugScope,
+        entity_id: Uuid,
+        old_slug: &str,
+        new_slug: &str,
+    ) -> Result<(), SlugError>;
+    /// The current slug of content which used to have `slug`
+    async fn redirect(&self, scope: SlugScope, slug: &str) -> Result<Option<String>, SlugError>;
+    async fn forget(&self, scope: SlugScope, entity_id: Uuid) -> Result<(), SlugError>;
+}
+
+pub struct SlugServiceImpl {
+    repository: Arc<dyn SlugRepository>,
+}
+
+impl SlugServiceImpl {
+    pub fn new(repository: Arc<dyn SlugRepository>) -> Self {
+        Self { repository }
+    }
+}
+
+/// Derive a slug from a title: lowercase ASCII words joined by single hyphens.
+///
+/// Other scripts are transliterated first, so `Crème brûlée` becomes `creme-brulee`.
+/// Titles with nothing to make a slug from are named after their scope, as in `post`.
+pub fn slugify(title: &str, scope: SlugScope) -> String {
+    let ascii = deunicode::deunicode(title);
+    let mut slug = String::with_capacity(ascii.len());
+    for c in ascii.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = truncate(&slug, scope.max_length());
+    if slug.is_empty() {
+        scope.as_str().to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Whether a slug belongs to a path of the site itself
+pub fn is_reserved(slug: &str) -> bool {
+    RESERVED_SLUGS.contains(&slug)
+}
+
+// cut an ASCII slug to a length, without leaving a hyphen at the end
+fn truncate(slug: &str, max_length: usize) -> &str {
+    slug[..slug.len().min(max_length)].trim_end_matches('-')
+}
+
+#[async_trait]
+impl SlugService for SlugServiceImpl {
+    async fn claim(
+        &self,
+        scope: SlugScope,
+        requested: Option<&str>,
+        title: &str,
+        owner: Option<Uuid>,
+    ) -> Result<String, SlugError> {
+        if let Some(slug) = requested {
+            if is_reserved(slug) {
+                return Err(SlugError::Reserved(slug.to_string()));
+            }
+            let taken = self.repository.find_taken(scope, slug, owner).await?;
+            if taken.iter().any(|other| other == slug) {
+                return Err(SlugError::Taken);
+            }
+            return Ok(slug.to_string());
+        }
+
+        let base = slugify(title, scope);
+        let taken: HashSet<String> = self
+            .repository
+            .find_taken(scope, &base, owner)
+            .await?
+            .into_iter()
+            .collect();
+        let free = |slug: &str| !is_reserved(slug) && !taken.contains(slug);
+        if free(&base) {
+            return Ok(base);
+        }
+
+        // `base-2`, `base-3`... the base is cut short when the suffix would not fit
+        (2..)
+            .map(|n| {
+                let suffix = format!("-{n}");
+                let base = truncate(&base, scope.max_length() - suffix.len());
+                format!("{base}{suffix}")
+            })
+            .find(|slug| free(slug))
+            .ok_or(SlugError::Taken)
+    }
+
+    async fn record_rename(
+        &self,
+        scope: SlugScope,
+        entity_id: Uuid,
+        old_slug: &str,
+        new_slug: &str,
+    ) -> Result<(), SlugError> {
+        if old_slug == new_slug {
+            return Ok(());
+        }
+        self.repository
+            .record_rename(scope, entity_id, old_slug, new_slug)
+            .await
+    }
+
+    async fn redirect(&self, scope: SlugScope, slug: &str) -> Result<Option<String>, SlugError> {
+        self.repository.find_current(scope, slug).await
+    }
+
+    async fn forget(&self, scope: SlugScope, entity_id: Uuid) -> Result<(), SlugError> {
+        self.repository.forget(scope, entity_id).await
+    }
+}
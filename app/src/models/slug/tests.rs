@@ -0,0 +1,263 @@
+// app/src/models/slug/tests.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::models::slug::*;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    // Mock repository for testing the service layer, with the content of every scope in one map
+    #[derive(Default)]
+    pub struct MockSlugRepository {
+        content: Mutex<HashMap<(SlugScope, Uuid), String>>,
+        history: Mutex<HashMap<(SlugScope, String), Uuid>>,
+    }
+
+    impl MockSlugRepository {
+        fn insert(&self, scope: SlugScope, slug: &str) -> Uuid {
+            let id = Uuid::new_v4();
+            self.content
+                .lock()
+                .unwrap()
+                .insert((scope, id), slug.to_string());
+            id
+        }
+
+        fn rename(&self, scope: SlugScope, id: Uuid, slug: &str) {
+            self.content
+                .lock()
+                .unwrap()
+                .insert((scope, id), slug.to_string());
+        }
+    }
+
+    #[async_trait]
+    impl SlugRepository for MockSlugRepository {
+        async fn find_taken(
+            &self,
+            scope: SlugScope,
+            base: &str,
+            except: Option<Uuid>,
+        ) -> Result<Vec<String>, SlugError> {
+            let prefix = format!("{base}-");
+            Ok(self
+                .content
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|((s, id), slug)| {
+                    *s == scope
+                        && Some(*id) != except
+                        && (slug.as_str() == base || slug.starts_with(&prefix))
+                })
+                .map(|(_, slug)| slug.clone())
+                .collect())
+        }
+
+        async fn record_rename(
+            &self,
+            scope: SlugScope,
+            entity_id: Uuid,
+            old_slug: &str,
+            new_slug: &str,
+        ) -> Result<(), SlugError> {
+            let mut history = self.history.lock().unwrap();
+            history.remove(&(scope, new_slug.to_string()));
+            history.insert((scope, old_slug.to_string()), entity_id);
+            Ok(())
+        }
+
+        async fn find_current(
+            &self,
+            scope: SlugScope,
+            slug: &str,
+        ) -> Result<Option<String>, SlugError> {
+            let history = self.history.lock().unwrap();
+            let content = self.content.lock().unwrap();
+            Ok(history
+                .get(&(scope, slug.to_string()))
+                .and_then(|id| content.get(&(scope, *id)))
+                .cloned())
+        }
+
+        async fn forget(&self, scope: SlugScope, entity_id: Uuid) -> Result<(), SlugError> {
+            self.history
+                .lock()
+                .unwrap()
+                .retain(|(s, _), id| !(*s == scope && *id == entity_id));
+            Ok(())
+        }
+    }
+
+    fn service() -> (Arc<MockSlugRepository>, SlugServiceImpl) {
+        let repository = Arc::new(MockSlugRepository::default());
+        (repository.clone(), SlugServiceImpl::new(repository))
+    }
+
+    #[test]
+    fn test_slugs_are_transliterated_to_ascii() {
+        assert_eq!(
+            slugify("Hello, World! It's 2026", SlugScope::Post),
+            "hello-world-it-s-2026"
+        );
+        assert_eq!(
+            slugify("  --Rust  &  Pavex--  ", SlugScope::Post),
+            "rust-pavex"
+        );
+        assert_eq!(slugify("Crème brûlée", SlugScope::Post), "creme-brulee");
+        assert_eq!(
+            slugify("Straße in Köln", SlugScope::Page),
+            "strasse-in-koln"
+        );
+        assert_eq!(slugify("Привет, мир", SlugScope::Post), "privet-mir");
+        assert_eq!(slugify("!!!", SlugScope::Post), "post");
+        assert_eq!(slugify("", SlugScope::Tag), "tag");
+    }
+
+    #[test]
+    fn test_slugs_fit_their_column() {
+        let title = "word ".repeat(100);
+
+        let slug = slugify(&title, SlugScope::Tag);
+        assert!(slug.len() <= 50);
+        assert!(!slug.ends_with('-'));
+        assert!(slugify(&title, SlugScope::Post).len() <= 255);
+    }
+
+    #[tokio::test]
+    async fn test_collisions_get_numeric_suffixes() {
+        let (repository, service) = service();
+
+        let first = service
+            .claim(SlugScope::Post, None, "Hello", None)
+            .await
+            .unwrap();
+        assert_eq!(first, "hello");
+        repository.insert(SlugScope::Post, &first);
+
+        let second = service
+            .claim(SlugScope::Post, None, "Hello", None)
+            .await
+            .unwrap();
+        assert_eq!(second, "hello-2");
+        repository.insert(SlugScope::Post, &second);
+
+        assert_eq!(
+            service
+                .claim(SlugScope::Post, None, "Hello!", None)
+                .await
+                .unwrap(),
+            "hello-3"
+        );
+        // Each kind of content has slugs of its own
+        assert_eq!(
+            service
+                .claim(SlugScope::Tag, None, "Hello", None)
+                .await
+                .unwrap(),
+            "hello"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_suffixes_fit_the_column() {
+        let (repository, service) = service();
+        let title = "a".repeat(60);
+        let base = slugify(&title, SlugScope::Tag);
+        repository.insert(SlugScope::Tag, &base);
+
+        let slug = service
+            .claim(SlugScope::Tag, None, &title, None)
+            .await
+            .unwrap();
+        assert_eq!(slug.len(), 50);
+        assert!(slug.ends_with("-2"));
+    }
+
+    #[tokio::test]
+    async fn test_content_keeps_its_own_slug() {
+        let (repository, service) = service();
+        let id = repository.insert(SlugScope::Post, "hello");
+
+        let slug = service
+            .claim(SlugScope::Post, Some("hello"), "Hello", Some(id))
+            .await
+            .unwrap();
+        assert_eq!(slug, "hello");
+        assert!(matches!(
+            service
+                .claim(SlugScope::Post, Some("hello"), "Hello", None)
+                .await,
+            Err(SlugError::Taken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reserved_paths_are_refused_or_avoided() {
+        let (_, service) = service();
+
+        assert!(matches!(
+            service
+                .claim(SlugScope::Page, Some("admin"), "Admin", None)
+                .await,
+            Err(SlugError::Reserved(_))
+        ));
+        assert_eq!(
+            service
+                .claim(SlugScope::Page, None, "Static", None)
+                .await
+                .unwrap(),
+            "static-2"
+        );
+        assert!(is_reserved("auth"));
+        assert!(!is_reserved("authors"));
+    }
+
+    #[tokio::test]
+    async fn test_old_slugs_redirect_to_the_current_one() {
+        let (repository, service) = service();
+        let id = repository.insert(SlugScope::Post, "first");
+
+        repository.rename(SlugScope::Post, id, "second");
+        service
+            .record_rename(SlugScope::Post, id, "first", "second")
+            .await
+            .unwrap();
+        repository.rename(SlugScope::Post, id, "third");
+        service
+            .record_rename(SlugScope::Post, id, "second", "third")
+            .await
+            .unwrap();
+
+        // Every old slug leads straight to the current one
+        for old in ["first", "second"] {
+            assert_eq!(
+                service.redirect(SlugScope::Post, old).await.unwrap(),
+                Some("third".to_string())
+            );
+        }
+        assert_eq!(
+            service.redirect(SlugScope::Page, "first").await.unwrap(),
+            None
+        );
+
+        // Taking an old slug back ends its redirect
+        repository.rename(SlugScope::Post, id, "first");
+        service
+            .record_rename(SlugScope::Post, id, "third", "first")
+            .await
+            .unwrap();
+        assert_eq!(
+            service.redirect(SlugScope::Post, "first").await.unwrap(),
+            None
+        );
+
+        service.forget(SlugScope::Post, id).await.unwrap();
+        assert_eq!(
+            service.redirect(SlugScope::Post, "second").await.unwrap(),
+            None
+        );
+    }
+}
@@ -30,8 +30,17 @@ pub struct CreateUserRequest {
     pub display_name: Option<String>,
 }
 
+/// Registration request DTO - an invite token creates the account with the role of its invitation
+#[derive(Clone, Debug, Deserialize)]
+pub struct RegisterRequest {
+    #[serde(flatten)]
+    pub user: CreateUserRequest,
+
+    pub invite_token: Option<String>,
+}
+
 /// Request DTO for user updates
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Clone, Debug, Deserialize, Validate)]
 pub struct UpdateUserRequest {
     #[validate(length(max = 100, message = "Display name cannot exceed 100 characters"))]
     pub display_name: Option<String>,
@@ -93,7 +102,7 @@ pub struct LoginRequest {
 }
 
 /// Password change request DTO
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Clone, Debug, Deserialize, Validate)]
 pub struct ChangePasswordRequest {
     #[validate(length(min = 1, message = "Current password is required"))]
     pub current_password: String,
@@ -102,6 +111,168 @@ pub struct ChangePasswordRequest {
     pub new_password: String,
 }
 
+/// Forgotten password request DTO
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct ForgotPasswordRequest {
+    #[validate(email(message = "Invalid email address"))]
+    #[validate(length(max = 255, message = "Email cannot exceed 255 characters"))]
+    pub email: String,
+}
+
+/// Password reset request DTO - the new password follows the same rules as a password change
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct ResetPasswordRequest {
+    #[validate(length(min = 1, message = "Reset token is required"))]
+    pub token: String,
+
+    #[validate(length(min = 8, max = 128, message = "New password must be 8-128 characters"))]
+    pub new_password: String,
+}
+
+/// Email change request DTO - the password is asked again, as for a password change
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct ChangeEmailRequest {
+    #[validate(email(message = "Invalid email address"))]
+    #[validate(length(max = 255, message = "Email cannot exceed 255 characters"))]
+    pub new_email: String,
+
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+/// Request DTO carrying the token of an email change link, to confirm or undo it
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct EmailChangeTokenRequest {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+}
+
+/// Query DTO for the admin user listing
+#[derive(Clone, Debug, Default, Deserialize, Validate)]
+pub struct UserListQuery {
+    #[validate(range(min = 1, message = "Page must be at least 1"))]
+    pub page: Option<u32>,
+
+    #[validate(range(min = 1, max = 100, message = "Page size must be 1-100"))]
+    pub per_page: Option<u32>,
+
+    // the filter form submits an empty value for "any role"
+    #[serde(default, deserialize_with = "deserialize_optional_role")]
+    pub role: Option<UserRole>,
+
+    #[validate(length(max = 255, message = "Search cannot exceed 255 characters"))]
+    pub search: Option<String>,
+}
+
+impl UserListQuery {
+    pub const DEFAULT_PER_PAGE: u32 = 20;
+
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> u32 {
+        self.per_page
+            .unwrap_or(Self::DEFAULT_PER_PAGE)
+            .clamp(1, 100)
+    }
+
+    pub fn offset(&self) -> i64 {
+        i64::from(self.page() - 1) * i64::from(self.per_page())
+    }
+
+    // Trimmed search term, if any
+    pub fn search_term(&self) -> Option<&str> {
+        self.search
+            .as_deref()
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+    }
+}
+
+/// Paginated user listing response DTO
+#[derive(Debug, Serialize)]
+pub struct UserListResponse {
+    pub users: Vec<UserResponse>,
+    pub total: i64,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+/// Role change request DTO
+#[derive(Clone, Debug, Deserialize)]
+pub struct UpdateRoleRequest {
+    pub role: UserRole,
+}
+
+/// Account activation request DTO
+#[derive(Clone, Debug, Deserialize)]
+pub struct SetActiveRequest {
+    pub is_active: bool,
+}
+
+/// Login response DTO - says whether the login is complete or waits on a second factor
+#[derive(Debug, Serialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum LoginResponse {
+    Complete(UserSummary),
+    TwoFactorRequired,
+    TwoFactorSetupRequired,
+}
+
+/// Two-factor code request DTO - takes either a TOTP code or a recovery code
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct TwoFactorCodeRequest {
+    #[validate(length(min = 1, max = 32, message = "Code must be 1-32 characters"))]
+    pub code: String,
+}
+
+/// Two-factor removal request DTO
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct DisableTwoFactorRequest {
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+
+    #[validate(length(min = 1, max = 32, message = "Code must be 1-32 characters"))]
+    pub code: String,
+}
+
+/// Two-factor status response DTO
+#[derive(Debug, Serialize)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub required: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+/// TOTP enrollment response DTO - the secret is shown once, for apps which cannot scan the QR code
+#[derive(Debug, Serialize)]
+pub struct TotpSetupResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub qr_svg: String,
+}
+
+/// Recovery codes response DTO - the codes are only ever shown in this response
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+// Deserialize an optional role, treating an empty value as no role at all
+fn deserialize_optional_role<'de, D>(deserializer: D) -> Result<Option<UserRole>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: Option<String> = Option::deserialize(deserializer)?;
+    match value.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(role) => UserRole::frm_str(role)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown role `{role}`"))),
+    }
+}
+
 // Validation regex - you'd put this in a constants module
 static USERNAME_REGEX: LazyLock<regex::Regex> =
     LazyLock::new(|| regex::Regex::new(r"^[a-zA-Z0-9_]+$").unwrap());
@@ -6,8 +6,16 @@ use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Type};
 use uuid::Uuid;
 
+/// The placeholder author content of deleted accounts is handed over to
+///
+/// It is created by the migrations, and can never sign in.
+pub const DELETED_USER_ID: Uuid = Uuid::nil();
+
+/// Password hash of accounts which can never sign in with a password
+pub const UNUSABLE_PASSWORD_HASH: &str = "!";
+
 /// User roles enum that matches your database enum
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Type, Serialize, Deserialize)]
 #[sqlx(type_name = "user_role", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum UserRole {
@@ -25,6 +33,25 @@ impl Default for UserRole {
 }
 
 impl UserRole {
+    pub const ALL: [UserRole; 5] = [
+        UserRole::Admin,
+        UserRole::Editor,
+        UserRole::Author,
+        UserRole::Contributor,
+        UserRole::Subscriber,
+    ];
+
+    // Name of the role, as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Editor => "editor",
+            Self::Author => "author",
+            Self::Contributor => "contributor",
+            Self::Subscriber => "subscriber",
+        }
+    }
+
     // Parse role from string - useful for manual row mapping
     pub fn frm_str(s: &str) -> Option<Self> {
         match s {
@@ -74,26 +101,34 @@ pub struct User {
 }
 
 impl User {
-    // Check if user can perform admin actions
-    pub fn is_admin(&self) -> bool {
-        matches!(self.role, UserRole::Admin)
+    // Get display name, falling back to username
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.username)
     }
+}
 
-    // Check if user can create/edit content
-    pub fn can_write(&self) -> bool {
-        matches!(
-            self.role,
-            UserRole::Admin | UserRole::Editor | UserRole::Author
-        )
-    }
+// TOTP authenticator enrolled by a user
+#[derive(Debug, Clone)]
+pub struct TotpCredential {
+    pub user_id: Uuid,
+    /// Base32 encoded shared secret
+    pub secret: String,
+    /// Unset while enrollment awaits its first code
+    pub enabled_at: Option<Timestamp>,
+    /// The last time step a code was accepted for
+    pub last_used_step: Option<i64>,
+}
 
-    // Check if user can moderate comments
-    pub fn can_moderate(&self) -> bool {
-        matches!(self.role, UserRole::Admin | UserRole::Editor)
+impl TotpCredential {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
     }
+}
 
-    // Get display name, falling back to username
-    pub fn display_name(&self) -> &str {
-        self.display_name.as_deref().unwrap_or(&self.username)
-    }
+// Change of the email address of a user, pending until confirmed from the new address
+#[derive(Debug, Clone)]
+pub struct EmailChange {
+    pub user_id: Uuid,
+    pub old_email: String,
+    pub new_email: String,
 }
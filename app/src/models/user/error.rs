@@ -2,6 +2,7 @@
 
 use crate::response::IntoApiError;
 use pavex::http::StatusCode;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -29,6 +30,18 @@ pub enum UserError {
 
     #[error("Password hashing error: {0}")]
     PasswordHash(String),
+
+    #[error("Invalid two-factor authentication code")]
+    InvalidSecondFactor,
+
+    #[error("Too many failed login attempts, please retry in {} seconds", retry_after.as_secs().max(1))]
+    AccountLocked { retry_after: Duration },
+
+    #[error("Registration is closed")]
+    RegistrationClosed,
+
+    #[error("Registration requires an invitation")]
+    InvitationRequired,
 }
 
 impl IntoApiError for UserError {
@@ -39,8 +52,12 @@ impl IntoApiError for UserError {
             UserError::EmailExists | UserError::UsernameExists => {
                 Some(StatusCode::CONFLICT.as_u16())
             }
-            UserError::InvalidCredentials | UserError::EmailNotVerified => {
-                Some(StatusCode::UNAUTHORIZED.as_u16())
+            UserError::InvalidCredentials
+            | UserError::EmailNotVerified
+            | UserError::InvalidSecondFactor => Some(StatusCode::UNAUTHORIZED.as_u16()),
+            UserError::AccountLocked { .. } => Some(StatusCode::TOO_MANY_REQUESTS.as_u16()),
+            UserError::RegistrationClosed | UserError::InvitationRequired => {
+                Some(StatusCode::FORBIDDEN.as_u16())
             }
             UserError::Database(_) | UserError::PasswordHash(_) => {
                 Some(StatusCode::INTERNAL_SERVER_ERROR.as_u16())
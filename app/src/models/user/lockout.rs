@@ -0,0 +1,35 @@
+// app/src/models/user/lockout.rs
+
+// dependencies
+use crate::configuration::LockoutPolicy;
+use std::net::IpAddr;
+use std::time::Duration;
+
+impl LockoutPolicy {
+    /// How long logins are blocked after `failures` consecutive failed attempts, if at all.
+    pub fn delay_after(&self, failures: u32) -> Option<Duration> {
+        if failures >= self.lockout_after {
+            return Some(self.lockout_duration);
+        }
+        if failures <= self.free_attempts {
+            return None;
+        }
+        // 1x, 2x, 4x ... the base delay, for every failure past the free ones
+        let exponent = (failures - self.free_attempts - 1).min(31);
+        let delay = self.base_delay.saturating_mul(1 << exponent);
+        Some(delay.min(self.max_delay))
+    }
+}
+
+/// Key under which failed logins against an account are counted.
+///
+/// Unknown accounts are keyed by the identifier that was tried, so they lock
+/// out exactly like existing ones.
+pub fn account_lockout_key(account: &str) -> String {
+    format!("account:{}", account.trim().to_lowercase())
+}
+
+/// Key under which failed logins from a client address are counted.
+pub fn ip_lockout_key(ip: IpAddr) -> String {
+    format!("ip:{ip}")
+}
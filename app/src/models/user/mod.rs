@@ -4,8 +4,11 @@
 mod dto;
 mod entity;
 mod error;
+mod lockout;
+mod password;
 mod repository;
 mod service;
+mod totp;
 
 #[cfg(test)]
 mod tests;
@@ -14,5 +17,8 @@ mod tests;
 pub use dto::*;
 pub use entity::*;
 pub use error::*;
+pub use lockout::*;
+pub use password::*;
 pub use repository::*;
 pub use service::*;
+pub use totp::*;
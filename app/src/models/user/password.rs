@@ -0,0 +1,123 @@
+// app/src/models/user/password.rs
+
+// dependencies
+use super::entity::UNUSABLE_PASSWORD_HASH;
+use super::error::UserError;
+use crate::configuration::{PasswordAlgorithm, PasswordHashingConfig};
+use argon2::password_hash::{SaltString, rand_core::OsRng};
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
+use secrecy::ExposeSecret;
+
+// enum type to represent the outcome of checking a password against its hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordCheck {
+    Invalid,
+    Valid,
+    /// The password matches, but its hash does not use the configured parameters or pepper
+    Outdated,
+}
+
+impl PasswordCheck {
+    pub fn is_valid(self) -> bool {
+        self != PasswordCheck::Invalid
+    }
+}
+
+/// Hashes passwords with the configured Argon2 parameters, and checks them against stored hashes
+#[derive(Clone)]
+pub struct PasswordHashing {
+    algorithm: Algorithm,
+    params: Params,
+    pepper: Option<Vec<u8>>,
+}
+
+impl PasswordHashing {
+    pub fn new(config: &PasswordHashingConfig) -> Result<Self, UserError> {
+        let algorithm = match config.algorithm {
+            PasswordAlgorithm::Argon2id => Algorithm::Argon2id,
+            PasswordAlgorithm::Argon2i => Algorithm::Argon2i,
+            PasswordAlgorithm::Argon2d => Algorithm::Argon2d,
+        };
+        let params = Params::new(config.m_cost, config.t_cost, config.p_cost, None)
+            .map_err(|e| UserError::PasswordHash(e.to_string()))?;
+        let pepper = config
+            .pepper
+            .as_ref()
+            .map(|pepper| pepper.expose_secret().as_bytes().to_vec())
+            .filter(|pepper| !pepper.is_empty());
+
+        Ok(Self {
+            algorithm,
+            params,
+            pepper,
+        })
+    }
+
+    fn argon2<'a>(&self, pepper: Option<&'a [u8]>) -> Result<Argon2<'a>, UserError> {
+        let params = self.params.clone();
+        match pepper {
+            Some(pepper) => Argon2::new_with_secret(pepper, self.algorithm, Version::V0x13, params)
+                .map_err(|e| UserError::PasswordHash(e.to_string())),
+            None => Ok(Argon2::new(self.algorithm, Version::V0x13, params)),
+        }
+    }
+
+    /// Hash a password with a fresh salt
+    pub fn hash(&self, password: &str) -> Result<String, UserError> {
+        let salt = SaltString::generate(&mut OsRng);
+
+        self.argon2(self.pepper.as_deref())?
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| UserError::PasswordHash(e.to_string()))
+    }
+
+    /// Check a password against a stored hash, which carries the parameters it was made with
+    pub fn verify(&self, password: &str, hash: &str) -> Result<PasswordCheck, UserError> {
+        if hash == UNUSABLE_PASSWORD_HASH {
+            return Ok(PasswordCheck::Invalid);
+        }
+        let parsed_hash =
+            PasswordHash::new(hash).map_err(|e| UserError::PasswordHash(e.to_string()))?;
+
+        let matches = |pepper: Option<&[u8]>| -> Result<bool, UserError> {
+            Ok(self
+                .argon2(pepper)?
+                .verify_password(password.as_bytes(), &parsed_hash)
+                .is_ok())
+        };
+        if matches(self.pepper.as_deref())? {
+            return Ok(if self.is_current(&parsed_hash) {
+                PasswordCheck::Valid
+            } else {
+                PasswordCheck::Outdated
+            });
+        }
+
+        // Hashes made before a pepper was configured still match without it, until rehashed
+        if self.pepper.is_some() && matches(None)? {
+            return Ok(PasswordCheck::Outdated);
+        }
+        Ok(PasswordCheck::Invalid)
+    }
+
+    // whether a hash was made with the configured algorithm and parameters
+    fn is_current(&self, hash: &PasswordHash<'_>) -> bool {
+        let Ok(params) = Params::try_from(hash) else {
+            return false;
+        };
+
+        Algorithm::try_from(hash.algorithm).ok() == Some(self.algorithm)
+            && hash.version == Some(Version::V0x13.into())
+            && params.m_cost() == self.params.m_cost()
+            && params.t_cost() == self.params.t_cost()
+            && params.p_cost() == self.params.p_cost()
+    }
+}
+
+impl Default for PasswordHashing {
+    fn default() -> Self {
+        Self::new(&PasswordHashingConfig::default())
+            .expect("the default Argon2 parameters are valid")
+    }
+}
@@ -1,11 +1,10 @@
 // app/src/models/user/repository.rs
 
 // dependencies
-use super::dto::{CreateUserRequest, UpdateUserRequest};
-use super::entity::{User, UserRole};
+use super::dto::{CreateUserRequest, UpdateUserRequest, UserListQuery};
+use super::entity::{DELETED_USER_ID, EmailChange, TotpCredential, User, UserRole};
 use super::error::UserError;
-use argon2::password_hash::{SaltString, rand_core::OsRng};
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use super::password::{PasswordCheck, PasswordHashing};
 use async_trait::async_trait;
 use jiff_sqlx::Timestamp as SqlxTimestamp;
 use pavex::time::Timestamp;
@@ -17,6 +16,12 @@ use uuid::Uuid;
 #[async_trait]
 pub trait UserRepository: Send + Sync {
     async fn create(&self, request: CreateUserRequest) -> Result<User, UserError>;
+    /// Create an account with the role of its invitation, its address proven by the invite link
+    async fn create_invited(
+        &self,
+        request: CreateUserRequest,
+        role: UserRole,
+    ) -> Result<User, UserError>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, UserError>;
     async fn find_by_username(&self, username: &str) -> Result<Option<User>, UserError>;
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, UserError>;
@@ -25,46 +30,185 @@ pub trait UserRepository: Send + Sync {
         username_or_email: &str,
     ) -> Result<Option<User>, UserError>;
     async fn update(&self, id: Uuid, request: UpdateUserRequest) -> Result<User, UserError>;
-    async fn verify_password(&self, user: &User, password: &str) -> Result<bool, UserError>;
+    async fn verify_password(
+        &self,
+        user: &User,
+        password: &str,
+    ) -> Result<PasswordCheck, UserError>;
     async fn change_password(&self, id: Uuid, new_password: &str) -> Result<(), UserError>;
-    async fn set_email_verification_token(&self, id: Uuid, token: String) -> Result<(), UserError>;
-    async fn verify_email(&self, token: &str) -> Result<Option<User>, UserError>;
-    async fn set_password_reset_token(&self, id: Uuid, token: String) -> Result<(), UserError>;
+    async fn set_email_verification_token(
+        &self,
+        id: Uuid,
+        token_hash: String,
+    ) -> Result<(), UserError>;
+    async fn verify_email(&self, token_hash: &str) -> Result<Option<User>, UserError>;
+    async fn set_password_reset_token(&self, id: Uuid, token_hash: String)
+    -> Result<(), UserError>;
     async fn reset_password(
         &self,
-        token: &str,
+        token_hash: &str,
         new_password: &str,
     ) -> Result<Option<User>, UserError>;
+    async fn invalidate_sessions(&self, id: Uuid) -> Result<u64, UserError>;
+    // Administration related operations
+    async fn list(&self, query: &UserListQuery) -> Result<Vec<User>, UserError>;
+    async fn count(&self, query: &UserListQuery) -> Result<i64, UserError>;
+    async fn update_role(&self, id: Uuid, role: UserRole) -> Result<User, UserError>;
+    async fn set_active(&self, id: Uuid, is_active: bool) -> Result<User, UserError>;
+    async fn force_verify_email(&self, id: Uuid) -> Result<User, UserError>;
+    // Login brute-force protection, counters are keyed by account or client address
+    async fn login_locked_until(&self, key: &str) -> Result<Option<Timestamp>, UserError>;
+    async fn record_login_failure(
+        &self,
+        key: &str,
+        reset_after: Duration,
+    ) -> Result<u32, UserError>;
+    async fn lock_login(&self, key: &str, until: Timestamp) -> Result<(), UserError>;
+    async fn clear_login_failures(&self, key: &str) -> Result<(), UserError>;
+    // Two-factor authentication related operations
+    async fn find_totp(&self, id: Uuid) -> Result<Option<TotpCredential>, UserError>;
+    async fn set_pending_totp(&self, id: Uuid, secret: &str) -> Result<(), UserError>;
+    async fn enable_totp(&self, id: Uuid, step: i64) -> Result<(), UserError>;
+    async fn use_totp_step(&self, id: Uuid, step: i64) -> Result<bool, UserError>;
+    async fn delete_totp(&self, id: Uuid) -> Result<(), UserError>;
+    async fn replace_recovery_codes(
+        &self,
+        id: Uuid,
+        code_hashes: &[String],
+    ) -> Result<(), UserError>;
+    async fn use_recovery_code(&self, id: Uuid, code_hash: &str) -> Result<bool, UserError>;
+    async fn count_recovery_codes(&self, id: Uuid) -> Result<i64, UserError>;
+    // Email change related operations
+    async fn create_email_change(
+        &self,
+        id: Uuid,
+        new_email: &str,
+        token_hash: &str,
+    ) -> Result<(), UserError>;
+    async fn confirm_email_change(
+        &self,
+        token_hash: &str,
+        undo_token_hash: &str,
+    ) -> Result<Option<EmailChange>, UserError>;
+    async fn undo_email_change(
+        &self,
+        undo_token_hash: &str,
+    ) -> Result<Option<EmailChange>, UserError>;
 }
 
 pub struct SqlxUserRepository {
     pool: PgPool,
+    hashing: PasswordHashing,
 }
 
 impl SqlxUserRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            hashing: PasswordHashing::default(),
+        }
+    }
+
+    pub fn with_hashing(self, hashing: PasswordHashing) -> Self {
+        Self { hashing, ..self }
     }
 
-    /// Hash a password using Argon2
-    fn hash_password(password: &str) -> Result<String, UserError> {
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
+    // insert a new account, refusing taken usernames and addresses
+    async fn insert(
+        &self,
+        request: CreateUserRequest,
+        role: UserRole,
+        email_verified: bool,
+    ) -> Result<User, UserError> {
+        // Check if username already exists
+        if (self.find_by_username(&request.username).await?).is_some() {
+            return Err(UserError::UsernameExists);
+        }
+
+        // Check if email already exists
+        if (self.find_by_email(&request.email).await?).is_some() {
+            return Err(UserError::EmailExists);
+        }
+
+        // Hash the password
+        let password_hash = self.hashing.hash(&request.password)?;
+
+        // Insert new user with manual query
+        let row = sqlx::query(
+            r#"
+            INSERT INTO users (username, email, password_hash, display_name, role, email_verified)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING 
+                id, username, email, password_hash, display_name, bio, avatar_url,
+                role, is_active, email_verified,
+                email_verification_token, email_verification_expires_at,
+                password_reset_token, password_reset_expires_at,
+                social_twitter, social_github, website_url,
+                created_at, updated_at
+            "#,
+        )
+        .bind(&request.username)
+        .bind(&request.email)
+        .bind(&password_hash)
+        .bind(&request.display_name)
+        .bind(role)
+        .bind(email_verified)
+        .fetch_one(&self.pool)
+        .await?;
 
-        argon2
-            .hash_password(password.as_bytes(), &salt)
-            .map(|hash| hash.to_string())
-            .map_err(|e| UserError::PasswordHash(e.to_string()))
+        Self::map_row_to_user(row)
     }
 
-    /// Verify a password against a hash
-    fn verify_password_hash(password: &str, hash: &str) -> Result<bool, UserError> {
-        let parsed_hash =
-            PasswordHash::new(hash).map_err(|e| UserError::PasswordHash(e.to_string()))?;
+    /// Helper function to map an email_changes row to EmailChange struct
+    fn map_row_to_email_change(row: &sqlx::postgres::PgRow) -> EmailChange {
+        EmailChange {
+            user_id: row.get("user_id"),
+            old_email: row.get("old_email"),
+            new_email: row.get("new_email"),
+        }
+    }
 
-        Ok(Argon2::default()
-            .verify_password(password.as_bytes(), &parsed_hash)
-            .is_ok())
+    /// Point the account at `email`, unless another account uses it already
+    async fn swap_email(
+        tx: &mut sqlx::PgConnection,
+        id: Uuid,
+        email: &str,
+    ) -> Result<(), UserError> {
+        let taken: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE email = $1 AND id <> $2)")
+                .bind(email)
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+        if taken {
+            return Err(UserError::EmailExists);
+        }
+
+        // The address was just proven reachable, and the unique index settles any race
+        sqlx::query(
+            "UPDATE users SET email = $1, email_verified = true, updated_at = NOW() WHERE id = $2",
+        )
+        .bind(email)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => UserError::EmailExists,
+            _ => e.into(),
+        })?;
+
+        Ok(())
+    }
+
+    /// Turn a search term into an ILIKE pattern, escaping the LIKE wildcards it may contain
+    fn search_pattern(query: &UserListQuery) -> Option<String> {
+        query.search_term().map(|term| {
+            let escaped = term
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("%{escaped}%")
+        })
     }
 
     /// Helper function to map database row to User struct
@@ -102,41 +246,15 @@ impl SqlxUserRepository {
 #[async_trait]
 impl UserRepository for SqlxUserRepository {
     async fn create(&self, request: CreateUserRequest) -> Result<User, UserError> {
-        // Check if username already exists
-        if (self.find_by_username(&request.username).await?).is_some() {
-            return Err(UserError::UsernameExists);
-        }
-
-        // Check if email already exists
-        if (self.find_by_email(&request.email).await?).is_some() {
-            return Err(UserError::EmailExists);
-        }
-
-        // Hash the password
-        let password_hash = Self::hash_password(&request.password)?;
-
-        // Insert new user with manual query
-        let row = sqlx::query(
-            r#"
-            INSERT INTO users (username, email, password_hash, display_name)
-            VALUES ($1, $2, $3, $4)
-            RETURNING 
-                id, username, email, password_hash, display_name, bio, avatar_url,
-                role, is_active, email_verified,
-                email_verification_token, email_verification_expires_at,
-                password_reset_token, password_reset_expires_at,
-                social_twitter, social_github, website_url,
-                created_at, updated_at
-            "#,
-        )
-        .bind(&request.username)
-        .bind(&request.email)
-        .bind(&password_hash)
-        .bind(&request.display_name)
-        .fetch_one(&self.pool)
-        .await?;
+        self.insert(request, UserRole::Subscriber, false).await
+    }
 
-        Self::map_row_to_user(row)
+    async fn create_invited(
+        &self,
+        request: CreateUserRequest,
+        role: UserRole,
+    ) -> Result<User, UserError> {
+        self.insert(request, role, true).await
     }
 
     async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, UserError> {
@@ -271,12 +389,16 @@ impl UserRepository for SqlxUserRepository {
         Self::map_row_to_user(row)
     }
 
-    async fn verify_password(&self, user: &User, password: &str) -> Result<bool, UserError> {
-        Self::verify_password_hash(password, &user.password_hash)
+    async fn verify_password(
+        &self,
+        user: &User,
+        password: &str,
+    ) -> Result<PasswordCheck, UserError> {
+        self.hashing.verify(password, &user.password_hash)
     }
 
     async fn change_password(&self, id: Uuid, new_password: &str) -> Result<(), UserError> {
-        let password_hash = Self::hash_password(new_password)?;
+        let password_hash = self.hashing.hash(new_password)?;
 
         let result =
             sqlx::query("UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2")
@@ -292,13 +414,17 @@ impl UserRepository for SqlxUserRepository {
         Ok(())
     }
 
-    async fn set_email_verification_token(&self, id: Uuid, token: String) -> Result<(), UserError> {
+    async fn set_email_verification_token(
+        &self,
+        id: Uuid,
+        token_hash: String,
+    ) -> Result<(), UserError> {
         let expires_at = Timestamp::now() + Duration::from_secs(24 * 60 * 60); // 24 hours
 
         let result = sqlx::query(
             "UPDATE users SET email_verification_token = $1, email_verification_expires_at = $2 WHERE id = $3"
         )
-        .bind(&token)
+        .bind(&token_hash)
         .bind(SqlxTimestamp::from(expires_at))
         .bind(id)
         .execute(&self.pool)
@@ -311,7 +437,7 @@ impl UserRepository for SqlxUserRepository {
         Ok(())
     }
 
-    async fn verify_email(&self, token: &str) -> Result<Option<User>, UserError> {
+    async fn verify_email(&self, token_hash: &str) -> Result<Option<User>, UserError> {
         let row = sqlx::query(
             r#"
             UPDATE users 
@@ -331,7 +457,7 @@ impl UserRepository for SqlxUserRepository {
                 created_at, updated_at
             "#,
         )
-        .bind(token)
+        .bind(token_hash)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -341,14 +467,18 @@ impl UserRepository for SqlxUserRepository {
         }
     }
 
-    async fn set_password_reset_token(&self, id: Uuid, token: String) -> Result<(), UserError> {
+    async fn set_password_reset_token(
+        &self,
+        id: Uuid,
+        token_hash: String,
+    ) -> Result<(), UserError> {
         let expires_at = Timestamp::now() + Duration::from_secs(60 * 60); // 1 hour
 
         let result = sqlx::query(
             "UPDATE users SET password_reset_token = $1, password_reset_expires_at = $2 WHERE id = $3"
         )
-        .bind(&token)
-        .bind(expires_at.to_string()) // Convert to string for SQL
+        .bind(&token_hash)
+        .bind(SqlxTimestamp::from(expires_at))
         .bind(id)
         .execute(&self.pool)
         .await?;
@@ -362,10 +492,10 @@ impl UserRepository for SqlxUserRepository {
 
     async fn reset_password(
         &self,
-        token: &str,
+        token_hash: &str,
         new_password: &str,
     ) -> Result<Option<User>, UserError> {
-        let password_hash = Self::hash_password(new_password)?;
+        let password_hash = self.hashing.hash(new_password)?;
 
         let row = sqlx::query(
             r#"
@@ -387,7 +517,7 @@ impl UserRepository for SqlxUserRepository {
             "#,
         )
         .bind(&password_hash)
-        .bind(token)
+        .bind(token_hash)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -396,4 +526,471 @@ impl UserRepository for SqlxUserRepository {
             None => Ok(None),
         }
     }
+
+    async fn invalidate_sessions(&self, id: Uuid) -> Result<u64, UserError> {
+        // Moving the account to a new session generation also refuses any session the
+        // deletion below misses, such as one written back by a request still in flight
+        sqlx::query("UPDATE users SET session_generation = session_generation + 1 WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        // Sessions are stored by pavex_session_sqlx as a JSONB map keyed by the
+        // session constants, so the owner can be matched on the `user.id` entry.
+        let result = sqlx::query("DELETE FROM sessions WHERE state ->> 'user.id' = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn list(&self, query: &UserListQuery) -> Result<Vec<User>, UserError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT 
+                id, username, email, password_hash, display_name, bio, avatar_url,
+                role, is_active, email_verified,
+                email_verification_token, email_verification_expires_at,
+                password_reset_token, password_reset_expires_at,
+                social_twitter, social_github, website_url,
+                created_at, updated_at
+            FROM users
+            WHERE ($1::user_role IS NULL OR role = $1)
+            AND ($2::text IS NULL OR username ILIKE $2 OR email ILIKE $2)
+            AND id <> $5
+            ORDER BY created_at DESC, id
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(query.role)
+        .bind(Self::search_pattern(query))
+        .bind(i64::from(query.per_page()))
+        .bind(query.offset())
+        .bind(DELETED_USER_ID)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(Self::map_row_to_user).collect()
+    }
+
+    async fn count(&self, query: &UserListQuery) -> Result<i64, UserError> {
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM users
+            WHERE ($1::user_role IS NULL OR role = $1)
+            AND ($2::text IS NULL OR username ILIKE $2 OR email ILIKE $2)
+            AND id <> $3
+            "#,
+        )
+        .bind(query.role)
+        .bind(Self::search_pattern(query))
+        .bind(DELETED_USER_ID)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(total)
+    }
+
+    async fn update_role(&self, id: Uuid, role: UserRole) -> Result<User, UserError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE users 
+            SET role = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING 
+                id, username, email, password_hash, display_name, bio, avatar_url,
+                role, is_active, email_verified,
+                email_verification_token, email_verification_expires_at,
+                password_reset_token, password_reset_expires_at,
+                social_twitter, social_github, website_url,
+                created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(role)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(UserError::UserNotFound)?;
+
+        Self::map_row_to_user(row)
+    }
+
+    async fn set_active(&self, id: Uuid, is_active: bool) -> Result<User, UserError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE users 
+            SET is_active = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING 
+                id, username, email, password_hash, display_name, bio, avatar_url,
+                role, is_active, email_verified,
+                email_verification_token, email_verification_expires_at,
+                password_reset_token, password_reset_expires_at,
+                social_twitter, social_github, website_url,
+                created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(is_active)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(UserError::UserNotFound)?;
+
+        Self::map_row_to_user(row)
+    }
+
+    async fn force_verify_email(&self, id: Uuid) -> Result<User, UserError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE users 
+            SET 
+                email_verified = true,
+                email_verification_token = NULL,
+                email_verification_expires_at = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING 
+                id, username, email, password_hash, display_name, bio, avatar_url,
+                role, is_active, email_verified,
+                email_verification_token, email_verification_expires_at,
+                password_reset_token, password_reset_expires_at,
+                social_twitter, social_github, website_url,
+                created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(UserError::UserNotFound)?;
+
+        Self::map_row_to_user(row)
+    }
+
+    async fn login_locked_until(&self, key: &str) -> Result<Option<Timestamp>, UserError> {
+        let locked_until: Option<SqlxTimestamp> = sqlx::query_scalar(
+            "SELECT locked_until FROM login_failures WHERE key = $1 AND locked_until > NOW()",
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(locked_until.map(|t| t.into()))
+    }
+
+    async fn record_login_failure(
+        &self,
+        key: &str,
+        reset_after: Duration,
+    ) -> Result<u32, UserError> {
+        // Forget the keys of the same kind whose failures were reset and lockout is over,
+        // the table would otherwise keep a row for every key ever attacked
+        let kind = key.split_once(':').map_or("", |(kind, _)| kind);
+        sqlx::query(
+            r#"
+            DELETE FROM login_failures
+            WHERE key LIKE $1
+            AND last_failure_at < NOW() - make_interval(secs => $2)
+            AND (locked_until IS NULL OR locked_until <= NOW())
+            "#,
+        )
+        .bind(format!("{kind}:%"))
+        .bind(reset_after.as_secs_f64())
+        .execute(&self.pool)
+        .await?;
+
+        // Start counting afresh when the previous failure is older than `reset_after`
+        let failures: i32 = sqlx::query_scalar(
+            r#"
+            INSERT INTO login_failures (key, failures, last_failure_at)
+            VALUES ($1, 1, NOW())
+            ON CONFLICT (key) DO UPDATE SET
+                failures = CASE
+                    WHEN login_failures.last_failure_at < NOW() - make_interval(secs => $2)
+                    THEN 1
+                    ELSE login_failures.failures + 1
+                END,
+                last_failure_at = NOW()
+            RETURNING failures
+            "#,
+        )
+        .bind(key)
+        .bind(reset_after.as_secs_f64())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(failures.max(0) as u32)
+    }
+
+    async fn lock_login(&self, key: &str, until: Timestamp) -> Result<(), UserError> {
+        sqlx::query("UPDATE login_failures SET locked_until = $2 WHERE key = $1")
+            .bind(key)
+            .bind(SqlxTimestamp::from(until))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn clear_login_failures(&self, key: &str) -> Result<(), UserError> {
+        sqlx::query("DELETE FROM login_failures WHERE key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_totp(&self, id: Uuid) -> Result<Option<TotpCredential>, UserError> {
+        let row = sqlx::query(
+            "SELECT user_id, secret, enabled_at, last_used_step FROM user_totp WHERE user_id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| TotpCredential {
+            user_id: row.get("user_id"),
+            secret: row.get("secret"),
+            enabled_at: row
+                .get::<Option<SqlxTimestamp>, _>("enabled_at")
+                .map(|t| t.into()),
+            last_used_step: row.get("last_used_step"),
+        }))
+    }
+
+    async fn set_pending_totp(&self, id: Uuid, secret: &str) -> Result<(), UserError> {
+        // An enabled authenticator is never replaced, it has to be removed first
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_totp (user_id, secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET
+                secret = EXCLUDED.secret,
+                last_used_step = NULL,
+                created_at = NOW()
+            WHERE user_totp.enabled_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(secret)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserError::Validation {
+                message: "Two-factor authentication is already enabled".into(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn enable_totp(&self, id: Uuid, step: i64) -> Result<(), UserError> {
+        sqlx::query(
+            r#"
+            UPDATE user_totp
+            SET enabled_at = NOW(), last_used_step = $2
+            WHERE user_id = $1 AND enabled_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(step)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn use_totp_step(&self, id: Uuid, step: i64) -> Result<bool, UserError> {
+        // Only move forward, so a code which was already accepted cannot be replayed
+        let result = sqlx::query(
+            r#"
+            UPDATE user_totp
+            SET last_used_step = $2
+            WHERE user_id = $1
+            AND (last_used_step IS NULL OR last_used_step < $2)
+            "#,
+        )
+        .bind(id)
+        .bind(step)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete_totp(&self, id: Uuid) -> Result<(), UserError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM user_totp WHERE user_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        id: Uuid,
+        code_hashes: &[String],
+    ) -> Result<(), UserError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO user_recovery_codes (user_id, code_hash)
+            SELECT $1, code_hash FROM UNNEST($2::text[]) AS code_hash
+            "#,
+        )
+        .bind(id)
+        .bind(code_hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn use_recovery_code(&self, id: Uuid, code_hash: &str) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_recovery_codes
+            SET used_at = NOW()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn count_recovery_codes(&self, id: Uuid) -> Result<i64, UserError> {
+        let remaining: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM user_recovery_codes WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(remaining)
+    }
+
+    async fn create_email_change(
+        &self,
+        id: Uuid,
+        new_email: &str,
+        token_hash: &str,
+    ) -> Result<(), UserError> {
+        let expires_at = Timestamp::now() + Duration::from_secs(24 * 60 * 60); // 24 hours
+        let mut tx = self.pool.begin().await?;
+
+        // Only the latest request can be confirmed
+        sqlx::query("DELETE FROM email_changes WHERE user_id = $1 AND confirmed_at IS NULL")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO email_changes (user_id, old_email, new_email, confirm_token, confirm_expires_at)
+            SELECT id, email, $2, $3, $4 FROM users WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(new_email)
+        .bind(token_hash)
+        .bind(SqlxTimestamp::from(expires_at))
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(UserError::UserNotFound);
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn confirm_email_change(
+        &self,
+        token_hash: &str,
+        undo_token_hash: &str,
+    ) -> Result<Option<EmailChange>, UserError> {
+        let undo_expires_at = Timestamp::now() + Duration::from_secs(7 * 24 * 60 * 60); // 7 days
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query(
+            r#"
+            UPDATE email_changes
+            SET confirmed_at = NOW(), undo_token = $2, undo_expires_at = $3
+            WHERE confirm_token = $1
+            AND confirm_expires_at > NOW()
+            AND confirmed_at IS NULL
+            RETURNING user_id, old_email, new_email
+            "#,
+        )
+        .bind(token_hash)
+        .bind(undo_token_hash)
+        .bind(SqlxTimestamp::from(undo_expires_at))
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(change) = row.as_ref().map(Self::map_row_to_email_change) else {
+            return Ok(None);
+        };
+
+        Self::swap_email(&mut tx, change.user_id, &change.new_email).await?;
+        tx.commit().await?;
+
+        Ok(Some(change))
+    }
+
+    async fn undo_email_change(
+        &self,
+        undo_token_hash: &str,
+    ) -> Result<Option<EmailChange>, UserError> {
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query(
+            r#"
+            UPDATE email_changes
+            SET undone_at = NOW()
+            WHERE undo_token = $1
+            AND undo_expires_at > NOW()
+            AND undone_at IS NULL
+            RETURNING user_id, old_email, new_email
+            "#,
+        )
+        .bind(undo_token_hash)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(change) = row.as_ref().map(Self::map_row_to_email_change) else {
+            return Ok(None);
+        };
+
+        Self::swap_email(&mut tx, change.user_id, &change.old_email).await?;
+        // Whoever changed the address must not be able to change it again right away
+        sqlx::query("DELETE FROM email_changes WHERE user_id = $1 AND confirmed_at IS NULL")
+            .bind(change.user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(Some(change))
+    }
 }
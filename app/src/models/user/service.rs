@@ -2,15 +2,31 @@
 
 // dependencies
 use super::dto::{
-    ChangePasswordRequest, CreateUserRequest, LoginRequest, UpdateUserRequest, UserResponse,
-    UserSummary,
+    ChangeEmailRequest, ChangePasswordRequest, CreateUserRequest, DisableTwoFactorRequest,
+    EmailChangeTokenRequest, ForgotPasswordRequest, LoginRequest, RecoveryCodesResponse,
+    ResetPasswordRequest, TotpSetupResponse, TwoFactorCodeRequest, TwoFactorStatus,
+    UpdateUserRequest, UserListQuery, UserListResponse, UserResponse, UserSummary,
 };
+use super::entity::{EmailChange, User, UserRole};
 use super::error::UserError;
+use super::lockout::{account_lockout_key, ip_lockout_key};
+use super::password::PasswordCheck;
 use super::repository::UserRepository;
+use super::totp::{
+    RECOVERY_CODE_COUNT, generate_recovery_codes, generate_totp_secret, normalize_recovery_code,
+    otpauth_uri, qr_svg, verify_totp,
+};
+use crate::configuration::{
+    LockoutConfig, LockoutPolicy, RegistrationConfig, RegistrationMode, TwoFactorConfig,
+};
+use crate::sealing::SecretSealer;
+use crate::tokens::TokenHasher;
 use async_trait::async_trait;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use pavex::time::Timestamp;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_log_error::log_error;
 use uuid::Uuid;
 use validator::Validate;
 
@@ -18,9 +34,21 @@ use validator::Validate;
 #[async_trait]
 pub trait UserService: Send + Sync {
     async fn register(&self, request: CreateUserRequest) -> Result<UserResponse, UserError>;
-    async fn login(&self, request: LoginRequest) -> Result<UserSummary, UserError>;
+    async fn register_invited(
+        &self,
+        request: CreateUserRequest,
+        role: UserRole,
+    ) -> Result<UserResponse, UserError>;
+    async fn login(
+        &self,
+        request: LoginRequest,
+        client_ip: IpAddr,
+    ) -> Result<UserSummary, UserError>;
     async fn get_user(&self, id: Uuid) -> Result<UserResponse, UserError>;
     async fn get_user_summary(&self, id: Uuid) -> Result<UserSummary, UserError>;
+    /// The summary of an account signing in without a password, refused like a password login
+    /// when the account is disabled or its address is unverified.
+    async fn get_login_summary(&self, id: Uuid) -> Result<UserSummary, UserError>;
     async fn update_profile(
         &self,
         id: Uuid,
@@ -31,29 +59,242 @@ pub trait UserService: Send + Sync {
         id: Uuid,
         request: ChangePasswordRequest,
     ) -> Result<(), UserError>;
+    async fn confirm_password(&self, id: Uuid, password: &str) -> Result<(), UserError>;
     // New: email verification related operations
     async fn set_verification_token(&self, id: Uuid) -> Result<String, UserError>;
     async fn verify_email(&self, token: &str) -> Result<bool, UserError>;
-    async fn resend_verification(&self, email: &str) -> Result<(), UserError>;
+    async fn resend_verification(&self, email: &str) -> Result<Option<String>, UserError>;
+    // Password reset related operations
+    async fn request_password_reset(
+        &self,
+        request: ForgotPasswordRequest,
+    ) -> Result<Option<String>, UserError>;
+    async fn reset_password(&self, request: ResetPasswordRequest) -> Result<(), UserError>;
+    // Administration related operations, `actor` is the id of the acting admin
+    async fn list_users(&self, query: UserListQuery) -> Result<UserListResponse, UserError>;
+    async fn change_role(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        role: UserRole,
+    ) -> Result<UserResponse, UserError>;
+    async fn set_active(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        is_active: bool,
+    ) -> Result<UserResponse, UserError>;
+    async fn force_verify_email(&self, id: Uuid) -> Result<UserResponse, UserError>;
+    async fn unlock_user(&self, id: Uuid) -> Result<(), UserError>;
+    // Two-factor authentication related operations
+    async fn two_factor_status(&self, id: Uuid) -> Result<TwoFactorStatus, UserError>;
+    async fn begin_totp_setup(&self, id: Uuid) -> Result<TotpSetupResponse, UserError>;
+    async fn enable_totp(
+        &self,
+        id: Uuid,
+        request: TwoFactorCodeRequest,
+    ) -> Result<RecoveryCodesResponse, UserError>;
+    async fn verify_second_factor(
+        &self,
+        id: Uuid,
+        request: TwoFactorCodeRequest,
+    ) -> Result<UserSummary, UserError>;
+    async fn regenerate_recovery_codes(
+        &self,
+        id: Uuid,
+        request: TwoFactorCodeRequest,
+    ) -> Result<RecoveryCodesResponse, UserError>;
+    async fn disable_two_factor(
+        &self,
+        id: Uuid,
+        request: DisableTwoFactorRequest,
+    ) -> Result<(), UserError>;
+    async fn reset_two_factor(&self, id: Uuid) -> Result<(), UserError>;
+    // Email change related operations, tokens are returned to be mailed out
+    async fn request_email_change(
+        &self,
+        id: Uuid,
+        request: ChangeEmailRequest,
+    ) -> Result<String, UserError>;
+    async fn confirm_email_change(
+        &self,
+        request: EmailChangeTokenRequest,
+    ) -> Result<(EmailChange, String), UserError>;
+    async fn undo_email_change(
+        &self,
+        request: EmailChangeTokenRequest,
+    ) -> Result<EmailChange, UserError>;
 }
 
 pub struct UserServiceImpl {
     repository: Arc<dyn UserRepository>,
-    resend_tracker: Mutex<HashMap<String, Instant>>, // in-memory rate limiter (per-process)
+    lockout: LockoutConfig,
+    two_factor: TwoFactorConfig,
+    registration: RegistrationConfig,
+    tokens: TokenHasher,
+    sealer: SecretSealer,
 }
 
 impl UserServiceImpl {
-    pub fn new(repository: Arc<dyn UserRepository>) -> Self {
+    pub fn new(
+        repository: Arc<dyn UserRepository>,
+        tokens: TokenHasher,
+        sealer: SecretSealer,
+    ) -> Self {
+        Self::with_lockout(repository, LockoutConfig::default(), tokens, sealer)
+    }
+
+    pub fn with_lockout(
+        repository: Arc<dyn UserRepository>,
+        lockout: LockoutConfig,
+        tokens: TokenHasher,
+        sealer: SecretSealer,
+    ) -> Self {
         Self {
             repository,
-            resend_tracker: Mutex::new(HashMap::new()),
+            lockout,
+            two_factor: TwoFactorConfig::default(),
+            registration: RegistrationConfig::default(),
+            tokens,
+            sealer,
+        }
+    }
+
+    pub fn with_two_factor(self, two_factor: TwoFactorConfig) -> Self {
+        Self { two_factor, ..self }
+    }
+
+    pub fn with_registration(self, registration: RegistrationConfig) -> Self {
+        Self {
+            registration,
+            ..self
+        }
+    }
+
+    // time left until `key` may attempt to log in again, if it is locked out
+    async fn lockout_remaining(&self, key: &str) -> Result<Option<Duration>, UserError> {
+        let Some(locked_until) = self.repository.login_locked_until(key).await? else {
+            return Ok(None);
+        };
+        let remaining = Timestamp::now().duration_until(locked_until);
+        // Round up, so a client retrying after the advertised delay is let through
+        let secs = remaining.as_secs() + i64::from(remaining.subsec_nanos() > 0);
+        Ok(u64::try_from(secs)
+            .ok()
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs))
+    }
+
+    // count a failed login against `key`, locking it out once the policy says so
+    async fn record_failure(&self, key: &str, policy: &LockoutPolicy) -> Result<(), UserError> {
+        let failures = self
+            .repository
+            .record_login_failure(key, policy.reset_after)
+            .await?;
+        if let Some(delay) = policy.delay_after(failures) {
+            self.repository
+                .lock_login(key, Timestamp::now() + delay)
+                .await?;
+            tracing::info!(
+                lockout.key = key,
+                lockout.failures = failures,
+                lockout.delay_secs = delay.as_secs(),
+                "Login locked after repeated failures"
+            );
+        }
+        Ok(())
+    }
+
+    // check a TOTP code or, failing that, an unused recovery code against the enabled authenticator
+    async fn check_second_factor(&self, id: Uuid, code: &str) -> Result<(), UserError> {
+        let credential = self
+            .repository
+            .find_totp(id)
+            .await?
+            .filter(|credential| credential.is_enabled())
+            .ok_or(UserError::InvalidSecondFactor)?;
+        let secret = self.open_totp_secret(id, &credential.secret)?;
+
+        let now = Timestamp::now().as_second();
+        let accepted = match verify_totp(&secret, code, now) {
+            Some(step) => self.repository.use_totp_step(id, step).await?,
+            None => {
+                let used = self
+                    .repository
+                    .use_recovery_code(id, &self.hash_recovery_code(code))
+                    .await?;
+                if used {
+                    tracing::info!(user.id = %id, "Recovery code used");
+                }
+                used
+            }
+        };
+
+        if accepted {
+            Ok(())
+        } else {
+            Err(UserError::InvalidSecondFactor)
         }
     }
+
+    // recovery codes are few and short, so they get the same keyed hash as mailed tokens
+    fn hash_recovery_code(&self, code: &str) -> String {
+        self.tokens.hash(&normalize_recovery_code(code))
+    }
+
+    // whether an account may sign in at all, whichever way it proves who it is
+    fn check_may_sign_in(user: &User) -> Result<(), UserError> {
+        if !user.is_active {
+            return Err(UserError::InvalidCredentials);
+        }
+        if !user.email_verified {
+            return Err(UserError::EmailNotVerified);
+        }
+        Ok(())
+    }
+
+    // decrypt a stored authenticator secret, which only opens with the configured key
+    fn open_totp_secret(&self, id: Uuid, sealed: &str) -> Result<String, UserError> {
+        self.sealer.open(sealed, id).ok_or_else(|| {
+            tracing::error!(user.id = %id, "Stored authenticator secret does not decrypt");
+            UserError::InvalidSecondFactor
+        })
+    }
+
+    // replace every recovery code of the user, returning the new ones in clear text
+    async fn issue_recovery_codes(&self, id: Uuid) -> Result<RecoveryCodesResponse, UserError> {
+        let recovery_codes = generate_recovery_codes(RECOVERY_CODE_COUNT);
+        let code_hashes: Vec<String> = recovery_codes
+            .iter()
+            .map(|code| self.hash_recovery_code(code))
+            .collect();
+        self.repository
+            .replace_recovery_codes(id, &code_hashes)
+            .await?;
+
+        Ok(RecoveryCodesResponse { recovery_codes })
+    }
+
+    // admins cannot lock themselves out of the admin area
+    fn forbid_self(actor: Uuid, id: Uuid, message: &str) -> Result<(), UserError> {
+        if actor == id {
+            return Err(UserError::Validation {
+                message: message.into(),
+            });
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl UserService for UserServiceImpl {
     async fn register(&self, request: CreateUserRequest) -> Result<UserResponse, UserError> {
+        match self.registration.mode {
+            RegistrationMode::Open => {}
+            RegistrationMode::InviteOnly => return Err(UserError::InvitationRequired),
+            RegistrationMode::Closed => return Err(UserError::RegistrationClosed),
+        }
+
         // Validate input
         request.validate().map_err(|e| UserError::Validation {
             message: format!("Validation failed: {e}"),
@@ -65,36 +306,90 @@ impl UserService for UserServiceImpl {
         Ok(UserResponse::from(user))
     }
 
-    async fn login(&self, request: LoginRequest) -> Result<UserSummary, UserError> {
+    async fn register_invited(
+        &self,
+        request: CreateUserRequest,
+        role: UserRole,
+    ) -> Result<UserResponse, UserError> {
+        // Closed means closed, even to invitations sent before
+        if self.registration.mode == RegistrationMode::Closed {
+            return Err(UserError::RegistrationClosed);
+        }
+
+        // Validate input
+        request.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let user = self.repository.create_invited(request, role).await?;
+        tracing::info!(user.id = %user.id, role = ?role, "Invited user registered");
+
+        Ok(UserResponse::from(user))
+    }
+
+    async fn login(
+        &self,
+        request: LoginRequest,
+        client_ip: IpAddr,
+    ) -> Result<UserSummary, UserError> {
         // Validate input
         request.validate().map_err(|e| UserError::Validation {
             message: format!("Validation failed: {e}"),
         })?;
 
+        // A locked out client address says nothing about the account, so it is safe to reveal
+        let ip_key = ip_lockout_key(client_ip);
+        if let Some(retry_after) = self.lockout_remaining(&ip_key).await? {
+            return Err(UserError::AccountLocked { retry_after });
+        }
+
         // Find user by username or email
         let user = self
             .repository
             .find_by_username_or_email(&request.username_or_email)
-            .await?
-            .ok_or(UserError::InvalidCredentials)?;
-
-        // Verify password
-        let is_valid = self
-            .repository
-            .verify_password(&user, &request.password)
             .await?;
-        if !is_valid {
+        let account_key = match &user {
+            Some(user) => account_lockout_key(&user.id.to_string()),
+            None => account_lockout_key(&request.username_or_email),
+        };
+
+        // A locked account answers like a wrong password, without checking it,
+        // so neither its existence nor a correct guess is revealed
+        if self.lockout_remaining(&account_key).await?.is_some() {
             return Err(UserError::InvalidCredentials);
         }
 
-        // Check if user is active and verified
-        if !user.is_active {
+        // Verify password
+        let check = match &user {
+            Some(user) => {
+                self.repository
+                    .verify_password(user, &request.password)
+                    .await?
+            }
+            None => PasswordCheck::Invalid,
+        };
+        let Some(user) = user.filter(|_| check.is_valid()) else {
+            self.record_failure(&account_key, &self.lockout.account)
+                .await?;
+            self.record_failure(&ip_key, &self.lockout.ip).await?;
             return Err(UserError::InvalidCredentials);
-        }
-        if !user.email_verified {
-            return Err(UserError::EmailNotVerified);
+        };
+        self.repository.clear_login_failures(&account_key).await?;
+
+        // The plain password is only at hand now, to move the hash onto the current parameters
+        if check == PasswordCheck::Outdated {
+            match self
+                .repository
+                .change_password(user.id, &request.password)
+                .await
+            {
+                Ok(()) => tracing::info!(user.id = %user.id, "Password rehashed"),
+                Err(e) => log_error!(e, "Failed to rehash a password"),
+            }
         }
 
+        Self::check_may_sign_in(&user)?;
+
         Ok(UserSummary {
             id: user.id,
             username: user.username,
@@ -124,6 +419,17 @@ impl UserService for UserServiceImpl {
         Ok(UserSummary::from(user))
     }
 
+    async fn get_login_summary(&self, id: Uuid) -> Result<UserSummary, UserError> {
+        let user = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::InvalidCredentials)?;
+        Self::check_may_sign_in(&user)?;
+
+        Ok(UserSummary::from(user))
+    }
+
     async fn update_profile(
         &self,
         id: Uuid,
@@ -161,7 +467,8 @@ impl UserService for UserServiceImpl {
         let is_valid = self
             .repository
             .verify_password(&user, &request.current_password)
-            .await?;
+            .await?
+            .is_valid();
         if !is_valid {
             return Err(UserError::InvalidCredentials);
         }
@@ -174,45 +481,454 @@ impl UserService for UserServiceImpl {
         Ok(())
     }
 
+    async fn confirm_password(&self, id: Uuid, password: &str) -> Result<(), UserError> {
+        let user = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::UserNotFound)?;
+        if !self
+            .repository
+            .verify_password(&user, password)
+            .await?
+            .is_valid()
+        {
+            return Err(UserError::InvalidCredentials);
+        }
+
+        Ok(())
+    }
+
     async fn set_verification_token(&self, id: Uuid) -> Result<String, UserError> {
-        let token = Uuid::new_v4().to_string();
+        let token = TokenHasher::generate();
         self.repository
-            .set_email_verification_token(id, token.clone())
+            .set_email_verification_token(id, self.tokens.hash(&token))
             .await?;
         Ok(token)
     }
 
     async fn verify_email(&self, token: &str) -> Result<bool, UserError> {
-        let user = self.repository.verify_email(token).await?;
+        let user = self
+            .repository
+            .verify_email(&self.tokens.hash(token))
+            .await?;
         Ok(user.is_some())
     }
 
-    async fn resend_verification(&self, email: &str) -> Result<(), UserError> {
-        // In-memory rate-limit: block re-requests within 60 seconds
-        {
-            let mut guard = self
-                .resend_tracker
-                .lock()
-                .expect("resend_tracker mutex poisoned");
-            let key = email.to_lowercase();
-            if let Some(last) = guard.get(&key) {
-                if last.elapsed() < Duration::from_secs(60) {
-                    return Err(UserError::Validation {
-                        message: "Please wait before requesting another verification email".into(),
-                    });
-                }
-            }
-            guard.insert(key, Instant::now());
-        }
-
+    async fn resend_verification(&self, email: &str) -> Result<Option<String>, UserError> {
         if let Some(user) = self.repository.find_by_email(email).await? {
             if user.email_verified {
-                return Ok(());
+                return Ok(None);
             }
             // Always issue a new token on resend
-            let _ = self.set_verification_token(user.id).await?;
+            let token = self.set_verification_token(user.id).await?;
+            return Ok(Some(token));
         }
         // Do not reveal if user exists
+        Ok(None)
+    }
+
+    async fn request_password_reset(
+        &self,
+        request: ForgotPasswordRequest,
+    ) -> Result<Option<String>, UserError> {
+        // Validate input
+        request.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        // Do not reveal if user exists, or whether the account is usable
+        let Some(user) = self.repository.find_by_email(&request.email).await? else {
+            return Ok(None);
+        };
+        if !user.is_active {
+            return Ok(None);
+        }
+
+        let token = TokenHasher::generate();
+        self.repository
+            .set_password_reset_token(user.id, self.tokens.hash(&token))
+            .await?;
+        tracing::info!(user.id = %user.id, "Password reset requested");
+
+        Ok(Some(token))
+    }
+
+    async fn reset_password(&self, request: ResetPasswordRequest) -> Result<(), UserError> {
+        // Validate input
+        request.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let user = self
+            .repository
+            .reset_password(&self.tokens.hash(&request.token), &request.new_password)
+            .await?
+            .ok_or_else(|| UserError::Validation {
+                message: "Invalid or expired password reset link".into(),
+            })?;
+
+        // Anyone holding a session for this account must sign in again
+        let invalidated = self.repository.invalidate_sessions(user.id).await?;
+        tracing::info!(
+            user.id = %user.id,
+            sessions.invalidated = invalidated,
+            "Password reset completed"
+        );
+
         Ok(())
     }
+
+    async fn list_users(&self, query: UserListQuery) -> Result<UserListResponse, UserError> {
+        // Validate input
+        query.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let users = self.repository.list(&query).await?;
+        let total = self.repository.count(&query).await?;
+
+        Ok(UserListResponse {
+            users: users.into_iter().map(UserResponse::from).collect(),
+            total,
+            page: query.page(),
+            per_page: query.per_page(),
+        })
+    }
+
+    async fn change_role(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        role: UserRole,
+    ) -> Result<UserResponse, UserError> {
+        Self::forbid_self(actor, id, "You cannot change your own role")?;
+
+        // Sessions read the role from the account, so signed-in devices follow without a rewrite
+        let user = self.repository.update_role(id, role).await?;
+        tracing::info!(
+            user.id = %id,
+            admin.id = %actor,
+            role = ?role,
+            "User role changed"
+        );
+
+        Ok(user.into())
+    }
+
+    async fn set_active(
+        &self,
+        actor: Uuid,
+        id: Uuid,
+        is_active: bool,
+    ) -> Result<UserResponse, UserError> {
+        Self::forbid_self(actor, id, "You cannot deactivate your own account")?;
+
+        let user = self.repository.set_active(id, is_active).await?;
+        if !is_active {
+            // A deactivated account must not keep any signed-in session
+            let invalidated = self.repository.invalidate_sessions(id).await?;
+            tracing::info!(
+                user.id = %id,
+                admin.id = %actor,
+                sessions.invalidated = invalidated,
+                "User deactivated"
+            );
+        } else {
+            tracing::info!(user.id = %id, admin.id = %actor, "User activated");
+        }
+
+        Ok(user.into())
+    }
+
+    async fn force_verify_email(&self, id: Uuid) -> Result<UserResponse, UserError> {
+        let user = self.repository.force_verify_email(id).await?;
+        tracing::info!(user.id = %id, "Email verified by an admin");
+
+        Ok(user.into())
+    }
+
+    async fn unlock_user(&self, id: Uuid) -> Result<(), UserError> {
+        // Make sure the account exists, so the admin gets a 404 for a stale id
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::UserNotFound)?;
+        self.repository
+            .clear_login_failures(&account_lockout_key(&id.to_string()))
+            .await?;
+        tracing::info!(user.id = %id, "Login lockout cleared by an admin");
+
+        Ok(())
+    }
+
+    async fn two_factor_status(&self, id: Uuid) -> Result<TwoFactorStatus, UserError> {
+        let user = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::UserNotFound)?;
+        let enabled = self
+            .repository
+            .find_totp(id)
+            .await?
+            .is_some_and(|credential| credential.is_enabled());
+        let recovery_codes_remaining = if enabled {
+            self.repository.count_recovery_codes(id).await?
+        } else {
+            0
+        };
+
+        Ok(TwoFactorStatus {
+            enabled,
+            required: self.two_factor.required_roles.contains(&user.role),
+            recovery_codes_remaining,
+        })
+    }
+
+    async fn begin_totp_setup(&self, id: Uuid) -> Result<TotpSetupResponse, UserError> {
+        let user = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::UserNotFound)?;
+
+        // Every new attempt gets a fresh secret, until one is confirmed with a code
+        let secret = generate_totp_secret();
+        self.repository
+            .set_pending_totp(id, &self.sealer.seal(&secret, id))
+            .await?;
+
+        let otpauth_uri = otpauth_uri(&self.two_factor.issuer, &user.email, &secret);
+        let qr_svg = qr_svg(&otpauth_uri);
+
+        Ok(TotpSetupResponse {
+            secret,
+            otpauth_uri,
+            qr_svg,
+        })
+    }
+
+    async fn enable_totp(
+        &self,
+        id: Uuid,
+        request: TwoFactorCodeRequest,
+    ) -> Result<RecoveryCodesResponse, UserError> {
+        // Validate input
+        request.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let credential = self
+            .repository
+            .find_totp(id)
+            .await?
+            .filter(|credential| !credential.is_enabled())
+            .ok_or_else(|| UserError::Validation {
+                message: "Start two-factor authentication setup first".into(),
+            })?;
+
+        // The first code proves the authenticator was set up correctly
+        let secret = self.open_totp_secret(id, &credential.secret)?;
+        let now = Timestamp::now().as_second();
+        let step =
+            verify_totp(&secret, &request.code, now).ok_or(UserError::InvalidSecondFactor)?;
+        self.repository.enable_totp(id, step).await?;
+        let recovery_codes = self.issue_recovery_codes(id).await?;
+        tracing::info!(user.id = %id, "Two-factor authentication enabled");
+
+        Ok(recovery_codes)
+    }
+
+    async fn verify_second_factor(
+        &self,
+        id: Uuid,
+        request: TwoFactorCodeRequest,
+    ) -> Result<UserSummary, UserError> {
+        // Validate input
+        request.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let user = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::InvalidCredentials)?;
+        // The account may have been deactivated while the login was pending
+        if !user.is_active {
+            return Err(UserError::InvalidCredentials);
+        }
+
+        self.check_second_factor(id, &request.code).await?;
+
+        Ok(UserSummary::from(user))
+    }
+
+    async fn regenerate_recovery_codes(
+        &self,
+        id: Uuid,
+        request: TwoFactorCodeRequest,
+    ) -> Result<RecoveryCodesResponse, UserError> {
+        // Validate input
+        request.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        self.check_second_factor(id, &request.code).await?;
+        let recovery_codes = self.issue_recovery_codes(id).await?;
+        tracing::info!(user.id = %id, "Recovery codes regenerated");
+
+        Ok(recovery_codes)
+    }
+
+    async fn disable_two_factor(
+        &self,
+        id: Uuid,
+        request: DisableTwoFactorRequest,
+    ) -> Result<(), UserError> {
+        // Validate input
+        request.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let user = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::UserNotFound)?;
+        if self.two_factor.required_roles.contains(&user.role) {
+            return Err(UserError::Validation {
+                message: "Two-factor authentication is required for your role".into(),
+            });
+        }
+
+        // Both factors are needed to remove the second one
+        let is_valid = self
+            .repository
+            .verify_password(&user, &request.password)
+            .await?
+            .is_valid();
+        if !is_valid {
+            return Err(UserError::InvalidCredentials);
+        }
+        self.check_second_factor(id, &request.code).await?;
+
+        self.repository.delete_totp(id).await?;
+        tracing::info!(user.id = %id, "Two-factor authentication disabled");
+
+        Ok(())
+    }
+
+    async fn reset_two_factor(&self, id: Uuid) -> Result<(), UserError> {
+        // Make sure the account exists, so the admin gets a 404 for a stale id
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::UserNotFound)?;
+        self.repository.delete_totp(id).await?;
+        tracing::info!(user.id = %id, "Two-factor authentication reset by an admin");
+
+        Ok(())
+    }
+
+    async fn request_email_change(
+        &self,
+        id: Uuid,
+        request: ChangeEmailRequest,
+    ) -> Result<String, UserError> {
+        // Validate input
+        request.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let user = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::UserNotFound)?;
+        if !self
+            .repository
+            .verify_password(&user, &request.password)
+            .await?
+            .is_valid()
+        {
+            return Err(UserError::InvalidCredentials);
+        }
+        if request.new_email == user.email {
+            return Err(UserError::Validation {
+                message: "The new email address is the current one".into(),
+            });
+        }
+        // Checked again on confirmation, the address may be taken meanwhile
+        if self
+            .repository
+            .find_by_email(&request.new_email)
+            .await?
+            .is_some()
+        {
+            return Err(UserError::EmailExists);
+        }
+
+        let token = TokenHasher::generate();
+        self.repository
+            .create_email_change(id, &request.new_email, &self.tokens.hash(&token))
+            .await?;
+        tracing::info!(user.id = %id, "Email change requested");
+
+        Ok(token)
+    }
+
+    async fn confirm_email_change(
+        &self,
+        request: EmailChangeTokenRequest,
+    ) -> Result<(EmailChange, String), UserError> {
+        // Validate input
+        request.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let undo_token = TokenHasher::generate();
+        let change = self
+            .repository
+            .confirm_email_change(
+                &self.tokens.hash(&request.token),
+                &self.tokens.hash(&undo_token),
+            )
+            .await?
+            .ok_or_else(|| UserError::Validation {
+                message: "Invalid or expired email change link".into(),
+            })?;
+        tracing::info!(user.id = %change.user_id, "Email changed");
+
+        Ok((change, undo_token))
+    }
+
+    async fn undo_email_change(
+        &self,
+        request: EmailChangeTokenRequest,
+    ) -> Result<EmailChange, UserError> {
+        // Validate input
+        request.validate().map_err(|e| UserError::Validation {
+            message: format!("Validation failed: {e}"),
+        })?;
+
+        let change = self
+            .repository
+            .undo_email_change(&self.tokens.hash(&request.token))
+            .await?
+            .ok_or_else(|| UserError::Validation {
+                message: "Invalid or expired undo link".into(),
+            })?;
+
+        // The change may come from someone who took over the account, sign everyone out
+        let invalidated = self.repository.invalidate_sessions(change.user_id).await?;
+        tracing::info!(
+            user.id = %change.user_id,
+            sessions.invalidated = invalidated,
+            "Email change undone"
+        );
+
+        Ok(change)
+    }
 }
@@ -2,11 +2,21 @@
 
 #[cfg(test)]
 mod tests {
+    use crate::configuration::{
+        LockoutConfig, LockoutPolicy, PasswordAlgorithm, PasswordHashingConfig, RegistrationConfig,
+        RegistrationMode, TokenHashingConfig, TwoFactorConfig,
+    };
     use crate::models::user::*;
+    use crate::sealing::SecretSealer;
+    use crate::tokens::TokenHasher;
     use async_trait::async_trait;
+    use data_encoding::BASE32_NOPAD;
     use pavex::time::Timestamp;
-    use std::collections::HashMap;
+    use secrecy::SecretString;
+    use std::collections::{HashMap, HashSet};
+    use std::net::{IpAddr, Ipv4Addr};
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
     use uuid::Uuid;
     use validator::Validate;
 
@@ -16,6 +26,21 @@ mod tests {
         users_by_username: Arc<Mutex<HashMap<String, Uuid>>>,
         users_by_email: Arc<Mutex<HashMap<String, Uuid>>>,
         password_verifications: Arc<Mutex<HashMap<Uuid, String>>>,
+        outdated_hashes: Arc<Mutex<HashSet<Uuid>>>,
+        reset_tokens: Arc<Mutex<HashMap<String, Uuid>>>,
+        invalidated_sessions: Arc<Mutex<Vec<Uuid>>>,
+        login_failures: Arc<Mutex<HashMap<String, (u32, Option<Timestamp>)>>>,
+        totp: Arc<Mutex<HashMap<Uuid, TotpCredential>>>,
+        recovery_codes: Arc<Mutex<HashMap<Uuid, Vec<(String, bool)>>>>,
+        email_changes: Arc<Mutex<Vec<MockEmailChange>>>,
+    }
+
+    // An email change as kept by the mock, with the hashes of its tokens
+    struct MockEmailChange {
+        change: EmailChange,
+        confirm_token: String,
+        undo_token: Option<String>,
+        undone: bool,
     }
 
     impl MockUserRepository {
@@ -25,6 +50,13 @@ mod tests {
                 users_by_username: Arc::new(Mutex::new(HashMap::new())),
                 users_by_email: Arc::new(Mutex::new(HashMap::new())),
                 password_verifications: Arc::new(Mutex::new(HashMap::new())),
+                outdated_hashes: Arc::new(Mutex::new(HashSet::new())),
+                reset_tokens: Arc::new(Mutex::new(HashMap::new())),
+                invalidated_sessions: Arc::new(Mutex::new(Vec::new())),
+                login_failures: Arc::new(Mutex::new(HashMap::new())),
+                totp: Arc::new(Mutex::new(HashMap::new())),
+                recovery_codes: Arc::new(Mutex::new(HashMap::new())),
+                email_changes: Arc::new(Mutex::new(Vec::new())),
             }
         }
 
@@ -43,12 +75,78 @@ mod tests {
             verifications.insert(user_id, password);
         }
 
+        // pretend the password of `user_id` was hashed with other parameters than the current ones
+        pub fn mark_hash_outdated(&self, user_id: Uuid) {
+            self.outdated_hashes.lock().unwrap().insert(user_id);
+        }
+
+        pub fn hash_outdated(&self, user_id: Uuid) -> bool {
+            self.outdated_hashes.lock().unwrap().contains(&user_id)
+        }
+
         pub fn mark_email_verified(&self, user_id: Uuid) {
             let mut users = self.users.lock().unwrap();
             if let Some(user) = users.get_mut(&user_id) {
                 user.email_verified = true;
             }
         }
+
+        pub fn reset_token_hash_for(&self, user_id: Uuid) -> Option<String> {
+            let tokens = self.reset_tokens.lock().unwrap();
+            tokens
+                .iter()
+                .find(|(_, id)| **id == user_id)
+                .map(|(token_hash, _)| token_hash.clone())
+        }
+
+        pub fn sessions_invalidated_for(&self, user_id: Uuid) -> bool {
+            self.invalidated_sessions.lock().unwrap().contains(&user_id)
+        }
+
+        pub fn stored_totp_secret(&self, user_id: Uuid) -> Option<String> {
+            let totp = self.totp.lock().unwrap();
+            totp.get(&user_id)
+                .map(|credential| credential.secret.clone())
+        }
+
+        // the secret as the authenticator app has it, sealed with the test key
+        pub fn totp_secret_for(&self, user_id: Uuid) -> Option<String> {
+            let sealed = self.stored_totp_secret(user_id)?;
+            sealer().open(&sealed, user_id)
+        }
+
+        pub fn recovery_code_hashes(&self, user_id: Uuid) -> Vec<String> {
+            let recovery_codes = self.recovery_codes.lock().unwrap();
+            recovery_codes
+                .get(&user_id)
+                .map(|codes| codes.iter().map(|(hash, _)| hash.clone()).collect())
+                .unwrap_or_default()
+        }
+
+        // point the account at `email`, as the unique index on `users.email` would allow
+        fn swap_email(&self, id: Uuid, email: &str) -> Result<(), UserError> {
+            let mut users = self.users.lock().unwrap();
+            let mut by_email = self.users_by_email.lock().unwrap();
+            if by_email.get(email).is_some_and(|owner| *owner != id) {
+                return Err(UserError::EmailExists);
+            }
+            let user = users.get_mut(&id).ok_or(UserError::UserNotFound)?;
+            by_email.remove(&user.email);
+            by_email.insert(email.to_string(), id);
+            user.email = email.to_string();
+            user.email_verified = true;
+            Ok(())
+        }
+
+        fn matches_query(user: &User, query: &UserListQuery) -> bool {
+            let role_matches = query.role.is_none_or(|role| user.role == role);
+            let search_matches = query.search_term().is_none_or(|term| {
+                let term = term.to_lowercase();
+                user.username.to_lowercase().contains(&term)
+                    || user.email.to_lowercase().contains(&term)
+            });
+            role_matches && search_matches
+        }
     }
 
     #[async_trait]
@@ -98,6 +196,18 @@ mod tests {
             Ok(user)
         }
 
+        async fn create_invited(
+            &self,
+            request: CreateUserRequest,
+            role: UserRole,
+        ) -> Result<User, UserError> {
+            let mut user = self.create(request).await?;
+            user.role = role;
+            user.email_verified = true;
+            self.insert_user(user.clone());
+            Ok(user)
+        }
+
         async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, UserError> {
             let users = self.users.lock().unwrap();
             Ok(users.get(&id).cloned())
@@ -166,13 +276,22 @@ mod tests {
             }
         }
 
-        async fn verify_password(&self, user: &User, password: &str) -> Result<bool, UserError> {
+        async fn verify_password(
+            &self,
+            user: &User,
+            password: &str,
+        ) -> Result<PasswordCheck, UserError> {
             let verifications = self.password_verifications.lock().unwrap();
-            if let Some(stored_password) = verifications.get(&user.id) {
-                Ok(stored_password == password)
+            let matches = if let Some(stored_password) = verifications.get(&user.id) {
+                stored_password == password
             } else {
-                Ok(password == "correct_password") // Default mock behavior
-            }
+                password == "correct_password" // Default mock behavior
+            };
+            Ok(match matches {
+                false => PasswordCheck::Invalid,
+                true if self.hash_outdated(user.id) => PasswordCheck::Outdated,
+                true => PasswordCheck::Valid,
+            })
         }
 
         async fn change_password(&self, id: Uuid, new_password: &str) -> Result<(), UserError> {
@@ -180,6 +299,7 @@ mod tests {
             if users.contains_key(&id) {
                 let mut verifications = self.password_verifications.lock().unwrap();
                 verifications.insert(id, new_password.to_string());
+                self.outdated_hashes.lock().unwrap().remove(&id);
                 Ok(())
             } else {
                 Err(UserError::UserNotFound)
@@ -189,29 +309,295 @@ mod tests {
         async fn set_email_verification_token(
             &self,
             _id: Uuid,
-            _token: String,
+            _token_hash: String,
         ) -> Result<(), UserError> {
             Ok(()) // Mock implementation
         }
 
-        async fn verify_email(&self, _token: &str) -> Result<Option<User>, UserError> {
+        async fn verify_email(&self, _token_hash: &str) -> Result<Option<User>, UserError> {
             Ok(None) // Mock implementation
         }
 
         async fn set_password_reset_token(
             &self,
-            _id: Uuid,
-            _token: String,
+            id: Uuid,
+            token_hash: String,
         ) -> Result<(), UserError> {
-            Ok(()) // Mock implementation
+            let users = self.users.lock().unwrap();
+            if !users.contains_key(&id) {
+                return Err(UserError::UserNotFound);
+            }
+            let mut tokens = self.reset_tokens.lock().unwrap();
+            tokens.retain(|_, user_id| *user_id != id);
+            tokens.insert(token_hash, id);
+            Ok(())
         }
 
         async fn reset_password(
             &self,
-            _token: &str,
-            _new_password: &str,
+            token_hash: &str,
+            new_password: &str,
         ) -> Result<Option<User>, UserError> {
-            Ok(None) // Mock implementation
+            let Some(id) = self.reset_tokens.lock().unwrap().remove(token_hash) else {
+                return Ok(None);
+            };
+            self.change_password(id, new_password).await?;
+            self.find_by_id(id).await
+        }
+
+        async fn invalidate_sessions(&self, id: Uuid) -> Result<u64, UserError> {
+            self.invalidated_sessions.lock().unwrap().push(id);
+            Ok(1)
+        }
+
+        async fn list(&self, query: &UserListQuery) -> Result<Vec<User>, UserError> {
+            let users = self.users.lock().unwrap();
+            let mut matching: Vec<User> = users
+                .values()
+                .filter(|user| Self::matches_query(user, query))
+                .cloned()
+                .collect();
+            matching.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.id.cmp(&b.id)));
+            Ok(matching
+                .into_iter()
+                .skip(query.offset() as usize)
+                .take(query.per_page() as usize)
+                .collect())
+        }
+
+        async fn count(&self, query: &UserListQuery) -> Result<i64, UserError> {
+            let users = self.users.lock().unwrap();
+            Ok(users
+                .values()
+                .filter(|user| Self::matches_query(user, query))
+                .count() as i64)
+        }
+
+        async fn update_role(&self, id: Uuid, role: UserRole) -> Result<User, UserError> {
+            let mut users = self.users.lock().unwrap();
+            let user = users.get_mut(&id).ok_or(UserError::UserNotFound)?;
+            user.role = role;
+            Ok(user.clone())
+        }
+
+        async fn set_active(&self, id: Uuid, is_active: bool) -> Result<User, UserError> {
+            let mut users = self.users.lock().unwrap();
+            let user = users.get_mut(&id).ok_or(UserError::UserNotFound)?;
+            user.is_active = is_active;
+            Ok(user.clone())
+        }
+
+        async fn force_verify_email(&self, id: Uuid) -> Result<User, UserError> {
+            let mut users = self.users.lock().unwrap();
+            let user = users.get_mut(&id).ok_or(UserError::UserNotFound)?;
+            user.email_verified = true;
+            user.email_verification_token = None;
+            user.email_verification_expires_at = None;
+            Ok(user.clone())
+        }
+
+        async fn login_locked_until(&self, key: &str) -> Result<Option<Timestamp>, UserError> {
+            let failures = self.login_failures.lock().unwrap();
+            Ok(failures
+                .get(key)
+                .and_then(|(_, locked_until)| *locked_until)
+                .filter(|locked_until| *locked_until > Timestamp::now()))
+        }
+
+        async fn record_login_failure(
+            &self,
+            key: &str,
+            _reset_after: Duration,
+        ) -> Result<u32, UserError> {
+            let mut failures = self.login_failures.lock().unwrap();
+            let entry = failures.entry(key.to_string()).or_insert((0, None));
+            entry.0 += 1;
+            Ok(entry.0)
+        }
+
+        async fn lock_login(&self, key: &str, until: Timestamp) -> Result<(), UserError> {
+            let mut failures = self.login_failures.lock().unwrap();
+            if let Some(entry) = failures.get_mut(key) {
+                entry.1 = Some(until);
+            }
+            Ok(())
+        }
+
+        async fn clear_login_failures(&self, key: &str) -> Result<(), UserError> {
+            self.login_failures.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        async fn find_totp(&self, id: Uuid) -> Result<Option<TotpCredential>, UserError> {
+            Ok(self.totp.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn set_pending_totp(&self, id: Uuid, secret: &str) -> Result<(), UserError> {
+            let mut totp = self.totp.lock().unwrap();
+            if totp
+                .get(&id)
+                .is_some_and(|credential| credential.is_enabled())
+            {
+                return Err(UserError::Validation {
+                    message: "Two-factor authentication is already enabled".into(),
+                });
+            }
+            totp.insert(
+                id,
+                TotpCredential {
+                    user_id: id,
+                    secret: secret.to_string(),
+                    enabled_at: None,
+                    last_used_step: None,
+                },
+            );
+            Ok(())
+        }
+
+        async fn enable_totp(&self, id: Uuid, step: i64) -> Result<(), UserError> {
+            if let Some(credential) = self.totp.lock().unwrap().get_mut(&id) {
+                credential.enabled_at = Some(Timestamp::now());
+                credential.last_used_step = Some(step);
+            }
+            Ok(())
+        }
+
+        async fn use_totp_step(&self, id: Uuid, step: i64) -> Result<bool, UserError> {
+            let mut totp = self.totp.lock().unwrap();
+            match totp.get_mut(&id) {
+                Some(credential) if credential.last_used_step.is_none_or(|last| last < step) => {
+                    credential.last_used_step = Some(step);
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        }
+
+        async fn delete_totp(&self, id: Uuid) -> Result<(), UserError> {
+            self.totp.lock().unwrap().remove(&id);
+            self.recovery_codes.lock().unwrap().remove(&id);
+            Ok(())
+        }
+
+        async fn replace_recovery_codes(
+            &self,
+            id: Uuid,
+            code_hashes: &[String],
+        ) -> Result<(), UserError> {
+            let codes = code_hashes.iter().map(|hash| (hash.clone(), false));
+            self.recovery_codes
+                .lock()
+                .unwrap()
+                .insert(id, codes.collect());
+            Ok(())
+        }
+
+        async fn use_recovery_code(&self, id: Uuid, code_hash: &str) -> Result<bool, UserError> {
+            let mut recovery_codes = self.recovery_codes.lock().unwrap();
+            let code = recovery_codes.get_mut(&id).and_then(|codes| {
+                codes
+                    .iter_mut()
+                    .find(|(hash, used)| hash == code_hash && !used)
+            });
+            match code {
+                Some((_, used)) => {
+                    *used = true;
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+
+        async fn count_recovery_codes(&self, id: Uuid) -> Result<i64, UserError> {
+            let recovery_codes = self.recovery_codes.lock().unwrap();
+            Ok(recovery_codes.get(&id).map_or(0, |codes| {
+                codes.iter().filter(|(_, used)| !used).count() as i64
+            }))
+        }
+
+        async fn create_email_change(
+            &self,
+            id: Uuid,
+            new_email: &str,
+            token_hash: &str,
+        ) -> Result<(), UserError> {
+            let old_email = self
+                .users
+                .lock()
+                .unwrap()
+                .get(&id)
+                .map(|user| user.email.clone())
+                .ok_or(UserError::UserNotFound)?;
+            let mut email_changes = self.email_changes.lock().unwrap();
+            email_changes
+                .retain(|pending| pending.change.user_id != id || pending.undo_token.is_some());
+            email_changes.push(MockEmailChange {
+                change: EmailChange {
+                    user_id: id,
+                    old_email,
+                    new_email: new_email.to_string(),
+                },
+                confirm_token: token_hash.to_string(),
+                undo_token: None,
+                undone: false,
+            });
+            Ok(())
+        }
+
+        async fn confirm_email_change(
+            &self,
+            token_hash: &str,
+            undo_token_hash: &str,
+        ) -> Result<Option<EmailChange>, UserError> {
+            let change = {
+                let email_changes = self.email_changes.lock().unwrap();
+                email_changes
+                    .iter()
+                    .find(|pending| {
+                        pending.confirm_token == token_hash && pending.undo_token.is_none()
+                    })
+                    .map(|pending| pending.change.clone())
+            };
+            let Some(change) = change else {
+                return Ok(None);
+            };
+
+            self.swap_email(change.user_id, &change.new_email)?;
+            let mut email_changes = self.email_changes.lock().unwrap();
+            if let Some(pending) = email_changes
+                .iter_mut()
+                .find(|pending| pending.confirm_token == token_hash)
+            {
+                pending.undo_token = Some(undo_token_hash.to_string());
+            }
+            Ok(Some(change))
+        }
+
+        async fn undo_email_change(
+            &self,
+            undo_token_hash: &str,
+        ) -> Result<Option<EmailChange>, UserError> {
+            let change = {
+                let email_changes = self.email_changes.lock().unwrap();
+                email_changes
+                    .iter()
+                    .find(|pending| {
+                        pending.undo_token.as_deref() == Some(undo_token_hash) && !pending.undone
+                    })
+                    .map(|pending| pending.change.clone())
+            };
+            let Some(change) = change else {
+                return Ok(None);
+            };
+
+            self.swap_email(change.user_id, &change.old_email)?;
+            let mut email_changes = self.email_changes.lock().unwrap();
+            for pending in email_changes.iter_mut() {
+                if pending.undo_token.as_deref() == Some(undo_token_hash) {
+                    pending.undone = true;
+                }
+            }
+            Ok(Some(change))
         }
     }
 
@@ -225,6 +611,50 @@ mod tests {
         }
     }
 
+    fn client_ip() -> IpAddr {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    }
+
+    fn sealer_with(key: &str) -> SecretSealer {
+        SecretSealer::new(&TwoFactorConfig {
+            sealing_key: Some(SecretString::from(key)),
+            ..TwoFactorConfig::default()
+        })
+    }
+
+    // the sealer of every service under test, which the mock opens stored secrets with
+    fn sealer() -> SecretSealer {
+        sealer_with("test-sealing-key")
+    }
+
+    fn hasher_with(secret: &str) -> TokenHasher {
+        TokenHasher::new(&TokenHashingConfig {
+            secret: Some(SecretString::from(secret)),
+            ..TokenHashingConfig::default()
+        })
+    }
+
+    // the token hasher of every service under test
+    fn hasher() -> TokenHasher {
+        hasher_with("test-token-secret")
+    }
+
+    // lock accounts after 3 failures and addresses after 5, without back-off before that
+    fn strict_lockout() -> LockoutConfig {
+        let policy = |lockout_after| LockoutPolicy {
+            free_attempts: lockout_after,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            lockout_after,
+            lockout_duration: Duration::from_secs(15 * 60),
+            reset_after: Duration::from_secs(60 * 60),
+        };
+        LockoutConfig {
+            account: policy(3),
+            ip: policy(5),
+        }
+    }
+
     fn create_valid_login_request() -> LoginRequest {
         LoginRequest {
             username_or_email: "testuser".to_string(),
@@ -247,41 +677,10 @@ mod tests {
     }
 
     #[test]
-    fn test_user_permissions() {
-        let admin_user = User {
-            id: Uuid::new_v4(),
-            username: "admin".to_string(),
-            email: "admin@test.com".to_string(),
-            password_hash: "hash".to_string(),
-            display_name: None,
-            bio: None,
-            avatar_url: None,
-            role: UserRole::Admin,
-            is_active: true,
-            email_verified: true,
-            email_verification_token: None,
-            email_verification_expires_at: None,
-            password_reset_token: None,
-            password_reset_expires_at: None,
-            social_twitter: None,
-            social_github: None,
-            website_url: None,
-            created_at: Timestamp::now(),
-            updated_at: Timestamp::now(),
-        };
-
-        assert!(admin_user.is_admin());
-        assert!(admin_user.can_write());
-        assert!(admin_user.can_moderate());
-
-        let subscriber_user = User {
-            role: UserRole::Subscriber,
-            ..admin_user.clone()
-        };
-
-        assert!(!subscriber_user.is_admin());
-        assert!(!subscriber_user.can_write());
-        assert!(!subscriber_user.can_moderate());
+    fn test_user_role_names_round_trip() {
+        for role in UserRole::ALL {
+            assert_eq!(UserRole::frm_str(role.as_str()), Some(role));
+        }
     }
 
     #[test]
@@ -500,24 +899,24 @@ mod tests {
         // Set up password verification
         repo.set_password_verification(created_user.id, "correct_password".to_string());
 
-        let is_valid = repo
+        let check = repo
             .verify_password(&created_user, "correct_password")
             .await
             .unwrap();
-        assert!(is_valid);
+        assert_eq!(check, PasswordCheck::Valid);
 
-        let is_invalid = repo
+        let check = repo
             .verify_password(&created_user, "wrong_password")
             .await
             .unwrap();
-        assert!(!is_invalid);
+        assert_eq!(check, PasswordCheck::Invalid);
     }
 
     // Service tests
     #[tokio::test]
     async fn test_service_register_success() {
         let repo = Arc::new(MockUserRepository::new());
-        let service = UserServiceImpl::new(repo);
+        let service = UserServiceImpl::new(repo, hasher(), sealer());
         let request = create_valid_user_request();
 
         let result = service.register(request.clone()).await;
@@ -532,7 +931,7 @@ mod tests {
     #[tokio::test]
     async fn test_service_register_invalid_data() {
         let repo = Arc::new(MockUserRepository::new());
-        let service = UserServiceImpl::new(repo);
+        let service = UserServiceImpl::new(repo, hasher(), sealer());
 
         let invalid_request = CreateUserRequest {
             username: "ab".to_string(), // Too short
@@ -545,10 +944,41 @@ mod tests {
         assert!(matches!(result, Err(UserError::Validation { .. })));
     }
 
+    #[tokio::test]
+    async fn test_service_register_follows_the_registration_mode() {
+        let repo = Arc::new(MockUserRepository::new());
+        let registration = |mode| RegistrationConfig {
+            mode,
+            ..RegistrationConfig::default()
+        };
+
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer())
+            .with_registration(registration(RegistrationMode::InviteOnly));
+        let result = service.register(create_valid_user_request()).await;
+        assert!(matches!(result, Err(UserError::InvitationRequired)));
+
+        // Invited accounts get the role of their invitation, their address already proven
+        let user = service
+            .register_invited(create_valid_user_request(), UserRole::Editor)
+            .await
+            .unwrap();
+        assert_eq!(user.role, UserRole::Editor);
+        assert!(user.email_verified);
+
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer())
+            .with_registration(registration(RegistrationMode::Closed));
+        let result = service.register(create_valid_user_request()).await;
+        assert!(matches!(result, Err(UserError::RegistrationClosed)));
+        let result = service
+            .register_invited(create_valid_user_request(), UserRole::Author)
+            .await;
+        assert!(matches!(result, Err(UserError::RegistrationClosed)));
+    }
+
     #[tokio::test]
     async fn test_service_login_success() {
         let repo = Arc::new(MockUserRepository::new());
-        let service = UserServiceImpl::new(repo.clone());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
 
         // Create a user first
         let create_request = create_valid_user_request();
@@ -558,31 +988,278 @@ mod tests {
 
         // Test login
         let login_request = create_valid_login_request();
-        let result = service.login(login_request).await;
+        let result = service.login(login_request, client_ip()).await;
         assert!(result.is_ok());
 
         let user_response = result.unwrap();
         assert_eq!(user_response.username, created_user.username);
     }
 
+    #[tokio::test]
+    async fn test_service_login_rehashes_outdated_password() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+        let created_user = repo.create(create_valid_user_request()).await.unwrap();
+        repo.set_password_verification(created_user.id, "correct_password".to_string());
+        repo.mark_email_verified(created_user.id);
+        repo.mark_hash_outdated(created_user.id);
+
+        // A failed login leaves the hash alone
+        let mut login_request = create_valid_login_request();
+        login_request.password = "wrong_password".to_string();
+        let result = service.login(login_request, client_ip()).await;
+        assert!(matches!(result, Err(UserError::InvalidCredentials)));
+        assert!(repo.hash_outdated(created_user.id));
+
+        let result = service
+            .login(create_valid_login_request(), client_ip())
+            .await;
+        assert!(result.is_ok());
+        assert!(!repo.hash_outdated(created_user.id));
+        let check = repo
+            .verify_password(&created_user, "correct_password")
+            .await
+            .unwrap();
+        assert_eq!(check, PasswordCheck::Valid);
+    }
+
+    // cheapest parameters Argon2 accepts, so the hashing tests stay fast
+    fn cheap_hashing_config() -> PasswordHashingConfig {
+        PasswordHashingConfig {
+            algorithm: PasswordAlgorithm::Argon2id,
+            m_cost: 8,
+            t_cost: 1,
+            p_cost: 1,
+            pepper: None,
+        }
+    }
+
+    #[test]
+    fn test_password_hashing_round_trip() {
+        let hashing = PasswordHashing::new(&cheap_hashing_config()).unwrap();
+        let hash = hashing.hash("correct_password").unwrap();
+
+        assert!(hash.starts_with("$argon2id$v=19$m=8,t=1,p=1$"));
+        assert_eq!(
+            hashing.verify("correct_password", &hash).unwrap(),
+            PasswordCheck::Valid
+        );
+        assert_eq!(
+            hashing.verify("wrong_password", &hash).unwrap(),
+            PasswordCheck::Invalid
+        );
+        assert_eq!(
+            hashing.verify("", UNUSABLE_PASSWORD_HASH).unwrap(),
+            PasswordCheck::Invalid
+        );
+    }
+
+    #[test]
+    fn test_password_hashing_flags_other_parameters_as_outdated() {
+        let old = PasswordHashing::new(&cheap_hashing_config()).unwrap();
+        let hash = old.hash("correct_password").unwrap();
+
+        let stronger = PasswordHashing::new(&PasswordHashingConfig {
+            t_cost: 2,
+            ..cheap_hashing_config()
+        })
+        .unwrap();
+        assert_eq!(
+            stronger.verify("correct_password", &hash).unwrap(),
+            PasswordCheck::Outdated
+        );
+        assert_eq!(
+            stronger.verify("wrong_password", &hash).unwrap(),
+            PasswordCheck::Invalid
+        );
+
+        let other_algorithm = PasswordHashing::new(&PasswordHashingConfig {
+            algorithm: PasswordAlgorithm::Argon2i,
+            ..cheap_hashing_config()
+        })
+        .unwrap();
+        assert_eq!(
+            other_algorithm.verify("correct_password", &hash).unwrap(),
+            PasswordCheck::Outdated
+        );
+    }
+
+    #[test]
+    fn test_password_hashing_with_pepper() {
+        let unpeppered = PasswordHashing::new(&cheap_hashing_config()).unwrap();
+        let peppered = PasswordHashing::new(&PasswordHashingConfig {
+            pepper: Some(SecretString::from("pepper")),
+            ..cheap_hashing_config()
+        })
+        .unwrap();
+
+        // Hashes from before the pepper still let their owner in, to be rehashed
+        let old_hash = unpeppered.hash("correct_password").unwrap();
+        assert_eq!(
+            peppered.verify("correct_password", &old_hash).unwrap(),
+            PasswordCheck::Outdated
+        );
+
+        // Peppered hashes are useless without the pepper
+        let new_hash = peppered.hash("correct_password").unwrap();
+        assert_eq!(
+            peppered.verify("correct_password", &new_hash).unwrap(),
+            PasswordCheck::Valid
+        );
+        assert_eq!(
+            unpeppered.verify("correct_password", &new_hash).unwrap(),
+            PasswordCheck::Invalid
+        );
+    }
+
+    #[test]
+    fn test_password_hashing_rejects_invalid_parameters() {
+        let result = PasswordHashing::new(&PasswordHashingConfig {
+            t_cost: 0,
+            ..cheap_hashing_config()
+        });
+        assert!(matches!(result, Err(UserError::PasswordHash(_))));
+    }
+
     #[tokio::test]
     async fn test_service_login_invalid_credentials() {
         let repo = Arc::new(MockUserRepository::new());
-        let service = UserServiceImpl::new(repo.clone());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
 
         let login_request = LoginRequest {
             username_or_email: "nonexistent".to_string(),
             password: "password".to_string(),
         };
 
-        let result = service.login(login_request).await;
+        let result = service.login(login_request, client_ip()).await;
+        assert!(matches!(result, Err(UserError::InvalidCredentials)));
+    }
+
+    #[test]
+    fn test_lockout_policy_backs_off_exponentially() {
+        let policy = LockoutPolicy {
+            free_attempts: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+            lockout_after: 10,
+            lockout_duration: Duration::from_secs(900),
+            reset_after: Duration::from_secs(3600),
+        };
+
+        assert_eq!(policy.delay_after(3), None);
+        assert_eq!(policy.delay_after(4), Some(Duration::from_secs(1)));
+        assert_eq!(policy.delay_after(5), Some(Duration::from_secs(2)));
+        assert_eq!(policy.delay_after(6), Some(Duration::from_secs(4)));
+        assert_eq!(policy.delay_after(7), Some(Duration::from_secs(5)));
+        assert_eq!(policy.delay_after(10), Some(Duration::from_secs(900)));
+    }
+
+    #[tokio::test]
+    async fn test_service_login_locks_account_after_repeated_failures() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::with_lockout(repo.clone(), strict_lockout(), hasher(), sealer());
+
+        let created_user = repo.create(create_valid_user_request()).await.unwrap();
+        repo.mark_email_verified(created_user.id);
+
+        let wrong = LoginRequest {
+            username_or_email: created_user.username.clone(),
+            password: "wrong_password".to_string(),
+        };
+        for _ in 0..3 {
+            let result = service.login(wrong.clone(), client_ip()).await;
+            assert!(matches!(result, Err(UserError::InvalidCredentials)));
+        }
+
+        // Even the right password is refused, without revealing the lockout
+        let result = service
+            .login(create_valid_login_request(), client_ip())
+            .await;
+        assert!(matches!(result, Err(UserError::InvalidCredentials)));
+
+        service.unlock_user(created_user.id).await.unwrap();
+        let result = service
+            .login(create_valid_login_request(), client_ip())
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_service_login_success_resets_account_failures() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::with_lockout(repo.clone(), strict_lockout(), hasher(), sealer());
+
+        let created_user = repo.create(create_valid_user_request()).await.unwrap();
+        repo.mark_email_verified(created_user.id);
+        let wrong = LoginRequest {
+            username_or_email: created_user.username.clone(),
+            password: "wrong_password".to_string(),
+        };
+
+        for _ in 0..2 {
+            let _ = service.login(wrong.clone(), client_ip()).await;
+        }
+        assert!(
+            service
+                .login(create_valid_login_request(), client_ip())
+                .await
+                .is_ok()
+        );
+        for _ in 0..2 {
+            let _ = service.login(wrong.clone(), client_ip()).await;
+        }
+        assert!(
+            service
+                .login(create_valid_login_request(), client_ip())
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_service_login_locks_out_client_address() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::with_lockout(repo.clone(), strict_lockout(), hasher(), sealer());
+
+        // Spread the guesses over unknown accounts, so only the address counter fills up
+        for i in 0..5 {
+            let result = service
+                .login(
+                    LoginRequest {
+                        username_or_email: format!("nobody_{i}"),
+                        password: "password".to_string(),
+                    },
+                    client_ip(),
+                )
+                .await;
+            assert!(matches!(result, Err(UserError::InvalidCredentials)));
+        }
+
+        let result = service
+            .login(create_valid_login_request(), client_ip())
+            .await;
+        match result {
+            Err(UserError::AccountLocked { retry_after }) => {
+                assert!(retry_after > Duration::ZERO);
+                assert!(retry_after <= Duration::from_secs(15 * 60));
+            }
+            other => panic!("expected AccountLocked, got {other:?}"),
+        }
+
+        // Another address is not affected
+        let result = service
+            .login(
+                create_valid_login_request(),
+                IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            )
+            .await;
         assert!(matches!(result, Err(UserError::InvalidCredentials)));
     }
 
     #[tokio::test]
     async fn test_service_get_user() {
         let repo = Arc::new(MockUserRepository::new());
-        let service = UserServiceImpl::new(repo.clone());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
 
         // Create a user first
         let create_request = create_valid_user_request();
@@ -600,7 +1277,7 @@ mod tests {
     #[tokio::test]
     async fn test_service_get_nonexistent_user() {
         let repo = Arc::new(MockUserRepository::new());
-        let service = UserServiceImpl::new(repo);
+        let service = UserServiceImpl::new(repo, hasher(), sealer());
 
         let result = service.get_user(Uuid::new_v4()).await;
         assert!(matches!(result, Err(UserError::UserNotFound)));
@@ -609,7 +1286,7 @@ mod tests {
     #[tokio::test]
     async fn test_service_update_profile() {
         let repo = Arc::new(MockUserRepository::new());
-        let service = UserServiceImpl::new(repo.clone());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
 
         // Create a user first
         let create_request = create_valid_user_request();
@@ -641,7 +1318,7 @@ mod tests {
     #[tokio::test]
     async fn test_service_change_password() {
         let repo = Arc::new(MockUserRepository::new());
-        let service = UserServiceImpl::new(repo.clone());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
 
         // Create a user first
         let create_request = create_valid_user_request();
@@ -663,7 +1340,7 @@ mod tests {
     #[tokio::test]
     async fn test_service_change_password_wrong_current() {
         let repo = Arc::new(MockUserRepository::new());
-        let service = UserServiceImpl::new(repo.clone());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
 
         // Create a user first
         let create_request = create_valid_user_request();
@@ -682,7 +1359,555 @@ mod tests {
         assert!(matches!(result, Err(UserError::InvalidCredentials)));
     }
 
+    #[tokio::test]
+    async fn test_service_request_password_reset_unknown_email() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo, hasher(), sealer());
+
+        let request = ForgotPasswordRequest {
+            email: "nobody@example.com".to_string(),
+        };
+
+        // Unknown addresses are accepted silently so accounts cannot be enumerated
+        let result = service.request_password_reset(request).await;
+        assert!(matches!(result, Ok(None)));
+    }
+
+    #[tokio::test]
+    async fn test_service_request_password_reset_replaces_previous_token() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+
+        let created_user = repo.create(create_valid_user_request()).await.unwrap();
+        let request = ForgotPasswordRequest {
+            email: created_user.email.clone(),
+        };
+
+        let first = service
+            .request_password_reset(request.clone())
+            .await
+            .unwrap();
+        assert!(first.is_some());
+        assert_eq!(
+            first
+                .as_deref()
+                .map(|token| hasher().hash(token)),
+            repo.reset_token_hash_for(created_user.id)
+        );
+
+        // Throttling is the job of the rate limiter, the service always issues a fresh token
+        let second = service.request_password_reset(request).await.unwrap();
+        assert!(second.is_some());
+        assert_ne!(first, second);
+        assert_eq!(
+            second
+                .as_deref()
+                .map(|token| hasher().hash(token)),
+            repo.reset_token_hash_for(created_user.id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_service_stores_only_keyed_hashes_of_reset_tokens() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher_with("secret"), sealer());
+
+        let created_user = repo.create(create_valid_user_request()).await.unwrap();
+        let token = service
+            .request_password_reset(ForgotPasswordRequest {
+                email: created_user.email.clone(),
+            })
+            .await
+            .unwrap()
+            .expect("a token should be issued for an existing account");
+        assert_eq!(token.len(), 52);
+        let stored = repo.reset_token_hash_for(created_user.id).unwrap();
+        assert!(!stored.contains(&token));
+        assert!(hasher_with("secret").verify(&token, &stored));
+
+        // Knowing the stored value is not enough to reset the password
+        let result = service
+            .reset_password(ResetPasswordRequest {
+                token: stored,
+                new_password: "new_password123".to_string(),
+            })
+            .await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+
+        // Nor is the token, without the secret it was hashed with
+        let other = UserServiceImpl::new(repo.clone(), hasher_with("other"), sealer());
+        let result = other
+            .reset_password(ResetPasswordRequest {
+                token: token.clone(),
+                new_password: "new_password123".to_string(),
+            })
+            .await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+
+        let result = service
+            .reset_password(ResetPasswordRequest {
+                token,
+                new_password: "new_password123".to_string(),
+            })
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_service_reset_password_invalidates_sessions() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+
+        let created_user = repo.create(create_valid_user_request()).await.unwrap();
+        let token = service
+            .request_password_reset(ForgotPasswordRequest {
+                email: created_user.email.clone(),
+            })
+            .await
+            .unwrap()
+            .expect("a token should be issued for an existing account");
+
+        let result = service
+            .reset_password(ResetPasswordRequest {
+                token,
+                new_password: "new_password123".to_string(),
+            })
+            .await;
+        assert!(result.is_ok());
+        assert!(repo.sessions_invalidated_for(created_user.id));
+        assert!(
+            repo.verify_password(&created_user, "new_password123")
+                .await
+                .unwrap()
+                .is_valid()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_service_reset_password_rejects_invalid_token_and_weak_password() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo, hasher(), sealer());
+
+        let result = service
+            .reset_password(ResetPasswordRequest {
+                token: "not-a-real-token".to_string(),
+                new_password: "new_password123".to_string(),
+            })
+            .await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+
+        let result = service
+            .reset_password(ResetPasswordRequest {
+                token: "whatever".to_string(),
+                new_password: "short".to_string(),
+            })
+            .await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+    }
+
+    async fn create_user_named(repo: &MockUserRepository, username: &str) -> User {
+        repo.create(CreateUserRequest {
+            username: username.to_string(),
+            email: format!("{username}@example.com"),
+            password: "password123".to_string(),
+            display_name: None,
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_service_list_users_filters_and_paginates() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+
+        for name in ["alice", "bob", "carol"] {
+            create_user_named(&repo, name).await;
+        }
+        let editor = create_user_named(&repo, "eddie").await;
+        repo.update_role(editor.id, UserRole::Editor).await.unwrap();
+
+        let page = service
+            .list_users(UserListQuery {
+                per_page: Some(2),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(page.total, 4);
+        assert_eq!(page.users.len(), 2);
+        assert_eq!(page.page, 1);
+
+        let editors = service
+            .list_users(UserListQuery {
+                role: Some(UserRole::Editor),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(editors.total, 1);
+        assert_eq!(editors.users[0].username, "eddie");
+
+        let search = service
+            .list_users(UserListQuery {
+                search: Some("BOB@".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(search.total, 1);
+        assert_eq!(search.users[0].username, "bob");
+
+        let invalid = service
+            .list_users(UserListQuery {
+                per_page: Some(500),
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(invalid, Err(UserError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_service_change_role() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+
+        let admin = create_user_named(&repo, "admin").await;
+        let user = create_user_named(&repo, "writer").await;
+
+        let updated = service
+            .change_role(admin.id, user.id, UserRole::Author)
+            .await
+            .unwrap();
+        assert_eq!(updated.role, UserRole::Author);
+
+        // Admins cannot change their own role
+        let result = service
+            .change_role(admin.id, admin.id, UserRole::Subscriber)
+            .await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_service_deactivate_signs_the_user_out() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+
+        let admin = create_user_named(&repo, "admin").await;
+        let user = create_user_named(&repo, "writer").await;
+
+        let updated = service.set_active(admin.id, user.id, false).await.unwrap();
+        assert!(!updated.is_active);
+        assert!(repo.sessions_invalidated_for(user.id));
+
+        let result = service.set_active(admin.id, admin.id, false).await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_service_login_summary_follows_the_password_login_rules() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+        let user = create_user_named(&repo, "writer").await;
+
+        let result = service.get_login_summary(user.id).await;
+        assert!(matches!(result, Err(UserError::EmailNotVerified)));
+
+        repo.mark_email_verified(user.id);
+        let summary = service.get_login_summary(user.id).await.unwrap();
+        assert_eq!(summary.id, user.id);
+
+        repo.set_active(user.id, false).await.unwrap();
+        let result = service.get_login_summary(user.id).await;
+        assert!(matches!(result, Err(UserError::InvalidCredentials)));
+        let result = service.get_login_summary(Uuid::new_v4()).await;
+        assert!(matches!(result, Err(UserError::InvalidCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_service_force_verify_email() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+
+        let user = create_user_named(&repo, "writer").await;
+        assert!(!user.email_verified);
+
+        let updated = service.force_verify_email(user.id).await.unwrap();
+        assert!(updated.email_verified);
+    }
+
     // DTO conversion tests
+    // the code an authenticator app shows for `secret` at time step `step`
+    fn totp_at(secret: &str, step: i64) -> String {
+        let secret = BASE32_NOPAD.decode(secret.as_bytes()).unwrap();
+        format!("{:06}", totp_code(&secret, step))
+    }
+
+    // the code an authenticator app shows for `secret` right now
+    fn totp_now(secret: &str) -> String {
+        totp_at(
+            secret,
+            Timestamp::now().as_second().div_euclid(TOTP_STEP_SECS),
+        )
+    }
+
+    fn code_request(code: &str) -> TwoFactorCodeRequest {
+        TwoFactorCodeRequest {
+            code: code.to_string(),
+        }
+    }
+
+    // a verified user with an enabled authenticator, along with its recovery codes
+    async fn create_user_with_totp(
+        repo: &Arc<MockUserRepository>,
+        service: &UserServiceImpl,
+    ) -> (User, Vec<String>) {
+        let user = create_user_named(repo, "two_factor_user").await;
+        repo.mark_email_verified(user.id);
+        service.begin_totp_setup(user.id).await.unwrap();
+        let secret = repo.totp_secret_for(user.id).unwrap();
+        let codes = service
+            .enable_totp(user.id, code_request(&totp_now(&secret)))
+            .await
+            .unwrap();
+        (user, codes.recovery_codes)
+    }
+
+    #[test]
+    fn test_totp_matches_rfc6238_vectors() {
+        // RFC 6238 appendix B, SHA-1, truncated to 6 digits
+        let secret = b"12345678901234567890";
+        assert_eq!(totp_code(secret, 59 / TOTP_STEP_SECS), 287082);
+        assert_eq!(totp_code(secret, 1111111109 / TOTP_STEP_SECS), 81804);
+        assert_eq!(totp_code(secret, 1234567890 / TOTP_STEP_SECS), 5924);
+        assert_eq!(totp_code(secret, 2000000000 / TOTP_STEP_SECS), 279037);
+
+        // One step of clock drift either way is tolerated, two are not
+        let encoded = BASE32_NOPAD.encode(secret);
+        assert_eq!(verify_totp(&encoded, "287082", 59), Some(1));
+        assert_eq!(verify_totp(&encoded, "287082", 89), Some(1));
+        assert_eq!(verify_totp(&encoded, "287082", 120), None);
+        assert_eq!(verify_totp(&encoded, "28708", 59), None);
+        assert_eq!(verify_totp(&encoded, "28708a", 59), None);
+    }
+
+    #[test]
+    fn test_otpauth_uri_and_recovery_codes() {
+        let uri = otpauth_uri("Rusty Word Smith", "a+b@example.com", "ABC");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Rusty%20Word%20Smith:a%2Bb%40example.com?secret=ABC&issuer=Rusty%20Word%20Smith&algorithm=SHA1&digits=6&period=30"
+        );
+        assert!(qr_svg(&uri).contains("<svg"));
+
+        let codes = generate_recovery_codes(RECOVERY_CODE_COUNT);
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert!(codes.iter().all(|code| code.len() == 11));
+        // Case and separators do not matter when a code is typed back
+        let code = &codes[0];
+        assert_eq!(
+            normalize_recovery_code(code),
+            normalize_recovery_code(&code.replace('-', " ").to_lowercase())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_service_enable_totp_requires_a_valid_code() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+        let user = create_user_named(&repo, "enrolling_user").await;
+
+        // No enrollment started yet
+        let result = service.enable_totp(user.id, code_request("123456")).await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+
+        let setup = service.begin_totp_setup(user.id).await.unwrap();
+        assert!(setup.otpauth_uri.contains(&setup.secret));
+        assert!(setup.qr_svg.contains("<svg"));
+        let wrong = if totp_now(&setup.secret) == "000000" {
+            "111111"
+        } else {
+            "000000"
+        };
+        let result = service.enable_totp(user.id, code_request(wrong)).await;
+        assert!(matches!(result, Err(UserError::InvalidSecondFactor)));
+        assert!(!service.two_factor_status(user.id).await.unwrap().enabled);
+
+        let codes = service
+            .enable_totp(user.id, code_request(&totp_now(&setup.secret)))
+            .await
+            .unwrap();
+        assert_eq!(codes.recovery_codes.len(), RECOVERY_CODE_COUNT);
+        let status = service.two_factor_status(user.id).await.unwrap();
+        assert!(status.enabled);
+        assert_eq!(status.recovery_codes_remaining, RECOVERY_CODE_COUNT as i64);
+
+        // An enabled authenticator cannot be swapped without removing it first
+        let result = service.begin_totp_setup(user.id).await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_service_verify_second_factor_rejects_replayed_codes() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+        let (user, _) = create_user_with_totp(&repo, &service).await;
+        let secret = repo.totp_secret_for(user.id).unwrap();
+        let used_step = repo
+            .find_totp(user.id)
+            .await
+            .unwrap()
+            .and_then(|credential| credential.last_used_step)
+            .unwrap();
+
+        // The code used to enable the authenticator cannot be used again
+        let result = service
+            .verify_second_factor(user.id, code_request(&totp_at(&secret, used_step)))
+            .await;
+        assert!(matches!(result, Err(UserError::InvalidSecondFactor)));
+
+        let summary = service
+            .verify_second_factor(user.id, code_request(&totp_at(&secret, used_step + 1)))
+            .await
+            .unwrap();
+        assert_eq!(summary.id, user.id);
+    }
+
+    #[tokio::test]
+    async fn test_service_keeps_no_second_factor_secret_in_clear_text() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher_with("secret"), sealer());
+        let user = create_user_named(&repo, "sealed_user").await;
+        repo.mark_email_verified(user.id);
+
+        // The authenticator secret is stored encrypted, and only opens with the same key
+        let setup = service.begin_totp_setup(user.id).await.unwrap();
+        let stored = repo.stored_totp_secret(user.id).unwrap();
+        assert!(!stored.contains(&setup.secret));
+        assert_eq!(sealer().open(&stored, user.id), Some(setup.secret.clone()));
+        assert_eq!(sealer_with("other").open(&stored, user.id), None);
+
+        // Recovery codes are stored as keyed hashes
+        let codes = service
+            .enable_totp(user.id, code_request(&totp_now(&setup.secret)))
+            .await
+            .unwrap();
+        let stored = repo.recovery_code_hashes(user.id);
+        let code = normalize_recovery_code(&codes.recovery_codes[0]);
+        assert!(stored.contains(&hasher_with("secret").hash(&code)));
+        assert!(!stored.contains(&TokenHasher::new(&TokenHashingConfig::default()).hash(&code)));
+        assert!(
+            service
+                .verify_second_factor(user.id, code_request(&codes.recovery_codes[0]))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_service_recovery_codes_are_single_use() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+        let (user, recovery_codes) = create_user_with_totp(&repo, &service).await;
+
+        let code = recovery_codes[0].to_lowercase();
+        assert!(
+            service
+                .verify_second_factor(user.id, code_request(&code))
+                .await
+                .is_ok()
+        );
+        let result = service
+            .verify_second_factor(user.id, code_request(&code))
+            .await;
+        assert!(matches!(result, Err(UserError::InvalidSecondFactor)));
+
+        let status = service.two_factor_status(user.id).await.unwrap();
+        assert_eq!(
+            status.recovery_codes_remaining,
+            RECOVERY_CODE_COUNT as i64 - 1
+        );
+
+        // Regenerating replaces every code, used or not
+        let fresh = service
+            .regenerate_recovery_codes(user.id, code_request(&recovery_codes[1]))
+            .await
+            .unwrap();
+        let result = service
+            .verify_second_factor(user.id, code_request(&recovery_codes[2]))
+            .await;
+        assert!(matches!(result, Err(UserError::InvalidSecondFactor)));
+        assert!(
+            service
+                .verify_second_factor(user.id, code_request(&fresh.recovery_codes[0]))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_service_disable_two_factor() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+        let (user, recovery_codes) = create_user_with_totp(&repo, &service).await;
+        repo.set_password_verification(user.id, "password123".to_string());
+
+        let result = service
+            .disable_two_factor(
+                user.id,
+                DisableTwoFactorRequest {
+                    password: "wrong_password".to_string(),
+                    code: recovery_codes[0].clone(),
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(UserError::InvalidCredentials)));
+
+        service
+            .disable_two_factor(
+                user.id,
+                DisableTwoFactorRequest {
+                    password: "password123".to_string(),
+                    code: recovery_codes[0].clone(),
+                },
+            )
+            .await
+            .unwrap();
+        let status = service.two_factor_status(user.id).await.unwrap();
+        assert!(!status.enabled);
+        assert_eq!(status.recovery_codes_remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn test_service_two_factor_is_required_for_configured_roles() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer()).with_two_factor(TwoFactorConfig {
+            required_roles: vec![UserRole::Editor],
+            ..TwoFactorConfig::default()
+        });
+        let (user, recovery_codes) = create_user_with_totp(&repo, &service).await;
+        repo.set_password_verification(user.id, "password123".to_string());
+        assert!(!service.two_factor_status(user.id).await.unwrap().required);
+
+        repo.update_role(user.id, UserRole::Editor).await.unwrap();
+        assert!(service.two_factor_status(user.id).await.unwrap().required);
+
+        // Required roles cannot remove their authenticator, only an admin can reset it
+        let result = service
+            .disable_two_factor(
+                user.id,
+                DisableTwoFactorRequest {
+                    password: "password123".to_string(),
+                    code: recovery_codes[0].clone(),
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+
+        service.reset_two_factor(user.id).await.unwrap();
+        assert!(!service.two_factor_status(user.id).await.unwrap().enabled);
+    }
+
     #[test]
     fn test_user_to_user_response() {
         let user = User {
@@ -756,4 +1981,103 @@ mod tests {
         assert_eq!(summary.avatar_url, user.avatar_url);
         assert_eq!(summary.role, user.role);
     }
+
+    fn change_email_request(new_email: &str) -> ChangeEmailRequest {
+        ChangeEmailRequest {
+            new_email: new_email.to_string(),
+            password: "current_password".to_string(),
+        }
+    }
+
+    fn token_request(token: &str) -> EmailChangeTokenRequest {
+        EmailChangeTokenRequest {
+            token: token.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_service_email_change_applies_only_once_confirmed() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+        let user = repo.create(create_valid_user_request()).await.unwrap();
+        repo.set_password_verification(user.id, "current_password".to_string());
+
+        let token = service
+            .request_email_change(user.id, change_email_request("new@example.com"))
+            .await
+            .unwrap();
+        let pending = service.get_user(user.id).await.unwrap();
+        assert_eq!(pending.email, user.email);
+
+        let (change, undo_token) = service
+            .confirm_email_change(token_request(&token))
+            .await
+            .unwrap();
+        assert_eq!(change.old_email, user.email);
+        assert_eq!(change.new_email, "new@example.com");
+        assert_ne!(undo_token, token);
+        let changed = service.get_user(user.id).await.unwrap();
+        assert_eq!(changed.email, "new@example.com");
+
+        // A confirmation link only works once
+        let result = service.confirm_email_change(token_request(&token)).await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_service_email_change_rejects_wrong_password_and_taken_addresses() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+        let user = repo.create(create_valid_user_request()).await.unwrap();
+        repo.set_password_verification(user.id, "current_password".to_string());
+        let other = create_user_named(&repo, "other").await;
+
+        let mut wrong_password = change_email_request("new@example.com");
+        wrong_password.password = "wrong".to_string();
+        let result = service.request_email_change(user.id, wrong_password).await;
+        assert!(matches!(result, Err(UserError::InvalidCredentials)));
+
+        let result = service
+            .request_email_change(user.id, change_email_request(&other.email))
+            .await;
+        assert!(matches!(result, Err(UserError::EmailExists)));
+
+        // Uniqueness is checked again when the change is confirmed
+        let token = service
+            .request_email_change(user.id, change_email_request("late@example.com"))
+            .await
+            .unwrap();
+        repo.swap_email(other.id, "late@example.com").unwrap();
+        let result = service.confirm_email_change(token_request(&token)).await;
+        assert!(matches!(result, Err(UserError::EmailExists)));
+        assert_eq!(service.get_user(user.id).await.unwrap().email, user.email);
+    }
+
+    #[tokio::test]
+    async fn test_service_undo_email_change_restores_the_address_and_signs_out() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserServiceImpl::new(repo.clone(), hasher(), sealer());
+        let user = repo.create(create_valid_user_request()).await.unwrap();
+        repo.set_password_verification(user.id, "current_password".to_string());
+
+        let token = service
+            .request_email_change(user.id, change_email_request("new@example.com"))
+            .await
+            .unwrap();
+        let (_, undo_token) = service
+            .confirm_email_change(token_request(&token))
+            .await
+            .unwrap();
+
+        let change = service
+            .undo_email_change(token_request(&undo_token))
+            .await
+            .unwrap();
+        assert_eq!(change.user_id, user.id);
+        assert_eq!(service.get_user(user.id).await.unwrap().email, user.email);
+        assert!(repo.sessions_invalidated_for(user.id));
+
+        let result = service.undo_email_change(token_request(&undo_token)).await;
+        assert!(matches!(result, Err(UserError::Validation { .. })));
+    }
 }
@@ -0,0 +1,110 @@
+// app/src/models/user/totp.rs
+
+// dependencies
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use qrcode::QrCode;
+use qrcode::render::svg;
+use sha1::Sha1;
+
+// RFC 6238 parameters understood by every authenticator app
+pub const TOTP_STEP_SECS: i64 = 30;
+pub const TOTP_DIGITS: u32 = 6;
+// accept codes from one step before and after the current one, to absorb clock drift
+const TOTP_SKEW_STEPS: i64 = 1;
+const SECRET_BYTES: usize = 20;
+/// Recovery codes issued at once, each of them usable a single time.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Generate a new random TOTP secret, base32 encoded as authenticator apps expect.
+pub fn generate_totp_secret() -> String {
+    let mut secret = [0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut secret);
+    BASE32_NOPAD.encode(&secret)
+}
+
+/// The HOTP value of `secret` for the given time step (RFC 4226).
+pub fn totp_code(secret: &[u8], step: i64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // Dynamic truncation
+    let offset = usize::from(digest[digest.len() - 1] & 0x0f);
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    binary % 10u32.pow(TOTP_DIGITS)
+}
+
+/// Check `code` against the base32 `secret` at `now_secs`, returning the matching time step.
+pub fn verify_totp(secret: &str, code: &str, now_secs: i64) -> Option<i64> {
+    let secret = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+
+    let current = now_secs.div_euclid(TOTP_STEP_SECS);
+    (current - TOTP_SKEW_STEPS..=current + TOTP_SKEW_STEPS)
+        .find(|step| totp_code(&secret, *step) == code)
+}
+
+/// The `otpauth://` URI authenticator apps enroll from.
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let issuer = percent_encode(issuer);
+    format!(
+        "otpauth://totp/{issuer}:{}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_STEP_SECS}",
+        percent_encode(account),
+    )
+}
+
+/// Render `data` as a QR code in SVG, to be embedded in a page.
+pub fn qr_svg(data: &str) -> String {
+    QrCode::new(data.as_bytes())
+        .map(|code| {
+            code.render::<svg::Color>()
+                .min_dimensions(200, 200)
+                .quiet_zone(true)
+                .build()
+        })
+        .unwrap_or_default()
+}
+
+/// Generate `count` random recovery codes, formatted as `XXXXX-XXXXX`.
+pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let mut bytes = [0u8; 7];
+            OsRng.fill_bytes(&mut bytes);
+            let encoded = BASE32_NOPAD.encode(&bytes);
+            format!("{}-{}", &encoded[..5], &encoded[5..10])
+        })
+        .collect()
+}
+
+/// The form a recovery code is hashed in, ignoring case and separators.
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+// percent-encode everything but RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
@@ -0,0 +1,32 @@
+// app/src/oidc/error.rs
+
+// dependencies
+use thiserror::Error;
+
+// enum type to represent the errors raised while signing in through an identity provider
+#[derive(Debug, Error)]
+pub enum OidcError {
+    #[error("Unknown identity provider")]
+    UnknownProvider,
+
+    #[error("The sign-in expired or did not start here, please try again")]
+    InvalidState,
+
+    #[error("The identity provider refused the sign-in: {0}")]
+    Denied(String),
+
+    #[error("Invalid ID token: {0}")]
+    InvalidIdToken(String),
+
+    #[error("Unexpected answer from the identity provider: {0}")]
+    Provider(String),
+
+    #[error("Unable to reach the identity provider: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+impl From<jsonwebtoken::errors::Error> for OidcError {
+    fn from(e: jsonwebtoken::errors::Error) -> Self {
+        OidcError::InvalidIdToken(e.to_string())
+    }
+}
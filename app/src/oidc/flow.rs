@@ -0,0 +1,69 @@
+// app/src/oidc/flow.rs
+
+// dependencies
+use crate::authorization::OIDC_LOGIN;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use data_encoding::BASE64URL_NOPAD;
+use pavex::time::Timestamp;
+use pavex_session::Session;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+
+// 32 random bytes, which also makes the PKCE verifier the recommended 43 characters
+const SECRET_BYTES: usize = 32;
+
+// struct type to represent a sign-in sent to an identity provider, awaiting its callback
+//
+// It lives in the session of the browser which started it, so a callback carrying the state
+// of another browser's sign-in is rejected.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingOidcLogin {
+    pub provider: String,
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub started_at: Timestamp,
+}
+
+impl PendingOidcLogin {
+    pub fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            state: random_secret(),
+            nonce: random_secret(),
+            code_verifier: random_secret(),
+            started_at: Timestamp::now(),
+        }
+    }
+
+    /// The PKCE challenge sent along with the authorization request, using the `S256` method.
+    pub fn code_challenge(&self) -> String {
+        BASE64URL_NOPAD.encode(&Sha256::digest(self.code_verifier.as_bytes()))
+    }
+
+    /// Whether a callback answers this sign-in, comparing the state in constant time.
+    pub fn matches(&self, provider: &str, state: &str, timeout: Duration) -> bool {
+        let same_state = bool::from(self.state.as_bytes().ct_eq(state.as_bytes()));
+
+        same_state && self.provider == provider && Timestamp::now() < self.started_at + timeout
+    }
+
+    /// Remember the sign-in in `session`, replacing one started earlier.
+    pub async fn store(&self, session: &mut Session<'_>) {
+        session.insert(OIDC_LOGIN, self).await.unwrap();
+    }
+
+    /// Take the sign-in out of `session`, so its state can only be used once.
+    pub async fn take(session: &mut Session<'_>) -> Option<Self> {
+        session.remove(OIDC_LOGIN).await.unwrap_or(None)
+    }
+}
+
+// a random, URL safe secret
+fn random_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    BASE64URL_NOPAD.encode(&bytes)
+}
@@ -0,0 +1,202 @@
+// app/src/oidc/mod.rs
+
+// modules
+mod error;
+mod flow;
+mod provider;
+
+// re-exports
+pub use error::*;
+pub use flow::*;
+pub use provider::*;
+
+// dependencies
+use crate::configuration::{OidcConfig, OidcProviderConfig};
+use crate::models::VerifiedIdentity;
+use jsonwebtoken::jwk::JwkSet;
+use pavex::methods;
+use secrecy::ExposeSecret;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// requests to a provider should never hold a sign-in up for long
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+// struct type to represent a provider as offered on the login page
+#[derive(Clone, Debug, Serialize)]
+pub struct OidcProviderSummary {
+    pub name: String,
+    pub display_name: String,
+}
+
+// struct type to wrap the configured identity providers in a container
+pub struct OidcClient {
+    config: OidcConfig,
+    http: reqwest::Client,
+    // discovery documents, read once per provider
+    metadata: Mutex<HashMap<String, Arc<ProviderMetadata>>>,
+}
+
+#[methods]
+impl OidcClient {
+    #[singleton]
+    pub fn new(config: &OidcConfig) -> Result<Self, OidcError> {
+        let http = reqwest::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Self {
+            config: config.clone(),
+            http,
+            metadata: Mutex::new(HashMap::new()),
+        })
+    }
+}
+
+impl OidcClient {
+    /// The providers users can sign in with.
+    pub fn providers(&self) -> Vec<OidcProviderSummary> {
+        self.config
+            .providers
+            .iter()
+            .map(|provider| OidcProviderSummary {
+                name: provider.name.clone(),
+                display_name: provider.display_name.clone(),
+            })
+            .collect()
+    }
+
+    /// How long a sign-in may take at the provider.
+    pub fn login_timeout(&self) -> Duration {
+        self.config.login_timeout
+    }
+
+    /// Start signing in with `provider`, returning where to send the browser and the sign-in
+    /// to remember until the provider calls back.
+    pub async fn start(
+        &self,
+        provider: &str,
+        redirect_uri: &str,
+    ) -> Result<(String, PendingOidcLogin), OidcError> {
+        let config = self.provider(provider)?;
+        let metadata = self.metadata(config).await?;
+        let pending = PendingOidcLogin::new(provider);
+
+        let mut scopes = vec!["openid"];
+        scopes.extend(
+            config
+                .scopes
+                .iter()
+                .map(String::as_str)
+                .filter(|scope| *scope != "openid"),
+        );
+        let mut url = reqwest::Url::parse(&metadata.authorization_endpoint)
+            .map_err(|e| OidcError::Provider(format!("invalid authorization endpoint: {e}")))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &config.client_id)
+            .append_pair("redirect_uri", redirect_uri)
+            .append_pair("scope", &scopes.join(" "))
+            .append_pair("state", &pending.state)
+            .append_pair("nonce", &pending.nonce)
+            .append_pair("code_challenge", &pending.code_challenge())
+            .append_pair("code_challenge_method", "S256");
+
+        Ok((url.into(), pending))
+    }
+
+    /// Redeem the authorization `code` of `pending`, returning the identity its ID token proves.
+    pub async fn finish(
+        &self,
+        pending: &PendingOidcLogin,
+        code: &str,
+        redirect_uri: &str,
+    ) -> Result<VerifiedIdentity, OidcError> {
+        let config = self.provider(&pending.provider)?;
+        let metadata = self.metadata(config).await?;
+
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .basic_auth(&config.client_id, Some(config.client_secret.expose_secret()))
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+                ("client_id", config.client_id.as_str()),
+                ("code_verifier", pending.code_verifier.as_str()),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(OidcError::Provider(format!(
+                "the token endpoint answered {}",
+                response.status()
+            )));
+        }
+        let tokens: TokenResponse = response.json().await?;
+        let id_token = tokens
+            .id_token
+            .ok_or_else(|| OidcError::Provider("no ID token was issued".into()))?;
+
+        // Keys rotate, so they are read for every sign-in
+        let jwks: JwkSet = self
+            .http
+            .get(&metadata.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        verify_id_token(
+            &id_token,
+            &jwks,
+            &metadata.issuer,
+            &config.client_id,
+            &pending.nonce,
+            &config.name,
+        )
+    }
+
+    fn provider(&self, name: &str) -> Result<&OidcProviderConfig, OidcError> {
+        self.config.provider(name).ok_or(OidcError::UnknownProvider)
+    }
+
+    /// The discovery document of `provider`, fetched on first use.
+    async fn metadata(
+        &self,
+        provider: &OidcProviderConfig,
+    ) -> Result<Arc<ProviderMetadata>, OidcError> {
+        if let Some(metadata) = self.metadata.lock().unwrap().get(&provider.name) {
+            return Ok(metadata.clone());
+        }
+
+        let issuer = provider.issuer_url.trim_end_matches('/');
+        let metadata: ProviderMetadata = self
+            .http
+            .get(format!("{issuer}/.well-known/openid-configuration"))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        // A document claiming another issuer could hand out that issuer's tokens
+        if metadata.issuer.trim_end_matches('/') != issuer {
+            return Err(OidcError::Provider(format!(
+                "the discovery document names the issuer {}",
+                metadata.issuer
+            )));
+        }
+
+        let metadata = Arc::new(metadata);
+        self.metadata
+            .lock()
+            .unwrap()
+            .insert(provider.name.clone(), metadata.clone());
+        Ok(metadata)
+    }
+}
@@ -0,0 +1,98 @@
+// app/src/oidc/provider.rs
+
+// dependencies
+use super::error::OidcError;
+use crate::models::VerifiedIdentity;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_bool_from_anything;
+
+// ID tokens must be signed with a key from the provider's JWKS, never with a shared secret
+const ALLOWED_ALGORITHMS: [Algorithm; 9] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+// struct type to represent the parts of a discovery document this application uses
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+// struct type to represent the answer of the token endpoint
+#[derive(Debug, Deserialize)]
+pub struct TokenResponse {
+    pub id_token: Option<String>,
+}
+
+// struct type to represent the claims of an ID token this application reads
+#[derive(Debug, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub nonce: Option<String>,
+    pub email: Option<String>,
+    // some providers send the flag as a string
+    #[serde(default, deserialize_with = "deserialize_bool_from_anything")]
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+    pub name: Option<String>,
+}
+
+/// Check the signature and the claims of `id_token`, returning who it identifies.
+pub fn verify_id_token(
+    id_token: &str,
+    jwks: &JwkSet,
+    issuer: &str,
+    client_id: &str,
+    nonce: &str,
+    provider: &str,
+) -> Result<VerifiedIdentity, OidcError> {
+    let header = jsonwebtoken::decode_header(id_token)?;
+    if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+        return Err(OidcError::InvalidIdToken(format!(
+            "unsupported algorithm {:?}",
+            header.alg
+        )));
+    }
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None => jwks.keys.first(),
+    }
+    .ok_or_else(|| OidcError::InvalidIdToken("unknown signing key".into()))?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[issuer]);
+    validation.set_audience(&[client_id]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+    let claims = jsonwebtoken::decode::<IdTokenClaims>(
+        id_token,
+        &DecodingKey::from_jwk(jwk)?,
+        &validation,
+    )?
+    .claims;
+
+    // The nonce ties the token to the sign-in this browser started
+    if claims.nonce.as_deref() != Some(nonce) {
+        return Err(OidcError::InvalidIdToken("nonce mismatch".into()));
+    }
+
+    Ok(VerifiedIdentity {
+        provider: provider.to_string(),
+        subject: claims.sub,
+        email: claims.email,
+        email_verified: claims.email_verified,
+        preferred_username: claims.preferred_username,
+        name: claims.name,
+    })
+}
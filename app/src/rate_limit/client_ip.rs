@@ -0,0 +1,86 @@
+// app/src/rate_limit/client_ip.rs
+
+// dependencies
+use crate::configuration::RateLimitConfig;
+use pavex::connection::ConnectionInfo;
+use pavex::methods;
+use pavex::request::RequestHead;
+use std::net::IpAddr;
+
+// struct type to represent the address of the client which sent the request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+#[methods]
+impl ClientIp {
+    #[request_scoped]
+    pub fn new(
+        request_head: &RequestHead,
+        connection_info: &ConnectionInfo,
+        config: &RateLimitConfig,
+    ) -> Self {
+        let forwarded = config
+            .trust_forwarded_for
+            .then(|| {
+                // a repeated header is one list, the proxy appends to the last of them
+                let header = request_head
+                    .headers
+                    .get_all("x-forwarded-for")
+                    .iter()
+                    .map(|value| value.to_str().ok())
+                    .collect::<Option<Vec<_>>>()?
+                    .join(",");
+                Self::forwarded_for(&header, config.trusted_proxy_hops)
+            })
+            .flatten();
+
+        Self(forwarded.unwrap_or_else(|| connection_info.peer_addr().ip()))
+    }
+}
+
+impl ClientIp {
+    // each trusted proxy appends the address it got the request from, so the entry `hops`
+    // places from the right is the client, anything left of it was sent by the client itself
+    fn forwarded_for(header: &str, hops: usize) -> Option<IpAddr> {
+        header
+            .rsplit(',')
+            .nth(hops.checked_sub(1)?)?
+            .trim()
+            .parse()
+            .ok()
+    }
+}
+
+impl std::fmt::Display for ClientIp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClientIp;
+    use std::net::IpAddr;
+
+    fn ip(s: &str) -> Option<IpAddr> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn the_entry_added_by_the_trusted_proxy_is_used() {
+        // the client made up the first entry, the proxy appended the real address
+        let header = "6.6.6.6, 203.0.113.7";
+        assert_eq!(ClientIp::forwarded_for(header, 1), ip("203.0.113.7"));
+        assert_eq!(ClientIp::forwarded_for("203.0.113.7", 1), ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn hops_are_counted_from_the_right() {
+        let header = "6.6.6.6, 203.0.113.7, 10.0.0.2";
+        assert_eq!(ClientIp::forwarded_for(header, 2), ip("203.0.113.7"));
+        // fewer entries than proxies, or none trusted, falls back to the peer address
+        assert_eq!(ClientIp::forwarded_for("203.0.113.7", 2), None);
+        assert_eq!(ClientIp::forwarded_for(header, 0), None);
+        assert_eq!(ClientIp::forwarded_for("not an address", 1), None);
+    }
+}
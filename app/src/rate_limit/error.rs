@@ -0,0 +1,19 @@
+// app/src/rate_limit/error.rs
+
+// dependencies
+use std::time::Duration;
+use thiserror::Error;
+
+// struct type to represent a request rejected by the rate limiter
+#[derive(Clone, Copy, Debug, Error)]
+#[error("Too many requests, please retry in {} seconds", self.retry_after_secs())]
+pub struct RateLimitExceeded {
+    pub retry_after: Duration,
+}
+
+impl RateLimitExceeded {
+    /// The delay to advertise in the `Retry-After` header, rounded up to whole seconds.
+    pub fn retry_after_secs(&self) -> u64 {
+        self.retry_after.as_secs_f64().ceil().max(1.0) as u64
+    }
+}
@@ -0,0 +1,92 @@
+// app/src/rate_limit/memory.rs
+
+// dependencies
+use super::{BucketState, RateLimitDecision};
+use crate::configuration::RateLimitPolicy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// forget expired keys every so many requests, so memory use stays bounded
+const SWEEP_EVERY: u64 = 1024;
+
+// struct type to represent rate limiting counters kept in process memory
+#[derive(Default)]
+pub struct MemoryStore {
+    // key -> (state, expiry in milliseconds)
+    entries: Mutex<HashMap<String, (BucketState, i64)>>,
+    hits: AtomicU64,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one request for `key` under `policy`.
+    pub fn hit(&self, key: &str, policy: &RateLimitPolicy, now_ms: i64) -> RateLimitDecision {
+        let mut entries = self.entries.lock().expect("rate limiter mutex poisoned");
+        if self.hits.fetch_add(1, Ordering::Relaxed) % SWEEP_EVERY == SWEEP_EVERY - 1 {
+            entries.retain(|_, (_, expires_at)| *expires_at > now_ms);
+        }
+
+        let state = entries
+            .get(key)
+            .map(|(state, _)| *state)
+            .unwrap_or_else(|| policy.initial_state(now_ms));
+        let (decision, state) = policy.apply(state, now_ms);
+        entries.insert(key.to_string(), (state, policy.expires_at_ms(&state)));
+
+        decision
+    }
+
+    /// The number of keys currently tracked.
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .expect("rate limiter mutex poisoned")
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MemoryStore, SWEEP_EVERY};
+    use crate::configuration::RateLimitPolicy;
+    use crate::rate_limit::RateLimitDecision;
+    use std::time::Duration;
+
+    const POLICY: RateLimitPolicy = RateLimitPolicy::FixedWindow {
+        limit: 1,
+        window: Duration::from_secs(60),
+    };
+
+    #[test]
+    fn keys_are_limited_independently() {
+        let store = MemoryStore::new();
+
+        assert_eq!(store.hit("a", &POLICY, 0), RateLimitDecision::Allowed);
+        assert!(matches!(
+            store.hit("a", &POLICY, 1),
+            RateLimitDecision::Limited { .. }
+        ));
+        assert_eq!(store.hit("b", &POLICY, 1), RateLimitDecision::Allowed);
+    }
+
+    #[test]
+    fn expired_keys_are_evicted() {
+        let store = MemoryStore::new();
+        for i in 0..SWEEP_EVERY - 1 {
+            store.hit(&format!("key-{i}"), &POLICY, 0);
+        }
+        assert_eq!(store.len() as u64, SWEEP_EVERY - 1);
+
+        // Long after every window closed, the next sweep drops the stale keys
+        store.hit("fresh", &POLICY, 120_000);
+        assert_eq!(store.len(), 1);
+    }
+}
@@ -0,0 +1,190 @@
+// app/src/rate_limit/mod.rs
+
+// modules
+mod client_ip;
+mod error;
+mod memory;
+mod policy;
+mod postgres;
+
+// re-exports
+pub use client_ip::*;
+pub use error::*;
+pub use memory::*;
+pub use policy::*;
+pub use postgres::*;
+
+// dependencies
+use crate::configuration::{RateLimitBackend, RateLimitConfig, RateLimitPolicies, RateLimitPolicy};
+use pavex::methods;
+use pavex::time::Timestamp;
+use sqlx::PgPool;
+use tracing_log_error::log_error;
+
+// enum type to represent the routes which are rate limited
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitedRoute {
+    Login,
+    Register,
+    ResendVerification,
+    PasswordReset,
+    PasswordResetIp,
+    TwoFactor,
+    EmailChange,
+    MagicLink,
+    MagicLinkIp,
+}
+
+impl RateLimitedRoute {
+    /// The prefix of the keys counted for this route.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RateLimitedRoute::Login => "login",
+            RateLimitedRoute::Register => "register",
+            RateLimitedRoute::ResendVerification => "resend_verification",
+            RateLimitedRoute::PasswordReset => "password_reset",
+            RateLimitedRoute::PasswordResetIp => "password_reset_ip",
+            RateLimitedRoute::TwoFactor => "two_factor",
+            RateLimitedRoute::EmailChange => "email_change",
+            RateLimitedRoute::MagicLink => "magic_link",
+            RateLimitedRoute::MagicLinkIp => "magic_link_ip",
+        }
+    }
+
+    fn policy(&self, policies: &RateLimitPolicies) -> RateLimitPolicy {
+        match self {
+            RateLimitedRoute::Login => policies.login,
+            RateLimitedRoute::Register => policies.register,
+            RateLimitedRoute::ResendVerification => policies.resend_verification,
+            RateLimitedRoute::PasswordReset => policies.password_reset,
+            RateLimitedRoute::PasswordResetIp => policies.password_reset_ip,
+            RateLimitedRoute::TwoFactor => policies.two_factor,
+            RateLimitedRoute::EmailChange => policies.email_change,
+            RateLimitedRoute::MagicLink => policies.magic_link,
+            RateLimitedRoute::MagicLinkIp => policies.magic_link_ip,
+        }
+    }
+}
+
+// struct type to represent the rate limiter shared by every request
+pub struct RateLimiter {
+    postgres: Option<PostgresStore>,
+    memory: MemoryStore,
+    policies: RateLimitPolicies,
+}
+
+#[methods]
+impl RateLimiter {
+    #[singleton]
+    pub fn new(config: &RateLimitConfig, pool: &PgPool) -> Self {
+        let postgres = match config.backend {
+            RateLimitBackend::Postgres => Some(PostgresStore::new(pool.clone())),
+            RateLimitBackend::Memory => None,
+        };
+
+        Self {
+            postgres,
+            memory: MemoryStore::new(),
+            policies: config.policies.clone(),
+        }
+    }
+}
+
+impl RateLimiter {
+    /// A rate limiter which only keeps counters in process memory.
+    pub fn in_memory(policies: RateLimitPolicies) -> Self {
+        Self {
+            postgres: None,
+            memory: MemoryStore::new(),
+            policies,
+        }
+    }
+
+    /// Count one request to `route` made on behalf of `key`, e.g. an email or client address.
+    ///
+    /// Keys are case-insensitive. When Postgres cannot be reached the request is counted
+    /// in process memory instead, so limits keep applying, if only per replica.
+    pub async fn check(&self, route: RateLimitedRoute, key: &str) -> Result<(), RateLimitExceeded> {
+        let policy = route.policy(&self.policies);
+        if policy == RateLimitPolicy::Unlimited {
+            return Ok(());
+        }
+
+        let key = format!("{}:{}", route.name(), key.trim().to_lowercase());
+        let now_ms = Timestamp::now().as_millisecond();
+        let decision = match &self.postgres {
+            Some(postgres) => match postgres.hit(&key, &policy, now_ms).await {
+                Ok(decision) => decision,
+                Err(e) => {
+                    log_error!(e, level: tracing::Level::WARN, "Rate limiter falling back to memory");
+                    self.memory.hit(&key, &policy, now_ms)
+                }
+            },
+            None => self.memory.hit(&key, &policy, now_ms),
+        };
+
+        match decision {
+            RateLimitDecision::Allowed => Ok(()),
+            RateLimitDecision::Limited { retry_after } => {
+                tracing::info!(rate_limit.route = route.name(), "Request rate limited");
+                Err(RateLimitExceeded { retry_after })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RateLimitedRoute, RateLimiter};
+    use crate::configuration::{RateLimitPolicies, RateLimitPolicy};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn routes_and_keys_are_counted_separately() {
+        let limiter = RateLimiter::in_memory(RateLimitPolicies::default());
+
+        assert!(
+            limiter
+                .check(RateLimitedRoute::PasswordReset, "a@example.com")
+                .await
+                .is_ok()
+        );
+        let err = limiter
+            .check(RateLimitedRoute::PasswordReset, " A@Example.com ")
+            .await
+            .unwrap_err();
+        assert!(err.retry_after <= Duration::from_secs(60));
+
+        // Same key on another route, and another key on the same route
+        assert!(
+            limiter
+                .check(RateLimitedRoute::ResendVerification, "a@example.com")
+                .await
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check(RateLimitedRoute::PasswordReset, "b@example.com")
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn unlimited_routes_are_never_limited() {
+        let limiter = RateLimiter::in_memory(RateLimitPolicies {
+            login: RateLimitPolicy::Unlimited,
+            ..RateLimitPolicies::default()
+        });
+
+        for _ in 0..100 {
+            assert!(
+                limiter
+                    .check(RateLimitedRoute::Login, "127.0.0.1")
+                    .await
+                    .is_ok()
+            );
+        }
+        assert!(limiter.memory.is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+// app/src/rate_limit/policy.rs
+
+// dependencies
+use crate::configuration::RateLimitPolicy;
+use std::time::Duration;
+
+// struct type to represent the counter kept for a single key
+//
+// For a fixed window `value` counts the requests seen since `stamp_ms`, the start
+// of the window. For a token bucket it is the number of tokens left at `stamp_ms`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BucketState {
+    pub value: f64,
+    pub stamp_ms: i64,
+}
+
+// enum type to represent the outcome of a rate limited request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    Allowed,
+    Limited { retry_after: Duration },
+}
+
+fn millis(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis())
+        .unwrap_or(i64::MAX)
+        .max(1)
+}
+
+impl RateLimitPolicy {
+    /// The state of a key which has not been seen yet.
+    pub fn initial_state(&self, now_ms: i64) -> BucketState {
+        let value = match self {
+            RateLimitPolicy::TokenBucket { capacity, .. } => f64::from(*capacity),
+            RateLimitPolicy::FixedWindow { .. } | RateLimitPolicy::Unlimited => 0.0,
+        };
+        BucketState {
+            value,
+            stamp_ms: now_ms,
+        }
+    }
+
+    /// Count one request against `state`, returning the decision and the state to store.
+    pub fn apply(&self, state: BucketState, now_ms: i64) -> (RateLimitDecision, BucketState) {
+        match *self {
+            RateLimitPolicy::FixedWindow { limit, window } => {
+                let window = millis(window);
+                // Start a new window once the current one is over
+                let mut state = if now_ms < state.stamp_ms || now_ms - state.stamp_ms >= window {
+                    self.initial_state(now_ms)
+                } else {
+                    state
+                };
+                if state.value < f64::from(limit) {
+                    state.value += 1.0;
+                    (RateLimitDecision::Allowed, state)
+                } else {
+                    let retry_after = state.stamp_ms.saturating_add(window) - now_ms;
+                    (
+                        RateLimitDecision::Limited {
+                            retry_after: Duration::from_millis(retry_after.max(0) as u64),
+                        },
+                        state,
+                    )
+                }
+            }
+            RateLimitPolicy::TokenBucket {
+                capacity,
+                refill_every,
+            } => {
+                let refill = millis(refill_every) as f64;
+                let elapsed = (now_ms - state.stamp_ms).max(0) as f64;
+                let tokens = (state.value + elapsed / refill).min(f64::from(capacity));
+                let mut state = BucketState {
+                    value: tokens,
+                    stamp_ms: now_ms.max(state.stamp_ms),
+                };
+                if tokens >= 1.0 {
+                    state.value -= 1.0;
+                    (RateLimitDecision::Allowed, state)
+                } else {
+                    let retry_after = ((1.0 - tokens) * refill).ceil() as u64;
+                    (
+                        RateLimitDecision::Limited {
+                            retry_after: Duration::from_millis(retry_after),
+                        },
+                        state,
+                    )
+                }
+            }
+            RateLimitPolicy::Unlimited => (RateLimitDecision::Allowed, state),
+        }
+    }
+
+    /// When `state` becomes indistinguishable from a fresh key and can be forgotten.
+    pub fn expires_at_ms(&self, state: &BucketState) -> i64 {
+        match *self {
+            RateLimitPolicy::FixedWindow { window, .. } => {
+                state.stamp_ms.saturating_add(millis(window))
+            }
+            RateLimitPolicy::TokenBucket {
+                capacity,
+                refill_every,
+            } => {
+                let missing = (f64::from(capacity) - state.value).max(0.0);
+                let refill_ms = (missing * millis(refill_every) as f64).ceil() as i64;
+                state.stamp_ms.saturating_add(refill_ms)
+            }
+            RateLimitPolicy::Unlimited => state.stamp_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BucketState, RateLimitDecision};
+    use crate::configuration::RateLimitPolicy;
+    use std::time::Duration;
+
+    // helper which applies `policy` to a sequence of request times, returning the decisions
+    fn run(policy: RateLimitPolicy, times_ms: &[i64]) -> Vec<RateLimitDecision> {
+        let mut state: Option<BucketState> = None;
+        times_ms
+            .iter()
+            .map(|&now| {
+                let current = state.unwrap_or_else(|| policy.initial_state(now));
+                let (decision, next) = policy.apply(current, now);
+                state = Some(next);
+                decision
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fixed_window_allows_limit_then_resets() {
+        let policy = RateLimitPolicy::FixedWindow {
+            limit: 2,
+            window: Duration::from_secs(60),
+        };
+        let decisions = run(policy, &[0, 1_000, 2_000, 60_000]);
+
+        assert_eq!(decisions[0], RateLimitDecision::Allowed);
+        assert_eq!(decisions[1], RateLimitDecision::Allowed);
+        assert_eq!(
+            decisions[2],
+            RateLimitDecision::Limited {
+                retry_after: Duration::from_secs(58)
+            }
+        );
+        assert_eq!(decisions[3], RateLimitDecision::Allowed);
+    }
+
+    #[test]
+    fn token_bucket_allows_bursts_and_refills() {
+        let policy = RateLimitPolicy::TokenBucket {
+            capacity: 3,
+            refill_every: Duration::from_secs(10),
+        };
+        let decisions = run(policy, &[0, 0, 0, 0, 5_000, 10_000, 10_000]);
+
+        assert!(
+            decisions[..3]
+                .iter()
+                .all(|d| *d == RateLimitDecision::Allowed)
+        );
+        assert_eq!(
+            decisions[3],
+            RateLimitDecision::Limited {
+                retry_after: Duration::from_secs(10)
+            }
+        );
+        assert_eq!(
+            decisions[4],
+            RateLimitDecision::Limited {
+                retry_after: Duration::from_secs(5)
+            }
+        );
+        assert_eq!(decisions[5], RateLimitDecision::Allowed);
+        assert!(matches!(decisions[6], RateLimitDecision::Limited { .. }));
+    }
+
+    #[test]
+    fn unlimited_never_limits() {
+        let decisions = run(RateLimitPolicy::Unlimited, &[0; 100]);
+        assert!(decisions.iter().all(|d| *d == RateLimitDecision::Allowed));
+    }
+
+    #[test]
+    fn state_expires_once_it_matches_a_fresh_key() {
+        let window = RateLimitPolicy::FixedWindow {
+            limit: 5,
+            window: Duration::from_secs(60),
+        };
+        let (_, state) = window.apply(window.initial_state(1_000), 1_000);
+        assert_eq!(window.expires_at_ms(&state), 61_000);
+
+        let bucket = RateLimitPolicy::TokenBucket {
+            capacity: 5,
+            refill_every: Duration::from_secs(10),
+        };
+        let (_, state) = bucket.apply(bucket.initial_state(0), 0);
+        let (_, state) = bucket.apply(state, 0);
+        assert_eq!(bucket.expires_at_ms(&state), 20_000);
+    }
+}
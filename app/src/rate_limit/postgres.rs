@@ -0,0 +1,104 @@
+// app/src/rate_limit/postgres.rs
+
+// dependencies
+use super::{BucketState, RateLimitDecision};
+use crate::configuration::RateLimitPolicy;
+use jiff_sqlx::Timestamp as SqlxTimestamp;
+use pavex::time::Timestamp;
+use sqlx::{PgPool, Row};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// purge expired keys every so many requests, so the table stays small
+const PURGE_EVERY: u64 = 256;
+
+// struct type to represent rate limiting counters shared through Postgres
+pub struct PostgresStore {
+    pool: PgPool,
+    hits: AtomicU64,
+}
+
+fn to_timestamp(ms: i64) -> Result<SqlxTimestamp, sqlx::Error> {
+    Timestamp::from_millisecond(ms)
+        .map(SqlxTimestamp::from)
+        .map_err(|e| sqlx::Error::Encode(Box::new(e)))
+}
+
+impl PostgresStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            hits: AtomicU64::new(0),
+        }
+    }
+
+    /// Count one request for `key` under `policy`.
+    ///
+    /// The row is locked for the duration of the update, so concurrent requests
+    /// from every replica are counted exactly once.
+    pub async fn hit(
+        &self,
+        key: &str,
+        policy: &RateLimitPolicy,
+        now_ms: i64,
+    ) -> Result<RateLimitDecision, sqlx::Error> {
+        if self.hits.fetch_add(1, Ordering::Relaxed) % PURGE_EVERY == PURGE_EVERY - 1 {
+            self.purge(now_ms).await?;
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // Make sure a row exists, so there is always something to lock
+        let initial = policy.initial_state(now_ms);
+        sqlx::query(
+            r#"
+            INSERT INTO rate_limits (key, value, stamp, expires_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (key) DO NOTHING
+            "#,
+        )
+        .bind(key)
+        .bind(initial.value)
+        .bind(to_timestamp(initial.stamp_ms)?)
+        .bind(to_timestamp(policy.expires_at_ms(&initial))?)
+        .execute(&mut *tx)
+        .await?;
+
+        let row = sqlx::query("SELECT value, stamp FROM rate_limits WHERE key = $1 FOR UPDATE")
+            .bind(key)
+            .fetch_one(&mut *tx)
+            .await?;
+        let state = BucketState {
+            value: row.get("value"),
+            stamp_ms: Timestamp::from(row.get::<SqlxTimestamp, _>("stamp")).as_millisecond(),
+        };
+
+        let (decision, state) = policy.apply(state, now_ms);
+        sqlx::query(
+            r#"
+            UPDATE rate_limits
+            SET value = $2, stamp = $3, expires_at = $4
+            WHERE key = $1
+            "#,
+        )
+        .bind(key)
+        .bind(state.value)
+        .bind(to_timestamp(state.stamp_ms)?)
+        .bind(to_timestamp(policy.expires_at_ms(&state))?)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(decision)
+    }
+
+    // delete every key which has gone back to its initial state
+    async fn purge(&self, now_ms: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM rate_limits WHERE expires_at <= $1")
+            .bind(to_timestamp(now_ms)?)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
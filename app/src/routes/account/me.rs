@@ -0,0 +1,120 @@
+// app/src/routes/account/me.rs
+
+// dependencies
+use crate::authorization::{CurrentUser, require_scope, require_session, session_info};
+use crate::email::MailerContainer;
+use crate::errors::ApiError;
+use crate::models::{
+    ChangeEmailRequest, ChangePasswordRequest, TokenScope, UpdateUserRequest, UserResponse,
+};
+use crate::rate_limit::{RateLimitedRoute, RateLimiter};
+use crate::response::ApiResponse;
+use crate::routes::account::SessionServiceContainer;
+use crate::routes::auth::UserServiceContainer;
+use pavex::{get, patch, post, request::body::JsonBody};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+use tracing_log_error::log_error;
+
+// handler which returns the profile of the signed-in user
+#[get(path = "/api/me")]
+pub async fn get_me(
+    user: &CurrentUser,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<UserResponse>, ApiError> {
+    require_scope(user, TokenScope::ProfileRead)?;
+    let user_response = user_service.0.get_user(user.id).await?;
+
+    Ok(ApiResponse::ok(user_response))
+}
+
+// handler which updates the profile of the signed-in user
+#[patch(path = "/api/me")]
+pub async fn update_me(
+    body: &JsonBody<UpdateUserRequest>,
+    user: &CurrentUser,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<UserResponse>, ApiError> {
+    require_scope(user, TokenScope::ProfileWrite)?;
+    let update_user_request = body.0.clone();
+    let user_response = user_service
+        .0
+        .update_profile(user.id, update_user_request)
+        .await?;
+
+    Ok(ApiResponse::ok_with_message(
+        user_response,
+        "Profile updated",
+    ))
+}
+
+// handler which changes the password of the signed-in user
+#[post(path = "/api/me/password")]
+pub async fn change_my_password(
+    body: &JsonBody<ChangePasswordRequest>,
+    user: &CurrentUser,
+    session: &mut Session<'_>,
+    user_service: &UserServiceContainer,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_session(user)?;
+    let change_password_request = body.0.clone();
+    user_service
+        .0
+        .change_password(user.id, change_password_request)
+        .await?;
+
+    // Sign every other device out, and issue a fresh session id so a previously
+    // captured cookie of this one stops working too
+    let current = session_info(session).await.map(|info| info.handle);
+    sessions.0.revoke_other_sessions(user.id, current).await?;
+    session.cycle_id();
+
+    Ok(ApiResponse::ok_with_message((), "Password changed"))
+}
+
+// handler which sends a confirmation link to the new email address, the current one stays until confirmed
+#[post(path = "/api/me/email")]
+pub async fn change_my_email(
+    body: &JsonBody<ChangeEmailRequest>,
+    user: &CurrentUser,
+    user_service: &UserServiceContainer,
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+    rate_limiter: &RateLimiter,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_session(user)?;
+    rate_limiter
+        .check(RateLimitedRoute::EmailChange, &user.id.to_string())
+        .await?;
+
+    let change_email_request = body.0.clone();
+    let new_email = change_email_request.new_email.clone();
+    let token = user_service
+        .0
+        .request_email_change(user.id, change_email_request)
+        .await?;
+
+    let mut context = Context::new();
+    context.insert(
+        "link",
+        &mailer.link(&format!("/auth/confirm-email-change?token={token}")),
+    );
+    if let Err(e) = mailer
+        .send_template(
+            template,
+            &new_email,
+            "Confirm your new email address",
+            "email_change_confirm",
+            &context,
+        )
+        .await
+    {
+        log_error!(e, "Failed to send the email change confirmation");
+    }
+
+    Ok(ApiResponse::ok_with_message(
+        (),
+        "Check your new inbox to confirm the change",
+    ))
+}
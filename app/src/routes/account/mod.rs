@@ -0,0 +1,88 @@
+// app/src/routes/account/mod.rs
+
+// modules
+pub mod me;
+pub mod passkeys;
+pub mod privacy;
+pub mod sessions;
+pub mod settings;
+pub mod tokens;
+pub mod two_factor;
+
+// re-exports
+pub use me::*;
+pub use passkeys::*;
+pub use privacy::*;
+pub use sessions::*;
+pub use settings::*;
+pub use tokens::*;
+pub use two_factor::*;
+
+// dependencies
+use crate::configuration::{
+    AccountDeletionConfig, SessionLimitsConfig, TokenHashingConfig, WebauthnConfig,
+};
+use crate::models::{
+    ApiTokenService, ApiTokenServiceImpl, PasskeyError, PasskeyService, PasskeyServiceImpl,
+    PrivacyService, PrivacyServiceImpl, SessionService, SessionServiceImpl,
+    SqlxApiTokenRepository, SqlxPasskeyRepository, SqlxPrivacyRepository, SqlxSessionRepository,
+};
+use crate::tokens::TokenHasher;
+use pavex::methods;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+// struct type to wrap an API token service in a container
+pub struct ApiTokenServiceContainer(pub Box<dyn ApiTokenService>);
+
+#[methods]
+impl ApiTokenServiceContainer {
+    #[singleton]
+    pub fn new(pool: &PgPool) -> Self {
+        let repository = Arc::new(SqlxApiTokenRepository::new(pool.clone()));
+        ApiTokenServiceContainer(Box::new(ApiTokenServiceImpl::new(repository)))
+    }
+}
+
+// struct type to wrap a session service in a container, shared so its account cache is too
+pub struct SessionServiceContainer(pub Box<dyn SessionService>);
+
+#[methods]
+impl SessionServiceContainer {
+    #[singleton]
+    pub fn new(pool: &PgPool, limits: &SessionLimitsConfig) -> Self {
+        let repository = Arc::new(SqlxSessionRepository::new(pool.clone()));
+        let service = SessionServiceImpl::new(repository).with_config(limits.clone());
+        SessionServiceContainer(Box::new(service))
+    }
+}
+
+// struct type to wrap a privacy service in a container
+pub struct PrivacyServiceContainer(pub Box<dyn PrivacyService>);
+
+#[methods]
+impl PrivacyServiceContainer {
+    #[singleton]
+    pub fn new(pool: &PgPool, deletion: &AccountDeletionConfig) -> Self {
+        let repository = Arc::new(SqlxPrivacyRepository::new(pool.clone()));
+        let service = PrivacyServiceImpl::new(repository).with_deletion(deletion.clone());
+        PrivacyServiceContainer(Box::new(service))
+    }
+}
+
+// struct type to wrap a passkey service in a container
+pub struct PasskeyServiceContainer(pub Box<dyn PasskeyService>);
+
+#[methods]
+impl PasskeyServiceContainer {
+    #[singleton]
+    pub fn new(
+        pool: &PgPool,
+        webauthn: &WebauthnConfig,
+        tokens: &TokenHashingConfig,
+    ) -> Result<Self, PasskeyError> {
+        let repository = Arc::new(SqlxPasskeyRepository::new(pool.clone()));
+        let service = PasskeyServiceImpl::new(repository, webauthn, TokenHasher::new(tokens))?;
+        Ok(PasskeyServiceContainer(Box::new(service)))
+    }
+}
@@ -0,0 +1,133 @@
+// app/src/routes/account/passkeys.rs
+
+// dependencies
+use super::two_factor::enrolling_user;
+use crate::authorization::{CurrentUser, PasskeyCeremony, SessionClient, require_session, sign_in};
+use crate::configuration::{TwoFactorConfig, WebauthnConfig};
+use crate::errors::ApiError;
+use crate::models::{
+    FinishPasskeyRegistrationRequest, PasskeyError, PasskeyResponse, RenamePasskeyRequest,
+};
+use crate::response::ApiResponse;
+use crate::routes::account::{PasskeyServiceContainer, SessionServiceContainer};
+use crate::routes::auth::UserServiceContainer;
+use pavex::request::path::PathParams;
+use pavex::{delete, get, patch, post, request::body::JsonBody};
+use pavex_session::Session;
+use uuid::Uuid;
+use webauthn_rs::prelude::{CreationChallengeResponse, PasskeyRegistration};
+
+// struct type to represent the id of the passkey a request applies to
+#[PathParams]
+pub struct PasskeyIdParams {
+    pub id: Uuid,
+}
+
+// handler which lists the passkeys of the signed-in user
+#[get(path = "/api/me/passkeys")]
+pub async fn list_my_passkeys(
+    user: &CurrentUser,
+    passkeys: &PasskeyServiceContainer,
+) -> Result<ApiResponse<Vec<PasskeyResponse>>, ApiError> {
+    require_session(user)?;
+    let passkeys = passkeys.0.list_passkeys(user.id).await?;
+
+    Ok(ApiResponse::ok(passkeys))
+}
+
+// handler which starts registering a passkey, used from the account page and during login
+#[post(path = "/api/me/passkeys/register/start")]
+pub async fn start_passkey_registration(
+    session: &mut Session<'_>,
+    user_service: &UserServiceContainer,
+    passkeys: &PasskeyServiceContainer,
+    config: &TwoFactorConfig,
+) -> Result<ApiResponse<CreationChallengeResponse>, ApiError> {
+    let (id, _) = enrolling_user(session, config).await?;
+    let user_summary = user_service.0.get_user_summary(id).await?;
+
+    let (challenge, state) = passkeys.0.start_registration(&user_summary).await?;
+    PasskeyCeremony::new(id, state).store(session).await;
+
+    Ok(ApiResponse::ok(challenge))
+}
+
+// handler which stores the passkey created by the browser
+#[post(path = "/api/me/passkeys/register/finish")]
+pub async fn finish_passkey_registration(
+    body: &JsonBody<FinishPasskeyRegistrationRequest>,
+    session: &mut Session<'_>,
+    user_service: &UserServiceContainer,
+    passkeys: &PasskeyServiceContainer,
+    config: &TwoFactorConfig,
+    webauthn: &WebauthnConfig,
+    client: &SessionClient,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<PasskeyResponse>, ApiError> {
+    let (id, pending) = enrolling_user(session, config).await?;
+    let ceremony: PasskeyCeremony<PasskeyRegistration> =
+        PasskeyCeremony::take(session, webauthn.ceremony_timeout)
+            .await
+            .filter(|ceremony| ceremony.user_id == id)
+            .ok_or_else(|| PasskeyError::Validation {
+                message: "No passkey registration is in progress".into(),
+            })?;
+
+    let registration_request = body.0.clone();
+    let passkey = passkeys
+        .0
+        .finish_registration(id, registration_request, &ceremony.state)
+        .await?;
+
+    // A login held back until a second factor was set up is now complete
+    if pending {
+        let user_summary = user_service.0.get_user_summary(id).await?;
+        sign_in(session, &user_summary, client, sessions).await?;
+    }
+
+    Ok(ApiResponse::ok_with_message(passkey, "Passkey registered"))
+}
+
+// handler which renames one of the passkeys of the signed-in user
+#[patch(path = "/api/me/passkeys/{id}")]
+pub async fn rename_my_passkey(
+    params: &PathParams<PasskeyIdParams>,
+    body: &JsonBody<RenamePasskeyRequest>,
+    user: &CurrentUser,
+    passkeys: &PasskeyServiceContainer,
+) -> Result<ApiResponse<PasskeyResponse>, ApiError> {
+    require_session(user)?;
+    let rename_request = body.0.clone();
+    let passkey = passkeys
+        .0
+        .rename_passkey(user.id, params.0.id, rename_request)
+        .await?;
+
+    Ok(ApiResponse::ok(passkey))
+}
+
+// handler which deletes one of the passkeys of the signed-in user
+#[delete(path = "/api/me/passkeys/{id}")]
+pub async fn delete_my_passkey(
+    params: &PathParams<PasskeyIdParams>,
+    user: &CurrentUser,
+    user_service: &UserServiceContainer,
+    passkeys: &PasskeyServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_session(user)?;
+
+    // The last second factor of a role which requires one stays
+    let two_factor = user_service.0.two_factor_status(user.id).await?;
+    if two_factor.required && !two_factor.enabled {
+        let remaining = passkeys.0.list_passkeys(user.id).await?;
+        if matches!(remaining.as_slice(), [last] if last.id == params.0.id) {
+            return Err(PasskeyError::Validation {
+                message: "Two-factor authentication is required for your role".into(),
+            }
+            .into());
+        }
+    }
+    passkeys.0.delete_passkey(user.id, params.0.id).await?;
+
+    Ok(ApiResponse::ok_with_message((), "Passkey deleted"))
+}
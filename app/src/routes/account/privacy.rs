@@ -0,0 +1,129 @@
+// app/src/routes/account/privacy.rs
+
+// dependencies
+use crate::authorization::{CurrentUser, require_session};
+use crate::email::MailerContainer;
+use crate::errors::ApiError;
+use crate::models::{AccountDeletionResponse, DeleteAccountRequest, DeletionMode};
+use crate::response::ApiResponse;
+use crate::routes::account::PrivacyServiceContainer;
+use crate::routes::auth::UserServiceContainer;
+use pavex::http::HeaderValue;
+use pavex::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use pavex::{Response, delete, get, post, request::body::JsonBody};
+use pavex_tera_template::{Context, TemplateEngine};
+use tracing_log_error::log_error;
+use uuid::Uuid;
+
+/// Wrap the personal data export of a user into a download
+pub fn export_response(archive: Vec<u8>, user_id: Uuid) -> Response {
+    let disposition = format!("attachment; filename=\"export-{user_id}.zip\"");
+
+    Response::ok()
+        .set_typed_body(archive)
+        .insert_header(CONTENT_TYPE, HeaderValue::from_static("application/zip"))
+        .insert_header(
+            CONTENT_DISPOSITION,
+            HeaderValue::from_str(&disposition).expect("a UUID is a valid header value"),
+        )
+}
+
+/// Let the owner of an account know it is going away, and how to keep it
+pub async fn send_deletion_notice(
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+    email: &str,
+    deletion: &AccountDeletionResponse,
+) {
+    let mut context = Context::new();
+    context.insert(
+        "scheduled_for",
+        &deletion
+            .scheduled_for
+            .strftime("%Y-%m-%d %H:%M UTC")
+            .to_string(),
+    );
+    context.insert("anonymize", &(deletion.mode == DeletionMode::Anonymize));
+    context.insert("link", &mailer.link("/account"));
+    if let Err(e) = mailer
+        .send_template(
+            template,
+            email,
+            "Your account is scheduled for deletion",
+            "account_deletion_scheduled",
+            &context,
+        )
+        .await
+    {
+        log_error!(e, "Failed to send the account deletion notice");
+    }
+}
+
+// handler which downloads every piece of personal data held about the signed-in user
+#[get(path = "/api/me/export")]
+pub async fn export_my_data(
+    user: &CurrentUser,
+    privacy: &PrivacyServiceContainer,
+) -> Result<Response, ApiError> {
+    require_session(user)?;
+    let archive = privacy.0.export_data(user.id).await?;
+
+    Ok(export_response(archive, user.id))
+}
+
+// handler which returns the pending deletion of the signed-in user, if any
+#[get(path = "/api/me/deletion")]
+pub async fn get_my_deletion(
+    user: &CurrentUser,
+    privacy: &PrivacyServiceContainer,
+) -> Result<ApiResponse<Option<AccountDeletionResponse>>, ApiError> {
+    require_session(user)?;
+    let deletion = privacy.0.pending_deletion(user.id).await?;
+
+    Ok(ApiResponse::ok(deletion))
+}
+
+// handler which schedules the deletion of the signed-in user, once the grace period is over
+#[post(path = "/api/me/deletion")]
+pub async fn delete_my_account(
+    body: &JsonBody<DeleteAccountRequest>,
+    user: &CurrentUser,
+    user_service: &UserServiceContainer,
+    privacy: &PrivacyServiceContainer,
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+) -> Result<ApiResponse<AccountDeletionResponse>, ApiError> {
+    require_session(user)?;
+    let request = body.0.clone();
+    user_service
+        .0
+        .confirm_password(user.id, &request.password)
+        .await?;
+    let deletion = privacy
+        .0
+        .schedule_deletion(user.id, user.id, request.mode)
+        .await?;
+
+    let profile = user_service.0.get_user(user.id).await?;
+    send_deletion_notice(mailer, template, &profile.email, &deletion).await;
+
+    Ok(ApiResponse::ok_with_message(
+        deletion,
+        "Account deletion scheduled",
+    ))
+}
+
+// handler which cancels the pending deletion of the signed-in user
+#[delete(path = "/api/me/deletion")]
+pub async fn cancel_my_deletion(
+    user: &CurrentUser,
+    privacy: &PrivacyServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_session(user)?;
+    privacy.0.cancel_deletion(user.id, user.id).await?;
+
+    Ok(ApiResponse::ok_with_message(
+        (),
+        "Account deletion cancelled",
+    ))
+}
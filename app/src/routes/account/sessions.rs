@@ -0,0 +1,89 @@
+// app/src/routes/account/sessions.rs
+
+// dependencies
+use crate::authorization::{CurrentUser, csrf_token, require_session, session_info};
+use crate::errors::ApiError;
+use crate::models::{RevokedSessionsResponse, SessionResponse};
+use crate::response::ApiResponse;
+use crate::routes::account::SessionServiceContainer;
+use pavex::request::path::PathParams;
+use pavex::{Response, delete, get, response::body::Html};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+use uuid::Uuid;
+
+// struct type to represent the handle of the session a request applies to
+#[PathParams]
+pub struct SessionIdParams {
+    pub id: Uuid,
+}
+
+// handler which renders the signed-in sessions of the current user
+#[get(path = "/account/sessions")]
+pub async fn account_sessions(
+    user: &CurrentUser,
+    session: &mut Session<'_>,
+    sessions: &SessionServiceContainer,
+    template: &TemplateEngine,
+) -> Result<Response, ApiError> {
+    require_session(user)?;
+    let current = session_info(session).await.map(|info| info.handle);
+    let active_sessions = sessions.0.list_sessions(user.id, current).await?;
+
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Sessions");
+    context.insert("sessions", &active_sessions);
+
+    let body: Html = template.render("account/sessions.html", &context)?.into();
+    Ok(Response::ok().set_typed_body(body))
+}
+
+// handler which lists the signed-in sessions of the current user
+#[get(path = "/api/me/sessions")]
+pub async fn list_my_sessions(
+    user: &CurrentUser,
+    session: &Session<'_>,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<Vec<SessionResponse>>, ApiError> {
+    require_session(user)?;
+    let current = session_info(session).await.map(|info| info.handle);
+    let active_sessions = sessions.0.list_sessions(user.id, current).await?;
+
+    Ok(ApiResponse::ok(active_sessions))
+}
+
+// handler which signs one of the other sessions of the current user out
+#[delete(path = "/api/me/sessions/{id}")]
+pub async fn revoke_my_session(
+    params: &PathParams<SessionIdParams>,
+    user: &CurrentUser,
+    session: &Session<'_>,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_session(user)?;
+    let current = session_info(session).await.map(|info| info.handle);
+    sessions
+        .0
+        .revoke_session(user.id, params.0.id, current)
+        .await?;
+
+    Ok(ApiResponse::ok_with_message((), "Session signed out"))
+}
+
+// handler which signs every session of the current user out, except the one making the request
+#[delete(path = "/api/me/sessions")]
+pub async fn revoke_my_other_sessions(
+    user: &CurrentUser,
+    session: &Session<'_>,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<RevokedSessionsResponse>, ApiError> {
+    require_session(user)?;
+    let current = session_info(session).await.map(|info| info.handle);
+    let revoked = sessions.0.revoke_other_sessions(user.id, current).await?;
+
+    Ok(ApiResponse::ok_with_message(
+        RevokedSessionsResponse { revoked },
+        "Signed out everywhere else",
+    ))
+}
@@ -0,0 +1,50 @@
+// app/src/routes/account/settings.rs
+
+// dependencies
+use crate::authorization::{CurrentUser, csrf_token, require_session};
+use crate::errors::ApiError;
+use crate::models::{TokenScope, UserRole};
+use crate::routes::account::{
+    ApiTokenServiceContainer, PasskeyServiceContainer, PrivacyServiceContainer,
+};
+use crate::routes::auth::UserServiceContainer;
+use pavex::{Response, get, response::body::Html};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+
+// handler which renders the account settings page of the signed-in user
+#[get(path = "/account")]
+pub async fn account_settings(
+    user: &CurrentUser,
+    user_service: &UserServiceContainer,
+    api_tokens: &ApiTokenServiceContainer,
+    passkeys: &PasskeyServiceContainer,
+    privacy: &PrivacyServiceContainer,
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    require_session(user)?;
+    let profile = user_service.0.get_user(user.id).await?;
+    let two_factor = user_service.0.two_factor_status(user.id).await?;
+    let passkeys = passkeys.0.list_passkeys(user.id).await?;
+    let tokens = api_tokens.0.list_tokens(user.id).await?;
+    let deletion = privacy.0.pending_deletion(user.id).await?;
+    let token_scopes: Vec<&str> = TokenScope::ALL
+        .iter()
+        .filter(|scope| user.role == UserRole::Admin || **scope != TokenScope::Admin)
+        .map(|scope| scope.as_str())
+        .collect();
+
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Account settings");
+    context.insert("profile", &profile);
+    context.insert("two_factor", &two_factor);
+    context.insert("passkeys", &passkeys);
+    context.insert("tokens", &tokens);
+    context.insert("token_scopes", &token_scopes);
+    context.insert("deletion", &deletion);
+
+    let body: Html = template.render("account/index.html", &context)?.into();
+    Ok(Response::ok().set_typed_body(body))
+}
@@ -0,0 +1,62 @@
+// app/src/routes/account/tokens.rs
+
+// dependencies
+use crate::authorization::{CurrentUser, require_session};
+use crate::errors::ApiError;
+use crate::models::{ApiTokenResponse, CreateApiTokenRequest, CreatedApiTokenResponse};
+use crate::response::ApiResponse;
+use crate::routes::account::ApiTokenServiceContainer;
+use pavex::request::path::PathParams;
+use pavex::{delete, get, post, request::body::JsonBody};
+use uuid::Uuid;
+
+// struct type to represent the id of the API token a request applies to
+#[PathParams]
+pub struct TokenIdParams {
+    pub id: Uuid,
+}
+
+// handler which lists the API tokens of the signed-in user
+#[get(path = "/api/me/tokens")]
+pub async fn list_my_tokens(
+    user: &CurrentUser,
+    api_tokens: &ApiTokenServiceContainer,
+) -> Result<ApiResponse<Vec<ApiTokenResponse>>, ApiError> {
+    require_session(user)?;
+    let tokens = api_tokens.0.list_tokens(user.id).await?;
+
+    Ok(ApiResponse::ok(tokens))
+}
+
+// handler which creates an API token for the signed-in user, the only response carrying the token
+#[post(path = "/api/me/tokens")]
+pub async fn create_my_token(
+    body: &JsonBody<CreateApiTokenRequest>,
+    user: &CurrentUser,
+    api_tokens: &ApiTokenServiceContainer,
+) -> Result<ApiResponse<CreatedApiTokenResponse>, ApiError> {
+    require_session(user)?;
+    let create_token_request = body.0.clone();
+    let created = api_tokens
+        .0
+        .create_token(user.id, user.role, create_token_request)
+        .await?;
+
+    Ok(ApiResponse::ok_with_message(
+        created,
+        "API token created, copy it now as it will not be shown again",
+    ))
+}
+
+// handler which revokes one of the API tokens of the signed-in user
+#[delete(path = "/api/me/tokens/{id}")]
+pub async fn revoke_my_token(
+    params: &PathParams<TokenIdParams>,
+    user: &CurrentUser,
+    api_tokens: &ApiTokenServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_session(user)?;
+    api_tokens.0.revoke_token(user.id, params.0.id).await?;
+
+    Ok(ApiResponse::ok_with_message((), "API token revoked"))
+}
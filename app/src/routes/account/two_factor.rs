@@ -0,0 +1,138 @@
+// app/src/routes/account/two_factor.rs
+
+// dependencies
+use crate::authorization::{
+    CurrentUser, PendingLogin, PendingStep, SessionClient, USER_ID, require_session, sign_in,
+};
+use crate::configuration::TwoFactorConfig;
+use crate::errors::ApiError;
+use crate::models::{
+    DisableTwoFactorRequest, RecoveryCodesResponse, TotpSetupResponse, TwoFactorCodeRequest,
+    TwoFactorStatus,
+};
+use crate::rate_limit::{RateLimitedRoute, RateLimiter};
+use crate::response::ApiResponse;
+use crate::routes::account::SessionServiceContainer;
+use crate::routes::auth::UserServiceContainer;
+use pavex::{get, post, request::body::JsonBody};
+use pavex_session::Session;
+use uuid::Uuid;
+
+// the account an authenticator is enrolled for, and whether it is a login pending on enrollment
+pub(crate) async fn enrolling_user(
+    session: &Session<'_>,
+    config: &TwoFactorConfig,
+) -> Result<(Uuid, bool), ApiError> {
+    if let Some(id) = session.get::<Uuid>(USER_ID).await.unwrap_or(None) {
+        return Ok((id, false));
+    }
+
+    PendingLogin::load(session, config.pending_timeout)
+        .await
+        .filter(|pending| pending.step == PendingStep::Setup)
+        .map(|pending| (pending.user_id, true))
+        .ok_or_else(|| ApiError::Unauthorized("Invalid session".into()))
+}
+
+// handler which returns the two-factor authentication status of the signed-in user
+#[get(path = "/api/me/2fa")]
+pub async fn get_my_two_factor(
+    user: &CurrentUser,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<TwoFactorStatus>, ApiError> {
+    require_session(user)?;
+    let status = user_service.0.two_factor_status(user.id).await?;
+
+    Ok(ApiResponse::ok(status))
+}
+
+// handler which starts enrolling an authenticator, returning its secret and QR code
+#[post(path = "/api/me/2fa/setup")]
+pub async fn begin_two_factor_setup(
+    session: &Session<'_>,
+    user_service: &UserServiceContainer,
+    config: &TwoFactorConfig,
+) -> Result<ApiResponse<TotpSetupResponse>, ApiError> {
+    let (id, _) = enrolling_user(session, config).await?;
+    let setup_response = user_service.0.begin_totp_setup(id).await?;
+
+    Ok(ApiResponse::ok(setup_response))
+}
+
+// handler which confirms the enrolled authenticator with a first code, returning the recovery codes
+#[post(path = "/api/me/2fa/enable")]
+pub async fn enable_two_factor(
+    body: &JsonBody<TwoFactorCodeRequest>,
+    session: &mut Session<'_>,
+    user_service: &UserServiceContainer,
+    rate_limiter: &RateLimiter,
+    config: &TwoFactorConfig,
+    client: &SessionClient,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<RecoveryCodesResponse>, ApiError> {
+    let (id, pending) = enrolling_user(session, config).await?;
+    rate_limiter
+        .check(RateLimitedRoute::TwoFactor, &id.to_string())
+        .await?;
+
+    let code_request = body.0.clone();
+    let recovery_codes = user_service.0.enable_totp(id, code_request).await?;
+
+    // A login held back until enrollment is now complete
+    if pending {
+        let user_summary = user_service.0.get_user_summary(id).await?;
+        sign_in(session, &user_summary, client, sessions).await?;
+    }
+
+    Ok(ApiResponse::ok_with_message(
+        recovery_codes,
+        "Two-factor authentication enabled",
+    ))
+}
+
+// handler which replaces the recovery codes of the signed-in user
+#[post(path = "/api/me/2fa/recovery-codes")]
+pub async fn regenerate_my_recovery_codes(
+    body: &JsonBody<TwoFactorCodeRequest>,
+    user: &CurrentUser,
+    user_service: &UserServiceContainer,
+    rate_limiter: &RateLimiter,
+) -> Result<ApiResponse<RecoveryCodesResponse>, ApiError> {
+    require_session(user)?;
+    rate_limiter
+        .check(RateLimitedRoute::TwoFactor, &user.id.to_string())
+        .await?;
+
+    let code_request = body.0.clone();
+    let recovery_codes = user_service
+        .0
+        .regenerate_recovery_codes(user.id, code_request)
+        .await?;
+
+    Ok(ApiResponse::ok(recovery_codes))
+}
+
+// handler which removes the authenticator of the signed-in user
+#[post(path = "/api/me/2fa/disable")]
+pub async fn disable_my_two_factor(
+    body: &JsonBody<DisableTwoFactorRequest>,
+    user: &CurrentUser,
+    user_service: &UserServiceContainer,
+    rate_limiter: &RateLimiter,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_session(user)?;
+    rate_limiter
+        .check(RateLimitedRoute::TwoFactor, &user.id.to_string())
+        .await?;
+
+    let disable_request = body.0.clone();
+    user_service
+        .0
+        .disable_two_factor(user.id, disable_request)
+        .await?;
+
+    Ok(ApiResponse::ok_with_message(
+        (),
+        "Two-factor authentication disabled",
+    ))
+}
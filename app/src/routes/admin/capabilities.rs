@@ -0,0 +1,75 @@
+// app/src/routes/admin/capabilities.rs
+
+// dependencies
+use crate::authorization::{CurrentUser, csrf_token, require_capability};
+use crate::errors::ApiError;
+use crate::models::{
+    Capability, CapabilityError, CapabilityResponse, UpdateCapabilityRolesRequest, UserRole,
+};
+use crate::response::ApiResponse;
+use crate::routes::admin::CapabilityServiceContainer;
+use pavex::request::path::PathParams;
+use pavex::{Response, get, patch, request::body::JsonBody, response::body::Html};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+
+// struct type to represent the name of the capability an admin action applies to
+#[PathParams]
+pub struct CapabilityParams {
+    pub capability: String,
+}
+
+// render the role to capability matrix
+#[get(path = "/admin/capabilities")]
+pub async fn admin_capabilities_page(
+    user: &CurrentUser,
+    capabilities: &CapabilityServiceContainer,
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    require_capability(user, Capability::ManageCapabilities)?;
+    let listing = capabilities.0.list_capabilities().await?;
+
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Capabilities");
+    context.insert("capabilities", &listing);
+    context.insert("roles", &UserRole::ALL);
+    let body: Html = template.render("admin/capabilities.html", &context)?.into();
+
+    Ok(Response::ok().set_typed_body(body))
+}
+
+// handler which lists every capability with the roles it is granted to
+#[get(path = "/api/admin/capabilities")]
+pub async fn list_capabilities(
+    user: &CurrentUser,
+    capabilities: &CapabilityServiceContainer,
+) -> Result<ApiResponse<Vec<CapabilityResponse>>, ApiError> {
+    require_capability(user, Capability::ManageCapabilities)?;
+    let listing = capabilities.0.list_capabilities().await?;
+
+    Ok(ApiResponse::ok(listing))
+}
+
+// handler which replaces the roles a capability is granted to
+#[patch(path = "/api/admin/capabilities/{capability}")]
+pub async fn update_capability_roles(
+    user: &CurrentUser,
+    params: &PathParams<CapabilityParams>,
+    body: &JsonBody<UpdateCapabilityRolesRequest>,
+    capabilities: &CapabilityServiceContainer,
+) -> Result<ApiResponse<CapabilityResponse>, ApiError> {
+    require_capability(user, Capability::ManageCapabilities)?;
+    let capability =
+        Capability::frm_str(&params.0.capability).ok_or(CapabilityError::CapabilityNotFound)?;
+    let capability_response = capabilities
+        .0
+        .update_capability_roles(user.id, capability, body.0.clone())
+        .await?;
+
+    Ok(ApiResponse::ok_with_message(
+        capability_response,
+        "Capability updated",
+    ))
+}
@@ -1,21 +1,26 @@
 // app/src/routes/admin/dashboard.rs
 
 // dependencies
-use crate::authorization::{CurrentUser, require_admin};
+use crate::authorization::{CurrentUser, csrf_token, require_capability};
 use crate::errors::ApiError;
-use pavex::{get, Response, response::body::Html};
+use crate::models::Capability;
+use pavex::{Response, get, response::body::Html};
+use pavex_session::Session;
 use pavex_tera_template::{Context, TemplateEngine};
 
-
 // handler which returns the admin dashboard, if the user has the proper role
 #[get(path = "/admin")]
-pub async fn admin_dashboard(user: &CurrentUser, template: &TemplateEngine) -> Result<Response, ApiError> {
-    require_admin(user)?;
+pub async fn admin_dashboard(
+    user: &CurrentUser,
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    require_capability(user, Capability::AccessAdmin)?;
     let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
     context.insert("title", "Admin");
     context.insert("username", &user.username);
     let body: Html = template.render("admin/index.html", &context)?.into();
 
     Ok(Response::ok().set_typed_body(body))
 }
-
@@ -0,0 +1,136 @@
+// app/src/routes/admin/invitations.rs
+
+// dependencies
+use crate::authorization::{CurrentUser, csrf_token, require_capability};
+use crate::configuration::RegistrationConfig;
+use crate::email::{EmailError, MailerContainer};
+use crate::errors::ApiError;
+use crate::models::{
+    Capability, CreateInvitationRequest, CreatedInvitationResponse, InvitationResponse,
+};
+use crate::response::ApiResponse;
+use crate::routes::admin::InvitationServiceContainer;
+use pavex::request::path::PathParams;
+use pavex::{Response, delete, get, post, request::body::JsonBody, response::body::Html};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+use tracing_log_error::log_error;
+use uuid::Uuid;
+
+// struct type to represent the id of the invitation an admin action applies to
+#[PathParams]
+pub struct InvitationIdParams {
+    pub id: Uuid,
+}
+
+// render the invitation management screen
+#[get(path = "/admin/invitations")]
+pub async fn admin_invitations_page(
+    user: &CurrentUser,
+    invitations: &InvitationServiceContainer,
+    registration: &RegistrationConfig,
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let listing = invitations.0.list_invitations().await?;
+
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Invitations");
+    context.insert("invitations", &listing);
+    context.insert("mode", &registration.mode);
+    context.insert(
+        "roles",
+        &["admin", "editor", "author", "contributor", "subscriber"],
+    );
+    let body: Html = template.render("admin/invitations.html", &context)?.into();
+
+    Ok(Response::ok().set_typed_body(body))
+}
+
+// handler which lists every invitation, most recent first
+#[get(path = "/api/admin/invitations")]
+pub async fn list_invitations(
+    user: &CurrentUser,
+    invitations: &InvitationServiceContainer,
+) -> Result<ApiResponse<Vec<InvitationResponse>>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let listing = invitations.0.list_invitations().await?;
+
+    Ok(ApiResponse::ok(listing))
+}
+
+// handler which invites someone to register with a pre-assigned role, mailing them the link
+#[post(path = "/api/admin/invitations")]
+pub async fn create_invitation(
+    user: &CurrentUser,
+    body: &JsonBody<CreateInvitationRequest>,
+    invitations: &InvitationServiceContainer,
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+) -> Result<ApiResponse<CreatedInvitationResponse>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let create_invitation_request = body.0.clone();
+    let created = invitations
+        .0
+        .create_invitation(user.id, create_invitation_request)
+        .await?;
+
+    // The response carries the token too, so the admin can pass the link on by other means
+    if let Err(e) = send_invitation_email(mailer, template, &user.username, &created).await {
+        log_error!(e, "Failed to send the invitation email");
+    }
+
+    Ok(ApiResponse::ok_with_message(created, "Invitation sent"))
+}
+
+// handler which revokes an invitation which was not accepted yet
+#[delete(path = "/api/admin/invitations/{id}")]
+pub async fn revoke_invitation(
+    user: &CurrentUser,
+    params: &PathParams<InvitationIdParams>,
+    invitations: &InvitationServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    invitations
+        .0
+        .revoke_invitation(user.id, params.0.id)
+        .await?;
+
+    Ok(ApiResponse::ok_with_message((), "Invitation revoked"))
+}
+
+// render and send the email which carries the invite link
+async fn send_invitation_email(
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+    invited_by: &str,
+    invitation: &CreatedInvitationResponse,
+) -> Result<(), EmailError> {
+    let mut context = Context::new();
+    context.insert("invited_by", invited_by);
+    context.insert("role", &invitation.details.role);
+    context.insert(
+        "expires_at",
+        &invitation
+            .details
+            .expires_at
+            .strftime("%Y-%m-%d %H:%M UTC")
+            .to_string(),
+    );
+    context.insert(
+        "link",
+        &mailer.link(&format!("/register?invite={}", invitation.token)),
+    );
+
+    mailer
+        .send_template(
+            template,
+            &invitation.details.email,
+            "You are invited to Rusty Word Smith",
+            "invitation",
+            &context,
+        )
+        .await
+}
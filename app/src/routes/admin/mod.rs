@@ -1,7 +1,70 @@
 // app/src/routes/admin/mod.rs
 
 // modules
+pub mod capabilities;
 pub mod dashboard;
+pub mod invitations;
+pub mod scheduled;
+pub mod users;
 
 // re-exports
-pub use dashboard::*;
\ No newline at end of file
+pub use capabilities::*;
+pub use dashboard::*;
+pub use invitations::*;
+pub use scheduled::*;
+pub use users::*;
+
+// dependencies
+use crate::configuration::{RegistrationConfig, SessionLimitsConfig, TokenHashingConfig};
+use crate::models::{
+    CapabilityService, CapabilityServiceImpl, InvitationService, InvitationServiceImpl,
+    ScheduleService, ScheduleServiceImpl, SqlxCapabilityRepository, SqlxInvitationRepository,
+    SqlxScheduleRepository,
+};
+use crate::tokens::TokenHasher;
+use pavex::methods;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+// struct type to wrap an invitation service in a container
+pub struct InvitationServiceContainer(pub Box<dyn InvitationService>);
+
+#[methods]
+impl InvitationServiceContainer {
+    #[singleton]
+    pub fn new(
+        pool: &PgPool,
+        registration: &RegistrationConfig,
+        tokens: &TokenHashingConfig,
+    ) -> Self {
+        let repository = Arc::new(SqlxInvitationRepository::new(pool.clone()));
+        let service = InvitationServiceImpl::new(repository, TokenHasher::new(tokens))
+            .with_registration(registration.clone());
+        InvitationServiceContainer(Box::new(service))
+    }
+}
+
+// struct type to wrap the capability registry in a container, shared so its cache is too
+pub struct CapabilityServiceContainer(pub Box<dyn CapabilityService>);
+
+#[methods]
+impl CapabilityServiceContainer {
+    #[singleton]
+    pub fn new(pool: &PgPool, limits: &SessionLimitsConfig) -> Self {
+        let repository = Arc::new(SqlxCapabilityRepository::new(pool.clone()));
+        let service = CapabilityServiceImpl::new(repository).with_config(limits.clone());
+        CapabilityServiceContainer(Box::new(service))
+    }
+}
+
+// struct type to wrap the schedule service in a container, for the scheduled view
+pub struct ScheduleServiceContainer(pub Box<dyn ScheduleService>);
+
+#[methods]
+impl ScheduleServiceContainer {
+    #[singleton]
+    pub fn new(pool: &PgPool) -> Self {
+        let repository = Arc::new(SqlxScheduleRepository::new(pool.clone()));
+        ScheduleServiceContainer(Box::new(ScheduleServiceImpl::new(repository)))
+    }
+}
@@ -0,0 +1,43 @@
+// app/src/routes/admin/scheduled.rs
+
+// dependencies
+use crate::authorization::{CurrentUser, csrf_token, require_capability};
+use crate::errors::ApiError;
+use crate::models::{Capability, ScheduledContent};
+use crate::response::ApiResponse;
+use crate::routes::admin::ScheduleServiceContainer;
+use pavex::{Response, get, response::body::Html};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+
+// render the posts and pages waiting for their publication date
+#[get(path = "/admin/scheduled")]
+pub async fn admin_scheduled_page(
+    user: &CurrentUser,
+    schedule: &ScheduleServiceContainer,
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    require_capability(user, Capability::AccessAdmin)?;
+    let scheduled = schedule.0.list_scheduled().await?;
+
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Scheduled");
+    context.insert("scheduled", &scheduled);
+    let body: Html = template.render("admin/scheduled.html", &context)?.into();
+
+    Ok(Response::ok().set_typed_body(body))
+}
+
+// handler which lists the posts and pages waiting for their publication date, soonest first
+#[get(path = "/api/admin/scheduled")]
+pub async fn list_scheduled(
+    user: &CurrentUser,
+    schedule: &ScheduleServiceContainer,
+) -> Result<ApiResponse<Vec<ScheduledContent>>, ApiError> {
+    require_capability(user, Capability::AccessAdmin)?;
+    let scheduled = schedule.0.list_scheduled().await?;
+
+    Ok(ApiResponse::ok(scheduled))
+}
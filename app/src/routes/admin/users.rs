@@ -0,0 +1,268 @@
+// app/src/routes/admin/users.rs
+
+// dependencies
+use crate::authorization::{CurrentUser, csrf_token, require_capability};
+use crate::email::MailerContainer;
+use crate::errors::ApiError;
+use crate::models::{
+    AccountDeletionResponse, Capability, DeletionMode, EraseAccountQuery, RevokedSessionsResponse,
+    ScheduleDeletionRequest, SetActiveRequest, UpdateRoleRequest, UserListQuery, UserListResponse,
+    UserResponse,
+};
+use crate::response::ApiResponse;
+use crate::routes::account::{
+    PrivacyServiceContainer, SessionServiceContainer, export_response, send_deletion_notice,
+};
+use crate::routes::auth::UserServiceContainer;
+use pavex::request::path::PathParams;
+use pavex::request::query::QueryParams;
+use pavex::{Response, delete, get, patch, post, request::body::JsonBody, response::body::Html};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+use uuid::Uuid;
+
+// struct type to represent the id of the user an admin action applies to
+#[PathParams]
+pub struct UserIdParams {
+    pub id: Uuid,
+}
+
+// render the user management screen
+#[get(path = "/admin/users")]
+pub async fn admin_users_page(
+    user: &CurrentUser,
+    params: &QueryParams<UserListQuery>,
+    user_service: &UserServiceContainer,
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let query = params.0.clone();
+    let listing = user_service.0.list_users(query.clone()).await?;
+
+    let last_page =
+        ((listing.total + i64::from(listing.per_page) - 1) / i64::from(listing.per_page)).max(1);
+
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Users");
+    context.insert("current_user_id", &user.id);
+    context.insert("users", &listing.users);
+    context.insert("total", &listing.total);
+    context.insert("page", &listing.page);
+    context.insert("per_page", &listing.per_page);
+    context.insert("has_prev", &(listing.page > 1));
+    context.insert("has_next", &(i64::from(listing.page) < last_page));
+    context.insert("role", &query.role);
+    context.insert("search", &query.search_term());
+    context.insert(
+        "roles",
+        &["admin", "editor", "author", "contributor", "subscriber"],
+    );
+    let body: Html = template.render("admin/users.html", &context)?.into();
+
+    Ok(Response::ok().set_typed_body(body))
+}
+
+// handler which returns a page of users, optionally filtered by role or search term
+#[get(path = "/api/admin/users")]
+pub async fn list_users(
+    user: &CurrentUser,
+    params: &QueryParams<UserListQuery>,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<UserListResponse>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let listing = user_service.0.list_users(params.0.clone()).await?;
+
+    Ok(ApiResponse::ok(listing))
+}
+
+// handler which changes the role of a user
+#[patch(path = "/api/admin/users/{id}/role")]
+pub async fn change_user_role(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    body: &JsonBody<UpdateRoleRequest>,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<UserResponse>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let user_response = user_service
+        .0
+        .change_role(user.id, params.0.id, body.0.role)
+        .await?;
+
+    Ok(ApiResponse::ok_with_message(user_response, "Role updated"))
+}
+
+// handler which activates or deactivates a user
+#[patch(path = "/api/admin/users/{id}/active")]
+pub async fn set_user_active(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    body: &JsonBody<SetActiveRequest>,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<UserResponse>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let user_response = user_service
+        .0
+        .set_active(user.id, params.0.id, body.0.is_active)
+        .await?;
+
+    let message = if user_response.is_active {
+        "User activated"
+    } else {
+        "User deactivated"
+    };
+    Ok(ApiResponse::ok_with_message(user_response, message))
+}
+
+// handler which marks the email address of a user as verified
+#[post(path = "/api/admin/users/{id}/verify-email")]
+pub async fn force_verify_user_email(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<UserResponse>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let user_response = user_service.0.force_verify_email(params.0.id).await?;
+
+    Ok(ApiResponse::ok_with_message(
+        user_response,
+        "Email marked as verified",
+    ))
+}
+
+// handler which lifts a login lockout caused by repeated failed attempts
+#[post(path = "/api/admin/users/{id}/unlock")]
+pub async fn unlock_user(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    user_service.0.unlock_user(params.0.id).await?;
+
+    Ok(ApiResponse::ok_with_message((), "User unlocked"))
+}
+
+// handler which removes the authenticator of a user who lost it, so they can enroll again
+#[post(path = "/api/admin/users/{id}/2fa/reset")]
+pub async fn reset_user_two_factor(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    user_service.0.reset_two_factor(params.0.id).await?;
+
+    Ok(ApiResponse::ok_with_message(
+        (),
+        "Two-factor authentication reset",
+    ))
+}
+
+// handler which signs a user out of every device
+#[delete(path = "/api/admin/users/{id}/sessions")]
+pub async fn revoke_user_sessions(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    user_service: &UserServiceContainer,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<RevokedSessionsResponse>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    // Make sure the account exists, so the admin gets a 404 for a stale id
+    user_service.0.get_user(params.0.id).await?;
+    let revoked = sessions.0.revoke_all_sessions(params.0.id).await?;
+
+    Ok(ApiResponse::ok_with_message(
+        RevokedSessionsResponse { revoked },
+        "User signed out everywhere",
+    ))
+}
+
+// handler which downloads every piece of personal data held about a user
+#[get(path = "/api/admin/users/{id}/export")]
+pub async fn export_user_data(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    privacy: &PrivacyServiceContainer,
+) -> Result<Response, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let archive = privacy.0.export_data(params.0.id).await?;
+
+    Ok(export_response(archive, params.0.id))
+}
+
+// handler which returns the pending deletion of a user, if any
+#[get(path = "/api/admin/users/{id}/deletion")]
+pub async fn get_user_deletion(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    privacy: &PrivacyServiceContainer,
+) -> Result<ApiResponse<Option<AccountDeletionResponse>>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let deletion = privacy.0.pending_deletion(params.0.id).await?;
+
+    Ok(ApiResponse::ok(deletion))
+}
+
+// handler which schedules the deletion of a user, once the grace period is over
+#[post(path = "/api/admin/users/{id}/deletion")]
+pub async fn schedule_user_deletion(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    body: &JsonBody<ScheduleDeletionRequest>,
+    user_service: &UserServiceContainer,
+    privacy: &PrivacyServiceContainer,
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+) -> Result<ApiResponse<AccountDeletionResponse>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let profile = user_service.0.get_user(params.0.id).await?;
+    let deletion = privacy
+        .0
+        .schedule_deletion(user.id, params.0.id, body.0.mode)
+        .await?;
+    send_deletion_notice(mailer, template, &profile.email, &deletion).await;
+
+    Ok(ApiResponse::ok_with_message(
+        deletion,
+        "Account deletion scheduled",
+    ))
+}
+
+// handler which cancels the pending deletion of a user
+#[delete(path = "/api/admin/users/{id}/deletion")]
+pub async fn cancel_user_deletion(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    privacy: &PrivacyServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    privacy.0.cancel_deletion(user.id, params.0.id).await?;
+
+    Ok(ApiResponse::ok_with_message(
+        (),
+        "Account deletion cancelled",
+    ))
+}
+
+// handler which deletes or anonymizes a user right away, handing their content over
+// to the placeholder author
+#[delete(path = "/api/admin/users/{id}")]
+pub async fn delete_user(
+    user: &CurrentUser,
+    params: &PathParams<UserIdParams>,
+    query: &QueryParams<EraseAccountQuery>,
+    privacy: &PrivacyServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    require_capability(user, Capability::ManageUsers)?;
+    let mode = query.0.mode.unwrap_or(DeletionMode::Delete);
+    privacy.0.erase_account(user.id, params.0.id, mode).await?;
+
+    let message = match mode {
+        DeletionMode::Delete => "User deleted",
+        DeletionMode::Anonymize => "User anonymized",
+    };
+    Ok(ApiResponse::ok_with_message((), message))
+}
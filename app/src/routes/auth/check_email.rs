@@ -1,16 +1,20 @@
 // app/src/routes/auth/check_email.rs
 
 // dependencies
+use crate::authorization::csrf_token;
 use crate::errors::ApiError;
-use pavex::{get, Response, response::body::Html};
+use pavex::{Response, get, response::body::Html};
+use pavex_session::Session;
 use pavex_tera_template::{Context, TemplateEngine};
 
 #[get(path = "/auth/check-email")]
-pub fn check_email_page(template: &TemplateEngine) -> Result<Response, ApiError> {
+pub async fn check_email_page(
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
     let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
     context.insert("title", "Check your email");
     let body: Html = template.render("auth/check_email.html", &context)?.into();
     Ok(Response::ok().set_typed_body(body))
 }
-
-
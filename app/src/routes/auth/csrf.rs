@@ -0,0 +1,25 @@
+// app/src/routes/auth/csrf.rs
+
+// dependencies
+use crate::authorization::csrf_token;
+use crate::errors::ApiError;
+use crate::response::ApiResponse;
+use pavex::get;
+use pavex_session::Session;
+use serde::Serialize;
+
+// struct type to represent the CSRF token of a session
+#[derive(Debug, Serialize)]
+pub struct CsrfTokenResponse {
+    pub token: String,
+}
+
+// handler which hands out the CSRF token of the session, for clients not served with a page
+#[get(path = "/auth/csrf")]
+pub async fn get_csrf_token(
+    session: &mut Session<'_>,
+) -> Result<ApiResponse<CsrfTokenResponse>, ApiError> {
+    let token = csrf_token(session).await;
+
+    Ok(ApiResponse::ok(CsrfTokenResponse { token }))
+}
@@ -0,0 +1,105 @@
+// app/src/routes/auth/email_change.rs
+
+// dependencies
+use super::UserServiceContainer;
+use crate::authorization::csrf_token;
+use crate::email::MailerContainer;
+use crate::errors::ApiError;
+use crate::models::EmailChangeTokenRequest;
+use crate::response::ApiResponse;
+use pavex::request::query::QueryParams;
+use pavex::{Response, get, post, request::body::JsonBody, response::body::Html};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+use serde::Deserialize;
+use tracing_log_error::log_error;
+
+// struct type to represent the query string of an email change link
+#[derive(Deserialize)]
+pub struct EmailChangeParams {
+    pub token: String,
+}
+
+// handler which swaps in the new email address, then tells the old one how to undo it
+#[post(path = "/auth/confirm-email-change")]
+pub async fn confirm_email_change(
+    body: &JsonBody<EmailChangeTokenRequest>,
+    user_service: &UserServiceContainer,
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+) -> Result<ApiResponse<()>, ApiError> {
+    let confirm_request = body.0.clone();
+    let (change, undo_token) = user_service.0.confirm_email_change(confirm_request).await?;
+
+    let mut context = Context::new();
+    context.insert("new_email", &change.new_email);
+    context.insert(
+        "link",
+        &mailer.link(&format!("/auth/undo-email-change?token={undo_token}")),
+    );
+    if let Err(e) = mailer
+        .send_template(
+            template,
+            &change.old_email,
+            "Your email address was changed",
+            "email_change_notice",
+            &context,
+        )
+        .await
+    {
+        log_error!(e, "Failed to send the email change notice");
+    }
+
+    Ok(ApiResponse::ok_with_message((), "Email address changed"))
+}
+
+// handler which restores the email address an account had before a change
+#[post(path = "/auth/undo-email-change")]
+pub async fn undo_email_change(
+    body: &JsonBody<EmailChangeTokenRequest>,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    let undo_request = body.0.clone();
+    user_service.0.undo_email_change(undo_request).await?;
+
+    Ok(ApiResponse::ok_with_message(
+        (),
+        "Email address restored, please log in and change your password",
+    ))
+}
+
+// render the page linked from an email change confirmation
+#[get(path = "/auth/confirm-email-change")]
+pub async fn confirm_email_change_page(
+    template: &TemplateEngine,
+    params: &QueryParams<EmailChangeParams>,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Confirm email change");
+    context.insert("token", &params.0.token);
+
+    let body: Html = template
+        .render("auth/confirm_email_change.html", &context)?
+        .into();
+    Ok(Response::ok().set_typed_body(body))
+}
+
+// render the page linked from an email change notice
+#[get(path = "/auth/undo-email-change")]
+pub async fn undo_email_change_page(
+    template: &TemplateEngine,
+    params: &QueryParams<EmailChangeParams>,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Undo email change");
+    context.insert("token", &params.0.token);
+
+    let body: Html = template
+        .render("auth/undo_email_change.html", &context)?
+        .into();
+    Ok(Response::ok().set_typed_body(body))
+}
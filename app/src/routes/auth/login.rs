@@ -1,45 +1,105 @@
 // app/src/routes/auth/login.rs
 
 // dependencies
-use crate::authorization::{USERNAME, USER_ID, USER_ROLE};
+use super::UserServiceContainer;
+use crate::authorization::{PendingLogin, PendingStep, SessionClient, csrf_token, sign_in};
 use crate::errors::ApiError;
-use crate::models::{LoginRequest, UserSummary};
+use crate::models::{LoginRequest, LoginResponse, UserSummary};
+use crate::oidc::OidcClient;
+use crate::rate_limit::{ClientIp, RateLimitedRoute, RateLimiter};
 use crate::response::ApiResponse;
-use pavex::{get, post, request::body::JsonBody, Response, response::body::Html};
-use pavex_tera_template::{Context, TemplateEngine};
+use crate::routes::account::{PasskeyServiceContainer, SessionServiceContainer};
+use pavex::http::header::LOCATION;
+use pavex::http::{HeaderValue, StatusCode};
+use pavex::{Response, get, post, request::body::JsonBody, response::body::Html};
 use pavex_session::Session;
-use super::UserServiceContainer;
+use pavex_tera_template::{Context, TemplateEngine};
 
-// handler which will be called when the user visits the login page
+// handler which checks the password, then either signs the user in or asks for a second factor
 #[post(path = "/auth/login")]
 pub async fn login(
     body: &JsonBody<LoginRequest>,
     session: &mut Session<'_>,
     user_service: &UserServiceContainer,
-) -> Result<ApiResponse<UserSummary>, ApiError> {
+    passkeys: &PasskeyServiceContainer,
+    rate_limiter: &RateLimiter,
+    client_ip: &ClientIp,
+    client: &SessionClient,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<LoginResponse>, ApiError> {
+    rate_limiter
+        .check(RateLimitedRoute::Login, &client_ip.to_string())
+        .await?;
+
     let login_request = body.0.clone();
-    let user_summary = user_service.0.login(login_request).await?;
+    let user_summary = user_service.0.login(login_request, client_ip.0).await?;
+    let login_response = begin_session(
+        session,
+        user_service,
+        passkeys,
+        user_summary,
+        client,
+        sessions,
+    )
+    .await?;
 
-    session.cycle_id();
+    Ok(ApiResponse::ok(login_response))
+}
 
-    session.insert(USER_ID, user_summary.id).await.unwrap();
-    session
-        .insert(USERNAME, user_summary.username.clone())
-        .await
-        .unwrap();
-    session
-        .insert(USER_ROLE, user_summary.role)
-        .await
-        .unwrap();
+/// Sign in a user whose first factor has been checked, or leave a pending login in the
+/// session if a second factor is still to be checked or set up.
+pub(crate) async fn begin_session(
+    session: &mut Session<'_>,
+    user_service: &UserServiceContainer,
+    passkeys: &PasskeyServiceContainer,
+    user_summary: UserSummary,
+    client: &SessionClient,
+    sessions: &SessionServiceContainer,
+) -> Result<LoginResponse, ApiError> {
+    // Until the second factor is checked the session only holds a pending login
+    let two_factor = user_service.0.two_factor_status(user_summary.id).await?;
+    // A passkey satisfies enforced two-factor authentication as well as an authenticator app
+    let verifiable = two_factor.enabled
+        || (two_factor.required && passkeys.0.has_passkeys(user_summary.id).await?);
+    let login_response = if verifiable {
+        PendingLogin::new(user_summary.id, PendingStep::Verify)
+            .store(session)
+            .await;
+        LoginResponse::TwoFactorRequired
+    } else if two_factor.required {
+        PendingLogin::new(user_summary.id, PendingStep::Setup)
+            .store(session)
+            .await;
+        LoginResponse::TwoFactorSetupRequired
+    } else {
+        sign_in(session, &user_summary, client, sessions).await?;
+        LoginResponse::Complete(user_summary)
+    };
 
-    Ok(ApiResponse::ok(user_summary))
+    Ok(login_response)
+}
+
+/// Send the browser on to the page a login continues with, for sign-ins completed by a redirect.
+pub(crate) fn redirect_after_login(login_response: &LoginResponse) -> Response {
+    let location = match login_response {
+        LoginResponse::Complete(_) => "/admin",
+        LoginResponse::TwoFactorRequired => "/auth/2fa",
+        LoginResponse::TwoFactorSetupRequired => "/auth/2fa/setup",
+    };
+    Response::new(StatusCode::SEE_OTHER).insert_header(LOCATION, HeaderValue::from_static(location))
 }
 
 // render the login page
 #[get(path = "/login")]
-pub fn login_page(template: &TemplateEngine) -> Result<Response, ApiError> {
+pub async fn login_page(
+    template: &TemplateEngine,
+    oidc: &OidcClient,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
     let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
     context.insert("title", "Login");
+    context.insert("providers", &oidc.providers());
 
     let body: Html = template.render("auth/login.html", &context)?.into();
     let response = Response::ok().set_typed_body(body);
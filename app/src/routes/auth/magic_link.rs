@@ -0,0 +1,118 @@
+// app/src/routes/auth/magic_link.rs
+
+// dependencies
+use super::{MagicLinkServiceContainer, UserServiceContainer, begin_session};
+use crate::authorization::{SessionClient, csrf_token};
+use crate::configuration::MagicLinkConfig;
+use crate::email::MailerContainer;
+use crate::errors::ApiError;
+use crate::models::{LoginResponse, MagicLinkError, MagicLinkRequest, MagicLinkTokenRequest};
+use crate::rate_limit::{ClientIp, RateLimitedRoute, RateLimiter};
+use crate::response::ApiResponse;
+use crate::routes::account::{PasskeyServiceContainer, SessionServiceContainer};
+use pavex::request::query::QueryParams;
+use pavex::{Response, get, post, request::body::JsonBody, response::body::Html};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+use serde::Deserialize;
+use tracing_log_error::log_error;
+
+// struct type to represent the query string of a sign-in link
+#[derive(Deserialize)]
+pub struct MagicLinkParams {
+    pub token: String,
+}
+
+// handler which emails a sign-in link, without revealing whether the account exists
+#[post(path = "/auth/magic-link")]
+pub async fn request_magic_link(
+    body: &JsonBody<MagicLinkRequest>,
+    magic_links: &MagicLinkServiceContainer,
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+    rate_limiter: &RateLimiter,
+    client_ip: &ClientIp,
+    config: &MagicLinkConfig,
+) -> Result<Response, ApiError> {
+    let magic_link_request = body.0.clone();
+    let email = magic_link_request.normalized_email().to_string();
+    rate_limiter
+        .check(RateLimitedRoute::MagicLinkIp, &client_ip.to_string())
+        .await?;
+    rate_limiter
+        .check(RateLimitedRoute::MagicLink, &email)
+        .await?;
+
+    // Delegate to service, which validates the request and issues the token
+    match magic_links.0.request_link(magic_link_request).await {
+        Ok(Some(token)) => {
+            let mut context = Context::new();
+            context.insert("link", &mailer.link(&format!("/auth/magic?token={token}")));
+            context.insert("expires_in_minutes", &(config.link_ttl.as_secs() / 60));
+            match mailer.compose(
+                template,
+                &email,
+                "Your sign-in link",
+                "magic_link",
+                &context,
+            ) {
+                Ok(message) => mailer.send_in_background(message),
+                Err(e) => log_error!(e, "Failed to render the sign-in link email"),
+            }
+            Ok(Response::no_content())
+        }
+        Ok(None) => Ok(Response::no_content()),
+        // Malformed input is reported, anything else maps to 204 to avoid enumeration
+        Err(e @ MagicLinkError::Validation { .. }) => Err(e.into()),
+        Err(e) => {
+            log_error!(e, "Failed to issue a sign-in link");
+            Ok(Response::no_content())
+        }
+    }
+}
+
+// handler which signs in the account an emailed link was sent to
+#[post(path = "/auth/magic")]
+pub async fn magic_link_login(
+    body: &JsonBody<MagicLinkTokenRequest>,
+    session: &mut Session<'_>,
+    magic_links: &MagicLinkServiceContainer,
+    user_service: &UserServiceContainer,
+    passkeys: &PasskeyServiceContainer,
+    client: &SessionClient,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<LoginResponse>, ApiError> {
+    let user_id = magic_links.0.redeem_link(&body.0.token).await?;
+
+    // The link stands in for the password, a second factor still applies
+    let user_summary = user_service.0.get_user_summary(user_id).await?;
+    let login_response = begin_session(
+        session,
+        user_service,
+        passkeys,
+        user_summary,
+        client,
+        sessions,
+    )
+    .await?;
+
+    Ok(ApiResponse::ok(login_response))
+}
+
+// render the page linked from a sign-in email, which only signs in once the button is pressed
+//
+// Mail scanners and link previews follow links on their own, so opening one must not use it up.
+#[get(path = "/auth/magic")]
+pub async fn magic_link_page(
+    template: &TemplateEngine,
+    params: &QueryParams<MagicLinkParams>,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Sign in");
+    context.insert("token", &params.0.token);
+
+    let body: Html = template.render("auth/magic_link.html", &context)?.into();
+    Ok(Response::ok().set_typed_body(body))
+}
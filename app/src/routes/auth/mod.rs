@@ -1,25 +1,49 @@
 // app/src/routes/auth/mod.rs
 
 // modules
+pub mod csrf;
+pub mod email_change;
 pub mod login;
+pub mod magic_link;
+pub mod oidc;
+pub mod passkey;
 pub mod logout;
+pub mod password_reset;
 pub mod verify;
 pub mod resend;
 pub mod check_email;
 pub mod register;
+pub mod two_factor;
 pub mod whoami;
 
 // re-exports
+pub use csrf::*;
+pub use email_change::*;
 pub use login::*;
+pub use magic_link::*;
+pub use oidc::*;
+pub use passkey::*;
 pub use logout::*;
+pub use password_reset::*;
 pub use resend::*;
 pub use check_email::*;
 pub use register::*;
+pub use two_factor::*;
 pub use verify::*;
 pub use whoami::*;
 
 // dependencies
-use crate::models::{SqlxUserRepository, UserService, UserServiceImpl};
+use crate::configuration::{
+    LockoutConfig, MagicLinkConfig, PasswordHashingConfig, RegistrationConfig, TokenHashingConfig,
+    TwoFactorConfig,
+};
+use crate::models::{
+    IdentityService, IdentityServiceImpl, MagicLinkService, MagicLinkServiceImpl, PasswordHashing,
+    SqlxIdentityRepository, SqlxMagicLinkRepository, SqlxUserRepository, UserError, UserService,
+    UserServiceImpl,
+};
+use crate::sealing::SecretSealer;
+use crate::tokens::TokenHasher;
 use pavex::methods;
 use sqlx::PgPool;
 use std::sync::Arc;
@@ -30,9 +54,52 @@ pub struct UserServiceContainer(pub Box<dyn UserService>);
 #[methods]
 impl UserServiceContainer {
     #[singleton]
-    pub fn new(pool: &PgPool) -> Self {
-        let repository = Arc::new(SqlxUserRepository::new(pool.clone()));
-        let service = UserServiceImpl::new(repository);
-        UserServiceContainer(Box::new(service))
+    pub fn new(
+        pool: &PgPool,
+        lockout: &LockoutConfig,
+        two_factor: &TwoFactorConfig,
+        hashing: &PasswordHashingConfig,
+        registration: &RegistrationConfig,
+        tokens: &TokenHashingConfig,
+    ) -> Result<Self, UserError> {
+        let repository = Arc::new(
+            SqlxUserRepository::new(pool.clone()).with_hashing(PasswordHashing::new(hashing)?),
+        );
+        let service = UserServiceImpl::with_lockout(
+            repository,
+            lockout.clone(),
+            TokenHasher::new(tokens),
+            SecretSealer::new(two_factor),
+        )
+        .with_two_factor(two_factor.clone())
+        .with_registration(registration.clone());
+        Ok(UserServiceContainer(Box::new(service)))
+    }
+}
+
+// struct type to wrap an identity service in a container
+pub struct IdentityServiceContainer(pub Box<dyn IdentityService>);
+
+#[methods]
+impl IdentityServiceContainer {
+    #[singleton]
+    pub fn new(pool: &PgPool, registration: &RegistrationConfig) -> Self {
+        let repository = Arc::new(SqlxIdentityRepository::new(pool.clone()));
+        let service = IdentityServiceImpl::new(repository).with_registration(registration.clone());
+        IdentityServiceContainer(Box::new(service))
+    }
+}
+
+// struct type to wrap a sign-in link service in a container
+pub struct MagicLinkServiceContainer(pub Box<dyn MagicLinkService>);
+
+#[methods]
+impl MagicLinkServiceContainer {
+    #[singleton]
+    pub fn new(pool: &PgPool, config: &MagicLinkConfig, tokens: &TokenHashingConfig) -> Self {
+        let repository = Arc::new(SqlxMagicLinkRepository::new(pool.clone()));
+        let service = MagicLinkServiceImpl::new(repository, TokenHasher::new(tokens))
+            .with_config(config.clone());
+        MagicLinkServiceContainer(Box::new(service))
     }
 }
@@ -0,0 +1,111 @@
+// app/src/routes/auth/oidc.rs
+
+// dependencies
+use super::{IdentityServiceContainer, UserServiceContainer, begin_session, redirect_after_login};
+use crate::authorization::SessionClient;
+use crate::configuration::SiteConfig;
+use crate::errors::ApiError;
+use crate::oidc::{OidcClient, OidcError, PendingOidcLogin};
+use crate::routes::account::{PasskeyServiceContainer, SessionServiceContainer};
+use pavex::http::header::LOCATION;
+use pavex::http::{HeaderValue, StatusCode};
+use pavex::request::path::PathParams;
+use pavex::request::query::QueryParams;
+use pavex::{Response, get};
+use pavex_session::Session;
+
+// struct type to represent the identity provider a sign-in goes through
+#[PathParams]
+pub struct OidcProviderParams {
+    pub provider: String,
+}
+
+// struct type to represent the parameters an identity provider calls back with
+#[derive(serde::Deserialize)]
+pub struct OidcCallbackParams {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+// handler which sends the browser to the identity provider
+#[get(path = "/auth/oidc/{provider}/login")]
+pub async fn oidc_login(
+    params: &PathParams<OidcProviderParams>,
+    session: &mut Session<'_>,
+    oidc: &OidcClient,
+    site: &SiteConfig,
+) -> Result<Response, ApiError> {
+    let provider = &params.0.provider;
+    let (authorization_url, pending) = oidc.start(provider, &callback_url(site, provider)).await?;
+    pending.store(session).await;
+
+    Ok(redirect(&authorization_url))
+}
+
+// handler which completes a sign-in the identity provider sent the browser back from
+#[get(path = "/auth/oidc/{provider}/callback")]
+pub async fn oidc_callback(
+    params: &PathParams<OidcProviderParams>,
+    query: &QueryParams<OidcCallbackParams>,
+    session: &mut Session<'_>,
+    oidc: &OidcClient,
+    site: &SiteConfig,
+    identities: &IdentityServiceContainer,
+    user_service: &UserServiceContainer,
+    passkeys: &PasskeyServiceContainer,
+    client: &SessionClient,
+    sessions: &SessionServiceContainer,
+) -> Result<Response, ApiError> {
+    let provider = &params.0.provider;
+    let callback = &query.0;
+
+    // The pending sign-in is taken whatever the outcome, so a state is only ever used once
+    let pending = PendingOidcLogin::take(session)
+        .await
+        .filter(|pending| {
+            pending.matches(
+                provider,
+                callback.state.as_deref().unwrap_or_default(),
+                oidc.login_timeout(),
+            )
+        })
+        .ok_or(OidcError::InvalidState)?;
+    if let Some(error) = &callback.error {
+        let reason = callback.error_description.as_deref().unwrap_or(error);
+        return Err(OidcError::Denied(reason.to_string()).into());
+    }
+    let code = callback
+        .code
+        .as_deref()
+        .ok_or_else(|| OidcError::Provider("no authorization code was returned".into()))?;
+
+    let identity = oidc
+        .finish(&pending, code, &callback_url(site, provider))
+        .await?;
+    let user_summary = identities.0.sign_in(identity).await?;
+    let login_response = begin_session(
+        session,
+        user_service,
+        passkeys,
+        user_summary,
+        client,
+        sessions,
+    )
+    .await?;
+
+    Ok(redirect_after_login(&login_response))
+}
+
+// the address the identity provider sends the browser back to
+fn callback_url(site: &SiteConfig, provider: &str) -> String {
+    site.link(&format!("/auth/oidc/{provider}/callback"))
+}
+
+fn redirect(location: &str) -> Response {
+    match HeaderValue::from_str(location) {
+        Ok(location) => Response::new(StatusCode::SEE_OTHER).insert_header(LOCATION, location),
+        Err(_) => Response::new(StatusCode::BAD_GATEWAY),
+    }
+}
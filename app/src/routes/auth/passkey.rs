@@ -0,0 +1,137 @@
+// app/src/routes/auth/passkey.rs
+
+// dependencies
+use super::UserServiceContainer;
+use crate::authorization::{
+    CeremonyState, PasskeyCeremony, PendingLogin, PendingStep, SessionClient, sign_in,
+};
+use crate::configuration::{TwoFactorConfig, WebauthnConfig};
+use crate::errors::ApiError;
+use crate::models::{
+    FinishPasskeyLoginRequest, LoginResponse, PasskeyError, PasskeyLoginState,
+    StartPasskeyLoginRequest,
+};
+use crate::rate_limit::{ClientIp, RateLimitedRoute, RateLimiter};
+use crate::response::ApiResponse;
+use crate::routes::account::{PasskeyServiceContainer, SessionServiceContainer};
+use pavex::{post, request::body::JsonBody};
+use pavex_session::Session;
+use uuid::Uuid;
+use webauthn_rs::prelude::{PasskeyAuthentication, RequestChallengeResponse};
+
+// the passkey sign-in started by this browser, for `user_id` if given
+async fn take_ceremony<S: CeremonyState>(
+    session: &mut Session<'_>,
+    user_id: Option<Uuid>,
+    webauthn: &WebauthnConfig,
+) -> Result<PasskeyCeremony<S>, ApiError> {
+    let ceremony = PasskeyCeremony::take(session, webauthn.ceremony_timeout)
+        .await
+        .filter(|ceremony| user_id.is_none_or(|id| ceremony.user_id == id))
+        .ok_or(PasskeyError::InvalidCredentials)?;
+
+    Ok(ceremony)
+}
+
+// handler which challenges the passkeys of an account, to sign in without a password
+#[post(path = "/auth/passkey/start")]
+pub async fn start_passkey_login(
+    body: &JsonBody<StartPasskeyLoginRequest>,
+    session: &mut Session<'_>,
+    passkeys: &PasskeyServiceContainer,
+    rate_limiter: &RateLimiter,
+    client_ip: &ClientIp,
+) -> Result<ApiResponse<RequestChallengeResponse>, ApiError> {
+    rate_limiter
+        .check(RateLimitedRoute::Login, &client_ip.to_string())
+        .await?;
+
+    let login_request = body.0.clone();
+    let (user_id, challenge, state) = passkeys.0.start_login(login_request).await?;
+    PasskeyCeremony::new(user_id, state).store(session).await;
+
+    Ok(ApiResponse::ok(challenge))
+}
+
+// handler which signs in with the passkey that answered the challenge
+//
+// A passkey proves possession and user verification at once, so no second factor follows.
+#[post(path = "/auth/passkey/finish")]
+pub async fn finish_passkey_login(
+    body: &JsonBody<FinishPasskeyLoginRequest>,
+    session: &mut Session<'_>,
+    user_service: &UserServiceContainer,
+    passkeys: &PasskeyServiceContainer,
+    webauthn: &WebauthnConfig,
+    client: &SessionClient,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<LoginResponse>, ApiError> {
+    let ceremony: PasskeyCeremony<PasskeyLoginState> =
+        take_ceremony(session, None, webauthn).await?;
+    // A decoy challenge, sent when there was no passkey to challenge, is never answered
+    let state = ceremony.state.ok_or(PasskeyError::InvalidCredentials)?;
+    passkeys
+        .0
+        .finish_authentication(ceremony.user_id, &body.0.credential, &state)
+        .await?;
+
+    // The account may have been disabled while the browser answered the challenge
+    let user_summary = user_service.0.get_login_summary(ceremony.user_id).await?;
+    sign_in(session, &user_summary, client, sessions).await?;
+
+    Ok(ApiResponse::ok(LoginResponse::Complete(user_summary)))
+}
+
+// handler which challenges the passkeys of a login pending on its second factor
+#[post(path = "/auth/2fa/passkey/start")]
+pub async fn start_passkey_two_factor(
+    session: &mut Session<'_>,
+    passkeys: &PasskeyServiceContainer,
+    config: &TwoFactorConfig,
+) -> Result<ApiResponse<RequestChallengeResponse>, ApiError> {
+    let pending = PendingLogin::load(session, config.pending_timeout)
+        .await
+        .filter(|pending| pending.step == PendingStep::Verify)
+        .ok_or_else(|| ApiError::Unauthorized("No login is awaiting a second factor".into()))?;
+
+    let (challenge, state) = passkeys.0.start_authentication(pending.user_id).await?;
+    PasskeyCeremony::new(pending.user_id, state)
+        .store(session)
+        .await;
+
+    Ok(ApiResponse::ok(challenge))
+}
+
+// handler which completes a login pending on its second factor with a passkey
+#[post(path = "/auth/2fa/passkey/finish")]
+pub async fn finish_passkey_two_factor(
+    body: &JsonBody<FinishPasskeyLoginRequest>,
+    session: &mut Session<'_>,
+    user_service: &UserServiceContainer,
+    passkeys: &PasskeyServiceContainer,
+    rate_limiter: &RateLimiter,
+    config: &TwoFactorConfig,
+    webauthn: &WebauthnConfig,
+    client: &SessionClient,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<LoginResponse>, ApiError> {
+    let pending = PendingLogin::load(session, config.pending_timeout)
+        .await
+        .filter(|pending| pending.step == PendingStep::Verify)
+        .ok_or_else(|| ApiError::Unauthorized("No login is awaiting a second factor".into()))?;
+    rate_limiter
+        .check(RateLimitedRoute::TwoFactor, &pending.user_id.to_string())
+        .await?;
+
+    let ceremony: PasskeyCeremony<PasskeyAuthentication> =
+        take_ceremony(session, Some(pending.user_id), webauthn).await?;
+    passkeys
+        .0
+        .finish_authentication(pending.user_id, &body.0.credential, &ceremony.state)
+        .await?;
+
+    let user_summary = user_service.0.get_login_summary(pending.user_id).await?;
+    sign_in(session, &user_summary, client, sessions).await?;
+
+    Ok(ApiResponse::ok(LoginResponse::Complete(user_summary)))
+}
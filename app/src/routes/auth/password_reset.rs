@@ -0,0 +1,127 @@
+// app/src/routes/auth/password_reset.rs
+
+// dependencies
+use super::UserServiceContainer;
+use crate::authorization::csrf_token;
+use crate::email::MailerContainer;
+use crate::errors::ApiError;
+use crate::models::{ForgotPasswordRequest, ResetPasswordRequest, UserError};
+use crate::rate_limit::{ClientIp, RateLimitedRoute, RateLimiter};
+use crate::response::ApiResponse;
+use pavex::request::query::QueryParams;
+use pavex::{Response, get, post, request::body::JsonBody, response::body::Html};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+use serde::Deserialize;
+use tracing_log_error::log_error;
+
+// struct type to represent the query string of a password reset link
+#[derive(Deserialize)]
+pub struct ResetPasswordParams {
+    pub token: String,
+}
+
+// handler which issues a password reset token, without revealing whether the account exists
+#[post(path = "/auth/forgot-password")]
+pub async fn forgot_password(
+    body: &JsonBody<ForgotPasswordRequest>,
+    user_service: &UserServiceContainer,
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+    rate_limiter: &RateLimiter,
+    client_ip: &ClientIp,
+) -> Result<Response, ApiError> {
+    let forgot_password_request = body.0.clone();
+    let email = forgot_password_request.email.clone();
+    rate_limiter
+        .check(RateLimitedRoute::PasswordResetIp, &client_ip.to_string())
+        .await?;
+    rate_limiter
+        .check(RateLimitedRoute::PasswordReset, &email)
+        .await?;
+
+    // Delegate to service, which validates the request and issues the token
+    match user_service
+        .0
+        .request_password_reset(forgot_password_request)
+        .await
+    {
+        Ok(Some(token)) => {
+            let mut context = Context::new();
+            context.insert(
+                "link",
+                &mailer.link(&format!("/auth/reset-password?token={token}")),
+            );
+            match mailer.compose(
+                template,
+                &email,
+                "Reset your password",
+                "password_reset",
+                &context,
+            ) {
+                Ok(message) => mailer.send_in_background(message),
+                Err(e) => log_error!(e, "Failed to render the password reset email"),
+            }
+            Ok(Response::no_content())
+        }
+        Ok(None) => Ok(Response::no_content()),
+        // Malformed input is reported, anything else maps to 204 to avoid enumeration
+        Err(e @ UserError::Validation { .. }) => Err(e.into()),
+        Err(e) => {
+            log_error!(e, "Failed to issue a password reset token");
+            Ok(Response::no_content())
+        }
+    }
+}
+
+// handler which sets a new password for the account owning a valid reset token
+#[post(path = "/auth/reset-password")]
+pub async fn reset_password(
+    body: &JsonBody<ResetPasswordRequest>,
+    user_service: &UserServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    let reset_password_request = body.0.clone();
+    user_service
+        .0
+        .reset_password(reset_password_request)
+        .await?;
+
+    Ok(ApiResponse::ok_with_message(
+        (),
+        "Password has been reset, please log in",
+    ))
+}
+
+// render the forgotten password page
+#[get(path = "/auth/forgot-password")]
+pub async fn forgot_password_page(
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Forgot password");
+
+    let body: Html = template
+        .render("auth/forgot_password.html", &context)?
+        .into();
+    Ok(Response::ok().set_typed_body(body))
+}
+
+// render the page linked from a password reset email
+#[get(path = "/auth/reset-password")]
+pub async fn reset_password_page(
+    template: &TemplateEngine,
+    params: &QueryParams<ResetPasswordParams>,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Reset password");
+    context.insert("token", &params.0.token);
+
+    let body: Html = template
+        .render("auth/reset_password.html", &context)?
+        .into();
+    Ok(Response::ok().set_typed_body(body))
+}
@@ -2,33 +2,155 @@
 
 // dependencies
 use super::UserServiceContainer;
+use crate::authorization::csrf_token;
+use crate::configuration::RegistrationConfig;
+use crate::email::{EmailError, EmailMessage, MailerContainer};
 use crate::errors::ApiError;
-use crate::models::{CreateUserRequest, UserResponse};
-use pavex::{get, post, request::body::JsonBody, Response, response::body::Html};
-// no extra imports needed
+use crate::models::{CreateUserRequest, RegisterRequest, UserResponse};
+use crate::rate_limit::{ClientIp, RateLimitedRoute, RateLimiter};
+use crate::routes::admin::InvitationServiceContainer;
+use pavex::request::query::QueryParams;
+use pavex::{Response, get, post, request::body::JsonBody, response::body::Html};
+use pavex_session::Session;
 use pavex_tera_template::{Context, TemplateEngine};
+use serde::Deserialize;
+use tracing_log_error::log_error;
+
+// struct type to represent the query string of the registration page, set by invite links
+#[derive(Deserialize)]
+pub struct RegisterPageParams {
+    pub invite: Option<String>,
+}
 
 // handler which will be called when the user visits the register page
 #[post(path = "/auth/register")]
 pub async fn register(
-    body: &JsonBody<CreateUserRequest>,
+    body: &JsonBody<RegisterRequest>,
     user_service: &UserServiceContainer,
+    invitations: &InvitationServiceContainer,
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+    rate_limiter: &RateLimiter,
+    client_ip: &ClientIp,
 ) -> Result<UserResponse, ApiError> {
-    let create_user_request = body.0.clone();
+    rate_limiter
+        .check(RateLimitedRoute::Register, &client_ip.to_string())
+        .await?;
+
+    let RegisterRequest {
+        user: create_user_request,
+        invite_token,
+    } = body.0.clone();
+
+    // The invite link proves the address, so invited accounts skip email verification
+    if let Some(token) = invite_token.filter(|token| !token.trim().is_empty()) {
+        return register_invited(user_service, invitations, create_user_request, &token).await;
+    }
 
     let user_response = user_service.0.register(create_user_request).await?;
 
-    // Generate a verification token via the user service
-    let _token = user_service.0.set_verification_token(user_response.id).await?;
+    // Generate a verification token via the user service and mail it to the new user.
+    // The account exists at this point, so a delivery failure is logged rather than
+    // surfaced: the user can always ask for another verification email.
+    let token = user_service
+        .0
+        .set_verification_token(user_response.id)
+        .await?;
+    if let Err(e) = send_verification_email(
+        mailer,
+        template,
+        &user_response.email,
+        Some(user_response.username.as_str()),
+        &token,
+    )
+    .await
+    {
+        log_error!(e, "Failed to send the verification email");
+    }
 
     Ok(user_response)
 }
 
-// render the registration page
+// create an account from an invitation, handing the invitation back if that fails
+async fn register_invited(
+    user_service: &UserServiceContainer,
+    invitations: &InvitationServiceContainer,
+    create_user_request: CreateUserRequest,
+    token: &str,
+) -> Result<UserResponse, ApiError> {
+    let invitation = invitations
+        .0
+        .claim_invitation(token, &create_user_request.email)
+        .await?;
+
+    match user_service
+        .0
+        .register_invited(create_user_request, invitation.role)
+        .await
+    {
+        Ok(user_response) => {
+            invitations
+                .0
+                .complete_invitation(invitation.id, user_response.id)
+                .await?;
+            Ok(user_response)
+        }
+        Err(e) => {
+            // e.g. a taken username, which the invitee can fix and retry with the same link
+            if let Err(release_err) = invitations.0.release_invitation(invitation.id).await {
+                log_error!(release_err, "Failed to release a claimed invitation");
+            }
+            Err(e.into())
+        }
+    }
+}
+
+// render and send the email which carries the verification link
+pub(crate) async fn send_verification_email(
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+    email: &str,
+    username: Option<&str>,
+    token: &str,
+) -> Result<(), EmailError> {
+    let message = verification_email(mailer, template, email, username, token)?;
+    mailer.send(message).await
+}
+
+// render the email which carries the verification link, without sending it
+pub(crate) fn verification_email(
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+    email: &str,
+    username: Option<&str>,
+    token: &str,
+) -> Result<EmailMessage, EmailError> {
+    let mut context = Context::new();
+    context.insert("username", &username);
+    context.insert("link", &mailer.link(&format!("/auth/verify?token={token}")));
+
+    mailer.compose(
+        template,
+        email,
+        "Verify your email address",
+        "verify_email",
+        &context,
+    )
+}
+
+// render the registration page, carrying the token of an invite link along
 #[get(path = "/register")]
-pub fn register_page(template: &TemplateEngine) -> Result<Response, ApiError> {
+pub async fn register_page(
+    template: &TemplateEngine,
+    params: &QueryParams<RegisterPageParams>,
+    registration: &RegistrationConfig,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
     let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
     context.insert("title", "Register");
+    context.insert("mode", &registration.mode);
+    context.insert("invite", &params.0.invite);
 
     let body: Html = template.render("auth/register.html", &context)?.into();
     let response = Response::ok().set_typed_body(body);
@@ -1,10 +1,15 @@
 // app/src/routes/auth/resend.rs
 
 // dependencies
+use crate::email::MailerContainer;
 use crate::errors::ApiError;
+use crate::rate_limit::{RateLimitedRoute, RateLimiter};
 use super::UserServiceContainer;
+use super::register::verification_email;
 use pavex::{post, request::body::JsonBody, Response};
+use pavex_tera_template::TemplateEngine;
 use serde::Deserialize;
+use tracing_log_error::log_error;
 
 // struct type to represent a resent request
 #[derive(Deserialize)]
@@ -16,19 +21,27 @@ pub struct ResendRequest {
 pub async fn resend_verification(
     body: &JsonBody<ResendRequest>,
     user_service: &UserServiceContainer,
+    mailer: &MailerContainer,
+    template: &TemplateEngine,
+    rate_limiter: &RateLimiter,
 ) -> Result<Response, ApiError> {
     let email = body.0.email.clone();
-    // Delegate to service, which enforces rate-limiting and token issuance
+    rate_limiter
+        .check(RateLimitedRoute::ResendVerification, &email)
+        .await?;
+
+    // Delegate to service, which issues a fresh token for unverified accounts
     match user_service.0.resend_verification(&email).await {
-        Ok(()) => Ok(Response::no_content()),
-        Err(e) => {
-            // If rate-limited, return 429; else map to generic OK to avoid enumeration
-            if matches!(e, crate::models::UserError::Validation { .. }) {
-                Ok(Response::too_many_requests())
-            } else {
-                Ok(Response::no_content())
+        Ok(Some(token)) => {
+            match verification_email(mailer, template, &email, None, &token) {
+                Ok(message) => mailer.send_in_background(message),
+                Err(e) => log_error!(e, "Failed to render the verification email"),
             }
+            Ok(Response::no_content())
         }
+        Ok(None) => Ok(Response::no_content()),
+        // Map failures to the generic response to avoid enumeration
+        Err(_) => Ok(Response::no_content()),
     }
 }
 
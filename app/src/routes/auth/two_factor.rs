@@ -0,0 +1,73 @@
+// app/src/routes/auth/two_factor.rs
+
+// dependencies
+use super::UserServiceContainer;
+use crate::authorization::{PendingLogin, PendingStep, SessionClient, csrf_token, sign_in};
+use crate::configuration::TwoFactorConfig;
+use crate::errors::ApiError;
+use crate::models::{LoginResponse, TwoFactorCodeRequest};
+use crate::rate_limit::{RateLimitedRoute, RateLimiter};
+use crate::response::ApiResponse;
+use crate::routes::account::SessionServiceContainer;
+use pavex::{Response, get, post, request::body::JsonBody, response::body::Html};
+use pavex_session::Session;
+use pavex_tera_template::{Context, TemplateEngine};
+
+// handler which completes a login pending on its second factor
+#[post(path = "/auth/2fa")]
+pub async fn verify_two_factor(
+    body: &JsonBody<TwoFactorCodeRequest>,
+    session: &mut Session<'_>,
+    user_service: &UserServiceContainer,
+    rate_limiter: &RateLimiter,
+    config: &TwoFactorConfig,
+    client: &SessionClient,
+    sessions: &SessionServiceContainer,
+) -> Result<ApiResponse<LoginResponse>, ApiError> {
+    let pending = PendingLogin::load(session, config.pending_timeout)
+        .await
+        .filter(|pending| pending.step == PendingStep::Verify)
+        .ok_or_else(|| ApiError::Unauthorized("No login is awaiting a code".into()))?;
+    rate_limiter
+        .check(RateLimitedRoute::TwoFactor, &pending.user_id.to_string())
+        .await?;
+
+    let code_request = body.0.clone();
+    let user_summary = user_service
+        .0
+        .verify_second_factor(pending.user_id, code_request)
+        .await?;
+    sign_in(session, &user_summary, client, sessions).await?;
+
+    Ok(ApiResponse::ok(LoginResponse::Complete(user_summary)))
+}
+
+// render the page asking for a second factor during login
+#[get(path = "/auth/2fa")]
+pub async fn two_factor_page(
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Two-factor authentication");
+
+    let body: Html = template.render("auth/two_factor.html", &context)?.into();
+    Ok(Response::ok().set_typed_body(body))
+}
+
+// render the authenticator enrollment page, used during login and from the account page
+#[get(path = "/auth/2fa/setup")]
+pub async fn two_factor_setup_page(
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
+    let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
+    context.insert("title", "Set up two-factor authentication");
+
+    let body: Html = template
+        .render("auth/two_factor_setup.html", &context)?
+        .into();
+    Ok(Response::ok().set_typed_body(body))
+}
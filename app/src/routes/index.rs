@@ -1,14 +1,20 @@
 // app/src/routes/index.rs
 
 // dependencies
+use crate::authorization::csrf_token;
 use crate::errors::ApiError;
 use pavex::{Response, get, response::body::Html};
+use pavex_session::Session;
 use pavex_tera_template::{Context, TemplateEngine};
 
 // handler which returns the index page template
 #[get(path = "/")]
-pub fn index(template: &TemplateEngine) -> Result<Response, ApiError> {
+pub async fn index(
+    template: &TemplateEngine,
+    session: &mut Session<'_>,
+) -> Result<Response, ApiError> {
     let mut context = Context::new();
+    context.insert("csrf_token", &csrf_token(session).await);
     context.insert("title", "Home");
     // Provide a default message to satisfy test templates
     context.insert("message", "Hello, world!");
@@ -1,8 +1,10 @@
 // app/src/routes/mod.rs
 
 // modules
+pub mod account;
 pub mod admin;
 pub mod auth;
 pub mod index;
 pub mod ping;
+pub mod posts;
 pub mod static_server;
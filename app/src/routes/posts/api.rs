@@ -0,0 +1,219 @@
+// app/src/routes/posts/api.rs
+
+// dependencies
+use crate::authorization::{
+    CurrentUser, require_capability, require_capability_over, require_scope,
+};
+use crate::content::ContentFormat;
+use crate::errors::ApiError;
+use crate::models::{
+    Capability, CreatePostRequest, PostError, PostFormatQuery, PostListQuery, PostListResponse,
+    PostResponse, PostVisibility, SlugLookup, TokenScope, UpdatePostRequest,
+};
+use crate::response::ApiResponse;
+use crate::routes::posts::PostServiceContainer;
+use pavex::http::header::{CONTENT_TYPE, LOCATION};
+use pavex::http::{HeaderValue, StatusCode};
+use pavex::request::RequestHead;
+use pavex::request::body::BufferedBody;
+use pavex::request::path::PathParams;
+use pavex::request::query::QueryParams;
+use pavex::{IntoResponse, Response, delete, get, patch, post};
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+// media type of Markdown, which post writes accept as well as JSON
+const MARKDOWN: &str = "text/markdown";
+// media type of JSON, the other body post writes accept
+const JSON: &str = "application/json";
+
+// struct type to represent the id of the post a request applies to
+#[PathParams]
+pub struct PostIdParams {
+    pub id: Uuid,
+}
+
+// struct type to represent the slug of the post a request applies to
+#[PathParams]
+pub struct PostSlugParams {
+    pub slug: String,
+}
+
+// handler which lists posts, drafts of others only for those who may edit them
+#[get(path = "/api/posts")]
+pub async fn list_posts(
+    params: &QueryParams<PostListQuery>,
+    user: &CurrentUser,
+    posts: &PostServiceContainer,
+) -> Result<ApiResponse<PostListResponse>, ApiError> {
+    require_scope(user, TokenScope::PostsRead)?;
+    let visibility = if user.has_capability(Capability::EditOthersPosts) {
+        PostVisibility::All
+    } else {
+        PostVisibility::PublishedOrOwnedBy(user.id)
+    };
+    let listing = posts.0.list_posts(params.0.clone(), visibility).await?;
+
+    Ok(ApiResponse::ok(listing))
+}
+
+// handler which creates a post authored by the signed-in user, from JSON or a Markdown document
+#[post(path = "/api/posts")]
+pub async fn create_post(
+    request_head: &RequestHead,
+    body: &BufferedBody,
+    user: &CurrentUser,
+    posts: &PostServiceContainer,
+) -> Result<ApiResponse<PostResponse>, ApiError> {
+    require_capability(user, Capability::EditPosts)?;
+    let create_post_request = parse_body(request_head, body, CreatePostRequest::from_markdown)?;
+    if create_post_request.status.requires_publishing() {
+        require_capability(user, Capability::PublishPosts)?;
+    }
+    let post = posts.0.create_post(user.id, create_post_request).await?;
+
+    Ok(ApiResponse::ok_with_message(post, "Post created"))
+}
+
+// handler which returns a post, unpublished posts only to those who may edit them
+#[get(path = "/api/posts/{id}")]
+pub async fn get_post(
+    params: &PathParams<PostIdParams>,
+    query: &QueryParams<PostFormatQuery>,
+    user: &CurrentUser,
+    posts: &PostServiceContainer,
+) -> Result<Response, ApiError> {
+    require_scope(user, TokenScope::PostsRead)?;
+    let post = posts.0.get_post(params.0.id).await?;
+    require_readable(user, &post)?;
+
+    Ok(post_response(post, query.0.format))
+}
+
+// handler which returns a post by its slug, the old slugs of renamed posts redirect to the new one
+#[get(path = "/api/posts/slug/{slug}")]
+pub async fn get_post_by_slug(
+    params: &PathParams<PostSlugParams>,
+    query: &QueryParams<PostFormatQuery>,
+    request_head: &RequestHead,
+    user: &CurrentUser,
+    posts: &PostServiceContainer,
+) -> Result<Response, ApiError> {
+    require_scope(user, TokenScope::PostsRead)?;
+    match posts.0.find_post_by_slug(&params.0.slug).await? {
+        SlugLookup::Found(post) => {
+            require_readable(user, &post)?;
+            Ok(post_response(post, query.0.format))
+        }
+        SlugLookup::Moved(post) => {
+            require_readable(user, &post)?;
+            let mut location = format!("/api/posts/slug/{}", post.slug);
+            if let Some(query) = request_head.target.query() {
+                location.push('?');
+                location.push_str(query);
+            }
+            // Only a query the client sent could make it invalid, so it is reported as theirs
+            let location = HeaderValue::from_str(&location).map_err(|_| PostError::Validation {
+                message: "The query cannot be carried over to the new location".to_string(),
+            })?;
+            Ok(Response::new(StatusCode::MOVED_PERMANENTLY).insert_header(LOCATION, location))
+        }
+    }
+}
+
+// handler which updates a post, publishing or editing a published post takes `publish_posts`
+#[patch(path = "/api/posts/{id}")]
+pub async fn update_post(
+    params: &PathParams<PostIdParams>,
+    request_head: &RequestHead,
+    body: &BufferedBody,
+    user: &CurrentUser,
+    posts: &PostServiceContainer,
+) -> Result<ApiResponse<PostResponse>, ApiError> {
+    let post = posts.0.get_post(params.0.id).await?;
+    require_capability_over(user, Capability::EditPosts, post.author_id)?;
+    let update_post_request = parse_body(request_head, body, UpdatePostRequest::from_markdown)?;
+    let publishing = update_post_request
+        .status
+        .is_some_and(|status| status.requires_publishing());
+    if publishing || post.status.requires_publishing() {
+        require_capability(user, Capability::PublishPosts)?;
+    }
+    let post = posts.0.update_post(post.id, update_post_request).await?;
+
+    Ok(ApiResponse::ok_with_message(post, "Post updated"))
+}
+
+// handler which deletes a post for good
+#[delete(path = "/api/posts/{id}")]
+pub async fn delete_post(
+    params: &PathParams<PostIdParams>,
+    user: &CurrentUser,
+    posts: &PostServiceContainer,
+) -> Result<ApiResponse<()>, ApiError> {
+    let post = posts.0.get_post(params.0.id).await?;
+    require_capability_over(user, Capability::DeletePosts, post.author_id)?;
+    posts.0.delete_post(post.id).await?;
+
+    Ok(ApiResponse::ok_with_message((), "Post deleted"))
+}
+
+// unpublished posts are not found, rather than forbidden, for those who may not edit them
+fn require_readable(user: &CurrentUser, post: &PostResponse) -> Result<(), ApiError> {
+    if !post.status.is_public()
+        && require_capability_over(user, Capability::EditPosts, post.author_id).is_err()
+    {
+        return Err(PostError::PostNotFound.into());
+    }
+    Ok(())
+}
+
+// a post as JSON, or as a Markdown document
+fn post_response(post: PostResponse, format: ContentFormat) -> Response {
+    match format {
+        ContentFormat::Document => ApiResponse::ok(post).into_response(),
+        ContentFormat::Markdown => Response::ok()
+            .set_typed_body(post.to_markdown().into_bytes())
+            .insert_header(
+                CONTENT_TYPE,
+                HeaderValue::from_static("text/markdown; charset=utf-8"),
+            ),
+    }
+}
+
+// the request carried by the body of a post write, a Markdown document or JSON
+fn parse_body<T: DeserializeOwned>(
+    request_head: &RequestHead,
+    body: &BufferedBody,
+    from_markdown: fn(&str) -> Result<T, PostError>,
+) -> Result<T, ApiError> {
+    let media_type = request_head
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(str::trim);
+
+    match media_type {
+        Some(media_type) if media_type.eq_ignore_ascii_case(MARKDOWN) => {
+            let markdown = std::str::from_utf8(&body.bytes).map_err(|_| PostError::Validation {
+                message: "Markdown must be UTF-8".to_string(),
+            })?;
+            Ok(from_markdown(markdown)?)
+        }
+        Some(media_type) if media_type.eq_ignore_ascii_case(JSON) => {
+            serde_json::from_slice(&body.bytes).map_err(|e| {
+                PostError::Validation {
+                    message: format!("Invalid JSON body: {e}"),
+                }
+                .into()
+            })
+        }
+        // Anything else would be parsed as JSON by guesswork, it is refused instead
+        other => Err(PostError::UnsupportedMediaType(format!(
+            "{}, send {JSON} or {MARKDOWN}",
+            other.unwrap_or("none")
+        ))
+        .into()),
+    }
+}
@@ -0,0 +1,32 @@
+// app/src/routes/posts/mod.rs
+
+// modules
+pub mod api;
+
+// re-exports
+pub use api::*;
+
+// dependencies
+use crate::configuration::ContentCacheConfig;
+use crate::models::{
+    PostService, PostServiceImpl, SlugServiceImpl, SqlxPostRepository, SqlxSlugRepository,
+};
+use pavex::methods;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+// struct type to wrap a post service in a container, shared so its listing cache is too
+pub struct PostServiceContainer(pub Box<dyn PostService>);
+
+#[methods]
+impl PostServiceContainer {
+    #[singleton]
+    pub fn new(pool: &PgPool, cache: &ContentCacheConfig) -> Self {
+        let repository = Arc::new(SqlxPostRepository::new(pool.clone()));
+        let slugs = Arc::new(SlugServiceImpl::new(Arc::new(SqlxSlugRepository::new(
+            pool.clone(),
+        ))));
+        let service = PostServiceImpl::new(repository, slugs).with_config(cache.clone());
+        PostServiceContainer(Box::new(service))
+    }
+}
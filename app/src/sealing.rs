@@ -0,0 +1,153 @@
+// app/src/sealing.rs
+
+// dependencies
+use crate::configuration::{PgPool, TwoFactorConfig};
+use aes_gcm_siv::aead::{Aead, KeyInit, Payload};
+use aes_gcm_siv::{Aes256GcmSiv, Nonce};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use data_encoding::HEXLOWER;
+use hmac::{Hmac, Mac};
+use secrecy::ExposeSecret;
+use sha2::Sha256;
+use sqlx::Row;
+use uuid::Uuid;
+
+/// Marks stored values made by `SecretSealer::seal`, anything else predates encryption
+pub const SEAL_PREFIX: &str = "aes256gcmsiv:";
+
+// the nonce of AES-GCM-SIV, random per sealed value
+const NONCE_BYTES: usize = 12;
+// derives the encryption key from the configured one, which may have any length
+const SEAL_KEY_LABEL: &[u8] = b"sealed secrets v1";
+
+/// Encrypts the secrets which have to be read back later, such as an authenticator's.
+///
+/// Unlike a token, such a secret cannot be stored as a hash. Encrypting it keeps a copy of the
+/// database, or a backup of it, from being enough to produce second factor codes.
+#[derive(Clone)]
+pub struct SecretSealer {
+    key: Vec<u8>,
+}
+
+impl SecretSealer {
+    pub fn new(config: &TwoFactorConfig) -> Self {
+        let key = config
+            .sealing_key
+            .as_ref()
+            .map(|key| key.expose_secret().as_bytes().to_vec())
+            .unwrap_or_default();
+        Self { key }
+    }
+
+    /// Whether a key is configured, without one sealed values open with an empty key
+    pub fn is_keyed(&self) -> bool {
+        !self.key.is_empty()
+    }
+
+    /// Encrypt a secret for storage.
+    ///
+    /// The value is bound to `owner`, so a sealed value copied onto another row does not open.
+    pub fn seal(&self, secret: &str, owner: Uuid) -> String {
+        let mut nonce = [0u8; NONCE_BYTES];
+        OsRng.fill_bytes(&mut nonce);
+        let payload = Payload {
+            msg: secret.as_bytes(),
+            aad: owner.as_bytes(),
+        };
+        let sealed = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), payload)
+            .expect("AES-GCM-SIV encrypts messages of any reasonable size");
+        format!(
+            "{SEAL_PREFIX}{}{}",
+            HEXLOWER.encode(&nonce),
+            HEXLOWER.encode(&sealed)
+        )
+    }
+
+    /// Decrypt a value made by `seal` for the same `owner`
+    pub fn open(&self, sealed: &str, owner: Uuid) -> Option<String> {
+        let sealed = HEXLOWER
+            .decode(sealed.strip_prefix(SEAL_PREFIX)?.as_bytes())
+            .ok()?;
+        if sealed.len() < NONCE_BYTES {
+            return None;
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_BYTES);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: owner.as_bytes(),
+        };
+        let secret = self
+            .cipher()
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .ok()?;
+        String::from_utf8(secret).ok()
+    }
+
+    /// Whether a stored value is already sealed
+    pub fn is_sealed(stored: &str) -> bool {
+        stored.starts_with(SEAL_PREFIX)
+    }
+
+    fn cipher(&self) -> Aes256GcmSiv {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC takes keys of any size");
+        mac.update(SEAL_KEY_LABEL);
+        Aes256GcmSiv::new_from_slice(&mac.finalize().into_bytes())
+            .expect("HMAC-SHA256 gives a 256-bit key")
+    }
+}
+
+/// Encrypt the authenticator secrets stored in clear text by earlier versions, returning how many.
+///
+/// Sealed values are left alone, so this runs on every start, and replicas starting together
+/// never seal a value twice.
+pub async fn seal_stored_secrets(pool: &PgPool, sealer: &SecretSealer) -> Result<u64, sqlx::Error> {
+    let select =
+        format!("SELECT user_id, secret FROM user_totp WHERE secret NOT LIKE '{SEAL_PREFIX}%'");
+    let mut sealed = 0;
+    for row in sqlx::query(&select).fetch_all(pool).await? {
+        let user_id: Uuid = row.get("user_id");
+        let secret: String = row.get("secret");
+        sealed +=
+            sqlx::query("UPDATE user_totp SET secret = $1 WHERE user_id = $2 AND secret = $3")
+                .bind(sealer.seal(&secret, user_id))
+                .bind(user_id)
+                .bind(&secret)
+                .execute(pool)
+                .await?
+                .rows_affected();
+    }
+    Ok(sealed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::SecretString;
+
+    fn sealer(key: &str) -> SecretSealer {
+        SecretSealer::new(&TwoFactorConfig {
+            sealing_key: Some(SecretString::from(key)),
+            ..TwoFactorConfig::default()
+        })
+    }
+
+    #[test]
+    fn sealed_secrets_open_only_with_the_key_and_owner() {
+        let owner = Uuid::new_v4();
+        let sealed = sealer("one").seal("JBSWY3DPEHPK3PXP", owner);
+
+        assert!(SecretSealer::is_sealed(&sealed));
+        assert!(!sealed.contains("JBSWY3DPEHPK3PXP"));
+        assert_ne!(sealed, sealer("one").seal("JBSWY3DPEHPK3PXP", owner));
+        assert_eq!(
+            sealer("one").open(&sealed, owner).as_deref(),
+            Some("JBSWY3DPEHPK3PXP")
+        );
+        assert_eq!(sealer("two").open(&sealed, owner), None);
+        assert_eq!(sealer("one").open(&sealed, Uuid::new_v4()), None);
+        assert_eq!(sealer("one").open("JBSWY3DPEHPK3PXP", owner), None);
+    }
+}
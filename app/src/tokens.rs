@@ -0,0 +1,190 @@
+// app/src/tokens.rs
+
+// dependencies
+use crate::configuration::{PgPool, TokenHashingConfig};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use data_encoding::{BASE32_NOPAD, HEXLOWER};
+use hmac::{Hmac, Mac};
+use secrecy::ExposeSecret;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+/// Number of random bytes in a token, 256 bits
+pub const TOKEN_BYTES: usize = 32;
+
+/// Marks stored values made by `TokenHasher::hash`, anything else predates keyed hashing
+pub const HASH_PREFIX: &str = "hmac-sha256:";
+
+/// Generates the one-time tokens mailed out in links, and the keyed hashes they are stored as.
+///
+/// Only the hash reaches the database, so reading it, or a backup of it, is not enough to use a
+/// link without the key as well. Lookups by hash reveal nothing through their timing, since an
+/// attacker cannot choose the hash they make the database compare; checks done here compare in
+/// constant time all the same.
+#[derive(Clone)]
+pub struct TokenHasher {
+    key: Vec<u8>,
+}
+
+impl TokenHasher {
+    pub fn new(config: &TokenHashingConfig) -> Self {
+        let key = config
+            .secret
+            .as_ref()
+            .map(|secret| secret.expose_secret().as_bytes().to_vec())
+            .unwrap_or_default();
+        Self { key }
+    }
+
+    /// Whether a secret is configured, without one the hashes can be recomputed from the tokens
+    pub fn is_keyed(&self) -> bool {
+        !self.key.is_empty()
+    }
+
+    /// Generate a new token, random enough to be the whole secret of a link
+    pub fn generate() -> String {
+        let mut bytes = [0u8; TOKEN_BYTES];
+        OsRng.fill_bytes(&mut bytes);
+        BASE32_NOPAD.encode(&bytes).to_lowercase()
+    }
+
+    /// Hash a token for storage
+    pub fn hash(&self, token: &str) -> String {
+        self.hash_digest(&Sha256::digest(token.as_bytes()))
+    }
+
+    /// Check a token against a stored hash
+    pub fn verify(&self, token: &str, stored: &str) -> bool {
+        self.hash(token).as_bytes().ct_eq(stored.as_bytes()).into()
+    }
+
+    /// Whether a stored value is already a keyed hash
+    pub fn is_hashed(stored: &str) -> bool {
+        stored.starts_with(HASH_PREFIX)
+    }
+
+    /// Rehash a plain SHA-256 hex digest, as invite and sign-in links used to be stored
+    pub fn upgrade_digest(&self, digest: &str) -> Option<String> {
+        let digest = HEXLOWER.decode(digest.as_bytes()).ok()?;
+        (digest.len() == 32).then(|| self.hash_digest(&digest))
+    }
+
+    // the token is digested first, so that older SHA-256 digests can be upgraded without it
+    fn hash_digest(&self, digest: &[u8]) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC takes keys of any size");
+        mac.update(digest);
+        format!(
+            "{HASH_PREFIX}{}",
+            HEXLOWER.encode(&mac.finalize().into_bytes())
+        )
+    }
+}
+
+// enum type to represent how a column stored its tokens before they were hashed with a key
+#[derive(Clone, Copy)]
+enum Legacy {
+    Plain,
+    Sha256,
+}
+
+// every column holding one-time tokens, with the way it used to hold them
+const TOKEN_COLUMNS: [(&str, &str, Legacy); 7] = [
+    ("users", "email_verification_token", Legacy::Plain),
+    ("users", "password_reset_token", Legacy::Plain),
+    ("email_changes", "confirm_token", Legacy::Plain),
+    ("email_changes", "undo_token", Legacy::Plain),
+    ("invitations", "token_hash", Legacy::Sha256),
+    ("magic_links", "token_hash", Legacy::Sha256),
+    ("user_recovery_codes", "code_hash", Legacy::Sha256),
+];
+
+/// Replace the tokens stored before keyed hashing with their keyed hash, returning how many.
+///
+/// Links mailed out earlier keep working. Values which are hashed already are left alone, so
+/// this runs on every start, and replicas starting together never hash a value twice.
+pub async fn upgrade_stored_tokens(
+    pool: &PgPool,
+    hasher: &TokenHasher,
+) -> Result<u64, sqlx::Error> {
+    let mut upgraded = 0;
+    for (table, column, legacy) in TOKEN_COLUMNS {
+        let select = format!(
+            "SELECT id, {column} AS stored FROM {table} \
+             WHERE {column} IS NOT NULL AND {column} NOT LIKE '{HASH_PREFIX}%'"
+        );
+        let update = format!("UPDATE {table} SET {column} = $1 WHERE id = $2 AND {column} = $3");
+        for row in sqlx::query(&select).fetch_all(pool).await? {
+            let id: Uuid = row.get("id");
+            let stored: String = row.get("stored");
+            let hash = match legacy {
+                Legacy::Plain => Some(hasher.hash(&stored)),
+                Legacy::Sha256 => hasher.upgrade_digest(&stored),
+            };
+            // A digest which does not parse can never match a token, it is left to expire
+            let Some(hash) = hash else {
+                continue;
+            };
+            upgraded += sqlx::query(&update)
+                .bind(hash)
+                .bind(id)
+                .bind(&stored)
+                .execute(pool)
+                .await?
+                .rows_affected();
+        }
+    }
+    Ok(upgraded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::SecretString;
+
+    fn hasher(secret: &str) -> TokenHasher {
+        TokenHasher::new(&TokenHashingConfig {
+            secret: Some(SecretString::from(secret)),
+            ..TokenHashingConfig::default()
+        })
+    }
+
+    #[test]
+    fn tokens_carry_256_random_bits() {
+        let token = TokenHasher::generate();
+        assert_eq!(
+            BASE32_NOPAD
+                .decode(token.to_uppercase().as_bytes())
+                .unwrap()
+                .len(),
+            32
+        );
+        assert_ne!(token, TokenHasher::generate());
+    }
+
+    #[test]
+    fn hashes_depend_on_the_secret() {
+        let token = TokenHasher::generate();
+        let stored = hasher("one").hash(&token);
+
+        assert!(TokenHasher::is_hashed(&stored));
+        assert!(!stored.contains(&token));
+        assert!(hasher("one").verify(&token, &stored));
+        assert!(!hasher("two").verify(&token, &stored));
+        assert!(!TokenHasher::new(&TokenHashingConfig::default()).verify(&token, &stored));
+        assert!(!hasher("one").verify("other", &stored));
+    }
+
+    #[test]
+    fn legacy_digests_upgrade_to_the_hash_of_their_token() {
+        let hasher = hasher("secret");
+        let token = TokenHasher::generate();
+        let digest = HEXLOWER.encode(&Sha256::digest(token.as_bytes()));
+
+        assert_eq!(hasher.upgrade_digest(&digest), Some(hasher.hash(&token)));
+        assert_eq!(hasher.upgrade_digest("not-hex"), None);
+        assert!(!TokenHasher::is_hashed(&digest));
+    }
+}
@@ -0,0 +1,36 @@
+// server/src/account_deletion.rs
+
+// dependencies
+use app::configuration::{AccountDeletionConfig, PgPool};
+use app::models::{PrivacyService, PrivacyServiceImpl, SqlxPrivacyRepository};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{MissedTickBehavior, interval};
+use tracing_log_error::log_error;
+
+/// Carry out account deletions whose grace period is over, every `sweep_every`.
+///
+/// Every replica runs a sweeper, erasing an account twice is harmless.
+pub fn spawn_account_deletion_sweeper(
+    pool: PgPool,
+    config: AccountDeletionConfig,
+) -> JoinHandle<()> {
+    let sweep_every = config.sweep_every;
+    let repository = Arc::new(SqlxPrivacyRepository::new(pool));
+    let service = PrivacyServiceImpl::new(repository).with_deletion(config);
+
+    tokio::spawn(async move {
+        let mut ticks = interval(sweep_every);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            match service.process_due_deletions().await {
+                Ok(0) => {}
+                Ok(erased) => {
+                    tracing::info!(accounts.erased = erased, "Account deletion sweep done")
+                }
+                Err(e) => log_error!(e, "Failed to carry out due account deletions"),
+            }
+        }
+    })
+}
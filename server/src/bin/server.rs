@@ -6,8 +6,12 @@ use app::configuration::{StaticServer, TemplateEngine};
 use pavex::config::ConfigLoader;
 use pavex::server::{Server, ServerHandle, ShutdownMode};
 use server::{
+    account_deletion::spawn_account_deletion_sweeper,
     configuration::Profile,
+    scheduler::spawn_scheduler,
+    secret_sealing::seal_authenticator_secrets,
     telemetry::{get_subscriber, init_telemetry},
+    token_upgrade::upgrade_one_time_tokens,
 };
 use server_sdk::{ApplicationConfig, ApplicationState, run};
 use std::time::Duration;
@@ -47,6 +51,10 @@ async fn _main() -> anyhow::Result<()> {
     let template_engine = TemplateEngine::from_config(&config.templateconfig)?;
     let static_server = StaticServer::from_config(config.staticserverconfig.clone());
     let db_pool = config.databaseconfig.get_database_pool().await;
+    upgrade_one_time_tokens(&db_pool, &config.tokenhashing).await?;
+    seal_authenticator_secrets(&db_pool, &config.twofactor).await?;
+    spawn_account_deletion_sweeper(db_pool.clone(), config.accountdeletion.clone());
+    spawn_scheduler(db_pool.clone(), config.scheduler.clone());
 
     let application_state = ApplicationState::new(config, db_pool, template_engine, static_server)
         .await
@@ -1,2 +1,6 @@
+pub mod account_deletion;
 pub mod configuration;
+pub mod scheduler;
+pub mod secret_sealing;
 pub mod telemetry;
+pub mod token_upgrade;
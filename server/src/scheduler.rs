@@ -0,0 +1,45 @@
+// server/src/scheduler.rs
+
+// dependencies
+use app::configuration::{PgPool, SchedulerConfig};
+use app::models::{ContentCacheHook, ScheduleService, ScheduleServiceImpl, SqlxScheduleRepository};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{MissedTickBehavior, interval};
+use tracing_log_error::log_error;
+
+/// The schedule service the scheduler runs, with the publish hooks every replica registers.
+pub fn schedule_service(pool: PgPool) -> ScheduleServiceImpl {
+    let repository = Arc::new(SqlxScheduleRepository::new(pool));
+    ScheduleServiceImpl::new(repository.clone())
+        .with_hook(Arc::new(ContentCacheHook::new(repository)))
+}
+
+/// Publish scheduled posts and pages whose date has come, every `publish_every`.
+///
+/// Every replica runs a scheduler, due rows are claimed with `SKIP LOCKED` so each one is
+/// published, and its publish hooks run, by a single replica.
+pub fn spawn_scheduler(pool: PgPool, config: SchedulerConfig) -> JoinHandle<()> {
+    let service = schedule_service(pool);
+
+    tokio::spawn(async move {
+        let mut ticks = interval(config.publish_every);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            match service.publish_due().await {
+                Ok(published) => {
+                    for content in published {
+                        tracing::info!(
+                            content.kind = ?content.kind,
+                            content.id = %content.id,
+                            content.slug = %content.slug,
+                            "Scheduled content published"
+                        );
+                    }
+                }
+                Err(e) => log_error!(e, "Failed to publish scheduled content"),
+            }
+        }
+    })
+}
@@ -0,0 +1,31 @@
+// server/src/secret_sealing.rs
+
+// dependencies
+use anyhow::Context;
+use app::configuration::{PgPool, TwoFactorConfig};
+use app::sealing::{SecretSealer, seal_stored_secrets};
+
+/// Encrypt the authenticator secrets stored by earlier versions, before the server takes requests.
+///
+/// Without a sealing key a copy of the database would be enough to produce second factor codes,
+/// so the server refuses to start.
+pub async fn seal_authenticator_secrets(
+    pool: &PgPool,
+    config: &TwoFactorConfig,
+) -> anyhow::Result<()> {
+    let sealer = SecretSealer::new(config);
+    if !sealer.is_keyed() {
+        anyhow::bail!("No authenticator sealing key is configured, set PX_TWOFACTOR__SEALING_KEY");
+    }
+
+    let sealed = seal_stored_secrets(pool, &sealer)
+        .await
+        .context("Failed to encrypt the stored authenticator secrets")?;
+    if sealed > 0 {
+        tracing::info!(
+            secrets.sealed = sealed,
+            "Stored authenticator secrets encrypted"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+// server/src/token_upgrade.rs
+
+// dependencies
+use anyhow::Context;
+use app::configuration::{PgPool, TokenHashingConfig};
+use app::tokens::{TokenHasher, upgrade_stored_tokens};
+
+/// Hash the one-time tokens stored by earlier versions, before the server takes requests.
+pub async fn upgrade_one_time_tokens(
+    pool: &PgPool,
+    config: &TokenHashingConfig,
+) -> anyhow::Result<()> {
+    let hasher = TokenHasher::new(config);
+    if !hasher.is_keyed() {
+        // Without a key, a copy of the database is enough to check guesses against
+        if config.require_secret {
+            anyhow::bail!(
+                "No token hashing secret is configured, set PX_TOKENHASHING__SECRET or turn off `tokenhashing.require_secret` for development"
+            );
+        }
+        tracing::warn!(
+            "No token hashing secret is configured, set PX_TOKENHASHING__SECRET so that stored tokens cannot be checked against guesses"
+        );
+    }
+
+    let upgraded = upgrade_stored_tokens(pool, &hasher)
+        .await
+        .context("Failed to hash the stored one-time tokens")?;
+    if upgraded > 0 {
+        tracing::info!(tokens.upgraded = upgraded, "Stored one-time tokens hashed");
+    }
+    Ok(())
+}
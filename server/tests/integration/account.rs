@@ -0,0 +1,108 @@
+// server/tests/integration/account.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use pavex::http::StatusCode;
+use reqwest::header;
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn me_requires_a_session() {
+    let app = TestApi::spawn().await;
+
+    let r = app.get_me().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let r = app.patch_me(&json!({ "bio": "hello" })).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let r = app
+        .post_change_password("whatever", "new_password123")
+        .await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn me_returns_the_signed_in_profile() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+
+    let r = app.get_me().await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["username"], user.username);
+    assert_eq!(body["data"]["email"], user.email);
+    assert!(body["data"].get("password_hash").is_none());
+}
+
+#[tokio::test]
+async fn patch_me_updates_the_profile() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+
+    let r = app
+        .patch_me(&json!({
+            "bio": "I write about Rust",
+            "website_url": "https://example.com"
+        }))
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["bio"], "I write about Rust");
+    assert_eq!(body["data"]["website_url"], "https://example.com");
+    // Fields left out of the request are untouched
+    assert_eq!(body["data"]["display_name"], user.display_name);
+}
+
+#[tokio::test]
+async fn patch_me_rejects_invalid_fields() {
+    let app = TestApi::spawn().await;
+    app.login_verified_user().await;
+
+    let r = app.patch_me(&json!({ "website_url": "not a url" })).await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn change_password_requires_the_current_password() {
+    let app = TestApi::spawn().await;
+    app.login_verified_user().await;
+
+    let r = app
+        .post_change_password("wrong_password", "new_password123")
+        .await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let r = app.post_change_password("password123", "short").await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn change_password_rotates_the_session_and_updates_credentials() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+
+    let r = app
+        .post_change_password(&user.password, "new_password123")
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    // A new session id is issued with the response
+    let set_cookie = r.headers().get_all(header::SET_COOKIE);
+    assert!(
+        set_cookie.iter().next().is_some(),
+        "changing the password must rotate the session cookie"
+    );
+
+    // The rotated session is still signed in
+    let r = app.get_me().await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    // Only the new password works from now on
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    let r = app.post_login(&user.username, "new_password123").await;
+    assert!(r.status().is_success());
+}
@@ -0,0 +1,154 @@
+// server/tests/integration/admin_users.rs
+
+// dependencies
+use crate::helpers::{TestApi, TestUser};
+use pavex::http::StatusCode;
+use serde_json::Value;
+
+#[tokio::test]
+async fn user_management_requires_an_admin() {
+    let app = TestApi::spawn().await;
+
+    let r = app.get_admin_users("").await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let user = app.login_verified_user().await;
+    let r = app.get_admin_users("").await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    let id = app.user_id(&user.email).await;
+    let r = app.patch_admin_user_role(id, "admin").await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn list_users_paginates_filters_and_searches() {
+    let app = TestApi::spawn().await;
+    let admin = app.login_admin().await;
+    let other = app.register_verified_user().await;
+    app.register_verified_user().await;
+
+    let r = app.get_admin_users("per_page=2").await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["total"], 3);
+    assert_eq!(body["data"]["users"].as_array().unwrap().len(), 2);
+
+    let r = app.get_admin_users("page=2&per_page=2").await;
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["users"].as_array().unwrap().len(), 1);
+
+    let r = app.get_admin_users("role=admin").await;
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["total"], 1);
+    assert_eq!(body["data"]["users"][0]["username"], admin.username);
+
+    let r = app
+        .get_admin_users(&format!("search={}", other.email))
+        .await;
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["total"], 1);
+    assert_eq!(body["data"]["users"][0]["username"], other.username);
+
+    // LIKE wildcards are matched literally
+    let r = app.get_admin_users("search=%25").await;
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["total"], 0);
+
+    let r = app.get_admin_users("per_page=1000").await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn role_change_applies_to_existing_sessions() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+    let user_client = app.login_with_new_client(&user).await;
+    app.login_admin().await;
+
+    let id = app.user_id(&user.email).await;
+    let r = app.patch_admin_user_role(id, "editor").await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["role"], "editor");
+
+    // The session the user opened before the change now carries the new role
+    let r = user_client
+        .get(format!("{}/auth/whoami", app.api_address))
+        .send()
+        .await
+        .expect("Failed to execute /auth/whoami");
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["role"], "editor");
+}
+
+#[tokio::test]
+async fn deactivating_a_user_signs_them_out_and_blocks_login() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+    let user_client = app.login_with_new_client(&user).await;
+    app.login_admin().await;
+
+    let id = app.user_id(&user.email).await;
+    let r = app.patch_admin_user_active(id, false).await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let r = user_client
+        .get(format!("{}/auth/whoami", app.api_address))
+        .send()
+        .await
+        .expect("Failed to execute /auth/whoami");
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let token = app.csrf_token(&user_client).await;
+    let r = user_client
+        .post(format!("{}/auth/login", app.api_address))
+        .header("X-CSRF-Token", token)
+        .json(&serde_json::json!({
+            "username_or_email": user.username,
+            "password": user.password
+        }))
+        .send()
+        .await
+        .expect("Failed to execute /auth/login");
+    assert!(!r.status().is_success(), "inactive users cannot log in");
+
+    let r = app.patch_admin_user_active(id, true).await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn admin_can_force_verify_and_delete_users() {
+    let app = TestApi::spawn().await;
+    app.login_admin().await;
+
+    let user = TestUser::unique();
+    let r = app.post_register(&user).await;
+    assert!(r.status().is_success(), "register should succeed");
+    let id = app.user_id(&user.email).await;
+
+    let r = app.post_admin_verify_email(id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["email_verified"], true);
+
+    let r = app.delete_admin_user(id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app.delete_admin_user(id).await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn admins_cannot_demote_deactivate_or_delete_themselves() {
+    let app = TestApi::spawn().await;
+    let admin = app.login_admin().await;
+    let id = app.user_id(&admin.email).await;
+
+    let r = app.patch_admin_user_role(id, "subscriber").await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+    let r = app.patch_admin_user_active(id, false).await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+    let r = app.delete_admin_user(id).await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,122 @@
+// server/tests/integration/api_tokens.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use pavex::http::StatusCode;
+use reqwest::Method;
+
+// create a token for the signed-in user, returning its id and the token itself
+async fn create_token(app: &TestApi, scopes: &[&str]) -> (String, String) {
+    let r = app.post_api_token("script", scopes).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.expect("token creation should return JSON");
+    (
+        body["data"]["id"].as_str().unwrap().to_string(),
+        body["data"]["token"].as_str().unwrap().to_string(),
+    )
+}
+
+#[tokio::test]
+async fn bearer_tokens_act_as_their_owner() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let (_, token) = create_token(&app, &["profile:read", "profile:write"]).await;
+
+    let r = app
+        .request_with_token(Method::GET, "/api/me", &token, None)
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["username"], user.username.as_str());
+
+    let r = app
+        .request_with_token(
+            Method::PATCH,
+            "/api/me",
+            &token,
+            Some(&serde_json::json!({ "display_name": "Scripted" })),
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    // The token is listed with its prefix, never in full
+    let r = app.get_api_tokens().await;
+    let body: serde_json::Value = r.json().await.unwrap();
+    let listed = &body["data"][0];
+    assert!(token.starts_with(listed["prefix"].as_str().unwrap()));
+    assert!(listed.get("token").is_none());
+    assert!(!listed["last_used_at"].is_null());
+}
+
+#[tokio::test]
+async fn missing_scopes_are_forbidden() {
+    let app = TestApi::spawn().await;
+    app.login_verified_user().await;
+    let (_, token) = create_token(&app, &["profile:read"]).await;
+
+    let r = app
+        .request_with_token(
+            Method::PATCH,
+            "/api/me",
+            &token,
+            Some(&serde_json::json!({ "display_name": "Scripted" })),
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    // Only admins may mint admin tokens
+    let r = app.post_api_token("escalate", &["admin"]).await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn revoked_and_malformed_tokens_are_rejected() {
+    let app = TestApi::spawn().await;
+    app.login_verified_user().await;
+    let (id, token) = create_token(&app, &["profile:read"]).await;
+
+    let r = app.delete_api_token(&id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app
+        .request_with_token(Method::GET, "/api/me", &token, None)
+        .await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let r = app
+        .request_with_token(Method::GET, "/api/me", "rws_made_up", None)
+        .await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let r = app.delete_api_token(&id).await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn tokens_cannot_manage_account_security() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let (_, token) = create_token(&app, &["profile:read", "profile:write"]).await;
+
+    let r = app
+        .request_with_token(
+            Method::POST,
+            "/api/me/tokens",
+            &token,
+            Some(&serde_json::json!({ "name": "child", "scopes": ["profile:read"] })),
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    let r = app
+        .request_with_token(
+            Method::POST,
+            "/api/me/password",
+            &token,
+            Some(&serde_json::json!({
+                "current_password": user.password,
+                "new_password": "AnotherPassword123!"
+            })),
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+}
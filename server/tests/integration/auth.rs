@@ -2,6 +2,7 @@
 
 // dependencies
 use crate::helpers::{TestApi, TestUser};
+use app::models::PasswordHashing;
 use reqwest::header;
 
 #[tokio::test]
@@ -98,3 +99,34 @@ async fn logout_invalidates_session_and_whoami_returns_401() {
     );
 }
 
+
+#[tokio::test]
+async fn login_rehashes_passwords_made_with_other_parameters() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+    let id = app.user_id(&user.email).await;
+
+    // The default parameters are stronger than the ones of the test profile
+    let old_hash = PasswordHashing::default().hash(&user.password).unwrap();
+    sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+        .bind(&old_hash)
+        .bind(id)
+        .execute(&app.api_db_pool)
+        .await
+        .unwrap();
+
+    let r = app.post_login(&user.username, &user.password).await;
+    assert!(r.status().is_success(), "login should succeed");
+
+    let new_hash: String = sqlx::query_scalar("SELECT password_hash FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert_ne!(new_hash, old_hash);
+    assert!(new_hash.starts_with("$argon2id$v=19$m=8,t=1,p=1$"));
+
+    // The new hash still lets the user in
+    let r = app.post_login(&user.username, &user.password).await;
+    assert!(r.status().is_success(), "login should succeed");
+}
@@ -0,0 +1,130 @@
+// server/tests/integration/capabilities.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use pavex::http::StatusCode;
+use serde_json::Value;
+
+// call the user listing of the admin API with the given client
+async fn list_users_as(app: &TestApi, client: &reqwest::Client) -> StatusCode {
+    client
+        .get(format!("{}/api/admin/users", &app.api_address))
+        .send()
+        .await
+        .expect("Failed to execute GET /api/admin/users")
+        .status()
+}
+
+#[tokio::test]
+async fn capabilities_can_only_be_managed_by_admins() {
+    let app = TestApi::spawn().await;
+
+    let r = app.get_admin_capabilities().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    app.login_verified_user().await;
+    let r = app.get_admin_capabilities().await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+    let r = app
+        .patch_admin_capability("manage_users", &["admin", "subscriber"])
+        .await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn capabilities_list_the_seeded_roles() {
+    let app = TestApi::spawn().await;
+    app.login_admin().await;
+
+    let r = app.get_admin_capabilities().await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    let capabilities = body["data"].as_array().unwrap();
+    let edit_posts = capabilities
+        .iter()
+        .find(|c| c["capability"] == "edit_posts")
+        .expect("edit_posts should be listed");
+    assert_eq!(
+        edit_posts["roles"],
+        serde_json::json!(["admin", "editor", "author", "contributor"])
+    );
+    assert!(edit_posts["description"].is_string());
+}
+
+#[tokio::test]
+async fn granting_a_capability_takes_effect_without_signing_in_again() {
+    let app = TestApi::spawn().await;
+    app.login_admin().await;
+    let editor = app.register_verified_user().await;
+    sqlx::query("UPDATE users SET role = 'editor' WHERE email = $1")
+        .bind(&editor.email)
+        .execute(&app.api_db_pool)
+        .await
+        .expect("failed to make the user an editor");
+    let editor_client = app.login_with_new_client(&editor).await;
+
+    assert_eq!(
+        list_users_as(&app, &editor_client).await,
+        StatusCode::FORBIDDEN
+    );
+
+    let r = app
+        .patch_admin_capability("manage_users", &["admin", "editor"])
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(
+        body["data"]["roles"],
+        serde_json::json!(["admin", "editor"])
+    );
+    assert_eq!(list_users_as(&app, &editor_client).await, StatusCode::OK);
+
+    let r = app.patch_admin_capability("manage_users", &["admin"]).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    assert_eq!(
+        list_users_as(&app, &editor_client).await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn admins_cannot_lock_themselves_out_of_the_registry() {
+    let app = TestApi::spawn().await;
+    app.login_admin().await;
+
+    let r = app
+        .patch_admin_capability("manage_capabilities", &["editor"])
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+    let r = app.get_admin_capabilities().await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let r = app.patch_admin_capability("fly", &["admin"]).await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn core_capabilities_are_kept_apart_from_plugin_ones() {
+    let app = TestApi::spawn().await;
+    app.login_admin().await;
+
+    // Taking a capability from every role is not mistaken for never having changed it
+    let r = app.patch_admin_capability("upload_media", &[]).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app.get_admin_capabilities().await;
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    let upload_media = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["capability"] == "upload_media")
+        .expect("upload_media should be listed");
+    assert_eq!(upload_media["roles"], serde_json::json!([]));
+
+    let without_plugin: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM plugin_capabilities WHERE plugin_slug IS NULL")
+            .fetch_one(&app.api_db_pool)
+            .await
+            .unwrap();
+    assert_eq!(without_plugin, 0);
+}
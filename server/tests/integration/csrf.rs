@@ -0,0 +1,117 @@
+// server/tests/integration/csrf.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use pavex::http::StatusCode;
+use reqwest::Method;
+
+// helper which changes the display name of the signed-in user with the given extra headers
+async fn patch_me_with(app: &TestApi, headers: &[(&str, &str)]) -> reqwest::Response {
+    let mut request = app
+        .api_client
+        .patch(format!("{}/api/me", &app.api_address))
+        .json(&serde_json::json!({ "display_name": "Changed" }));
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    request
+        .send()
+        .await
+        .expect("Failed to execute PATCH /api/me")
+}
+
+#[tokio::test]
+async fn state_changing_requests_need_the_session_token() {
+    let app = TestApi::spawn().await;
+    app.login_verified_user().await;
+
+    let r = patch_me_with(&app, &[]).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    let r = patch_me_with(&app, &[("X-CSRF-Token", "made-up")]).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    let token = app.csrf_token(&app.api_client).await;
+    let r = patch_me_with(&app, &[("X-CSRF-Token", &token)]).await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn logout_without_a_token_is_refused() {
+    let app = TestApi::spawn().await;
+    app.login_verified_user().await;
+
+    let r = app
+        .api_client
+        .post(format!("{}/auth/logout", &app.api_address))
+        .send()
+        .await
+        .expect("Failed to execute POST /auth/logout");
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn foreign_origins_are_refused_even_with_a_token() {
+    let app = TestApi::spawn().await;
+    app.login_verified_user().await;
+    let token = app.csrf_token(&app.api_client).await;
+
+    let r = patch_me_with(
+        &app,
+        &[("X-CSRF-Token", &token), ("Origin", "https://evil.example")],
+    )
+    .await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    let r = patch_me_with(&app, &[("X-CSRF-Token", &token), ("Origin", "null")]).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    let referer = format!("{}/account", &app.api_address);
+    let r = patch_me_with(&app, &[("X-CSRF-Token", &token), ("Referer", &referer)]).await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn token_is_stable_until_the_session_changes_hands() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    let first = app.csrf_token(&app.api_client).await;
+    assert_eq!(app.csrf_token(&app.api_client).await, first);
+
+    // Signing in starts a new session, so the token of the anonymous page no longer works
+    let r = app.post_login(&user.username, &user.password).await;
+    assert!(r.status().is_success());
+    let r = patch_me_with(&app, &[("X-CSRF-Token", &first)]).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    let second = app.csrf_token(&app.api_client).await;
+    assert_ne!(second, first);
+    let r = patch_me_with(&app, &[("X-CSRF-Token", &second)]).await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn bearer_token_calls_are_exempt() {
+    let app = TestApi::spawn().await;
+    app.login_verified_user().await;
+    let r = app
+        .post_api_token("script", &["profile:read", "profile:write"])
+        .await;
+    assert!(r.status().is_success());
+    let body: serde_json::Value = r.json().await.unwrap();
+    let token = body["data"]["token"].as_str().unwrap().to_string();
+
+    let r = app
+        .request_with_token(
+            Method::PATCH,
+            "/api/me",
+            &token,
+            Some(&serde_json::json!({ "display_name": "Scripted" })),
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
@@ -0,0 +1,95 @@
+// server/tests/integration/email_change.rs
+
+// dependencies
+use crate::helpers::{TestApi, TestUser};
+use pavex::http::StatusCode;
+use serde_json::Value;
+
+// the token of the first link pointing at `path` in the emails sent to `address`
+fn token_sent_to(app: &TestApi, address: &str, path: &str) -> String {
+    let email = app
+        .emails_to(address)
+        .into_iter()
+        .find(|email| email.contains(path))
+        .expect("an email with the link should have been sent");
+    let link = TestApi::link_in_email(&email, path).unwrap();
+    link.split("token=").nth(1).unwrap().to_string()
+}
+
+// the email address of the signed-in user
+async fn my_email(app: &TestApi) -> String {
+    let body: Value = app.get_me().await.json().await.unwrap();
+    body["data"]["email"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn email_changes_once_the_new_address_confirms() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let new_email = TestUser::unique().email;
+
+    let r = app.post_change_email(&new_email, &user.password).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    assert_eq!(my_email(&app).await, user.email);
+
+    let token = token_sent_to(&app, &new_email, "/auth/confirm-email-change");
+    let r = app.post_confirm_email_change(&token).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    assert_eq!(my_email(&app).await, new_email);
+
+    // The old address is told, and given a way back
+    token_sent_to(&app, &user.email, "/auth/undo-email-change");
+
+    let r = app.post_confirm_email_change(&token).await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn email_change_requires_the_password() {
+    let app = TestApi::spawn().await;
+    app.login_verified_user().await;
+
+    let r = app
+        .post_change_email(&TestUser::unique().email, "wrong_password")
+        .await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn address_taken_before_confirmation_is_a_conflict() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let wanted = TestUser::unique();
+
+    let r = app.post_change_email(&wanted.email, &user.password).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let token = token_sent_to(&app, &wanted.email, "/auth/confirm-email-change");
+
+    // Someone registers the address before the link is followed
+    let r = app.post_register(&wanted).await;
+    assert!(r.status().is_success());
+
+    let r = app.post_confirm_email_change(&token).await;
+    assert_eq!(r.status(), StatusCode::CONFLICT);
+    assert_eq!(my_email(&app).await, user.email);
+}
+
+#[tokio::test]
+async fn undoing_an_email_change_restores_the_address_and_signs_out() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let new_email = TestUser::unique().email;
+
+    app.post_change_email(&new_email, &user.password).await;
+    let token = token_sent_to(&app, &new_email, "/auth/confirm-email-change");
+    app.post_confirm_email_change(&token).await;
+
+    let undo_token = token_sent_to(&app, &user.email, "/auth/undo-email-change");
+    let r = app.post_undo_email_change(&undo_token).await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let r = app.get_me().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    let r = app.post_login(&user.email, &user.password).await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
@@ -16,7 +16,7 @@ async fn verify_endpoint_marks_user_verified_and_redirects() {
     // Set a verification token directly (simulating email being sent)
     let token = uuid::Uuid::new_v4().to_string();
     sqlx::query("UPDATE users SET email_verification_token = $1, email_verification_expires_at = NOW() + interval '24 hours' WHERE email = $2")
-        .bind(&token)
+        .bind(app.tokens.hash(&token))
         .bind(&user.email)
         .execute(&app.api_db_pool)
         .await
@@ -38,6 +38,42 @@ async fn verify_endpoint_marks_user_verified_and_redirects() {
     assert!(token_db.is_none());
 }
 
+#[tokio::test]
+async fn register_sends_verification_email_with_working_link() {
+    let app = TestApi::spawn().await;
+    let user = TestUser::unique();
+
+    let r = app.post_register(&user).await;
+    assert!(r.status().is_success());
+
+    // A single multipart email is written to the outbox
+    let emails = app.emails_to(&user.email);
+    assert_eq!(emails.len(), 1, "exactly one verification email should be sent");
+    let email = &emails[0];
+    assert!(email.contains("multipart/alternative"));
+    assert!(email.contains("text/plain"));
+    assert!(email.contains("text/html"));
+
+    // Following the link verifies the account
+    let link = TestApi::link_in_email(email, "/auth/verify?token=")
+        .expect("the email should contain a verification link");
+    let r = app
+        .api_client
+        .get(format!("{}{}", app.api_address, link))
+        .send()
+        .await
+        .expect("Failed to follow the verification link");
+    assert!(r.status().is_redirection(), "verify should redirect on success, got {:?}", r.status());
+
+    let verified: bool = sqlx::query("SELECT email_verified FROM users WHERE email = $1")
+        .bind(&user.email)
+        .fetch_one(&app.api_db_pool)
+        .await
+        .expect("user should exist")
+        .get("email_verified");
+    assert!(verified);
+}
+
 #[tokio::test]
 async fn verify_endpoint_with_invalid_or_expired_token_shows_error() {
     let app = TestApi::spawn().await;
@@ -48,7 +84,7 @@ async fn verify_endpoint_with_invalid_or_expired_token_shows_error() {
     // Expired token
     let expired = uuid::Uuid::new_v4().to_string();
     sqlx::query("UPDATE users SET email_verification_token = $1, email_verification_expires_at = NOW() - interval '1 hour' WHERE email = $2")
-        .bind(&expired)
+        .bind(app.tokens.hash(&expired))
         .bind(&user.email)
         .execute(&app.api_db_pool)
         .await
@@ -69,9 +105,18 @@ async fn resend_verification_sets_new_token_and_rate_limits() {
     let r = app.post_register(&user).await;
     assert!(r.status().is_success());
 
-    // First resend succeeds
+    // First resend succeeds and mails a fresh link
     let r = app.post_resend_verification(&user.email).await;
     assert!(r.status().is_success());
+    let mut sent = app.emails_to(&user.email).len();
+    for _ in 0..40 {
+        if sent == 2 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        sent = app.emails_to(&user.email).len();
+    }
+    assert_eq!(sent, 2, "registration and resend should both send an email");
 
     // Second immediate resend should be rate-limited (implementation may vary: 429 is common)
     let r = app.post_resend_verification(&user.email).await;
@@ -1,11 +1,16 @@
 // server/tests/integration/helpers.rs
 
 // dependencies
-use app::configuration::{StaticServer, TemplateEngine};
+use app::configuration::{MailerTransport, StaticServer, TemplateEngine};
+use app::models::{TOTP_STEP_SECS, totp_code};
+use app::sealing::SecretSealer;
+use app::tokens::TokenHasher;
+use data_encoding::BASE32_NOPAD;
 use pavex::{config::ConfigLoader, server::Server};
 use server::configuration::Profile;
 use server_sdk::{ApplicationConfig, ApplicationState, run};
 use sqlx::{Connection, Executor, PgConnection, PgPool};
+use std::path::PathBuf;
 use std::sync::Once;
 use tracing::subscriber::set_global_default;
 use tracing_subscriber::EnvFilter;
@@ -57,6 +62,18 @@ impl TestUser {
     }
 }
 
+/// The code an authenticator app shows for `secret`, `offset` time steps from now.
+pub fn totp_for(secret: &str, offset: i64) -> String {
+    let secret = BASE32_NOPAD
+        .decode(secret.as_bytes())
+        .expect("secret should be base32");
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock should be after the epoch")
+        .as_secs() as i64;
+    format!("{:06}", totp_code(&secret, now / TOTP_STEP_SECS + offset))
+}
+
 impl Default for TestUser {
     fn default() -> Self {
         Self::new("", "", "", "")
@@ -97,15 +114,30 @@ pub struct TestApi {
     pub api_address: String,
     pub api_client: reqwest::Client,
     pub api_db_pool: PgPool,
+    pub outbox_dir: PathBuf,
+    /// Hashes tokens like the server under test, to plant or find them in the database
+    pub tokens: TokenHasher,
+    /// Seals authenticator secrets like the server under test
+    pub sealer: SecretSealer,
 }
 
 /// Convenient methods for calling the API under test.
 impl TestApi {
     pub async fn spawn() -> Self {
+        Self::spawn_with(|_| {}).await
+    }
+
+    /// Spawn the server after adjusting the test configuration, e.g. to tighten a policy.
+    pub async fn spawn_with(configure: impl FnOnce(&mut ApplicationConfig)) -> Self {
         Self::init_telemetry();
 
         let mut config = Self::get_config_with_absolute_paths();
+        configure(&mut config);
         config.databaseconfig.database_name = Uuid::new_v4().to_string();
+        // every test gets its own outbox, so emails can be attributed to it
+        config.mailer.transport = MailerTransport::File {
+            dir: std::env::temp_dir().join(format!("rws-outbox-{}", Uuid::new_v4())),
+        };
         configure_database(&config).await;
         Self::spawn_with_config(config).await
     }
@@ -122,12 +154,18 @@ impl TestApi {
             .expect("The server TCP listener doesn't have a local socket address");
         let server_builder = Server::new().listen(tcp_listener);
         let api_address = format!("http://{}:{}", config.server.ip, address.port());
+        let outbox_dir = match &config.mailer.transport {
+            MailerTransport::File { dir } => dir.clone(),
+            MailerTransport::Smtp(_) => panic!("Integration tests must use the file mailer"),
+        };
 
         // build the template engine and static server with proper error handling
         let template_engine = TemplateEngine::from_config(&config.templateconfig)
             .expect("Failed to build template engine");
         let static_server = StaticServer::from_config(config.staticserverconfig.clone());
         let api_db_pool = config.databaseconfig.get_database_pool().await;
+        let tokens = TokenHasher::new(&config.tokenhashing);
+        let sealer = SecretSealer::new(&config.twofactor);
 
         let application_state =
             ApplicationState::new(config, api_db_pool.clone(), template_engine, static_server)
@@ -147,6 +185,9 @@ impl TestApi {
             api_address,
             api_client,
             api_db_pool,
+            outbox_dir,
+            tokens,
+            sealer,
         }
     }
 
@@ -200,6 +241,148 @@ impl TestApi {
     }
 }
 
+/// Convenient methods for seeding the API under test.
+impl TestApi {
+    /// Register a unique user and mark them as verified, so they can log in.
+    pub async fn register_verified_user(&self) -> TestUser {
+        let user = TestUser::unique();
+        let r = self.post_register(&user).await;
+        assert!(r.status().is_success(), "register should succeed");
+
+        sqlx::query("UPDATE users SET email_verified = true WHERE email = $1")
+            .bind(&user.email)
+            .execute(&self.api_db_pool)
+            .await
+            .expect("failed to set email_verified");
+
+        user
+    }
+
+    /// Register a unique, verified user and log them in with the API client.
+    pub async fn login_verified_user(&self) -> TestUser {
+        let user = self.register_verified_user().await;
+        let r = self.post_login(&user.username, &user.password).await;
+        assert!(r.status().is_success(), "login should succeed");
+
+        user
+    }
+
+    /// Register a unique, verified user, promote them to admin and log them in.
+    pub async fn login_admin(&self) -> TestUser {
+        self.login_with_role("admin").await
+    }
+
+    /// Register a unique, verified user with the given role and log them in.
+    pub async fn login_with_role(&self, role: &str) -> TestUser {
+        let user = self.register_verified_user().await;
+        sqlx::query("UPDATE users SET role = $1::user_role WHERE email = $2")
+            .bind(role)
+            .bind(&user.email)
+            .execute(&self.api_db_pool)
+            .await
+            .expect("failed to change the role of the user");
+        let r = self.post_login(&user.username, &user.password).await;
+        assert!(r.status().is_success(), "login should succeed");
+
+        user
+    }
+
+    /// Log a user in with a fresh client, standing in for another browser or device.
+    pub async fn login_with_new_client(&self, user: &TestUser) -> reqwest::Client {
+        let client = reqwest::Client::builder()
+            .cookie_store(true)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("Failed to build a reqwest client");
+        let token = self.csrf_token(&client).await;
+        let r = client
+            .post(format!("{}/auth/login", &self.api_address))
+            .header("X-CSRF-Token", token)
+            .json(&serde_json::json!({
+                "username_or_email": user.username,
+                "password": user.password
+            }))
+            .send()
+            .await
+            .expect("Failed to execute /auth/login");
+        assert!(r.status().is_success(), "login should succeed");
+
+        client
+    }
+
+    /// Enroll an authenticator for the signed-in user, returning its secret and recovery codes.
+    pub async fn enable_two_factor(&self) -> (String, Vec<String>) {
+        let r = self.post_two_factor_setup().await;
+        assert!(r.status().is_success(), "2FA setup should succeed");
+        let body: serde_json::Value = r.json().await.expect("setup should return JSON");
+        let secret = body["data"]["secret"].as_str().unwrap().to_string();
+
+        let r = self.post_two_factor_enable(&totp_for(&secret, 0)).await;
+        assert!(r.status().is_success(), "2FA enable should succeed");
+        let body: serde_json::Value = r.json().await.expect("enable should return JSON");
+        let recovery_codes = body["data"]["recovery_codes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|code| code.as_str().unwrap().to_string())
+            .collect();
+
+        (secret, recovery_codes)
+    }
+
+    /// Look up the id of a user by email.
+    pub async fn user_id(&self, email: &str) -> Uuid {
+        sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_one(&self.api_db_pool)
+            .await
+            .expect("user should exist")
+    }
+}
+
+/// Convenient methods for inspecting the emails sent by the API under test.
+impl TestApi {
+    /// Return the raw content of every email sent to the given address.
+    pub fn emails_to(&self, address: &str) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(&self.outbox_dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "eml"))
+            .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+            // undo quoted-printable soft line breaks and escaped `=` signs
+            .map(|raw| raw.replace("=\r\n", "").replace("=3D", "="))
+            .filter(|raw| raw.contains(&format!("To: {address}")))
+            .collect()
+    }
+
+    /// Wait for an email to the given address containing `needle`, as some are sent in the background.
+    pub async fn wait_for_email(&self, address: &str, needle: &str) -> Option<String> {
+        for _ in 0..40 {
+            let found = self
+                .emails_to(address)
+                .into_iter()
+                .find(|email| email.contains(needle));
+            if found.is_some() {
+                return found;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        None
+    }
+
+    /// Extract the first link pointing at `path` from an email body.
+    pub fn link_in_email(email: &str, path: &str) -> Option<String> {
+        let start = email.find(path)?;
+        let link: String = email[start..]
+            .chars()
+            .take_while(|c| !c.is_whitespace() && *c != '"' && *c != '<')
+            .collect();
+        Some(link)
+    }
+}
+
 /// Convenient methods for calling the API under test.
 impl TestApi {
     pub async fn get_ping(&self) -> reqwest::Response {
@@ -227,24 +410,30 @@ impl TestApi {
     }
 
     pub async fn post_register(&self, user: &crate::helpers::TestUser) -> reqwest::Response {
-        self.api_client
-            .post(&format!("{}/auth/register", &self.api_address))
-            .json(&user.to_json_body())
-            .send()
-            .await
-            .expect("Failed to execute request.")
+        self.csrf_request(
+            reqwest::Method::POST,
+            &format!("{}/auth/register", &self.api_address),
+        )
+        .await
+        .json(&user.to_json_body())
+        .send()
+        .await
+        .expect("Failed to execute request.")
     }
 
     pub async fn post_login(&self, username_or_email: &str, password: &str) -> reqwest::Response {
-        self.api_client
-            .post(format!("{}/auth/login", &self.api_address))
-            .json(&serde_json::json!({
-                "username_or_email": username_or_email,
-                "password": password
-            }))
-            .send()
-            .await
-            .expect("Failed to execute /auth/login")
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/login", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({
+            "username_or_email": username_or_email,
+            "password": password
+        }))
+        .send()
+        .await
+        .expect("Failed to execute /auth/login")
     }
 
     pub async fn get_verify(&self, token: &str) -> reqwest::Response {
@@ -256,14 +445,17 @@ impl TestApi {
     }
 
     pub async fn post_resend_verification(&self, email: &str) -> reqwest::Response {
-        self.api_client
-            .post(format!("{}/auth/resend-verification", &self.api_address))
-            .json(&serde_json::json!({
-                "email": email
-            }))
-            .send()
-            .await
-            .expect("Failed to execute /auth/resend-verification")
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/resend-verification", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({
+            "email": email
+        }))
+        .send()
+        .await
+        .expect("Failed to execute /auth/resend-verification")
     }
 
     pub async fn get_check_email_page(&self) -> reqwest::Response {
@@ -283,10 +475,805 @@ impl TestApi {
     }
 
     pub async fn post_logout(&self) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/logout", &self.api_address),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute /auth/logout")
+    }
+
+    pub async fn post_forgot_password(&self, email: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/forgot-password", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({
+            "email": email
+        }))
+        .send()
+        .await
+        .expect("Failed to execute /auth/forgot-password")
+    }
+
+    pub async fn post_magic_link(&self, email: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/magic-link", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({
+            "email": email
+        }))
+        .send()
+        .await
+        .expect("Failed to execute /auth/magic-link")
+    }
+
+    pub async fn get_magic_link(&self, token: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/auth/magic?token={}", &self.api_address, token))
+            .send()
+            .await
+            .expect("Failed to execute /auth/magic")
+    }
+
+    pub async fn post_magic_link_login(&self, token: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/magic", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({
+            "token": token
+        }))
+        .send()
+        .await
+        .expect("Failed to execute /auth/magic")
+    }
+
+    pub async fn post_reset_password(&self, token: &str, new_password: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/reset-password", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({
+            "token": token,
+            "new_password": new_password
+        }))
+        .send()
+        .await
+        .expect("Failed to execute /auth/reset-password")
+    }
+
+    pub async fn get_me(&self) -> reqwest::Response {
         self.api_client
-            .post(format!("{}/auth/logout", &self.api_address))
+            .get(format!("{}/api/me", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/me")
+    }
+
+    pub async fn patch_me(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::PATCH,
+            format!("{}/api/me", &self.api_address),
+        )
+        .await
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to execute PATCH /api/me")
+    }
+
+    pub async fn post_change_password(
+        &self,
+        current_password: &str,
+        new_password: &str,
+    ) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/me/password", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({
+            "current_password": current_password,
+            "new_password": new_password
+        }))
+        .send()
+        .await
+        .expect("Failed to execute /api/me/password")
+    }
+
+    pub async fn post_change_email(&self, new_email: &str, password: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/me/email", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "new_email": new_email, "password": password }))
+        .send()
+        .await
+        .expect("Failed to execute POST /api/me/email")
+    }
+
+    pub async fn post_confirm_email_change(&self, token: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/confirm-email-change", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "token": token }))
+        .send()
+        .await
+        .expect("Failed to execute POST /auth/confirm-email-change")
+    }
+
+    pub async fn post_undo_email_change(&self, token: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/undo-email-change", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "token": token }))
+        .send()
+        .await
+        .expect("Failed to execute POST /auth/undo-email-change")
+    }
+
+    pub async fn get_admin_users(&self, query: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/admin/users?{}", &self.api_address, query))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/admin/users")
+    }
+
+    pub async fn patch_admin_user_role(&self, id: Uuid, role: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::PATCH,
+            format!("{}/api/admin/users/{}/role", &self.api_address, id),
+        )
+        .await
+        .json(&serde_json::json!({ "role": role }))
+        .send()
+        .await
+        .expect("Failed to execute PATCH /api/admin/users/{id}/role")
+    }
+
+    pub async fn patch_admin_user_active(&self, id: Uuid, is_active: bool) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::PATCH,
+            format!("{}/api/admin/users/{}/active", &self.api_address, id),
+        )
+        .await
+        .json(&serde_json::json!({ "is_active": is_active }))
+        .send()
+        .await
+        .expect("Failed to execute PATCH /api/admin/users/{id}/active")
+    }
+
+    pub async fn post_admin_verify_email(&self, id: Uuid) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/admin/users/{}/verify-email", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute POST /api/admin/users/{id}/verify-email")
+    }
+
+    pub async fn post_admin_unlock_user(&self, id: Uuid) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/admin/users/{}/unlock", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute POST /api/admin/users/{id}/unlock")
+    }
+
+    pub async fn delete_admin_user(&self, id: Uuid) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/admin/users/{}", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/admin/users/{id}")
+    }
+
+    pub async fn post_admin_reset_two_factor(&self, id: Uuid) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/admin/users/{}/2fa/reset", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute POST /api/admin/users/{id}/2fa/reset")
+    }
+
+    pub async fn delete_admin_user_sessions(&self, id: Uuid) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/admin/users/{}/sessions", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/admin/users/{id}/sessions")
+    }
+
+    pub async fn post_two_factor(&self, code: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/2fa", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "code": code }))
+        .send()
+        .await
+        .expect("Failed to execute POST /auth/2fa")
+    }
+
+    pub async fn get_my_two_factor(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/me/2fa", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/me/2fa")
+    }
+
+    pub async fn post_two_factor_setup(&self) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/me/2fa/setup", &self.api_address),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute POST /api/me/2fa/setup")
+    }
+
+    pub async fn post_two_factor_enable(&self, code: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/me/2fa/enable", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "code": code }))
+        .send()
+        .await
+        .expect("Failed to execute POST /api/me/2fa/enable")
+    }
+
+    pub async fn post_two_factor_disable(&self, password: &str, code: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/me/2fa/disable", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "password": password, "code": code }))
+        .send()
+        .await
+        .expect("Failed to execute POST /api/me/2fa/disable")
+    }
+
+    pub async fn get_my_sessions(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/me/sessions", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/me/sessions")
+    }
+
+    pub async fn delete_my_session(&self, id: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/me/sessions/{}", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/me/sessions/{id}")
+    }
+
+    pub async fn delete_my_other_sessions(&self) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/me/sessions", &self.api_address),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/me/sessions")
+    }
+
+    pub async fn post_api_token(&self, name: &str, scopes: &[&str]) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/me/tokens", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "name": name, "scopes": scopes }))
+        .send()
+        .await
+        .expect("Failed to execute POST /api/me/tokens")
+    }
+
+    pub async fn get_api_tokens(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/me/tokens", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/me/tokens")
+    }
+
+    pub async fn delete_api_token(&self, id: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/me/tokens/{}", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/me/tokens/{id}")
+    }
+
+    pub async fn get_my_export(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/me/export", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/me/export")
+    }
+
+    pub async fn get_my_deletion(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/me/deletion", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/me/deletion")
+    }
+
+    pub async fn post_my_deletion(&self, password: &str, mode: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/me/deletion", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "password": password, "mode": mode }))
+        .send()
+        .await
+        .expect("Failed to execute POST /api/me/deletion")
+    }
+
+    pub async fn delete_my_deletion(&self) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/me/deletion", &self.api_address),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/me/deletion")
+    }
+
+    pub async fn get_admin_user_export(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .get(format!(
+                "{}/api/admin/users/{}/export",
+                &self.api_address, id
+            ))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/admin/users/{id}/export")
+    }
+
+    pub async fn post_admin_user_deletion(&self, id: Uuid, mode: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/admin/users/{}/deletion", &self.api_address, id),
+        )
+        .await
+        .json(&serde_json::json!({ "mode": mode }))
+        .send()
+        .await
+        .expect("Failed to execute POST /api/admin/users/{id}/deletion")
+    }
+
+    pub async fn delete_admin_user_deletion(&self, id: Uuid) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/admin/users/{}/deletion", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/admin/users/{id}/deletion")
+    }
+
+    pub async fn delete_admin_user_as(&self, id: Uuid, mode: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/admin/users/{}?mode={}", &self.api_address, id, mode),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/admin/users/{id}?mode=")
+    }
+
+    pub async fn post_register_with_invite(
+        &self,
+        user: &TestUser,
+        token: &str,
+    ) -> reqwest::Response {
+        let mut body = user.to_json_body();
+        body["invite_token"] = serde_json::Value::String(token.to_string());
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/register", &self.api_address),
+        )
+        .await
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to execute /auth/register")
+    }
+
+    pub async fn get_register_page(&self, query: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/register?{}", &self.api_address, query))
+            .send()
+            .await
+            .expect("Failed to execute GET /register")
+    }
+
+    pub async fn post_admin_invitation(&self, email: &str, role: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/admin/invitations", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({
+            "email": email,
+            "role": role
+        }))
+        .send()
+        .await
+        .expect("Failed to execute POST /api/admin/invitations")
+    }
+
+    pub async fn get_admin_invitations(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/admin/invitations", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/admin/invitations")
+    }
+
+    pub async fn delete_admin_invitation(&self, id: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/admin/invitations/{}", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/admin/invitations/{id}")
+    }
+
+    pub async fn get_admin_scheduled(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/admin/scheduled", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/admin/scheduled")
+    }
+
+    pub async fn get_admin_capabilities(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/admin/capabilities", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/admin/capabilities")
+    }
+
+    pub async fn patch_admin_capability(
+        &self,
+        capability: &str,
+        roles: &[&str],
+    ) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::PATCH,
+            format!(
+                "{}/api/admin/capabilities/{}",
+                &self.api_address, capability
+            ),
+        )
+        .await
+        .json(&serde_json::json!({
+            "roles": roles
+        }))
+        .send()
+        .await
+        .expect("Failed to execute PATCH /api/admin/capabilities/{capability}")
+    }
+
+    pub async fn get_oidc_login(&self, provider: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!(
+                "{}/auth/oidc/{}/login",
+                &self.api_address, provider
+            ))
+            .send()
+            .await
+            .expect("Failed to execute GET /auth/oidc/{provider}/login")
+    }
+
+    pub async fn get_oidc_callback(&self, provider: &str, query: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!(
+                "{}/auth/oidc/{}/callback?{}",
+                &self.api_address, provider, query
+            ))
+            .send()
+            .await
+            .expect("Failed to execute GET /auth/oidc/{provider}/callback")
+    }
+
+    pub async fn post_passkey_register_start(&self) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/me/passkeys/register/start", &self.api_address),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute POST /api/me/passkeys/register/start")
+    }
+
+    pub async fn post_passkey_register_finish(
+        &self,
+        name: &str,
+        credential: &serde_json::Value,
+    ) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/me/passkeys/register/finish", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({
+            "name": name,
+            "credential": credential
+        }))
+        .send()
+        .await
+        .expect("Failed to execute POST /api/me/passkeys/register/finish")
+    }
+
+    pub async fn get_my_passkeys(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/me/passkeys", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/me/passkeys")
+    }
+
+    pub async fn patch_my_passkey(&self, id: &str, name: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::PATCH,
+            format!("{}/api/me/passkeys/{}", &self.api_address, id),
+        )
+        .await
+        .json(&serde_json::json!({ "name": name }))
+        .send()
+        .await
+        .expect("Failed to execute PATCH /api/me/passkeys/{id}")
+    }
+
+    pub async fn delete_my_passkey(&self, id: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/me/passkeys/{}", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/me/passkeys/{id}")
+    }
+
+    pub async fn post_passkey_login_start(&self, username_or_email: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/passkey/start", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "username_or_email": username_or_email }))
+        .send()
+        .await
+        .expect("Failed to execute POST /auth/passkey/start")
+    }
+
+    pub async fn post_passkey_login_finish(
+        &self,
+        credential: &serde_json::Value,
+    ) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/passkey/finish", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "credential": credential }))
+        .send()
+        .await
+        .expect("Failed to execute POST /auth/passkey/finish")
+    }
+
+    pub async fn post_two_factor_passkey_start(&self) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/2fa/passkey/start", &self.api_address),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute POST /auth/2fa/passkey/start")
+    }
+
+    pub async fn post_two_factor_passkey_finish(
+        &self,
+        credential: &serde_json::Value,
+    ) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/auth/2fa/passkey/finish", &self.api_address),
+        )
+        .await
+        .json(&serde_json::json!({ "credential": credential }))
+        .send()
+        .await
+        .expect("Failed to execute POST /auth/2fa/passkey/finish")
+    }
+
+    pub async fn post_post(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/posts", &self.api_address),
+        )
+        .await
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to execute POST /api/posts")
+    }
+
+    pub async fn post_post_markdown(&self, markdown: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::POST,
+            format!("{}/api/posts", &self.api_address),
+        )
+        .await
+        .header("Content-Type", "text/markdown; charset=utf-8")
+        .body(markdown.to_string())
+        .send()
+        .await
+        .expect("Failed to execute POST /api/posts")
+    }
+
+    pub async fn get_posts(&self, query: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/posts?{}", &self.api_address, query))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/posts")
+    }
+
+    pub async fn get_post(&self, id: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/posts/{}", &self.api_address, id))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/posts/{id}")
+    }
+
+    pub async fn get_post_by_slug(&self, slug: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/posts/slug/{}", &self.api_address, slug))
+            .send()
+            .await
+            .expect("Failed to execute GET /api/posts/slug/{slug}")
+    }
+
+    pub async fn patch_post(&self, id: &str, body: &serde_json::Value) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::PATCH,
+            format!("{}/api/posts/{}", &self.api_address, id),
+        )
+        .await
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to execute PATCH /api/posts/{id}")
+    }
+
+    pub async fn patch_post_markdown(&self, id: &str, markdown: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::PATCH,
+            format!("{}/api/posts/{}", &self.api_address, id),
+        )
+        .await
+        .header("Content-Type", "text/markdown; charset=utf-8")
+        .body(markdown.to_string())
+        .send()
+        .await
+        .expect("Failed to execute PATCH /api/posts/{id}")
+    }
+
+    pub async fn delete_post(&self, id: &str) -> reqwest::Response {
+        self.csrf_request(
+            reqwest::Method::DELETE,
+            format!("{}/api/posts/{}", &self.api_address, id),
+        )
+        .await
+        .send()
+        .await
+        .expect("Failed to execute DELETE /api/posts/{id}")
+    }
+
+    /// Fetch the CSRF token of the session `client` holds, as a page of the site would embed it.
+    pub async fn csrf_token(&self, client: &reqwest::Client) -> String {
+        let r = client
+            .get(format!("{}/auth/csrf", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute GET /auth/csrf");
+        assert!(
+            r.status().is_success(),
+            "fetching a CSRF token should succeed"
+        );
+        let body: serde_json::Value = r.json().await.expect("/auth/csrf should return JSON");
+        body["data"]["token"].as_str().unwrap().to_string()
+    }
+
+    /// Start a state-changing request from the test client, carrying the token of its session.
+    pub async fn csrf_request(
+        &self,
+        method: reqwest::Method,
+        url: impl reqwest::IntoUrl,
+    ) -> reqwest::RequestBuilder {
+        let token = self.csrf_token(&self.api_client).await;
+        self.api_client
+            .request(method, url)
+            .header("X-CSRF-Token", token)
+    }
+
+    /// Call the API with a Bearer token from a client without cookies, as a script would.
+    pub async fn request_with_token(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        token: &str,
+        body: Option<&serde_json::Value>,
+    ) -> reqwest::Response {
+        let mut request = reqwest::Client::new()
+            .request(method, format!("{}{}", &self.api_address, path))
+            .bearer_auth(token);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        request
             .send()
             .await
-            .expect("Failed to execute /auth/logout")
+            .expect("Failed to execute request with a Bearer token")
     }
 }
@@ -0,0 +1,159 @@
+// server/tests/integration/invitations.rs
+
+// dependencies
+use crate::helpers::{TestApi, TestUser};
+use app::configuration::RegistrationMode;
+use pavex::http::StatusCode;
+use serde_json::Value;
+
+// add an invitation straight to the database, as if an admin had sent it
+async fn insert_invitation(app: &TestApi, email: &str, role: &str) -> String {
+    let token = format!("token-{}", uuid::Uuid::new_v4());
+    sqlx::query(
+        r#"
+        INSERT INTO invitations (email, role, token_hash, expires_at)
+        VALUES ($1, $2::user_role, $3, NOW() + INTERVAL '1 day')
+        "#,
+    )
+    .bind(email)
+    .bind(role)
+    .bind(app.tokens.hash(&token))
+    .execute(&app.api_db_pool)
+    .await
+    .expect("failed to insert an invitation");
+
+    token
+}
+
+#[tokio::test]
+async fn invitations_require_an_admin() {
+    let app = TestApi::spawn().await;
+
+    let r = app.post_admin_invitation("new@example.com", "author").await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    app.login_verified_user().await;
+    let r = app.post_admin_invitation("new@example.com", "author").await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+    let r = app.get_admin_invitations().await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn invited_user_registers_with_the_assigned_role_and_a_verified_email() {
+    let app = TestApi::spawn().await;
+    app.login_admin().await;
+    let invitee = TestUser::unique();
+
+    let r = app.post_admin_invitation(&invitee.email, "author").await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["status"], "pending");
+    let token = body["data"]["token"].as_str().unwrap().to_string();
+
+    // The invitee receives the link to the registration page
+    let emails = app.emails_to(&invitee.email);
+    assert_eq!(emails.len(), 1);
+    let link = TestApi::link_in_email(&emails[0], "/register?invite=").unwrap();
+    assert!(link.ends_with(&token));
+
+    let r = app.get_register_page(&format!("invite={token}")).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    assert!(r.text().await.unwrap().contains(&token));
+
+    let r = app.post_register_with_invite(&invitee, &token).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["role"], "author");
+    assert_eq!(body["email_verified"], true);
+    // No verification email is needed on top of the invitation
+    assert_eq!(app.emails_to(&invitee.email).len(), 1);
+
+    let r = app.post_login(&invitee.username, &invitee.password).await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    // The invitation is used up
+    let other = TestUser::unique();
+    let r = app
+        .post_register_with_invite(
+            &TestUser {
+                email: invitee.email.clone(),
+                ..other
+            },
+            &token,
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn invitation_cannot_be_used_for_another_address_or_after_revocation() {
+    let app = TestApi::spawn().await;
+    app.login_admin().await;
+    let invitee = TestUser::unique();
+
+    let r = app.post_admin_invitation(&invitee.email, "editor").await;
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    let token = body["data"]["token"].as_str().unwrap().to_string();
+    let id = body["data"]["id"].as_str().unwrap().to_string();
+
+    let r = app
+        .post_register_with_invite(&TestUser::unique(), &token)
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+
+    let r = app.delete_admin_invitation(&id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app.get_admin_invitations().await;
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"].as_array().unwrap().len(), 0);
+
+    let r = app.post_register_with_invite(&invitee, &token).await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+    let r = app.delete_admin_invitation(&id).await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn invite_only_mode_requires_an_invitation() {
+    let app = TestApi::spawn_with(|config| {
+        config.registration.mode = RegistrationMode::InviteOnly;
+    })
+    .await;
+
+    let user = TestUser::unique();
+    let r = app.post_register(&user).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+    assert!(app.emails_to(&user.email).is_empty());
+
+    let token = insert_invitation(&app, &user.email, "contributor").await;
+    let r = app.post_register_with_invite(&user, &token).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["role"], "contributor");
+}
+
+#[tokio::test]
+async fn closed_mode_refuses_every_registration() {
+    let app = TestApi::spawn_with(|config| {
+        config.registration.mode = RegistrationMode::Closed;
+    })
+    .await;
+
+    let user = TestUser::unique();
+    let r = app.post_register(&user).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    let token = insert_invitation(&app, &user.email, "author").await;
+    let r = app.post_register_with_invite(&user, &token).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    // A refused registration does not use the invitation up
+    let accepted: bool =
+        sqlx::query_scalar("SELECT accepted_at IS NOT NULL FROM invitations WHERE email = $1")
+            .bind(&user.email)
+            .fetch_one(&app.api_db_pool)
+            .await
+            .unwrap();
+    assert!(!accepted);
+}
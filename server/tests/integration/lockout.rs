@@ -0,0 +1,130 @@
+// server/tests/integration/lockout.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::configuration::LockoutPolicy;
+use pavex::http::StatusCode;
+use reqwest::header;
+use std::time::Duration;
+
+// lock out after `failures` failed attempts, without any back-off before that
+fn lock_after(failures: u32) -> LockoutPolicy {
+    LockoutPolicy {
+        free_attempts: failures,
+        base_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(60),
+        lockout_after: failures,
+        lockout_duration: Duration::from_secs(15 * 60),
+        reset_after: Duration::from_secs(60 * 60),
+    }
+}
+
+#[tokio::test]
+async fn account_is_locked_after_repeated_failures_until_an_admin_unlocks_it() {
+    let app = TestApi::spawn_with(|config| {
+        config.lockout.account = lock_after(3);
+    })
+    .await;
+    let user = app.register_verified_user().await;
+
+    for _ in 0..3 {
+        let r = app.post_login(&user.username, "wrong_password").await;
+        assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    // The right password is refused with the same answer as a wrong one
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    // Switching to the email address does not get around the lockout
+    let r = app.post_login(&user.email, &user.password).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    app.login_admin().await;
+    let id = app.user_id(&user.email).await;
+    let r = app.post_admin_unlock_user(id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let r = app.post_login(&user.username, &user.password).await;
+    assert!(
+        r.status().is_success(),
+        "login should succeed once unlocked"
+    );
+}
+
+#[tokio::test]
+async fn unknown_accounts_lock_out_like_existing_ones() {
+    let app = TestApi::spawn_with(|config| {
+        config.lockout.account = lock_after(3);
+    })
+    .await;
+
+    for _ in 0..4 {
+        let r = app.post_login("nobody_here", "wrong_password").await;
+        assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[tokio::test]
+async fn client_address_is_locked_out_with_retry_after() {
+    let app = TestApi::spawn_with(|config| {
+        config.lockout.ip = lock_after(5);
+    })
+    .await;
+    let user = app.register_verified_user().await;
+
+    // Spread over several accounts, so only the address counter reaches the limit
+    for i in 0..5 {
+        let r = app
+            .post_login(&format!("nobody_{i}"), "wrong_password")
+            .await;
+        assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(r.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(r.headers().contains_key(header::RETRY_AFTER));
+}
+
+#[tokio::test]
+async fn unlocking_requires_an_admin() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let id = app.user_id(&user.email).await;
+
+    let r = app.post_admin_unlock_user(id).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn stale_login_failures_are_purged() {
+    let app = TestApi::spawn_with(|config| {
+        config.lockout.account = lock_after(3);
+    })
+    .await;
+    for (key, locked) in [("account:stale", false), ("account:still-locked", true)] {
+        sqlx::query(
+            r#"
+            INSERT INTO login_failures (key, failures, last_failure_at, locked_until)
+            VALUES ($1, 2, NOW() - interval '2 hours',
+                CASE WHEN $2 THEN NOW() + interval '1 hour' END)
+            "#,
+        )
+        .bind(key)
+        .bind(locked)
+        .execute(&app.api_db_pool)
+        .await
+        .unwrap();
+    }
+
+    let r = app.post_login("someone-else", "wrong_password").await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    // Failures past `reset_after` are gone, unless their key is still locked out
+    let keys: Vec<String> = sqlx::query_scalar("SELECT key FROM login_failures ORDER BY key")
+        .fetch_all(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert!(!keys.contains(&"account:stale".to_string()));
+    assert!(keys.contains(&"account:still-locked".to_string()));
+    assert!(keys.contains(&"account:someone-else".to_string()));
+}
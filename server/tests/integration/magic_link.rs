@@ -0,0 +1,187 @@
+// server/tests/integration/magic_link.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::configuration::RateLimitPolicy;
+use pavex::http::StatusCode;
+use sqlx::Row;
+use std::time::Duration;
+
+const LINK_PATH: &str = "/auth/magic?token=";
+
+// helper which reads the token of the sign-in link mailed to `email`, if any
+async fn mailed_token(app: &TestApi, email: &str) -> Option<String> {
+    let email = app.wait_for_email(email, LINK_PATH).await?;
+    let link = TestApi::link_in_email(&email, LINK_PATH)?;
+    link.split("token=").nth(1).map(str::to_string)
+}
+
+// the step reported by a successful sign-in
+async fn login_step(r: reqwest::Response) -> String {
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.expect("sign-in should return JSON");
+    body["data"]["step"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn emailed_link_signs_in_once() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    let r = app.post_magic_link(&user.email).await;
+    assert_eq!(r.status(), StatusCode::NO_CONTENT);
+    let token = mailed_token(&app, &user.email)
+        .await
+        .expect("a sign-in link should be sent");
+
+    // Only a hash of the token is stored
+    let row = sqlx::query("SELECT token_hash FROM magic_links WHERE token_hash = $1")
+        .bind(&token)
+        .fetch_optional(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert!(row.is_none());
+    let row = sqlx::query("SELECT token_hash FROM magic_links WHERE token_hash = $1")
+        .bind(app.tokens.hash(&token))
+        .fetch_optional(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert!(row.is_some());
+
+    // Opening the link only shows a page, as mail scanners do
+    let r = app.get_magic_link(&token).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    assert!(r.text().await.unwrap().contains(&token));
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let r = app.post_magic_link_login(&token).await;
+    assert_eq!(login_step(r).await, "complete");
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["username"], user.username.as_str());
+    assert_eq!(body["data"]["role"], "subscriber");
+
+    // The link is single use
+    app.post_logout().await;
+    let r = app.post_magic_link_login(&token).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn no_link_is_sent_to_unknown_or_privileged_accounts() {
+    let app = TestApi::spawn().await;
+
+    let r = app.post_magic_link("nobody@example.com").await;
+    assert_eq!(
+        r.status(),
+        StatusCode::NO_CONTENT,
+        "unknown emails must get the same response as known ones"
+    );
+    assert!(mailed_token(&app, "nobody@example.com").await.is_none());
+
+    let editor = app.register_verified_user().await;
+    sqlx::query("UPDATE users SET role = 'editor' WHERE email = $1")
+        .bind(&editor.email)
+        .execute(&app.api_db_pool)
+        .await
+        .expect("failed to promote user to editor");
+    let r = app.post_magic_link(&editor.email).await;
+    assert_eq!(r.status(), StatusCode::NO_CONTENT);
+    assert!(mailed_token(&app, &editor.email).await.is_none());
+
+    let r = app.post_magic_link("not-an-email").await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn magic_link_requests_are_rate_limited() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    let r = app.post_magic_link(&user.email).await;
+    assert!(r.status().is_success());
+
+    let r = app.post_magic_link(&user.email).await;
+    assert_eq!(r.status(), StatusCode::TOO_MANY_REQUESTS);
+    // The address is counted the way accounts are looked up, surrounding spaces do not matter
+    let r = app.post_magic_link(&format!("  {}  ", user.email)).await;
+    assert_eq!(r.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn magic_link_requests_are_rate_limited_per_client_address() {
+    let app = TestApi::spawn_with(|config| {
+        config.ratelimit.policies.magic_link_ip = RateLimitPolicy::FixedWindow {
+            limit: 2,
+            window: Duration::from_secs(60 * 60),
+        };
+    })
+    .await;
+
+    // Spread over several addresses, so only the client address counter reaches the limit
+    for i in 0..2 {
+        let r = app
+            .post_magic_link(&format!("nobody_{i}@example.com"))
+            .await;
+        assert_eq!(r.status(), StatusCode::NO_CONTENT);
+    }
+    let r = app.post_magic_link("nobody_2@example.com").await;
+    assert_eq!(r.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn expired_links_do_not_sign_in() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    app.post_magic_link(&user.email).await;
+    let token = mailed_token(&app, &user.email)
+        .await
+        .expect("a sign-in link should be sent");
+    let expired = sqlx::query(
+        "UPDATE magic_links SET expires_at = NOW() - INTERVAL '1 minute'
+         WHERE user_id = (SELECT id FROM users WHERE email = $1)",
+    )
+    .bind(&user.email)
+    .execute(&app.api_db_pool)
+    .await
+    .unwrap();
+    assert_eq!(expired.rows_affected(), 1);
+
+    let r = app.post_magic_link_login(&token).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn magic_link_is_followed_by_the_second_factor() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    app.enable_two_factor().await;
+    app.post_logout().await;
+
+    app.post_magic_link(&user.email).await;
+    let token = mailed_token(&app, &user.email)
+        .await
+        .expect("a sign-in link should be sent");
+
+    let r = app.post_magic_link_login(&token).await;
+    assert_eq!(login_step(r).await, "two_factor_required");
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let row = sqlx::query(
+        "SELECT used_at IS NOT NULL AS used FROM magic_links
+         WHERE user_id = (SELECT id FROM users WHERE email = $1)",
+    )
+    .bind(&user.email)
+    .fetch_one(&app.api_db_pool)
+    .await
+    .unwrap();
+    assert!(row.get::<bool, _>("used"));
+}
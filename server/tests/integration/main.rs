@@ -1,8 +1,26 @@
+mod account;
 mod admin;
+mod admin_users;
+mod api_tokens;
 mod auth;
+mod capabilities;
+mod csrf;
+mod email_change;
 mod email_verification;
 mod helpers;
+mod invitations;
+mod lockout;
+mod magic_link;
+mod oidc;
+mod passkeys;
+mod password_reset;
+mod privacy;
 mod ping;
+mod posts;
+mod rate_limit;
 mod registration;
+mod sessions;
 mod static_files;
 mod template;
+mod token_upgrade;
+mod two_factor;
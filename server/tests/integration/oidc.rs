@@ -0,0 +1,337 @@
+// server/tests/integration/oidc.rs
+
+// dependencies
+use crate::helpers::{TestApi, TestUser};
+use app::configuration::OidcProviderConfig;
+use data_encoding::BASE64URL_NOPAD;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use pavex::http::StatusCode;
+use pavex::http::header::LOCATION;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+const PROVIDER: &str = "stub";
+const CLIENT_ID: &str = "rws-test-client";
+const AUTHORIZATION_CODE: &str = "stub-authorization-code";
+const KEY_ID: &str = "stub-key";
+const SIGNING_KEY: &str = include_str!("../../test_dir/oidc/signing_key.pem");
+// the modulus of the signing key, as published in the JWKS
+const SIGNING_KEY_MODULUS: &str = "nNkoZTlSX1t4BHTM7S6BPa5mpsHK7e3Bnv0vOdaSN1uhRCqNMtXmDuAO-gVIQee3-6YXddZLVV93HbjyEq8D9A0p9MXrbk-Jh7ACVl5fJLgPT1ogqmDO1yqP98h0etQE8GUFxztB4bIJBv5ZS0kW7T8nOUy1i-mbQ1HzO015OIcA7XfQqIc8PuNTMf06FGo_XK4T2vrxxuKUszhc5ENEnZvPWRPf1E4s3MscLBRK16v3eErrFcEIHJj-VlNOIU44bXNmibdgaW4cHKboMLeCNcRZGAExZHv2fIAzgGAfN8nQe5fPolIdNLecvfN7FwnGWqpakb9bb1gEMtkfziw_ow";
+
+// A local identity provider serving discovery, keys and a token endpoint
+struct StubIdp {
+    server: MockServer,
+}
+
+impl StubIdp {
+    async fn start() -> Self {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "issuer": server.uri(),
+                "authorization_endpoint": format!("{}/authorize", server.uri()),
+                "token_endpoint": format!("{}/token", server.uri()),
+                "jwks_uri": format!("{}/jwks", server.uri()),
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/jwks"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "keys": [{
+                    "kty": "RSA",
+                    "kid": KEY_ID,
+                    "use": "sig",
+                    "alg": "RS256",
+                    "n": SIGNING_KEY_MODULUS,
+                    "e": "AQAB",
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        Self { server }
+    }
+
+    fn provider(&self) -> OidcProviderConfig {
+        serde_json::from_value(json!({
+            "name": PROVIDER,
+            "display_name": "Stub",
+            "issuer_url": self.server.uri(),
+            "client_id": CLIENT_ID,
+            "client_secret": "stub-secret",
+        }))
+        .expect("the stub provider configuration should be valid")
+    }
+
+    /// Answer the code exchange of `authorization` with an ID token carrying `claims`.
+    async fn issue(&self, authorization: &Authorization, claims: Value) {
+        let mut claims = claims;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let defaults = json!({
+            "iss": self.server.uri(),
+            "aud": CLIENT_ID,
+            "iat": now,
+            "exp": now + 300,
+            "nonce": authorization.params["nonce"],
+        });
+        for (claim, value) in defaults.as_object().unwrap() {
+            claims
+                .as_object_mut()
+                .unwrap()
+                .entry(claim.clone())
+                .or_insert(value.clone());
+        }
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(KEY_ID.to_string());
+        let id_token = jsonwebtoken::encode(
+            &header,
+            &claims,
+            &EncodingKey::from_rsa_pem(SIGNING_KEY.as_bytes()).unwrap(),
+        )
+        .unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(TokenEndpoint {
+                code_challenge: authorization.params["code_challenge"].clone(),
+                id_token,
+            })
+            .mount(&self.server)
+            .await;
+    }
+}
+
+// The token endpoint, only redeeming the code for the holder of the PKCE verifier
+struct TokenEndpoint {
+    code_challenge: String,
+    id_token: String,
+}
+
+impl Respond for TokenEndpoint {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body = String::from_utf8_lossy(&request.body);
+        let form: HashMap<String, String> = reqwest::Url::parse(&format!("http://form/?{body}"))
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect();
+        let challenge = form
+            .get("code_verifier")
+            .map(|verifier| BASE64URL_NOPAD.encode(&Sha256::digest(verifier.as_bytes())));
+
+        if form.get("code").map(String::as_str) != Some(AUTHORIZATION_CODE)
+            || challenge.as_deref() != Some(self.code_challenge.as_str())
+        {
+            return ResponseTemplate::new(400).set_body_json(json!({ "error": "invalid_grant" }));
+        }
+        ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "stub-access-token",
+            "token_type": "Bearer",
+            "id_token": self.id_token,
+        }))
+    }
+}
+
+// The authorization request the application sent the browser to
+struct Authorization {
+    params: HashMap<String, String>,
+}
+
+impl Authorization {
+    fn callback_query(&self) -> String {
+        format!("code={}&state={}", AUTHORIZATION_CODE, self.params["state"])
+    }
+}
+
+async fn spawn_with_stub_idp() -> (TestApi, StubIdp) {
+    let idp = StubIdp::start().await;
+    let provider = idp.provider();
+    let app = TestApi::spawn_with(|config| config.oidc.providers = vec![provider]).await;
+    (app, idp)
+}
+
+async fn start_login(app: &TestApi) -> Authorization {
+    let r = app.get_oidc_login(PROVIDER).await;
+    assert_eq!(r.status(), StatusCode::SEE_OTHER);
+    let location = r.headers()[LOCATION].to_str().unwrap();
+    let params = reqwest::Url::parse(location)
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect();
+
+    Authorization { params }
+}
+
+async fn signed_in_username(app: &TestApi) -> Option<String> {
+    let r = app.get_whoami().await;
+    if r.status() != StatusCode::OK {
+        return None;
+    }
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    body["data"]["username"].as_str().map(str::to_string)
+}
+
+#[tokio::test]
+async fn new_identity_signs_in_to_a_new_subscriber_account() {
+    let (app, idp) = spawn_with_stub_idp().await;
+
+    let authorization = start_login(&app).await;
+    assert_eq!(authorization.params["client_id"], CLIENT_ID);
+    assert_eq!(authorization.params["code_challenge_method"], "S256");
+    assert!(authorization.params["scope"].starts_with("openid"));
+    assert!(authorization.params["redirect_uri"].ends_with("/auth/oidc/stub/callback"));
+
+    idp.issue(
+        &authorization,
+        json!({
+            "sub": "subject-1",
+            "email": "jane.doe@example.com",
+            "email_verified": true,
+            "name": "Jane Doe",
+        }),
+    )
+    .await;
+    let r = app
+        .get_oidc_callback(PROVIDER, &authorization.callback_query())
+        .await;
+    assert_eq!(r.status(), StatusCode::SEE_OTHER);
+    assert_eq!(r.headers()[LOCATION], "/admin");
+    assert_eq!(signed_in_username(&app).await.as_deref(), Some("jane_doe"));
+
+    let (role, email_verified): (String, bool) =
+        sqlx::query_as("SELECT role::text, email_verified FROM users WHERE email = $1")
+            .bind("jane.doe@example.com")
+            .fetch_one(&app.api_db_pool)
+            .await
+            .unwrap();
+    assert_eq!(role, "subscriber");
+    assert!(email_verified);
+}
+
+#[tokio::test]
+async fn identity_links_to_the_account_with_its_verified_email() {
+    let (app, idp) = spawn_with_stub_idp().await;
+    let user = app.register_verified_user().await;
+
+    let authorization = start_login(&app).await;
+    idp.issue(
+        &authorization,
+        json!({ "sub": "subject-1", "email": user.email, "email_verified": true }),
+    )
+    .await;
+    let r = app
+        .get_oidc_callback(PROVIDER, &authorization.callback_query())
+        .await;
+    assert_eq!(r.status(), StatusCode::SEE_OTHER);
+    assert_eq!(signed_in_username(&app).await, Some(user.username.clone()));
+
+    let linked: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM user_identities WHERE provider = $1 AND subject = $2",
+    )
+    .bind(PROVIDER)
+    .bind("subject-1")
+    .fetch_one(&app.api_db_pool)
+    .await
+    .unwrap();
+    assert_eq!(linked, 1);
+}
+
+#[tokio::test]
+async fn identity_never_links_to_an_unverified_account() {
+    let (app, idp) = spawn_with_stub_idp().await;
+    // Someone registers the address first, without ever proving they own it
+    let squatter = TestUser::unique();
+    let r = app.post_register(&squatter).await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let authorization = start_login(&app).await;
+    idp.issue(
+        &authorization,
+        json!({ "sub": "subject-1", "email": squatter.email, "email_verified": true }),
+    )
+    .await;
+    let r = app
+        .get_oidc_callback(PROVIDER, &authorization.callback_query())
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(signed_in_username(&app).await, None);
+}
+
+#[tokio::test]
+async fn callback_requires_the_state_of_this_browser() {
+    let (app, idp) = spawn_with_stub_idp().await;
+
+    let authorization = start_login(&app).await;
+    idp.issue(
+        &authorization,
+        json!({ "sub": "subject-1", "email": "jane@example.com", "email_verified": true }),
+    )
+    .await;
+    let r = app
+        .get_oidc_callback(
+            PROVIDER,
+            &format!("code={AUTHORIZATION_CODE}&state=forged-state"),
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+
+    // The failed attempt used up the pending sign-in
+    let r = app
+        .get_oidc_callback(PROVIDER, &authorization.callback_query())
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(signed_in_username(&app).await, None);
+
+    // A sign-in refused at the provider ends there
+    let authorization = start_login(&app).await;
+    let r = app
+        .get_oidc_callback(
+            PROVIDER,
+            &format!(
+                "error=access_denied&state={}",
+                authorization.params["state"]
+            ),
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn id_token_must_carry_the_nonce_of_the_sign_in() {
+    let (app, idp) = spawn_with_stub_idp().await;
+
+    let authorization = start_login(&app).await;
+    idp.issue(
+        &authorization,
+        json!({
+            "sub": "subject-1",
+            "email": "jane@example.com",
+            "email_verified": true,
+            "nonce": "replayed-nonce",
+        }),
+    )
+    .await;
+    let r = app
+        .get_oidc_callback(PROVIDER, &authorization.callback_query())
+        .await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(signed_in_username(&app).await, None);
+}
+
+#[tokio::test]
+async fn unknown_provider_is_not_found() {
+    let (app, _idp) = spawn_with_stub_idp().await;
+
+    let r = app.get_oidc_login("nowhere").await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+}
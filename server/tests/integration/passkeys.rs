@@ -0,0 +1,211 @@
+// server/tests/integration/passkeys.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::models::UserRole;
+use pavex::http::StatusCode;
+use serde_json::Value;
+use webauthn_authenticator_rs::WebauthnAuthenticator;
+use webauthn_authenticator_rs::softpasskey::SoftPasskey;
+use webauthn_rs_proto::{CreationChallengeResponse, RequestChallengeResponse};
+
+// the origin the test configuration expects ceremonies to run from
+const ORIGIN: &str = "http://localhost:8000";
+
+type Authenticator = WebauthnAuthenticator<SoftPasskey>;
+
+fn authenticator() -> Authenticator {
+    WebauthnAuthenticator::new(SoftPasskey::new(true))
+}
+
+fn origin() -> reqwest::Url {
+    reqwest::Url::parse(ORIGIN).unwrap()
+}
+
+async fn data(r: reqwest::Response) -> Value {
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    body["data"].clone()
+}
+
+// register a passkey of `authenticator` for the signed-in user or pending login
+async fn register_passkey(app: &TestApi, authenticator: &mut Authenticator, name: &str) -> Value {
+    let options: CreationChallengeResponse =
+        serde_json::from_value(data(app.post_passkey_register_start().await).await).unwrap();
+    let credential = authenticator
+        .do_registration(origin(), options)
+        .expect("the authenticator should create a passkey");
+
+    let r = app
+        .post_passkey_register_finish(name, &serde_json::to_value(credential).unwrap())
+        .await;
+    data(r).await
+}
+
+// answer a sign-in challenge with `authenticator`
+fn answer(authenticator: &mut Authenticator, options: Value) -> Value {
+    let options: RequestChallengeResponse = serde_json::from_value(options).unwrap();
+    let credential = authenticator
+        .do_authentication(origin(), options)
+        .expect("the authenticator should answer the challenge");
+    serde_json::to_value(credential).unwrap()
+}
+
+async fn signed_in_username(app: &TestApi) -> Option<String> {
+    let r = app.get_whoami().await;
+    if r.status() != StatusCode::OK {
+        return None;
+    }
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    body["data"]["username"].as_str().map(str::to_string)
+}
+
+#[tokio::test]
+async fn registered_passkey_signs_in_without_a_password() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let mut authenticator = authenticator();
+
+    let passkey = register_passkey(&app, &mut authenticator, "Laptop").await;
+    assert_eq!(passkey["name"], "Laptop");
+    assert!(passkey.get("credential").is_none());
+
+    app.post_logout().await;
+    assert_eq!(signed_in_username(&app).await, None);
+
+    let options = data(app.post_passkey_login_start(&user.email).await).await;
+    let credential = answer(&mut authenticator, options);
+    let r = app.post_passkey_login_finish(&credential).await;
+    let login = data(r).await;
+    assert_eq!(login["step"], "complete");
+    assert_eq!(signed_in_username(&app).await, Some(user.username.clone()));
+
+    let passkeys = data(app.get_my_passkeys().await).await;
+    assert_eq!(passkeys.as_array().unwrap().len(), 1);
+    assert!(passkeys[0]["last_used_at"].is_string());
+
+    // An answered challenge is used up
+    app.post_logout().await;
+    let r = app.post_passkey_login_finish(&credential).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(signed_in_username(&app).await, None);
+}
+
+#[tokio::test]
+async fn passkey_login_is_refused_to_accounts_disabled_meanwhile() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let mut authenticator = authenticator();
+    register_passkey(&app, &mut authenticator, "Laptop").await;
+    app.post_logout().await;
+
+    let options = data(app.post_passkey_login_start(&user.email).await).await;
+    sqlx::query("UPDATE users SET is_active = FALSE WHERE email = $1")
+        .bind(&user.email)
+        .execute(&app.api_db_pool)
+        .await
+        .expect("failed to disable the account");
+
+    let credential = answer(&mut authenticator, options);
+    let r = app.post_passkey_login_finish(&credential).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(signed_in_username(&app).await, None);
+}
+
+#[tokio::test]
+async fn passkey_login_needs_an_account_with_passkeys() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    // Accounts without passkeys, and unknown ones, are challenged like any other
+    for identifier in [user.username.as_str(), "nobody@example.com"] {
+        let options = data(app.post_passkey_login_start(identifier).await).await;
+        let allowed = options["publicKey"]["allowCredentials"].as_array().unwrap();
+        assert_eq!(allowed.len(), 1);
+
+        // but nothing finishes the challenge
+        let credential = serde_json::json!({
+            "id": allowed[0]["id"],
+            "rawId": allowed[0]["id"],
+            "type": "public-key",
+            "response": {
+                "authenticatorData": "",
+                "clientDataJSON": "",
+                "signature": "",
+            },
+        });
+        let r = app.post_passkey_login_finish(&credential).await;
+        assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(signed_in_username(&app).await, None);
+    }
+
+    // A challenge nobody asked for is rejected
+    let r = app.post_passkey_login_finish(&serde_json::json!({})).await;
+    assert!(r.status().is_client_error());
+}
+
+#[tokio::test]
+async fn passkeys_can_be_renamed_and_deleted_by_their_owner_only() {
+    let app = TestApi::spawn().await;
+    let owner = app.login_verified_user().await;
+    let passkey = register_passkey(&app, &mut authenticator(), "Laptop").await;
+    let id = passkey["id"].as_str().unwrap().to_string();
+
+    let r = app.patch_my_passkey(&id, "Work laptop").await;
+    assert_eq!(data(r).await["name"], "Work laptop");
+    let r = app.patch_my_passkey(&id, "").await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+
+    app.post_logout().await;
+    app.login_verified_user().await;
+    let r = app.patch_my_passkey(&id, "Mine now").await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+    let r = app.delete_my_passkey(&id).await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+
+    app.post_logout().await;
+    let r = app.post_login(&owner.username, &owner.password).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app.delete_my_passkey(&id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let passkeys = data(app.get_my_passkeys().await).await;
+    assert!(passkeys.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn passkey_satisfies_enforced_two_factor_authentication() {
+    let app = TestApi::spawn_with(|config| {
+        config.twofactor.required_roles = vec![UserRole::Admin];
+    })
+    .await;
+    let user = app.register_verified_user().await;
+    sqlx::query("UPDATE users SET role = 'admin' WHERE email = $1")
+        .bind(&user.email)
+        .execute(&app.api_db_pool)
+        .await
+        .expect("failed to promote user to admin");
+    let mut authenticator = authenticator();
+
+    // Registering a passkey completes a login held back until a second factor is set up
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(data(r).await["step"], "two_factor_setup_required");
+    assert_eq!(signed_in_username(&app).await, None);
+    let passkey = register_passkey(&app, &mut authenticator, "Phone").await;
+    assert_eq!(signed_in_username(&app).await, Some(user.username.clone()));
+
+    // The only second factor of the account stays
+    let r = app.delete_my_passkey(passkey["id"].as_str().unwrap()).await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+
+    // From now on the password is followed by the passkey
+    app.post_logout().await;
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(data(r).await["step"], "two_factor_required");
+    assert_eq!(signed_in_username(&app).await, None);
+
+    let options = data(app.post_two_factor_passkey_start().await).await;
+    let credential = answer(&mut authenticator, options);
+    let r = app.post_two_factor_passkey_finish(&credential).await;
+    assert_eq!(data(r).await["step"], "complete");
+    assert_eq!(signed_in_username(&app).await, Some(user.username.clone()));
+}
@@ -0,0 +1,216 @@
+// server/tests/integration/password_reset.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::configuration::RateLimitPolicy;
+use pavex::http::StatusCode;
+use sqlx::Row;
+use std::time::Duration;
+
+// helper which reads the token of the reset link mailed to a user, if any
+async fn reset_token_for(app: &TestApi, email: &str) -> Option<String> {
+    let email = app
+        .wait_for_email(email, "/auth/reset-password?token=")
+        .await?;
+    let link = TestApi::link_in_email(&email, "/auth/reset-password?token=")?;
+    link.split("token=").nth(1).map(str::to_string)
+}
+
+// helper which reads what is stored of the outstanding reset token for a user
+async fn stored_reset_token(app: &TestApi, email: &str) -> Option<String> {
+    sqlx::query("SELECT password_reset_token FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_one(&app.api_db_pool)
+        .await
+        .expect("user should exist")
+        .get("password_reset_token")
+}
+
+#[tokio::test]
+async fn forgot_password_stores_token_with_future_expiry() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    let r = app.post_forgot_password(&user.email).await;
+    assert_eq!(r.status(), StatusCode::NO_CONTENT);
+
+    let row = sqlx::query(
+        "SELECT password_reset_token, password_reset_expires_at > NOW() AS is_pending FROM users WHERE email = $1",
+    )
+    .bind(&user.email)
+    .fetch_one(&app.api_db_pool)
+    .await
+    .expect("user should exist");
+
+    let stored: Option<String> = row.get("password_reset_token");
+    let is_pending: bool = row.get("is_pending");
+    assert!(stored.is_some(), "a reset token should be stored");
+    assert!(is_pending, "the reset token should expire in the future");
+
+    // Only a keyed hash of the mailed token is kept
+    let token = reset_token_for(&app, &user.email)
+        .await
+        .expect("a reset link should be sent");
+    assert_eq!(stored, Some(app.tokens.hash(&token)));
+}
+
+#[tokio::test]
+async fn forgot_password_sends_reset_email_with_working_link() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    let r = app.post_forgot_password(&user.email).await;
+    assert!(r.status().is_success());
+
+    let email = app
+        .wait_for_email(&user.email, "/auth/reset-password?token=")
+        .await
+        .expect("a password reset email should be sent");
+    let link = TestApi::link_in_email(&email, "/auth/reset-password?token=")
+        .expect("the email should contain a reset link");
+    let token = link
+        .split("token=")
+        .nth(1)
+        .expect("the link should carry a token");
+
+    let r = app.post_reset_password(token, "brand_new_password").await;
+    assert!(
+        r.status().is_success(),
+        "reset with the mailed token should succeed"
+    );
+}
+
+#[tokio::test]
+async fn forgot_password_does_not_reveal_unknown_accounts() {
+    let app = TestApi::spawn().await;
+
+    let r = app.post_forgot_password("nobody@example.com").await;
+    assert_eq!(
+        r.status(),
+        StatusCode::NO_CONTENT,
+        "unknown emails must get the same response as known ones"
+    );
+}
+
+#[tokio::test]
+async fn forgot_password_is_rate_limited() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    let r = app.post_forgot_password(&user.email).await;
+    assert!(r.status().is_success());
+
+    let r = app.post_forgot_password(&user.email).await;
+    assert_eq!(r.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn forgot_password_is_rate_limited_per_client_address() {
+    let app = TestApi::spawn_with(|config| {
+        config.ratelimit.policies.password_reset_ip = RateLimitPolicy::FixedWindow {
+            limit: 2,
+            window: Duration::from_secs(60 * 60),
+        };
+    })
+    .await;
+
+    // Spread over several addresses, so only the client address counter reaches the limit
+    for i in 0..2 {
+        let r = app
+            .post_forgot_password(&format!("nobody_{i}@example.com"))
+            .await;
+        assert_eq!(r.status(), StatusCode::NO_CONTENT);
+    }
+    let r = app.post_forgot_password("nobody_2@example.com").await;
+    assert_eq!(r.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn reset_password_changes_password_and_consumes_token() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    let r = app.post_forgot_password(&user.email).await;
+    assert!(r.status().is_success());
+    let token = reset_token_for(&app, &user.email)
+        .await
+        .expect("a reset link should be sent");
+
+    let r = app.post_reset_password(&token, "brand_new_password").await;
+    assert!(
+        r.status().is_success(),
+        "reset should succeed, got {}",
+        r.status()
+    );
+
+    // The old password no longer works, the new one does
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    let r = app.post_login(&user.username, "brand_new_password").await;
+    assert!(
+        r.status().is_success(),
+        "login with the new password should succeed"
+    );
+
+    // The token is single use
+    assert!(stored_reset_token(&app, &user.email).await.is_none());
+    let r = app.post_reset_password(&token, "another_password").await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn reset_password_invalidates_existing_sessions() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    // Log in and confirm the session works
+    let r = app.post_login(&user.username, &user.password).await;
+    assert!(r.status().is_success(), "login should succeed");
+    let r = app.get_whoami().await;
+    assert!(r.status().is_success());
+
+    let r = app.post_forgot_password(&user.email).await;
+    assert!(r.status().is_success());
+    let token = reset_token_for(&app, &user.email)
+        .await
+        .expect("a reset link should be sent");
+
+    let r = app.post_reset_password(&token, "brand_new_password").await;
+    assert!(r.status().is_success());
+
+    // The session created before the reset is gone
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn reset_password_rejects_weak_password_and_expired_token() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    let r = app.post_forgot_password(&user.email).await;
+    assert!(r.status().is_success());
+    let token = reset_token_for(&app, &user.email)
+        .await
+        .expect("a reset link should be sent");
+
+    // Same rules as a password change
+    let r = app.post_reset_password(&token, "short").await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+
+    // Expire the token
+    sqlx::query(
+        "UPDATE users SET password_reset_expires_at = NOW() - interval '1 minute' WHERE email = $1",
+    )
+    .bind(&user.email)
+    .execute(&app.api_db_pool)
+    .await
+    .expect("failed to expire reset token");
+
+    let r = app.post_reset_password(&token, "brand_new_password").await;
+    assert_eq!(
+        r.status(),
+        StatusCode::BAD_REQUEST,
+        "expired tokens should be rejected"
+    );
+}
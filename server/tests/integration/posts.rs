@@ -0,0 +1,463 @@
+// server/tests/integration/posts.rs
+
+// dependencies
+use crate::helpers::{TestApi, TestUser};
+use app::models::ScheduleService;
+use pavex::http::StatusCode;
+use reqwest::Method;
+use serde_json::json;
+use server::scheduler::schedule_service;
+
+// create a post as the signed-in user, returning its id
+async fn create_post(app: &TestApi, title: &str, status: &str) -> String {
+    let r = app
+        .post_post(&json!({
+            "title": title,
+            "content": { "version": 1, "blocks": [] },
+            "status": status,
+        }))
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.expect("post creation should return JSON");
+    body["data"]["id"].as_str().unwrap().to_string()
+}
+
+// sign out the current user and sign in someone with the given role
+async fn switch_to_role(app: &TestApi, role: &str) -> TestUser {
+    app.post_logout().await;
+    app.login_with_role(role).await
+}
+
+// how many posts the listing shows the signed-in user
+async fn listed_posts(app: &TestApi) -> i64 {
+    let r = app.get_posts("").await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    body["data"]["total"].as_i64().unwrap()
+}
+
+#[tokio::test]
+async fn authors_create_read_update_and_delete_their_posts() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("author").await;
+
+    let r = app
+        .post_post(&json!({
+            "title": "Hello, Pavex!",
+            "content": { "version": 1, "blocks": [] },
+            "meta_title": "Hello",
+            "custom_fields": { "mood": "happy" },
+        }))
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["data"]["slug"], "hello-pavex");
+    assert_eq!(body["data"]["status"], "draft");
+    assert_eq!(body["data"]["meta_title"], "Hello");
+    assert_eq!(body["data"]["post_type"], "post");
+    assert!(body["data"]["published_at"].is_null());
+    let id = body["data"]["id"].as_str().unwrap().to_string();
+
+    let r = app
+        .patch_post(
+            &id,
+            &json!({ "title": "Hello again", "status": "published" }),
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["title"], "Hello again");
+    assert_eq!(body["data"]["slug"], "hello-pavex");
+    assert_eq!(body["data"]["meta_title"], "Hello");
+    assert!(!body["data"]["published_at"].is_null());
+
+    let r = app.get_post(&id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["custom_fields"]["mood"], "happy");
+
+    let r = app.delete_post(&id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app.get_post(&id).await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn contributors_cannot_publish() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("contributor").await;
+
+    let r = app
+        .post_post(&json!({
+            "title": "Straight to print",
+            "content": { "version": 1, "blocks": [] },
+            "status": "published",
+        }))
+        .await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    let id = create_post(&app, "For review", "draft").await;
+    let r = app.patch_post(&id, &json!({ "title": "Ready" })).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app.patch_post(&id, &json!({ "status": "published" })).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn subscribers_cannot_write_posts() {
+    let app = TestApi::spawn().await;
+    app.login_verified_user().await;
+
+    let r = app
+        .post_post(&json!({ "title": "Hi", "content": { "version": 1, "blocks": [] } }))
+        .await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn only_editors_touch_posts_of_others() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("author").await;
+    let id = create_post(&app, "Mine", "draft").await;
+
+    // Another author cannot see the draft, let alone change it
+    switch_to_role(&app, "author").await;
+    let r = app.get_post(&id).await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+    let r = app.patch_post(&id, &json!({ "title": "Theirs" })).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+    let r = app.delete_post(&id).await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+    switch_to_role(&app, "editor").await;
+    let r = app.get_post(&id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app.patch_post(&id, &json!({ "title": "Edited" })).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app.delete_post(&id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn listings_show_published_posts_and_own_drafts() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("author").await;
+    create_post(&app, "Public news", "published").await;
+    create_post(&app, "Secret plans", "draft").await;
+
+    let reader = switch_to_role(&app, "author").await;
+    create_post(&app, "My notes", "draft").await;
+
+    let r = app.get_posts("").await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    let mut titles: Vec<_> = body["data"]["posts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|post| post["title"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    assert_eq!(titles, ["My notes", "Public news"]);
+    assert_eq!(body["data"]["total"], 2);
+    assert!(body["data"]["posts"][0].get("content").is_none());
+
+    let reader_id = app.user_id(&reader.email).await;
+    let r = app.get_posts(&format!("author_id={reader_id}")).await;
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["total"], 1);
+
+    switch_to_role(&app, "editor").await;
+    let r = app.get_posts("search=secret").await;
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["total"], 1);
+    assert_eq!(body["data"]["posts"][0]["title"], "Secret plans");
+}
+
+#[tokio::test]
+async fn invalid_posts_and_taken_slugs_are_rejected() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("author").await;
+
+    let r = app
+        .post_post(&json!({ "title": "", "content": { "version": 1, "blocks": [] } }))
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+
+    let r = app
+        .post_post(&json!({ "title": "Bad slug", "slug": "Bad Slug", "content": { "version": 1 } }))
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+
+    create_post(&app, "Taken", "draft").await;
+    let r = app
+        .post_post(&json!({ "title": "Taken", "content": { "version": 1, "blocks": [] } }))
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["slug"], "taken-2");
+
+    let r = app
+        .post_post(
+            &json!({ "title": "Mine", "slug": "taken", "content": { "version": 1, "blocks": [] } }),
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::CONFLICT);
+
+    let r = app
+        .post_post(&json!({ "title": "Admin", "slug": "admin", "content": { "version": 1, "blocks": [] } }))
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn renamed_posts_redirect_from_their_old_slug() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("author").await;
+
+    let id = create_post(&app, "Café ünd Straße", "published").await;
+    let r = app.get_post_by_slug("cafe-und-strasse").await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let r = app.patch_post(&id, &json!({ "slug": "renamed" })).await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let r = app
+        .get_post_by_slug("cafe-und-strasse?format=markdown")
+        .await;
+    assert_eq!(r.status(), StatusCode::MOVED_PERMANENTLY);
+    assert_eq!(
+        r.headers()["location"],
+        "/api/posts/slug/renamed?format=markdown"
+    );
+    let r = app.get_post_by_slug("renamed").await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["id"], id.as_str());
+
+    // Old slugs of drafts lead nowhere for those who cannot see them
+    let draft = create_post(&app, "Hidden", "draft").await;
+    app.patch_post(&draft, &json!({ "slug": "still-hidden" }))
+        .await;
+    switch_to_role(&app, "subscriber").await;
+    let r = app.get_post_by_slug("hidden").await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+    let r = app.get_post_by_slug("nothing-here").await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn content_documents_are_checked_and_stored_as_sent() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("author").await;
+
+    let content = json!({
+        "version": 1,
+        "blocks": [
+            { "type": "heading", "level": 2, "children": [{ "type": "text", "text": "Intro" }] },
+            { "type": "paragraph", "children": [
+                { "type": "text", "text": "Read ", "marks": ["italic"] },
+                { "type": "link", "href": "https://example.com", "children": [{ "type": "text", "text": "this" }] }
+            ] },
+            { "type": "divider" }
+        ]
+    });
+    let r = app
+        .post_post(&json!({ "title": "Structured", "content": content }))
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["content"], content);
+
+    let unsafe_link = json!({
+        "version": 1,
+        "blocks": [{ "type": "paragraph", "children": [
+            { "type": "link", "href": "javascript:alert(1)", "children": [] }
+        ] }]
+    });
+    let r = app
+        .post_post(&json!({ "title": "Sneaky", "content": unsafe_link }))
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert!(
+        body["message"]
+            .as_str()
+            .unwrap()
+            .contains("blocks[0].children[0]")
+    );
+
+    let r = app
+        .post_post(&json!({ "title": "Future", "content": { "version": 99, "blocks": [] } }))
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn posts_are_written_in_markdown_and_read_back() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("author").await;
+
+    let markdown = "# Markdown post\n\nSome **bold** text.\n\n- [x] done\n- [ ] to do\n";
+    let r = app.post_post_markdown(markdown).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["title"], "Markdown post");
+    assert_eq!(body["data"]["slug"], "markdown-post");
+    assert_eq!(body["data"]["content"]["blocks"][1]["type"], "list");
+    let id = body["data"]["id"].as_str().unwrap().to_string();
+
+    let r = app.get_post(&format!("{id}?format=markdown")).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    assert_eq!(r.headers()["content-type"], "text/markdown; charset=utf-8");
+    assert_eq!(r.text().await.unwrap(), markdown);
+
+    // Markdown can also be sent in JSON, alongside the other fields
+    let r = app
+        .patch_post(
+            &id,
+            &json!({ "content": "Just *one* line.", "format": "markdown" }),
+        )
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["title"], "Markdown post");
+    assert_eq!(
+        body["data"]["content"]["blocks"][0]["children"][1],
+        json!({ "type": "text", "text": "one", "marks": ["italic"] })
+    );
+
+    let r = app
+        .patch_post_markdown(&id, "# Renamed\n\nNew text.\n")
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["title"], "Renamed");
+
+    // Images must be uploaded media, and posts sent as Markdown need their title
+    let r = app
+        .post_post_markdown("# Hotlinked\n\n![cat](https://example.com/cat.png)\n")
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert!(body["message"].as_str().unwrap().contains("line 3"));
+
+    let r = app.post_post_markdown("No title here.\n").await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+
+    let r = app
+        .post_post(&json!({ "title": "Wrong", "content": { "version": 1 }, "format": "markdown" }))
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn post_writes_refuse_other_content_types() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("author").await;
+    let url = format!("{}/api/posts", &app.api_address);
+    let document = r#"{ "title": "Plain", "content": "Text." }"#;
+
+    for content_type in [
+        Some("text/plain"),
+        Some("application/x-www-form-urlencoded"),
+        None,
+    ] {
+        let request = app.csrf_request(Method::POST, url.clone()).await;
+        let request = match content_type {
+            Some(content_type) => request.header("Content-Type", content_type),
+            None => request,
+        };
+        let r = request.body(document).send().await.unwrap();
+        assert_eq!(r.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+}
+
+#[tokio::test]
+async fn scheduled_posts_are_published_once_when_due() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("author").await;
+
+    let r = app
+        .post_post(&json!({
+            "title": "Coming soon",
+            "content": { "version": 1, "blocks": [] },
+            "status": "published",
+            "published_at": "2999-01-01T00:00:00Z",
+        }))
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["status"], "scheduled");
+    let id = body["data"]["id"].as_str().unwrap().to_string();
+
+    // Only admins see the scheduled view, and others cannot read the post yet
+    let r = app.get_admin_scheduled().await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+    switch_to_role(&app, "admin").await;
+    let r = app.get_admin_scheduled().await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"][0]["id"], id.as_str());
+    assert_eq!(body["data"][0]["kind"], "post");
+    switch_to_role(&app, "subscriber").await;
+    let r = app.get_post(&id).await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+    assert_eq!(listed_posts(&app).await, 0);
+
+    // Two replicas looking at once publish the post a single time
+    let scheduler = || schedule_service(app.api_db_pool.clone());
+    let (first, second) = (scheduler(), scheduler());
+    assert!(first.publish_due().await.unwrap().is_empty());
+    sqlx::query("UPDATE posts SET published_at = NOW() - interval '1 minute' WHERE id = $1::uuid")
+        .bind(&id)
+        .execute(&app.api_db_pool)
+        .await
+        .unwrap();
+    let (a, b) = tokio::join!(first.publish_due(), second.publish_due());
+    assert_eq!(a.unwrap().len() + b.unwrap().len(), 1);
+    assert!(first.publish_due().await.unwrap().is_empty());
+
+    let r = app.get_post(&id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["status"], "published");
+
+    // The publish hooks ran, the listing cached by the server shows the post right away
+    assert_eq!(listed_posts(&app).await, 1);
+    let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM publish_outbox")
+        .fetch_one(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert_eq!(queued, 0);
+}
+
+#[tokio::test]
+async fn bearer_tokens_need_post_scopes() {
+    let app = TestApi::spawn().await;
+    app.login_with_role("author").await;
+    let id = create_post(&app, "Scripted", "published").await;
+
+    let r = app.post_api_token("reader", &["posts:read"]).await;
+    let body: serde_json::Value = r.json().await.unwrap();
+    let reader = body["data"]["token"].as_str().unwrap().to_string();
+    let r = app.post_api_token("writer", &["posts:write"]).await;
+    let body: serde_json::Value = r.json().await.unwrap();
+    let writer = body["data"]["token"].as_str().unwrap().to_string();
+
+    let path = format!("/api/posts/{id}");
+    let r = app
+        .request_with_token(Method::GET, &path, &reader, None)
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let edit = json!({ "title": "Scripted again" });
+    let r = app
+        .request_with_token(Method::PATCH, &path, &reader, Some(&edit))
+        .await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+    let r = app
+        .request_with_token(Method::PATCH, &path, &writer, Some(&edit))
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
@@ -0,0 +1,253 @@
+// server/tests/integration/privacy.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use app::models::{
+    DeletionMode, PrivacyRepository, PrivacyService, PrivacyServiceImpl, SqlxPrivacyRepository,
+};
+use pavex::http::StatusCode;
+use serde_json::Value;
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+use uuid::Uuid;
+
+// open an export archive and return the given file as JSON
+fn file_in_export(bytes: &[u8], name: &str) -> Value {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).expect("export should be a ZIP");
+    let mut contents = String::new();
+    archive
+        .by_name(name)
+        .expect("export should contain the file")
+        .read_to_string(&mut contents)
+        .unwrap();
+    serde_json::from_str(&contents).expect("exported files should be JSON")
+}
+
+// add a post written by the given user
+async fn insert_post(app: &TestApi, author_id: Uuid) -> Uuid {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO posts (title, slug, content, author_id)
+        VALUES ('Hello', $1, '{}'::jsonb, $2)
+        RETURNING id
+        "#,
+    )
+    .bind(format!("hello-{}", Uuid::new_v4()))
+    .bind(author_id)
+    .fetch_one(&app.api_db_pool)
+    .await
+    .expect("failed to insert a post")
+}
+
+async fn post_author(app: &TestApi, post_id: Uuid) -> Uuid {
+    sqlx::query_scalar("SELECT author_id FROM posts WHERE id = $1")
+        .bind(post_id)
+        .fetch_one(&app.api_db_pool)
+        .await
+        .expect("post should still exist")
+}
+
+#[tokio::test]
+async fn export_contains_the_personal_data_of_the_user() {
+    let app = TestApi::spawn().await;
+
+    let r = app.get_my_export().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let user = app.login_verified_user().await;
+    let id = app.user_id(&user.email).await;
+    insert_post(&app, id).await;
+
+    let r = app.get_my_export().await;
+    assert_eq!(r.status(), StatusCode::OK);
+    assert_eq!(r.headers()["content-type"], "application/zip");
+    let bytes = r.bytes().await.unwrap();
+
+    let exported = file_in_export(&bytes, "user.json");
+    assert_eq!(exported["email"], user.email.as_str());
+    assert!(exported.get("password_hash").is_none());
+    // Only listed columns are exported, internal ones added later are not
+    assert!(exported.get("session_generation").is_none());
+    assert_eq!(
+        file_in_export(&bytes, "posts.json")
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        file_in_export(&bytes, "comments.json"),
+        Value::Array(vec![])
+    );
+    assert_eq!(file_in_export(&bytes, "media.json"), Value::Array(vec![]));
+    assert_eq!(
+        file_in_export(&bytes, "sessions.json")
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn deletion_is_scheduled_with_the_password_and_can_be_cancelled() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+
+    let r = app.post_my_deletion("wrong_password", "delete").await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let r = app.post_my_deletion(&user.password, "delete").await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["mode"], "delete");
+    assert!(
+        app.emails_to(&user.email)
+            .iter()
+            .any(|email| email.contains("scheduled for deletion"))
+    );
+
+    let body: Value = app.get_my_deletion().await.json().await.unwrap();
+    assert_eq!(body["data"]["mode"], "delete");
+
+    let r = app.delete_my_deletion().await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = app.get_my_deletion().await.json().await.unwrap();
+    assert!(body["data"].is_null());
+    let r = app.delete_my_deletion().await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn due_deletions_hand_content_over_to_the_placeholder_author() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let id = app.user_id(&user.email).await;
+    let post_id = insert_post(&app, id).await;
+
+    let r = app.post_my_deletion(&user.password, "delete").await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let service = PrivacyServiceImpl::new(Arc::new(SqlxPrivacyRepository::new(
+        app.api_db_pool.clone(),
+    )));
+    // Nothing happens during the grace period
+    assert_eq!(service.process_due_deletions().await.unwrap(), 0);
+
+    sqlx::query("UPDATE account_deletions SET scheduled_for = NOW() WHERE user_id = $1")
+        .bind(id)
+        .execute(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert_eq!(service.process_due_deletions().await.unwrap(), 1);
+
+    assert_eq!(post_author(&app, post_id).await, Uuid::nil());
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+    let r = app.get_me().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn erasing_a_due_deletion_checks_it_again() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let id = app.user_id(&user.email).await;
+    let repository = SqlxPrivacyRepository::new(app.api_db_pool.clone());
+
+    let r = app.post_my_deletion(&user.password, "delete").await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    // Still within its grace period, as if it was pushed back after the sweep read it
+    assert_eq!(repository.erase_due(id).await.unwrap(), None);
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 1);
+
+    // The mode stored at the time of erasing wins over the one the sweep read
+    sqlx::query(
+        "UPDATE account_deletions SET scheduled_for = NOW(), mode = 'anonymize' WHERE user_id = $1",
+    )
+    .bind(id)
+    .execute(&app.api_db_pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        repository.erase_due(id).await.unwrap(),
+        Some(DeletionMode::Anonymize)
+    );
+    let email: String = sqlx::query_scalar("SELECT email FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert!(email.ends_with("@users.invalid"));
+
+    // Once erased, there is nothing left to erase
+    assert_eq!(repository.erase_due(id).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn admins_can_export_schedule_and_anonymize_any_user() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+    let id = app.user_id(&user.email).await;
+    let post_id = insert_post(&app, id).await;
+    let client = app.login_with_new_client(&user).await;
+    app.login_admin().await;
+
+    let r = app.get_admin_user_export(id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let bytes = r.bytes().await.unwrap();
+    assert_eq!(
+        file_in_export(&bytes, "user.json")["email"],
+        user.email.as_str()
+    );
+
+    let r = app.post_admin_user_deletion(id, "anonymize").await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app.delete_admin_user_deletion(id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let r = app.delete_admin_user_as(id, "anonymize").await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    let row: (String, String, bool) =
+        sqlx::query_as("SELECT username, email, is_active FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_one(&app.api_db_pool)
+            .await
+            .expect("anonymized accounts are kept");
+    assert!(row.0.starts_with("deleted_"));
+    assert!(row.1.ends_with("@users.invalid"));
+    assert!(!row.2);
+    assert_eq!(post_author(&app, post_id).await, id);
+
+    // The user is signed out, and can no longer sign in
+    let r = client
+        .get(format!("{}/api/me", &app.api_address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn the_placeholder_author_cannot_be_deleted() {
+    let app = TestApi::spawn().await;
+    app.login_admin().await;
+
+    let r = app.delete_admin_user_as(Uuid::nil(), "delete").await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+    let r = app.post_admin_user_deletion(Uuid::nil(), "delete").await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+}
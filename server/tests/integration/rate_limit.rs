@@ -0,0 +1,110 @@
+// server/tests/integration/rate_limit.rs
+
+// dependencies
+use crate::helpers::{TestApi, TestUser};
+use app::configuration::{RateLimitBackend, RateLimitPolicy};
+use pavex::http::StatusCode;
+use reqwest::header;
+use std::time::Duration;
+
+fn two_per_hour() -> RateLimitPolicy {
+    RateLimitPolicy::FixedWindow {
+        limit: 2,
+        window: Duration::from_secs(60 * 60),
+    }
+}
+
+#[tokio::test]
+async fn login_is_rate_limited_per_client() {
+    let app = TestApi::spawn_with(|config| {
+        config.ratelimit.policies.login = RateLimitPolicy::TokenBucket {
+            capacity: 2,
+            refill_every: Duration::from_secs(60),
+        };
+    })
+    .await;
+    let user = app.register_verified_user().await;
+
+    // Failed attempts count just like successful ones
+    let r = app.post_login(&user.username, "wrong_password").await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    let r = app.post_login(&user.username, &user.password).await;
+    assert!(r.status().is_success());
+
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(r.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = r
+        .headers()
+        .get(header::RETRY_AFTER)
+        .expect("rate limited responses should carry Retry-After")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+}
+
+#[tokio::test]
+async fn registration_is_rate_limited_per_client() {
+    let app = TestApi::spawn_with(|config| {
+        config.ratelimit.policies.register = two_per_hour();
+    })
+    .await;
+
+    for _ in 0..2 {
+        let r = app.post_register(&TestUser::unique()).await;
+        assert!(r.status().is_success());
+    }
+    let r = app.post_register(&TestUser::unique()).await;
+    assert_eq!(r.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn counters_are_shared_through_postgres() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+
+    let r = app.post_forgot_password(&user.email).await;
+    assert!(r.status().is_success());
+
+    let key = format!("password_reset:{}", user.email.to_lowercase());
+    let value: f64 = sqlx::query_scalar("SELECT value FROM rate_limits WHERE key = $1")
+        .bind(&key)
+        .fetch_one(&app.api_db_pool)
+        .await
+        .expect("the counter should be stored in Postgres");
+    assert_eq!(value, 1.0);
+
+    // Once the window is over, another request goes through
+    sqlx::query(
+        "UPDATE rate_limits SET stamp = stamp - interval '2 minutes', expires_at = expires_at - interval '2 minutes' WHERE key = $1",
+    )
+    .bind(&key)
+    .execute(&app.api_db_pool)
+    .await
+    .expect("failed to age the counter");
+    let r = app.post_forgot_password(&user.email).await;
+    assert!(r.status().is_success());
+}
+
+#[tokio::test]
+async fn memory_backend_limits_without_touching_postgres() {
+    let app = TestApi::spawn_with(|config| {
+        config.ratelimit.backend = RateLimitBackend::Memory;
+        config.ratelimit.policies.register = two_per_hour();
+    })
+    .await;
+
+    for _ in 0..2 {
+        let r = app.post_register(&TestUser::unique()).await;
+        assert!(r.status().is_success());
+    }
+    let r = app.post_register(&TestUser::unique()).await;
+    assert_eq!(r.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM rate_limits")
+        .fetch_one(&app.api_db_pool)
+        .await
+        .expect("failed to count rate limit rows");
+    assert_eq!(stored, 0);
+}
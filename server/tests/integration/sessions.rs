@@ -0,0 +1,263 @@
+// server/tests/integration/sessions.rs
+
+// dependencies
+use crate::helpers::TestApi;
+use pavex::http::StatusCode;
+use serde_json::Value;
+
+// whether `client` is still signed in
+async fn is_signed_in(app: &TestApi, client: &reqwest::Client) -> bool {
+    let r = client
+        .get(format!("{}/auth/whoami", app.api_address))
+        .send()
+        .await
+        .expect("Failed to execute /auth/whoami");
+    r.status() == StatusCode::OK
+}
+
+// the sessions listed for the signed-in user of the API client
+async fn my_sessions(app: &TestApi) -> Vec<Value> {
+    let r = app.get_my_sessions().await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    body["data"].as_array().unwrap().clone()
+}
+
+#[tokio::test]
+async fn sessions_are_listed_with_the_current_one_marked() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    app.login_with_new_client(&user).await;
+
+    let sessions = my_sessions(&app).await;
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions.iter().filter(|s| s["current"] == true).count(), 1);
+    for session in &sessions {
+        assert_eq!(session["ip"], "127.0.0.0/24");
+        assert!(session["created_at"].is_string());
+        assert!(session["last_seen_at"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn one_session_can_be_signed_out_remotely() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let other = app.login_with_new_client(&user).await;
+
+    let sessions = my_sessions(&app).await;
+    let current = sessions.iter().find(|s| s["current"] == true).unwrap();
+    let remote = sessions.iter().find(|s| s["current"] == false).unwrap();
+
+    // The current session is ended by logging out instead
+    let r = app.delete_my_session(current["id"].as_str().unwrap()).await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+
+    let r = app.delete_my_session(remote["id"].as_str().unwrap()).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    assert!(!is_signed_in(&app, &other).await);
+    assert!(is_signed_in(&app, &app.api_client).await);
+
+    let r = app.delete_my_session(remote["id"].as_str().unwrap()).await;
+    assert_eq!(r.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn signing_out_everywhere_else_keeps_the_current_session() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let first = app.login_with_new_client(&user).await;
+    let second = app.login_with_new_client(&user).await;
+
+    let r = app.delete_my_other_sessions().await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["revoked"], 2);
+
+    assert!(!is_signed_in(&app, &first).await);
+    assert!(!is_signed_in(&app, &second).await);
+    assert_eq!(my_sessions(&app).await.len(), 1);
+}
+
+#[tokio::test]
+async fn changing_the_password_signs_out_other_sessions() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let other = app.login_with_new_client(&user).await;
+
+    let r = app
+        .post_change_password(&user.password, "AnotherPassword123!")
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    assert!(!is_signed_in(&app, &other).await);
+    assert!(is_signed_in(&app, &app.api_client).await);
+}
+
+#[tokio::test]
+async fn admins_can_sign_a_user_out_everywhere() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+    let user_client = app.login_with_new_client(&user).await;
+    app.login_admin().await;
+
+    let id = app.user_id(&user.email).await;
+    let r = app.delete_admin_user_sessions(id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    assert!(!is_signed_in(&app, &user_client).await);
+}
+
+#[tokio::test]
+async fn demotions_and_deactivations_apply_to_open_sessions() {
+    let app = TestApi::spawn().await;
+    let admin = app.login_admin().await;
+    let r = app.get_admin_users("").await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    // Changed behind the application's back, nothing rewrites or deletes the session
+    sqlx::query("UPDATE users SET role = 'subscriber' WHERE email = $1")
+        .bind(&admin.email)
+        .execute(&app.api_db_pool)
+        .await
+        .unwrap();
+    let r = app.get_admin_users("").await;
+    assert_eq!(r.status(), StatusCode::FORBIDDEN);
+    let r = app.get_whoami().await;
+    let body: Value = r.json().await.expect("Failed to parse response JSON");
+    assert_eq!(body["data"]["role"], "subscriber");
+
+    sqlx::query("UPDATE users SET is_active = FALSE WHERE email = $1")
+        .bind(&admin.email)
+        .execute(&app.api_db_pool)
+        .await
+        .unwrap();
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn a_new_session_generation_signs_out_every_session() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let other = app.login_with_new_client(&user).await;
+
+    sqlx::query("UPDATE users SET session_generation = session_generation + 1 WHERE email = $1")
+        .bind(&user.email)
+        .execute(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert!(!is_signed_in(&app, &app.api_client).await);
+    assert!(!is_signed_in(&app, &other).await);
+
+    // Signing in again joins the new generation
+    let r = app.post_login(&user.username, &user.password).await;
+    assert!(r.status().is_success());
+    assert!(is_signed_in(&app, &app.api_client).await);
+}
+
+#[tokio::test]
+async fn idle_and_old_sessions_are_signed_out() {
+    let app = TestApi::spawn_with(|config| {
+        config.sessionlimits.idle_timeout = std::time::Duration::from_secs(60 * 60);
+        config.sessionlimits.absolute_timeout = std::time::Duration::from_secs(24 * 60 * 60);
+    })
+    .await;
+    let user = app.login_verified_user().await;
+    let other = app.login_with_new_client(&user).await;
+    let user_id = app.user_id(&user.email).await;
+    assert!(is_signed_in(&app, &app.api_client).await);
+
+    // Unused for longer than the idle timeout
+    sqlx::query(
+        r#"
+        UPDATE sessions
+        SET state = jsonb_set(state, '{session.info,last_seen_at}', to_jsonb(to_char((NOW() - INTERVAL '2 hours') AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"')))
+        WHERE state ->> 'user.id' = $1
+        "#,
+    )
+    .bind(user_id.to_string())
+    .execute(&app.api_db_pool)
+    .await
+    .unwrap();
+    assert!(!is_signed_in(&app, &app.api_client).await);
+    assert!(!is_signed_in(&app, &other).await);
+
+    // Active, but signed in longer ago than the absolute timeout
+    let r = app.post_login(&user.username, &user.password).await;
+    assert!(r.status().is_success());
+    sqlx::query(
+        r#"
+        UPDATE sessions
+        SET state = jsonb_set(state, '{session.info,created_at}', to_jsonb(to_char((NOW() - INTERVAL '2 days') AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"')))
+        WHERE state ->> 'user.id' = $1
+        "#,
+    )
+    .bind(user_id.to_string())
+    .execute(&app.api_db_pool)
+    .await
+    .unwrap();
+    assert!(!is_signed_in(&app, &app.api_client).await);
+}
+
+#[tokio::test]
+async fn activity_is_recorded_apart_from_the_session_state() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let user_id = app.user_id(&user.email).await;
+
+    // Last used a few minutes ago, as far as the session state knows
+    sqlx::query(
+        r#"
+        UPDATE sessions
+        SET state = jsonb_set(state, '{session.info,last_seen_at}', to_jsonb(to_char((NOW() - INTERVAL '5 minutes') AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"')))
+        WHERE state ->> 'user.id' = $1
+        "#,
+    )
+    .bind(user_id.to_string())
+    .execute(&app.api_db_pool)
+    .await
+    .unwrap();
+    let state =
+        "SELECT (state -> 'session.info')::text FROM sessions WHERE state ->> 'user.id' = $1";
+    let before: String = sqlx::query_scalar(state)
+        .bind(user_id.to_string())
+        .fetch_one(&app.api_db_pool)
+        .await
+        .unwrap();
+
+    assert!(is_signed_in(&app, &app.api_client).await);
+    let recorded: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM session_activity activity
+        JOIN sessions ON activity.handle::text = state -> 'session.info' ->> 'handle'
+        WHERE state ->> 'user.id' = $1
+        AND activity.last_seen_at > NOW() - INTERVAL '1 minute'
+        "#,
+    )
+    .bind(user_id.to_string())
+    .fetch_one(&app.api_db_pool)
+    .await
+    .unwrap();
+    assert_eq!(recorded, 1);
+
+    // The state is left to the session store
+    let after: String = sqlx::query_scalar(state)
+        .bind(user_id.to_string())
+        .fetch_one(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert_eq!(after, before);
+    let info: Value = serde_json::from_str(&before).unwrap();
+    let sessions = my_sessions(&app).await;
+    assert_ne!(sessions[0]["last_seen_at"], info["last_seen_at"]);
+
+    // Signing out drops the activity with the session
+    let r = app.post_logout().await;
+    assert!(r.status().is_success());
+    let left: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM session_activity WHERE handle = $1")
+        .bind(uuid::Uuid::parse_str(info["handle"].as_str().unwrap()).unwrap())
+        .fetch_one(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert_eq!(left, 0);
+}
@@ -0,0 +1,117 @@
+// server/tests/integration/token_upgrade.rs
+
+// dependencies
+use crate::helpers::{TestApi, totp_for};
+use app::models::normalize_recovery_code;
+use app::sealing::{SecretSealer, seal_stored_secrets};
+use app::tokens::upgrade_stored_tokens;
+use data_encoding::HEXLOWER;
+use pavex::http::StatusCode;
+use sha2::{Digest, Sha256};
+
+#[tokio::test]
+async fn links_mailed_before_keyed_hashing_keep_working() {
+    let app = TestApi::spawn().await;
+    let user = app.register_verified_user().await;
+    let user_id = app.user_id(&user.email).await;
+
+    // A reset token stored in clear, and a sign-in token stored as a plain SHA-256 digest
+    let reset_token = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        "UPDATE users SET password_reset_token = $1, password_reset_expires_at = NOW() + interval '1 hour' WHERE id = $2",
+    )
+    .bind(&reset_token)
+    .bind(user_id)
+    .execute(&app.api_db_pool)
+    .await
+    .expect("failed to store a legacy reset token");
+    let link_token = "legacy-sign-in-token";
+    sqlx::query(
+        "INSERT INTO magic_links (user_id, token_hash, expires_at) VALUES ($1, $2, NOW() + interval '10 minutes')",
+    )
+    .bind(user_id)
+    .bind(HEXLOWER.encode(&Sha256::digest(link_token.as_bytes())))
+    .execute(&app.api_db_pool)
+    .await
+    .expect("failed to store a legacy sign-in link");
+
+    let upgraded = upgrade_stored_tokens(&app.api_db_pool, &app.tokens)
+        .await
+        .unwrap();
+    assert_eq!(upgraded, 2);
+    // Running it again finds nothing left to do
+    let upgraded = upgrade_stored_tokens(&app.api_db_pool, &app.tokens)
+        .await
+        .unwrap();
+    assert_eq!(upgraded, 0);
+
+    let stored: Option<String> =
+        sqlx::query_scalar("SELECT password_reset_token FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(&app.api_db_pool)
+            .await
+            .unwrap();
+    assert_eq!(stored, Some(app.tokens.hash(&reset_token)));
+
+    let r = app.post_magic_link_login(link_token).await;
+    assert_eq!(r.status(), StatusCode::OK);
+    let r = app
+        .post_reset_password(&reset_token, "brand_new_password")
+        .await;
+    assert!(r.status().is_success(), "got {}", r.status());
+}
+
+#[tokio::test]
+async fn second_factors_enrolled_before_encryption_keep_working() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let user_id = app.user_id(&user.email).await;
+    let (secret, recovery_codes) = app.enable_two_factor().await;
+
+    // The secret stored in clear, and a recovery code stored as a plain SHA-256 digest
+    sqlx::query("UPDATE user_totp SET secret = $1 WHERE user_id = $2")
+        .bind(&secret)
+        .bind(user_id)
+        .execute(&app.api_db_pool)
+        .await
+        .expect("failed to store a legacy authenticator secret");
+    let code = normalize_recovery_code(&recovery_codes[0]);
+    sqlx::query("UPDATE user_recovery_codes SET code_hash = $1 WHERE code_hash = $2")
+        .bind(HEXLOWER.encode(&Sha256::digest(code.as_bytes())))
+        .bind(app.tokens.hash(&code))
+        .execute(&app.api_db_pool)
+        .await
+        .expect("failed to store a legacy recovery code");
+
+    let upgraded = upgrade_stored_tokens(&app.api_db_pool, &app.tokens)
+        .await
+        .unwrap();
+    assert_eq!(upgraded, 1);
+    let sealed = seal_stored_secrets(&app.api_db_pool, &app.sealer)
+        .await
+        .unwrap();
+    assert_eq!(sealed, 1);
+    let sealed = seal_stored_secrets(&app.api_db_pool, &app.sealer)
+        .await
+        .unwrap();
+    assert_eq!(sealed, 0);
+
+    let stored: String = sqlx::query_scalar("SELECT secret FROM user_totp WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&app.api_db_pool)
+        .await
+        .unwrap();
+    assert!(SecretSealer::is_sealed(&stored));
+    assert_eq!(app.sealer.open(&stored, user_id), Some(secret.clone()));
+
+    // Both the authenticator and the upgraded recovery code still sign in
+    app.post_logout().await;
+    app.post_login(&user.username, &user.password).await;
+    let r = app.post_two_factor(&totp_for(&secret, 1)).await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    app.post_logout().await;
+    app.post_login(&user.username, &user.password).await;
+    let r = app.post_two_factor(&recovery_codes[0]).await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
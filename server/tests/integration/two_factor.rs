@@ -0,0 +1,150 @@
+// server/tests/integration/two_factor.rs
+
+// dependencies
+use crate::helpers::{TestApi, totp_for};
+use app::models::UserRole;
+use pavex::http::StatusCode;
+
+// the step reported by a successful login
+async fn login_step(r: reqwest::Response) -> String {
+    assert_eq!(r.status(), StatusCode::OK);
+    let body: serde_json::Value = r.json().await.expect("login should return JSON");
+    body["data"]["step"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn enrolled_users_must_enter_a_code_to_log_in() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+
+    let (secret, recovery_codes) = app.enable_two_factor().await;
+    assert_eq!(recovery_codes.len(), 10);
+    let r = app.get_my_two_factor().await;
+    let body: serde_json::Value = r.json().await.unwrap();
+    assert_eq!(body["data"]["enabled"], true);
+    assert_eq!(body["data"]["recovery_codes_remaining"], 10);
+
+    app.post_logout().await;
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(login_step(r).await, "two_factor_required");
+
+    // The password alone only gets a half-authenticated session
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    let r = app.get_me().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    let r = app.post_two_factor("000000").await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    // The code used during enrollment was consumed, the next one is accepted
+    let r = app.post_two_factor(&totp_for(&secret, 1)).await;
+    assert_eq!(login_step(r).await, "complete");
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn recovery_codes_sign_in_once() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let (_, recovery_codes) = app.enable_two_factor().await;
+
+    app.post_logout().await;
+    app.post_login(&user.username, &user.password).await;
+    let r = app.post_two_factor(&recovery_codes[0]).await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    app.post_logout().await;
+    app.post_login(&user.username, &user.password).await;
+    let r = app.post_two_factor(&recovery_codes[0]).await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    let r = app.get_whoami().await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn code_step_requires_a_pending_login() {
+    let app = TestApi::spawn().await;
+
+    let r = app.post_two_factor("123456").await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn required_roles_must_enroll_before_signing_in() {
+    let app = TestApi::spawn_with(|config| {
+        config.twofactor.required_roles = vec![UserRole::Admin];
+    })
+    .await;
+    let user = app.register_verified_user().await;
+    sqlx::query("UPDATE users SET role = 'admin' WHERE email = $1")
+        .bind(&user.email)
+        .execute(&app.api_db_pool)
+        .await
+        .expect("failed to promote user to admin");
+
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(login_step(r).await, "two_factor_setup_required");
+    let r = app.get_admin_users("").await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+
+    // Enrolling completes the login
+    let (secret, recovery_codes) = app.enable_two_factor().await;
+    let r = app.get_admin_users("").await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    // Admins cannot remove their authenticator themselves
+    let r = app
+        .post_two_factor_disable(&user.password, &recovery_codes[0])
+        .await;
+    assert_eq!(r.status(), StatusCode::BAD_REQUEST);
+
+    app.post_logout().await;
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(login_step(r).await, "two_factor_required");
+    let r = app.post_two_factor(&totp_for(&secret, 1)).await;
+    assert_eq!(r.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn users_can_disable_two_factor_with_both_factors() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    let (_, recovery_codes) = app.enable_two_factor().await;
+
+    let r = app
+        .post_two_factor_disable("wrong_password", &recovery_codes[0])
+        .await;
+    assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+    let r = app
+        .post_two_factor_disable(&user.password, &recovery_codes[0])
+        .await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    app.post_logout().await;
+    let r = app.post_login(&user.username, &user.password).await;
+    assert_eq!(login_step(r).await, "complete");
+}
+
+#[tokio::test]
+async fn admin_can_reset_a_lost_authenticator() {
+    let app = TestApi::spawn().await;
+    let user = app.login_verified_user().await;
+    app.enable_two_factor().await;
+    app.post_logout().await;
+
+    app.login_admin().await;
+    let id = app.user_id(&user.email).await;
+    let r = app.post_admin_reset_two_factor(id).await;
+    assert_eq!(r.status(), StatusCode::OK);
+
+    // The user signs in with their password alone again
+    let client = app.login_with_new_client(&user).await;
+    let r = client
+        .get(format!("{}/auth/whoami", app.api_address))
+        .send()
+        .await
+        .expect("Failed to execute /auth/whoami");
+    assert_eq!(r.status(), StatusCode::OK);
+}
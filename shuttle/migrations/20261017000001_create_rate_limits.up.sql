@@ -0,0 +1,10 @@
+-- Create the rate_limits table, holding one counter per rate limited key
+CREATE UNLOGGED TABLE IF NOT EXISTS rate_limits (
+    key TEXT PRIMARY KEY,
+    value DOUBLE PRECISION NOT NULL,
+    stamp TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+-- Create the index used to purge expired counters
+CREATE INDEX IF NOT EXISTS idx_rate_limits_expires_at ON rate_limits(expires_at);
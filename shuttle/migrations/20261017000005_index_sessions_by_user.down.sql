@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_sessions_handle;
+DROP INDEX IF EXISTS idx_sessions_user_id;
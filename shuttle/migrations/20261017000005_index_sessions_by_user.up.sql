@@ -0,0 +1,5 @@
+-- Index sessions on their owner and handle, both kept in the JSONB state, so a user's
+-- sessions can be listed and revoked without scanning every session
+CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON sessions ((state ->> 'user.id'));
+
+CREATE INDEX IF NOT EXISTS idx_sessions_handle ON sessions ((state -> 'session.info' ->> 'handle'));
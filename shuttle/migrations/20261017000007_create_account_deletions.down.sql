@@ -0,0 +1,10 @@
+ALTER TABLE media DROP CONSTRAINT IF EXISTS media_uploaded_by_fkey,
+    ADD CONSTRAINT media_uploaded_by_fkey FOREIGN KEY (uploaded_by) REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE revisions DROP CONSTRAINT IF EXISTS revisions_author_id_fkey,
+    ADD CONSTRAINT revisions_author_id_fkey FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE pages DROP CONSTRAINT IF EXISTS pages_author_id_fkey,
+    ADD CONSTRAINT pages_author_id_fkey FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE posts DROP CONSTRAINT IF EXISTS posts_author_id_fkey,
+    ADD CONSTRAINT posts_author_id_fkey FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE CASCADE;
+-- The placeholder author is kept, content of deleted accounts still points at it
+DROP TABLE IF EXISTS account_deletions;
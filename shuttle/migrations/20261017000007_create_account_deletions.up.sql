@@ -0,0 +1,26 @@
+-- Create the account_deletions table, accounts are erased once their grace period is over
+CREATE TABLE IF NOT EXISTS account_deletions (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- 'anonymize' scrubs the account in place, 'delete' removes it
+    mode VARCHAR(16) NOT NULL CHECK (mode IN ('anonymize', 'delete')),
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    scheduled_for TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_deletions_scheduled_for ON account_deletions(scheduled_for);
+
+-- Content of deleted accounts is handed over to this placeholder author, it can never sign in
+INSERT INTO users (id, username, email, password_hash, display_name, is_active, email_verified)
+VALUES ('00000000-0000-0000-0000-000000000000', 'deleted', 'deleted@users.invalid', '!', 'Deleted user', false, false)
+ON CONFLICT (id) DO NOTHING;
+
+-- Deleting an author must no longer take their content along
+ALTER TABLE posts DROP CONSTRAINT IF EXISTS posts_author_id_fkey,
+    ADD CONSTRAINT posts_author_id_fkey FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE RESTRICT;
+ALTER TABLE pages DROP CONSTRAINT IF EXISTS pages_author_id_fkey,
+    ADD CONSTRAINT pages_author_id_fkey FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE RESTRICT;
+ALTER TABLE revisions DROP CONSTRAINT IF EXISTS revisions_author_id_fkey,
+    ADD CONSTRAINT revisions_author_id_fkey FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE RESTRICT;
+ALTER TABLE media DROP CONSTRAINT IF EXISTS media_uploaded_by_fkey,
+    ADD CONSTRAINT media_uploaded_by_fkey FOREIGN KEY (uploaded_by) REFERENCES users(id) ON DELETE RESTRICT;
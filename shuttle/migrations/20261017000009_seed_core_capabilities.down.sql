@@ -0,0 +1,4 @@
+-- Remove the core capabilities, every remaining capability belongs to a plugin again
+DELETE FROM plugin_capabilities WHERE plugin_slug IS NULL;
+DROP INDEX IF EXISTS idx_plugin_capabilities_core;
+ALTER TABLE plugin_capabilities ALTER COLUMN plugin_slug SET NOT NULL;
@@ -0,0 +1,20 @@
+-- Core capabilities live next to the plugin ones, without a plugin
+ALTER TABLE plugin_capabilities ALTER COLUMN plugin_slug DROP NOT NULL;
+
+-- UNIQUE(plugin_slug, capability) does not cover rows without a plugin
+CREATE UNIQUE INDEX IF NOT EXISTS idx_plugin_capabilities_core
+    ON plugin_capabilities(capability) WHERE plugin_slug IS NULL;
+
+-- default_roles holds the roles granted each capability, admins edit it at runtime
+INSERT INTO plugin_capabilities (plugin_slug, capability, description, default_roles) VALUES
+    (NULL, 'access_admin', 'Open the admin dashboard', ARRAY['admin']),
+    (NULL, 'edit_posts', 'Write posts and edit their own posts', ARRAY['admin', 'editor', 'author', 'contributor']),
+    (NULL, 'edit_others_posts', 'Edit posts written by other users', ARRAY['admin', 'editor']),
+    (NULL, 'publish_posts', 'Publish posts', ARRAY['admin', 'editor', 'author']),
+    (NULL, 'delete_posts', 'Delete their own posts', ARRAY['admin', 'editor', 'author', 'contributor']),
+    (NULL, 'delete_others_posts', 'Delete posts written by other users', ARRAY['admin', 'editor']),
+    (NULL, 'moderate_comments', 'Approve, edit and delete comments', ARRAY['admin', 'editor']),
+    (NULL, 'upload_media', 'Upload media files', ARRAY['admin', 'editor', 'author']),
+    (NULL, 'manage_users', 'Manage user accounts and invitations', ARRAY['admin']),
+    (NULL, 'manage_capabilities', 'Change which roles are granted each capability', ARRAY['admin'])
+ON CONFLICT DO NOTHING;
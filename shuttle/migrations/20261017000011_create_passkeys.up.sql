@@ -0,0 +1,14 @@
+-- Create the passkeys table, holding the WebAuthn credentials users registered
+CREATE TABLE IF NOT EXISTS passkeys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    -- the id the authenticator knows the credential by
+    credential_id BYTEA NOT NULL UNIQUE,
+    -- the public key and signature counter, as kept by the WebAuthn library
+    credential JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_passkeys_user_id ON passkeys(user_id);
@@ -0,0 +1,3 @@
+-- Add a per-user session generation, sessions signed in under an older generation are no longer valid.
+-- Existing sessions carry no generation and count as generation 0, so they stay signed in.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS session_generation INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+-- Create the slug_history table, old slugs of renamed posts, pages, categories and tags redirect to their current one
+CREATE TABLE IF NOT EXISTS slug_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    scope TEXT NOT NULL CHECK (scope IN ('post', 'page', 'category', 'tag')),
+    entity_id UUID NOT NULL,
+    slug VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (scope, slug)
+);
+
+CREATE INDEX IF NOT EXISTS idx_slug_history_entity ON slug_history(scope, entity_id);
@@ -0,0 +1,3 @@
+-- Enum values cannot be dropped, content still waiting to be published goes back to drafts
+UPDATE posts SET status = 'draft' WHERE status = 'scheduled';
+UPDATE pages SET status = 'draft' WHERE status = 'scheduled';
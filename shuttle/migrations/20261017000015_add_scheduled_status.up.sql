@@ -0,0 +1,2 @@
+-- Add the scheduled content status, posts and pages dated in the future are published by the scheduler once due
+ALTER TYPE content_status ADD VALUE IF NOT EXISTS 'scheduled';
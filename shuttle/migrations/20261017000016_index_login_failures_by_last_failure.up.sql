@@ -0,0 +1,2 @@
+-- Index login failures by their last failure, stale ones are purged whenever a new one is recorded
+CREATE INDEX IF NOT EXISTS idx_login_failures_last_failure_at ON login_failures(last_failure_at);
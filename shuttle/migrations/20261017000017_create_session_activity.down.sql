@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS delete_sessions_activity ON sessions;
+DROP FUNCTION IF EXISTS delete_session_activity();
+DROP TABLE IF EXISTS session_activity;
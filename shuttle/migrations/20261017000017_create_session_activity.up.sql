@@ -0,0 +1,21 @@
+-- Record when each signed-in session was last used outside its state, which only pavex_session
+-- writes. Rows are keyed by the session handle kept in `session.info`, since the session id
+-- changes whenever it is cycled.
+CREATE TABLE IF NOT EXISTS session_activity (
+    handle UUID PRIMARY KEY,
+    last_seen_at TIMESTAMPTZ NOT NULL
+);
+
+-- Drop the activity of a session along with it, however the session is deleted
+CREATE OR REPLACE FUNCTION delete_session_activity()
+RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM session_activity
+    WHERE handle::text = OLD.state -> 'session.info' ->> 'handle';
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER delete_sessions_activity
+    AFTER DELETE ON sessions
+    FOR EACH ROW EXECUTE FUNCTION delete_session_activity();
@@ -0,0 +1,14 @@
+-- Put the core capabilities back next to the plugin ones, without a plugin
+ALTER TABLE plugin_capabilities ALTER COLUMN plugin_slug DROP NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_plugin_capabilities_core
+    ON plugin_capabilities(capability) WHERE plugin_slug IS NULL;
+
+INSERT INTO plugin_capabilities (plugin_slug, capability, description, default_roles)
+SELECT NULL, core.capability, core.description,
+    ARRAY(SELECT role::text FROM role_capabilities WHERE capability = core.capability ORDER BY role)
+FROM core_capabilities core
+ON CONFLICT DO NOTHING;
+
+DROP TABLE IF EXISTS role_capabilities;
+DROP TABLE IF EXISTS core_capabilities;
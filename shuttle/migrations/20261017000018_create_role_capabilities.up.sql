@@ -0,0 +1,31 @@
+-- Core capabilities get their own tables, plugin_capabilities only holds plugin ones again.
+-- A capability listed in core_capabilities is granted to exactly the roles in role_capabilities,
+-- one missing from it keeps its built-in default roles.
+CREATE TABLE IF NOT EXISTS core_capabilities (
+    capability VARCHAR(100) PRIMARY KEY,
+    description TEXT
+);
+
+CREATE TABLE IF NOT EXISTS role_capabilities (
+    role user_role NOT NULL,
+    capability VARCHAR(100) NOT NULL REFERENCES core_capabilities(capability) ON DELETE CASCADE,
+    PRIMARY KEY (role, capability)
+);
+
+CREATE INDEX IF NOT EXISTS idx_role_capabilities_capability ON role_capabilities(capability);
+
+-- Move the core capabilities seeded without a plugin, along with any role changes made since
+INSERT INTO core_capabilities (capability, description)
+SELECT capability, description FROM plugin_capabilities WHERE plugin_slug IS NULL
+ON CONFLICT DO NOTHING;
+
+INSERT INTO role_capabilities (role, capability)
+SELECT role::user_role, capability
+FROM plugin_capabilities, unnest(COALESCE(default_roles, '{}')) AS role
+WHERE plugin_slug IS NULL
+AND role IN (SELECT unnest(enum_range(NULL::user_role))::text)
+ON CONFLICT DO NOTHING;
+
+DELETE FROM plugin_capabilities WHERE plugin_slug IS NULL;
+DROP INDEX IF EXISTS idx_plugin_capabilities_core;
+ALTER TABLE plugin_capabilities ALTER COLUMN plugin_slug SET NOT NULL;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS content_versions;
+DROP TABLE IF EXISTS publish_outbox;
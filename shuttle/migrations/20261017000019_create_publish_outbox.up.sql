@@ -0,0 +1,24 @@
+-- Content published by the scheduler whose publish hooks have yet to succeed. Rows are written
+-- in the same statement that publishes the content, so a replica stopping before the hooks ran
+-- leaves them to the next scheduler run instead of skipping them.
+CREATE TABLE IF NOT EXISTS publish_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    kind VARCHAR(20) NOT NULL,
+    content_id UUID NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    slug VARCHAR(255) NOT NULL,
+    author_id UUID NOT NULL,
+    published_at TIMESTAMPTZ NOT NULL,
+    -- set while a replica runs the hooks, they are run again once it has passed
+    claimed_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Bumped whenever the published content of a kind changes, cached listings are only served
+-- while the version they were read at is still the current one
+CREATE TABLE IF NOT EXISTS content_versions (
+    kind VARCHAR(20) PRIMARY KEY,
+    version BIGINT NOT NULL DEFAULT 0
+);
+
+INSERT INTO content_versions (kind) VALUES ('post'), ('page') ON CONFLICT DO NOTHING;
@@ -6,12 +6,16 @@ mod startup;
 
 // dependencies
 use pavex::server::Server;
+use server::account_deletion::spawn_account_deletion_sweeper;
+use server::scheduler::spawn_scheduler;
+use server::secret_sealing::seal_authenticator_secrets;
+use server::token_upgrade::upgrade_one_time_tokens;
 use shuttle_runtime::{SecretStore, Secrets};
 use shuttle_shared_db::Postgres;
 use sqlx::PgPool;
 use startup::{
-    build_application_state, determine_profile, load_application_config, run_migrations,
-    setup_components, setup_telemetry,
+    apply_secrets, build_application_state, determine_profile, load_application_config,
+    run_migrations, setup_components, setup_telemetry,
 };
 
 // main function
@@ -23,8 +27,13 @@ async fn shuttle_pavex(
     setup_telemetry()?;
     run_migrations(&pool).await?;
     let app_profile = determine_profile(&secrets)?;
-    let app_config = load_application_config(app_profile)?;
+    let mut app_config = load_application_config(app_profile)?;
+    apply_secrets(&mut app_config, &secrets);
     let (template_engine, static_server) = setup_components(&app_config)?;
+    upgrade_one_time_tokens(&pool, &app_config.tokenhashing).await?;
+    seal_authenticator_secrets(&pool, &app_config.twofactor).await?;
+    spawn_account_deletion_sweeper(pool.clone(), app_config.accountdeletion.clone());
+    spawn_scheduler(pool.clone(), app_config.scheduler.clone());
     let app_state =
         build_application_state(app_config, template_engine, static_server, pool).await?;
 
@@ -3,6 +3,7 @@
 //  dependencies
 use app::configuration::{StaticServer, TemplateEngine};
 use pavex::config::ConfigLoader;
+use secrecy::SecretString;
 use server::configuration::Profile::{Dev, Prod};
 use server::telemetry::{get_subscriber, init_telemetry};
 use server_sdk::{ApplicationConfig, ApplicationState};
@@ -60,6 +61,22 @@ pub fn load_application_config(
     Ok(app_config)
 }
 
+// hand the secrets over to the configuration, since they are not environment variables here
+pub fn apply_secrets(app_config: &mut ApplicationConfig, secrets: &SecretStore) {
+    if let Some(pepper) = secrets.get("PX_PASSWORDHASHING__PEPPER") {
+        app_config.passwordhashing.pepper = Some(SecretString::from(pepper));
+        tracing::info!("Password pepper set from Secrets");
+    }
+    if let Some(secret) = secrets.get("PX_TOKENHASHING__SECRET") {
+        app_config.tokenhashing.secret = Some(SecretString::from(secret));
+        tracing::info!("Token hashing secret set from Secrets");
+    }
+    if let Some(key) = secrets.get("PX_TWOFACTOR__SEALING_KEY") {
+        app_config.twofactor.sealing_key = Some(SecretString::from(key));
+        tracing::info!("Authenticator sealing key set from Secrets");
+    }
+}
+
 // setup the components
 pub fn setup_components(
     app_config: &ApplicationConfig,
@@ -11,6 +11,19 @@ function onReady(fn) {
   }
 }
 
+// The CSRF token of the session, rendered into every page by the server
+function csrfToken() {
+  const meta = document.querySelector('meta[name="csrf-token"]');
+  return meta ? meta.content : '';
+}
+
+// `fetch` for state-changing requests, which the server only accepts with the page's CSRF token
+function csrfFetch(url, options = {}) {
+  const headers = new Headers(options.headers || {});
+  headers.set('X-CSRF-Token', csrfToken());
+  return fetch(url, { ...options, headers, credentials: 'same-origin' });
+}
+
 onReady(() => {
   // Login form handler
   const loginForm = document.getElementById('login-form');
@@ -23,13 +36,29 @@ onReady(() => {
         password: form.password.value,
       };
       try {
-        const r = await fetch('/auth/login', {
+        const r = await csrfFetch('/auth/login', {
           method: 'POST',
           headers: { 'Content-Type': 'application/json' },
           body: JSON.stringify(payload),
         });
         if (r.ok) {
-          window.location.assign('/admin');
+          // The password alone may not be enough, follow the step the server asks for
+          let step = 'complete';
+          try {
+            const body = await r.json();
+            if (body && body.data && typeof body.data.step === 'string') {
+              step = body.data.step;
+            }
+          } catch (_) {
+            // ignore parse errors; assume the login is complete
+          }
+          if (step === 'two_factor_required') {
+            window.location.assign('/auth/2fa');
+          } else if (step === 'two_factor_setup_required') {
+            window.location.assign('/auth/2fa/setup');
+          } else {
+            window.location.assign('/admin');
+          }
         } else {
           let msg = 'Login failed';
           const ct = r.headers.get('content-type') || '';
@@ -83,6 +112,575 @@ onReady(() => {
     }
   }
 
+  // Fill a list with freshly issued recovery codes
+  const showRecoveryCodes = (list, codes) => {
+    list.replaceChildren(...codes.map((code) => {
+      const item = document.createElement('li');
+      const text = document.createElement('code');
+      text.textContent = code;
+      item.appendChild(text);
+      return item;
+    }));
+  };
+
+  // Read the message of a failed API call, falling back to a generic one
+  const errorMessage = async (r, fallback) => {
+    try {
+      const body = await r.json();
+      if (body && typeof body.message === 'string') {
+        return body.message;
+      }
+    } catch (_) {
+      // ignore parse errors; fall back to generic message
+    }
+    return fallback;
+  };
+
+  // Second factor form, shown during login
+  const twoFactorForm = document.getElementById('two-factor-form');
+  if (twoFactorForm) {
+    twoFactorForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      try {
+        const r = await csrfFetch('/auth/2fa', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ code: form.code.value.trim() }),
+        });
+        if (r.ok) {
+          window.location.assign('/admin');
+        } else if (r.status === 429) {
+          alert('Too many attempts. Please wait a bit before trying again.');
+        } else {
+          const msg = await errorMessage(r, 'Verification failed');
+          alert(msg);
+          if (msg.toLowerCase().includes('no login')) {
+            window.location.assign('/login');
+            return;
+          }
+          form.reset();
+          form.code.focus();
+        }
+      } catch (err) {
+        alert('Verification failed: network error');
+      }
+    });
+  }
+
+  // WebAuthn options and credentials travel as JSON, with their binary fields base64url encoded
+  const fromBase64Url = (value) => {
+    const base64 = value.replace(/-/g, '+').replace(/_/g, '/');
+    const padded = base64 + '='.repeat((4 - (base64.length % 4)) % 4);
+    return Uint8Array.from(atob(padded), (c) => c.charCodeAt(0)).buffer;
+  };
+  const toBase64Url = (buffer) => {
+    const text = String.fromCharCode(...new Uint8Array(buffer));
+    return btoa(text).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+  };
+  const withBinaryIds = (credentials) =>
+    (credentials || []).map((credential) => ({ ...credential, id: fromBase64Url(credential.id) }));
+
+  // Create a passkey for the options the server sent, in the shape the server reads it
+  const createPasskey = async (options) => {
+    const publicKey = {
+      ...options.publicKey,
+      challenge: fromBase64Url(options.publicKey.challenge),
+      user: { ...options.publicKey.user, id: fromBase64Url(options.publicKey.user.id) },
+      excludeCredentials: withBinaryIds(options.publicKey.excludeCredentials),
+    };
+    const credential = await navigator.credentials.create({ publicKey });
+    return {
+      id: credential.id,
+      rawId: toBase64Url(credential.rawId),
+      type: credential.type,
+      extensions: credential.getClientExtensionResults(),
+      response: {
+        attestationObject: toBase64Url(credential.response.attestationObject),
+        clientDataJSON: toBase64Url(credential.response.clientDataJSON),
+      },
+    };
+  };
+
+  // Answer the challenge the server sent with one of the passkeys it allows
+  const getPasskey = async (options) => {
+    const publicKey = {
+      ...options.publicKey,
+      challenge: fromBase64Url(options.publicKey.challenge),
+      allowCredentials: withBinaryIds(options.publicKey.allowCredentials),
+    };
+    const credential = await navigator.credentials.get({ publicKey });
+    const { response } = credential;
+    return {
+      id: credential.id,
+      rawId: toBase64Url(credential.rawId),
+      type: credential.type,
+      extensions: credential.getClientExtensionResults(),
+      response: {
+        authenticatorData: toBase64Url(response.authenticatorData),
+        clientDataJSON: toBase64Url(response.clientDataJSON),
+        signature: toBase64Url(response.signature),
+        userHandle: response.userHandle ? toBase64Url(response.userHandle) : null,
+      },
+    };
+  };
+
+  // Sign in with a passkey, asking `startUrl` for a challenge and sending the answer to `finishUrl`
+  const signInWithPasskey = async (startUrl, startPayload, finishUrl) => {
+    try {
+      const start = await csrfFetch(startUrl, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(startPayload),
+      });
+      if (!start.ok) {
+        alert(await errorMessage(start, 'Signing in with a passkey failed'));
+        return;
+      }
+      const options = (await start.json()).data;
+      let credential;
+      try {
+        credential = await getPasskey(options);
+      } catch (_) {
+        // the browser prompt was dismissed
+        return;
+      }
+      const finish = await csrfFetch(finishUrl, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ credential }),
+      });
+      if (finish.ok) {
+        window.location.assign('/admin');
+      } else if (finish.status === 429) {
+        alert('Too many attempts. Please wait a bit before trying again.');
+      } else {
+        alert(await errorMessage(finish, 'Signing in with a passkey failed'));
+      }
+    } catch (err) {
+      alert('Signing in with a passkey failed: network error');
+    }
+  };
+
+  // Passwordless sign-in from the login page
+  const passkeyLoginBtn = document.getElementById('passkey-login-btn');
+  if (passkeyLoginBtn && loginForm) {
+    passkeyLoginBtn.addEventListener('click', () => {
+      const usernameOrEmail = loginForm.username_or_email.value.trim();
+      if (!usernameOrEmail) {
+        alert('Enter your username or email first');
+        loginForm.username_or_email.focus();
+        return;
+      }
+      signInWithPasskey(
+        '/auth/passkey/start',
+        { username_or_email: usernameOrEmail },
+        '/auth/passkey/finish',
+      );
+    });
+  }
+
+  // Passkey as the second factor of a login
+  const twoFactorPasskeyBtn = document.getElementById('two-factor-passkey-btn');
+  if (twoFactorPasskeyBtn) {
+    twoFactorPasskeyBtn.addEventListener('click', () => {
+      signInWithPasskey('/auth/2fa/passkey/start', {}, '/auth/2fa/passkey/finish');
+    });
+  }
+
+  // Passkey registration, on the account page and during a login awaiting two-factor setup
+  const passkeyRegisterForm = document.getElementById('passkey-register-form');
+  if (passkeyRegisterForm) {
+    passkeyRegisterForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      try {
+        const start = await csrfFetch('/api/me/passkeys/register/start', { method: 'POST' });
+        if (!start.ok) {
+          alert(await errorMessage(start, 'Registering the passkey failed'));
+          return;
+        }
+        const options = (await start.json()).data;
+        let credential;
+        try {
+          credential = await createPasskey(options);
+        } catch (_) {
+          // the browser prompt was dismissed
+          return;
+        }
+        const finish = await csrfFetch('/api/me/passkeys/register/finish', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ name: form.name.value.trim(), credential }),
+        });
+        if (finish.ok) {
+          if (form.dataset.next) {
+            window.location.assign(form.dataset.next);
+          } else {
+            window.location.reload();
+          }
+        } else {
+          alert(await errorMessage(finish, 'Registering the passkey failed'));
+        }
+      } catch (err) {
+        alert('Registering the passkey failed: network error');
+      }
+    });
+  }
+
+  // Passkey renaming and deletion on the account page
+  const passkeysTable = document.getElementById('passkeys-table');
+  if (passkeysTable) {
+    passkeysTable.addEventListener('click', async (e) => {
+      const button = e.target.closest('.passkey-rename-btn, .passkey-delete-btn');
+      if (!button) return;
+      const row = button.closest('tr');
+      const url = '/api/me/passkeys/' + row.dataset.passkeyId;
+      try {
+        if (button.classList.contains('passkey-rename-btn')) {
+          const r = await csrfFetch(url, {
+            method: 'PATCH',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({ name: row.querySelector('.passkey-name').value.trim() }),
+          });
+          if (!r.ok) {
+            alert(await errorMessage(r, 'Renaming the passkey failed'));
+          }
+        } else {
+          if (!confirm('Delete this passkey? You will no longer be able to sign in with it.')) return;
+          const r = await csrfFetch(url, { method: 'DELETE' });
+          if (r.ok) {
+            row.remove();
+          } else {
+            alert(await errorMessage(r, 'Deleting the passkey failed'));
+          }
+        }
+      } catch (err) {
+        alert('Updating the passkey failed: network error');
+      }
+    });
+  }
+
+  // Authenticator enrollment page
+  const twoFactorSetup = document.getElementById('two-factor-setup');
+  if (twoFactorSetup) {
+    (async () => {
+      try {
+        const r = await csrfFetch('/api/me/2fa/setup', { method: 'POST' });
+        if (!r.ok) {
+          alert(await errorMessage(r, 'Could not start two-factor authentication setup'));
+          if (r.status === 401) window.location.assign('/login');
+          return;
+        }
+        const body = await r.json();
+        // The SVG is rendered by the server from the otpauth URI
+        document.getElementById('two-factor-qr').innerHTML = body.data.qr_svg;
+        document.getElementById('two-factor-secret').textContent = body.data.secret;
+      } catch (err) {
+        alert('Could not start two-factor authentication setup: network error');
+      }
+    })();
+
+    const enableForm = document.getElementById('two-factor-enable-form');
+    enableForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      try {
+        const r = await csrfFetch('/api/me/2fa/enable', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ code: form.code.value.trim() }),
+        });
+        if (r.ok) {
+          const body = await r.json();
+          form.hidden = true;
+          showRecoveryCodes(document.getElementById('recovery-codes-list'), body.data.recovery_codes);
+          document.getElementById('recovery-codes').hidden = false;
+        } else {
+          alert(await errorMessage(r, 'Enabling two-factor authentication failed'));
+          form.reset();
+        }
+      } catch (err) {
+        alert('Enabling two-factor authentication failed: network error');
+      }
+    });
+  }
+
+  // Recovery codes regeneration on the account page
+  const recoveryCodesForm = document.getElementById('recovery-codes-form');
+  if (recoveryCodesForm) {
+    recoveryCodesForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      try {
+        const r = await csrfFetch('/api/me/2fa/recovery-codes', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ code: form.code.value.trim() }),
+        });
+        if (r.ok) {
+          const body = await r.json();
+          showRecoveryCodes(document.getElementById('recovery-codes-list'), body.data.recovery_codes);
+          alert('New recovery codes generated. Store them somewhere safe, they will not be shown again.');
+        } else {
+          alert(await errorMessage(r, 'Generating recovery codes failed'));
+        }
+        form.reset();
+      } catch (err) {
+        alert('Generating recovery codes failed: network error');
+      }
+    });
+  }
+
+  // Two-factor removal on the account page
+  const twoFactorDisableForm = document.getElementById('two-factor-disable-form');
+  if (twoFactorDisableForm) {
+    twoFactorDisableForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      const payload = {
+        password: form.password.value,
+        code: form.code.value.trim(),
+      };
+      try {
+        const r = await csrfFetch('/api/me/2fa/disable', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify(payload),
+        });
+        if (r.ok) {
+          alert('Two-factor authentication disabled.');
+          window.location.reload();
+        } else {
+          alert(await errorMessage(r, 'Disabling two-factor authentication failed'));
+          form.reset();
+        }
+      } catch (err) {
+        alert('Disabling two-factor authentication failed: network error');
+      }
+    });
+  }
+
+  // API token creation on the account page
+  const apiTokenForm = document.getElementById('api-token-form');
+  if (apiTokenForm) {
+    apiTokenForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      const days = form.expires_in_days.value.trim();
+      const payload = {
+        name: form.name.value.trim(),
+        scopes: Array.from(form.querySelectorAll('input[name="scopes"]:checked'), (input) => input.value),
+        expires_in_days: days ? Number(days) : null,
+      };
+      try {
+        const r = await csrfFetch('/api/me/tokens', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify(payload),
+        });
+        if (r.ok) {
+          const body = await r.json();
+          document.getElementById('api-token-value').textContent = body.data.token;
+          document.getElementById('api-token-created').hidden = false;
+          form.reset();
+        } else {
+          alert(await errorMessage(r, 'Creating the API token failed'));
+        }
+      } catch (err) {
+        alert('Creating the API token failed: network error');
+      }
+    });
+  }
+
+  // API token revocation on the account page
+  const apiTokensTable = document.getElementById('api-tokens-table');
+  if (apiTokensTable) {
+    apiTokensTable.addEventListener('click', async (e) => {
+      const button = e.target.closest('.api-token-revoke-btn');
+      if (!button) return;
+      const row = button.closest('tr');
+      if (!confirm('Revoke this token? Anything using it will stop working.')) return;
+      try {
+        const r = await csrfFetch('/api/me/tokens/' + row.dataset.tokenId, { method: 'DELETE' });
+        if (r.ok) {
+          row.remove();
+        } else {
+          alert(await errorMessage(r, 'Revoking the API token failed'));
+        }
+      } catch (err) {
+        alert('Revoking the API token failed: network error');
+      }
+    });
+  }
+
+  // Session revocation on the sessions page
+  const sessionsTable = document.getElementById('sessions-table');
+  if (sessionsTable) {
+    sessionsTable.addEventListener('click', async (e) => {
+      const button = e.target.closest('.session-revoke-btn');
+      if (!button) return;
+      const row = button.closest('tr');
+      try {
+        const r = await csrfFetch('/api/me/sessions/' + row.dataset.sessionId, { method: 'DELETE' });
+        if (r.ok) {
+          row.remove();
+        } else {
+          alert(await errorMessage(r, 'Signing the session out failed'));
+        }
+      } catch (err) {
+        alert('Signing the session out failed: network error');
+      }
+    });
+  }
+
+  // Sign out of every other session
+  const revokeOtherSessionsBtn = document.getElementById('revoke-other-sessions-btn');
+  if (revokeOtherSessionsBtn) {
+    revokeOtherSessionsBtn.addEventListener('click', async (e) => {
+      e.preventDefault();
+      if (!confirm('Sign out of every other device?')) return;
+      try {
+        const r = await csrfFetch('/api/me/sessions', { method: 'DELETE' });
+        if (r.ok) {
+          window.location.reload();
+        } else {
+          alert(await errorMessage(r, 'Signing out other sessions failed'));
+        }
+      } catch (err) {
+        alert('Signing out other sessions failed: network error');
+      }
+    });
+  }
+
+  // Email change request on the account page
+  const emailChangeForm = document.getElementById('email-change-form');
+  if (emailChangeForm) {
+    emailChangeForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      const payload = {
+        new_email: form.new_email.value.trim(),
+        password: form.password.value,
+      };
+      try {
+        const r = await csrfFetch('/api/me/email', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify(payload),
+        });
+        if (r.ok) {
+          alert('Check the inbox of ' + payload.new_email + ' to confirm the change.');
+          form.reset();
+        } else {
+          alert(await errorMessage(r, 'Changing the email address failed'));
+          form.password.value = '';
+        }
+      } catch (err) {
+        alert('Changing the email address failed: network error');
+      }
+    });
+  }
+
+  // Account deletion on the account page, carried out once the grace period is over
+  const deleteAccountForm = document.getElementById('delete-account-form');
+  if (deleteAccountForm) {
+    deleteAccountForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      if (!confirm('Delete your account? You can cancel until the grace period is over.')) return;
+      try {
+        const r = await csrfFetch('/api/me/deletion', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ mode: form.mode.value, password: form.password.value }),
+        });
+        if (r.ok) {
+          window.location.reload();
+        } else {
+          alert(await errorMessage(r, 'Deleting the account failed'));
+          form.password.value = '';
+        }
+      } catch (err) {
+        alert('Deleting the account failed: network error');
+      }
+    });
+  }
+
+  const cancelDeletionBtn = document.getElementById('cancel-deletion-btn');
+  if (cancelDeletionBtn) {
+    cancelDeletionBtn.addEventListener('click', async () => {
+      try {
+        const r = await csrfFetch('/api/me/deletion', { method: 'DELETE' });
+        if (r.ok) {
+          window.location.reload();
+        } else {
+          alert(await errorMessage(r, 'Cancelling the deletion failed'));
+        }
+      } catch (err) {
+        alert('Cancelling the deletion failed: network error');
+      }
+    });
+  }
+
+  // Email change confirmation and undo, both posting the token of the link they came from
+  [
+    ['confirm-email-change-form', '/auth/confirm-email-change', 'Your email address has been changed.', '/account'],
+    ['undo-email-change-form', '/auth/undo-email-change', 'Your email address has been restored. Please log in and change your password.', '/login'],
+  ].forEach(([id, url, success, next]) => {
+    const form = document.getElementById(id);
+    if (!form) return;
+    form.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      try {
+        const r = await csrfFetch(url, {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ token: form.token.value }),
+        });
+        if (r.ok) {
+          alert(success);
+          window.location.assign(next);
+        } else {
+          alert(await errorMessage(r, 'The link could not be used'));
+        }
+      } catch (err) {
+        alert('The link could not be used: network error');
+      }
+    });
+  });
+
+  // Sign-in link confirmation, posting the token of the link it came from
+  const magicLinkConfirmForm = document.getElementById('magic-link-confirm-form');
+  if (magicLinkConfirmForm) {
+    magicLinkConfirmForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      try {
+        const r = await csrfFetch('/auth/magic', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ token: magicLinkConfirmForm.token.value }),
+        });
+        if (r.ok) {
+          // The link stands in for the password, a second factor may still be asked for
+          const body = await r.json().catch(() => null);
+          const step = body && body.data ? body.data.step : 'complete';
+          if (step === 'two_factor_required') {
+            window.location.assign('/auth/2fa');
+          } else if (step === 'two_factor_setup_required') {
+            window.location.assign('/auth/2fa/setup');
+          } else {
+            window.location.assign('/admin');
+          }
+        } else {
+          alert(await errorMessage(r, 'The sign-in link could not be used'));
+        }
+      } catch (err) {
+        alert('The sign-in link could not be used: network error');
+      }
+    });
+  }
+
   // Registration form handler
   const registerForm = document.getElementById('register-form');
   if (registerForm) {
@@ -94,15 +692,17 @@ onReady(() => {
         email: form.email.value,
         password: form.password.value,
         display_name: form.display_name.value || null,
+        invite_token: form.invite_token ? form.invite_token.value : null,
       };
       try {
-        const r = await fetch('/auth/register', {
+        const r = await csrfFetch('/auth/register', {
           method: 'POST',
           headers: { 'Content-Type': 'application/json' },
           body: JSON.stringify(payload),
         });
         if (r.ok) {
-          window.location.assign('/auth/check-email');
+          // Invited accounts are verified by their invite link, and can sign in right away
+          window.location.assign(payload.invite_token ? '/login' : '/auth/check-email');
         } else {
           const t = await r.text();
           alert('Registration failed' + (t ? (': ' + t) : ''));
@@ -129,7 +729,7 @@ onReady(() => {
       e.preventDefault();
       const form = e.currentTarget;
       try {
-        const r = await fetch('/auth/resend-verification', {
+        const r = await csrfFetch('/auth/resend-verification', {
           method: 'POST',
           headers: { 'Content-Type': 'application/json' },
           body: JSON.stringify({ email: form.email.value })
@@ -147,13 +747,332 @@ onReady(() => {
     });
   }
 
+  // Forgot password form
+  const forgotForm = document.getElementById('forgot-password-form');
+  if (forgotForm) {
+    forgotForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      try {
+        const r = await csrfFetch('/auth/forgot-password', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ email: form.email.value })
+        });
+        if (r.ok) {
+          alert('Password reset email sent (if the account exists).');
+          window.location.assign('/login');
+        } else if (r.status === 429) {
+          alert('Too many requests. Please wait a bit before trying again.');
+        } else {
+          alert('Failed to request a password reset.');
+        }
+      } catch (err) {
+        alert('Network error. Please try again later.');
+      }
+    });
+  }
+
+  // Sign-in link form on the login page
+  const magicLinkForm = document.getElementById('magic-link-form');
+  if (magicLinkForm) {
+    magicLinkForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      try {
+        const r = await csrfFetch('/auth/magic-link', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ email: form.email.value })
+        });
+        if (r.ok) {
+          alert('Sign-in link sent (if the account can use one). Please check your inbox.');
+          form.reset();
+        } else if (r.status === 429) {
+          alert('Too many requests. Please wait a bit before trying again.');
+        } else {
+          alert('Failed to request a sign-in link.');
+        }
+      } catch (err) {
+        alert('Network error. Please try again later.');
+      }
+    });
+  }
+
+  // Reset password form
+  const resetForm = document.getElementById('reset-password-form');
+  if (resetForm) {
+    resetForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      const payload = {
+        token: form.token.value,
+        new_password: form.new_password.value,
+      };
+      try {
+        const r = await csrfFetch('/auth/reset-password', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify(payload),
+        });
+        if (r.ok) {
+          alert('Your password has been reset. Please log in.');
+          window.location.assign('/login');
+        } else {
+          let msg = 'Password reset failed';
+          try {
+            const payload = await r.json();
+            if (payload && typeof payload.message === 'string') {
+              msg = payload.message;
+            }
+          } catch (_) {
+            // ignore parse errors; fall back to generic message
+          }
+          alert(msg);
+        }
+      } catch (err) {
+        alert('Password reset failed: network error');
+      }
+    });
+
+    // Show/hide password toggle for password reset
+    const toggleReset = document.getElementById('toggle-password-reset');
+    const pwdReset = resetForm.querySelector('input[name="new_password"]');
+    if (toggleReset && pwdReset) {
+      toggleReset.addEventListener('change', () => {
+        pwdReset.type = toggleReset.checked ? 'text' : 'password';
+      });
+    }
+  }
+
+  // Account profile form
+  const profileForm = document.getElementById('profile-form');
+  if (profileForm) {
+    profileForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      // Empty fields are sent as null so they leave the stored value untouched
+      const payload = {};
+      for (const name of ['display_name', 'bio', 'avatar_url', 'social_twitter', 'social_github', 'website_url']) {
+        const value = form[name].value.trim();
+        payload[name] = value === '' ? null : value;
+      }
+      try {
+        const r = await csrfFetch('/api/me', {
+          method: 'PATCH',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify(payload),
+        });
+        if (r.ok) {
+          alert('Profile updated.');
+        } else {
+          let msg = 'Profile update failed';
+          try {
+            const payload = await r.json();
+            if (payload && typeof payload.message === 'string') {
+              msg = payload.message;
+            }
+          } catch (_) {
+            // ignore parse errors; fall back to generic message
+          }
+          alert(msg);
+        }
+      } catch (err) {
+        alert('Profile update failed: network error');
+      }
+    });
+  }
+
+  // Account password form
+  const passwordForm = document.getElementById('password-form');
+  if (passwordForm) {
+    passwordForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      const payload = {
+        current_password: form.current_password.value,
+        new_password: form.new_password.value,
+      };
+      try {
+        const r = await csrfFetch('/api/me/password', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify(payload),
+        });
+        if (r.ok) {
+          alert('Password changed.');
+          form.reset();
+        } else if (r.status === 401) {
+          alert('Your current password is incorrect.');
+        } else {
+          let msg = 'Password change failed';
+          try {
+            const payload = await r.json();
+            if (payload && typeof payload.message === 'string') {
+              msg = payload.message;
+            }
+          } catch (_) {
+            // ignore parse errors; fall back to generic message
+          }
+          alert(msg);
+        }
+      } catch (err) {
+        alert('Password change failed: network error');
+      }
+    });
+  }
+
+  // Admin user management actions
+  const usersTable = document.getElementById('users-table');
+  if (usersTable) {
+    const adminRequest = async (url, method, payload) => {
+      try {
+        const r = await csrfFetch(url, {
+          method,
+          headers: { 'Content-Type': 'application/json' },
+          body: payload === undefined ? undefined : JSON.stringify(payload),
+        });
+        if (r.ok) {
+          window.location.reload();
+          return;
+        }
+        let msg = 'Action failed';
+        try {
+          const body = await r.json();
+          if (body && typeof body.message === 'string') {
+            msg = body.message;
+          }
+        } catch (_) {
+          // ignore parse errors; fall back to generic message
+        }
+        alert(msg);
+      } catch (err) {
+        alert('Action failed: network error');
+      }
+    };
+
+    usersTable.addEventListener('change', (e) => {
+      const select = e.target.closest('.user-role-select');
+      if (!select) return;
+      const id = select.closest('tr').dataset.userId;
+      adminRequest('/api/admin/users/' + id + '/role', 'PATCH', { role: select.value });
+    });
+
+    usersTable.addEventListener('click', (e) => {
+      const button = e.target.closest('button');
+      if (!button) return;
+      const id = button.closest('tr').dataset.userId;
+      if (button.classList.contains('user-active-btn')) {
+        const isActive = button.dataset.active === 'true';
+        adminRequest('/api/admin/users/' + id + '/active', 'PATCH', { is_active: !isActive });
+      } else if (button.classList.contains('user-verify-btn')) {
+        adminRequest('/api/admin/users/' + id + '/verify-email', 'POST');
+      } else if (button.classList.contains('user-unlock-btn')) {
+        adminRequest('/api/admin/users/' + id + '/unlock', 'POST');
+      } else if (button.classList.contains('user-reset-2fa-btn')) {
+        if (confirm('Remove the authenticator of this user? They will have to enroll again.')) {
+          adminRequest('/api/admin/users/' + id + '/2fa/reset', 'POST');
+        }
+      } else if (button.classList.contains('user-sessions-btn')) {
+        if (confirm('Sign this user out of every device?')) {
+          adminRequest('/api/admin/users/' + id + '/sessions', 'DELETE');
+        }
+      } else if (button.classList.contains('user-anonymize-btn')) {
+        if (confirm('Anonymize this user? Their personal details are removed for good.')) {
+          adminRequest('/api/admin/users/' + id + '?mode=anonymize', 'DELETE');
+        }
+      } else if (button.classList.contains('user-delete-btn')) {
+        if (confirm('Delete this user? Their content is kept under a placeholder author.')) {
+          adminRequest('/api/admin/users/' + id + '?mode=delete', 'DELETE');
+        }
+      }
+    });
+  }
+
+  // Invitation creation on the admin invitations page
+  const invitationForm = document.getElementById('invitation-form');
+  if (invitationForm) {
+    invitationForm.addEventListener('submit', async (e) => {
+      e.preventDefault();
+      const form = e.currentTarget;
+      const days = form.expires_in_days.value.trim();
+      const payload = {
+        email: form.email.value.trim(),
+        role: form.role.value,
+        expires_in_days: days ? Number(days) : null,
+      };
+      try {
+        const r = await csrfFetch('/api/admin/invitations', {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify(payload),
+        });
+        if (r.ok) {
+          const body = await r.json();
+          const link = new URL('/register?invite=' + encodeURIComponent(body.data.token), window.location.origin);
+          document.getElementById('invitation-link').textContent = link.href;
+          document.getElementById('invitation-created').hidden = false;
+          form.reset();
+        } else {
+          alert(await errorMessage(r, 'Sending the invitation failed'));
+        }
+      } catch (err) {
+        alert('Sending the invitation failed: network error');
+      }
+    });
+  }
+
+  // Invitation revocation on the admin invitations page
+  const invitationsTable = document.getElementById('invitations-table');
+  if (invitationsTable) {
+    invitationsTable.addEventListener('click', async (e) => {
+      const button = e.target.closest('.invitation-revoke-btn');
+      if (!button) return;
+      const row = button.closest('tr');
+      if (!confirm('Revoke this invitation? Its link will stop working.')) return;
+      try {
+        const r = await csrfFetch('/api/admin/invitations/' + row.dataset.invitationId, { method: 'DELETE' });
+        if (r.ok) {
+          row.remove();
+        } else {
+          alert(await errorMessage(r, 'Revoking the invitation failed'));
+        }
+      } catch (err) {
+        alert('Revoking the invitation failed: network error');
+      }
+    });
+  }
+
+  // Role to capability matrix on the admin capabilities page
+  const capabilitiesTable = document.getElementById('capabilities-table');
+  if (capabilitiesTable) {
+    capabilitiesTable.addEventListener('click', async (e) => {
+      const button = e.target.closest('.capability-save-btn');
+      if (!button) return;
+      const row = button.closest('tr');
+      const roles = Array.from(row.querySelectorAll('.capability-role:checked')).map((input) => input.value);
+      try {
+        const r = await csrfFetch('/api/admin/capabilities/' + row.dataset.capability, {
+          method: 'PATCH',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ roles }),
+        });
+        if (!r.ok) {
+          alert(await errorMessage(r, 'Updating the capability failed'));
+        }
+      } catch (err) {
+        alert('Updating the capability failed: network error');
+      }
+    });
+  }
+
   // Logout button handler
   const logoutBtn = document.getElementById('logout-btn');
   if (logoutBtn) {
     logoutBtn.addEventListener('click', async (e) => {
       e.preventDefault();
       try {
-        const r = await fetch('/auth/logout', { method: 'POST' });
+        const r = await csrfFetch('/auth/logout', { method: 'POST' });
         if (r.ok) {
           window.location.assign('/');
         } else {